@@ -0,0 +1,147 @@
+use iced::widget::button;
+use iced::{Background, Color, Theme};
+use iced_modern_theme::Modern;
+use crate::settings::ThemeChoice;
+use futures::Stream;
+use std::time::Duration;
+
+// Brand color layered over the Modern themes. The accent travels in the theme's
+// palette, and the button styles below repaint primary and selected buttons with
+// it. Without an accent they are exactly the Modern styles.
+
+// Marks themes built with an accent, so the plain Modern themes are left alone
+const ACCENT_SUFFIX: &str = " (Accent)";
+
+// Quick picks shown next to the hex input
+pub const PALETTE: &[(&str, [u8; 3])] = &[
+    ("Blue", [0x25, 0x63, 0xeb]),
+    ("Green", [0x16, 0xa3, 0x4a]),
+    ("Orange", [0xea, 0x58, 0x0c]),
+    ("Red", [0xdc, 0x26, 0x26]),
+    ("Purple", [0x7c, 0x3a, 0xed]),
+    ("Teal", [0x0d, 0x94, 0x88]),
+];
+
+// system_dark is the OS preference, only read for ThemeChoice::System
+pub fn app_theme(choice: ThemeChoice, accent: Option<[u8; 3]>, system_dark: bool) -> Theme {
+    let dark = match choice {
+        ThemeChoice::Light => false,
+        ThemeChoice::Dark => true,
+        ThemeChoice::System => system_dark,
+    };
+    let base = if dark { Modern::dark_theme() } else { Modern::light_theme() };
+
+    match accent {
+        Some(rgb) => {
+            let palette = iced::theme::Palette {
+                primary: to_color(rgb),
+                ..base.palette()
+            };
+            Theme::custom(format!("{}{}", base, ACCENT_SUFFIX), palette)
+        }
+        None => base,
+    }
+}
+
+// Platforms that can't report a preference get the app's default dark theme
+pub fn system_prefers_dark() -> bool {
+    !matches!(dark_light::detect(), dark_light::Mode::Light)
+}
+
+// How often the OS light/dark preference is rechecked under ThemeChoice::System
+const SYSTEM_THEME_POLL: Duration = Duration::from_secs(5);
+
+// The OS preference when first polled and again each time it flips. The
+// platform query can block on a settings daemon, so it runs on a blocking
+// thread rather than in update.
+pub fn system_theme_changes() -> impl Stream<Item = bool> {
+    futures::stream::unfold(None, |last: Option<bool>| async move {
+        loop {
+            if last.is_some() {
+                tokio::time::sleep(SYSTEM_THEME_POLL).await;
+            }
+            let dark = tokio::task::spawn_blocking(system_prefers_dark).await.ok()?;
+            if last != Some(dark) {
+                return Some((dark, Some(dark)));
+            }
+        }
+    })
+}
+
+// Accepts "#2563eb" or "2563eb"
+pub fn parse_hex(input: &str) -> Result<[u8; 3], String> {
+    let hex = input.trim().trim_start_matches('#');
+
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Use a 6 digit hex color, e.g. #2563eb".to_string());
+    }
+
+    let channel = |start: usize| u8::from_str_radix(&hex[start..start + 2], 16).map_err(|e| e.to_string());
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+pub fn to_hex(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+pub fn to_color(rgb: [u8; 3]) -> Color {
+    Color::from_rgb8(rgb[0], rgb[1], rgb[2])
+}
+
+pub fn primary_button() -> impl Fn(&Theme, button::Status) -> button::Style {
+    let base = Modern::primary_button();
+    move |theme, status| with_accent(theme, status, base(theme, status))
+}
+
+// Selected sidebar entries and list rows
+pub fn selected_button() -> impl Fn(&Theme, button::Status) -> button::Style {
+    let base = Modern::selected_button_style(Modern::system_button());
+    move |theme, status| with_accent(theme, status, base(theme, status))
+}
+
+// A button filled with a fixed color, for the palette swatches
+pub fn swatch_button(rgb: [u8; 3]) -> impl Fn(&Theme, button::Status) -> button::Style {
+    move |_theme, status| fill(button::Style::default(), to_color(rgb), status)
+}
+
+fn with_accent(theme: &Theme, status: button::Status, style: button::Style) -> button::Style {
+    if theme.to_string().ends_with(ACCENT_SUFFIX) {
+        fill(style, theme.palette().primary, status)
+    } else {
+        style
+    }
+}
+
+fn fill(style: button::Style, color: Color, status: button::Status) -> button::Style {
+    let color = match status {
+        button::Status::Active => color,
+        button::Status::Hovered => mix(color, Color::WHITE, 0.15),
+        button::Status::Pressed => mix(color, Color::BLACK, 0.15),
+        button::Status::Disabled => Color { a: 0.5, ..color },
+    };
+
+    button::Style {
+        background: Some(Background::Color(color)),
+        text_color: readable_on(color),
+        ..style
+    }
+}
+
+fn mix(from: Color, to: Color, amount: f32) -> Color {
+    Color {
+        r: from.r + (to.r - from.r) * amount,
+        g: from.g + (to.g - from.g) * amount,
+        b: from.b + (to.b - from.b) * amount,
+        a: from.a,
+    }
+}
+
+// Black or white, whichever stays legible on the background
+fn readable_on(background: Color) -> Color {
+    let luminance = 0.299 * background.r + 0.587 * background.g + 0.114 * background.b;
+    if luminance > 0.6 {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    }
+}
@@ -0,0 +1,101 @@
+use iced::Task;
+
+// Shared safeguard for operations touching many records. Bulk flows hand over
+// a description, the names of the affected records and the work to run; small
+// changes run straight away, large ones wait for the summary modal.
+
+// Number of affected names shown in the summary
+pub const SAMPLE_SIZE: usize = 5;
+
+pub fn default_threshold() -> usize {
+    50
+}
+
+pub fn needs_confirmation(count: usize, threshold: usize) -> bool {
+    count > threshold
+}
+
+pub struct PendingBulk<S, M> {
+    pub description: String,
+    pub count: usize,
+    pub sample: Vec<String>,
+    execute: Box<dyn FnOnce(&mut S) -> Task<M>>,
+}
+
+impl<S, M> PendingBulk<S, M> {
+    pub fn new(
+        description: String,
+        affected: Vec<String>,
+        execute: impl FnOnce(&mut S) -> Task<M> + 'static,
+    ) -> Self {
+        Self {
+            description,
+            count: affected.len(),
+            sample: affected.into_iter().take(SAMPLE_SIZE).collect(),
+            execute: Box::new(execute),
+        }
+    }
+
+    pub fn execute(self, state: &mut S) -> Task<M> {
+        println!("Bulk operation: {} ({} records)", self.description, self.count);
+        (self.execute)(state)
+    }
+}
+
+// Either runs the work now, or returns it to be held until confirmed
+pub fn gate<S, M>(
+    state: &mut S,
+    threshold: usize,
+    pending: PendingBulk<S, M>,
+) -> Result<Task<M>, PendingBulk<S, M>> {
+    if needs_confirmation(pending.count, threshold) {
+        Err(pending)
+    } else {
+        Ok(pending.execute(state))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renaming(count: usize) -> PendingBulk<Vec<String>, ()> {
+        let names: Vec<String> = (1..=count).map(|n| format!("Item {}", n)).collect();
+        PendingBulk::new("Rename".to_string(), names, |state: &mut Vec<String>| {
+            state.push("ran".to_string());
+            Task::none()
+        })
+    }
+
+    #[test]
+    fn only_counts_above_the_threshold_need_confirmation() {
+        assert!(!needs_confirmation(50, 50));
+        assert!(needs_confirmation(51, 50));
+    }
+
+    #[test]
+    fn summary_keeps_the_count_and_a_short_sample() {
+        let pending = renaming(SAMPLE_SIZE + 3);
+
+        assert_eq!(pending.count, SAMPLE_SIZE + 3);
+        assert_eq!(pending.sample.len(), SAMPLE_SIZE);
+        assert_eq!(pending.sample[0], "Item 1");
+    }
+
+    #[test]
+    fn gate_runs_small_changes_and_holds_large_ones() {
+        let mut state = Vec::new();
+
+        assert!(gate(&mut state, 10, renaming(10)).is_ok());
+        assert_eq!(state, vec!["ran".to_string()]);
+
+        let Err(held) = gate(&mut state, 10, renaming(11)) else {
+            panic!("a change over the threshold ran without confirmation");
+        };
+        assert_eq!(state.len(), 1);
+
+        let _ = held.execute(&mut state);
+        assert_eq!(state.len(), 2);
+    }
+}
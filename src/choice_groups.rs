@@ -1,36 +1,84 @@
-use crate::data_types::{EntityId, ValidationError};
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState};
+use crate::entity_component::{self, Entity, EditState, ListSort, MergeSelection};
+use crate::items::Item;
+use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
-use iced::Element;
+use iced::{Element, Length};
+use iced::widget::{button, checkbox, column, container, row, scrollable, text, text_input};
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub enum Message {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyChoiceGroup(EntityId),
     EditChoiceGroup(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    MoveUp(EntityId),
+    MoveDown(EntityId),
+    ToggleSortByUsage(bool),
+    ToggleNeverUsed(bool),
+    ExportUsage,
+    UsageExported(Result<PathBuf, String>),
+    ShowMembers(Option<EntityId>),
+    SearchMembers(String),
+    ToggleItemMembership(EntityId, EntityId),
+    SelectMerge(MergeSelection),
+    // Moves the items of the first group to the second, then deletes the first
+    Merge(EntityId, EntityId),
 }
 
 #[derive(Debug, Clone)]
 pub enum Operation {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyChoiceGroup(EntityId),
     EditChoiceGroup(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    MoveUp(EntityId),
+    MoveDown(EntityId),
+    ToggleSortByUsage(bool),
+    ToggleNeverUsed(bool),
+    ExportUsage,
+    UsageExported(Result<PathBuf, String>),
+    ShowMembers(Option<EntityId>),
+    SearchMembers(String),
+    ToggleItemMembership(EntityId, EntityId),
+    SelectMerge(MergeSelection),
+    Merge(EntityId, EntityId),
+}
+
+// List display options for the choice group screen
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    pub sort_by_usage: bool,
+    pub never_used_only: bool,
+    pub export_message: String,
+    // Group whose items are being edited below the list
+    pub members_of: Option<EntityId>,
+    pub member_search: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChoiceGroup {
     pub id: EntityId,
     pub name: String,
+    // Position in the list, groups without one follow in id order
+    #[serde(default)]
+    pub sort_order: Option<u32>,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for ChoiceGroup {
@@ -44,6 +92,8 @@ impl Default for ChoiceGroup {
         Self {
             id: -1,
             name: String::new(),
+            sort_order: None,
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -70,7 +120,24 @@ impl Entity for ChoiceGroup {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
+    }
+}
+
+impl entity_component::Ordered for ChoiceGroup {
+    fn sort_order(&self) -> Option<u32> {
+        self.sort_order
+    }
+
+    fn set_sort_order(&mut self, sort_order: Option<u32>) {
+        self.sort_order = sort_order;
     }
 }
 
@@ -79,12 +146,8 @@ impl ChoiceGroup {
         Self::default()
     }
 
-    fn validate(&self, other_groups: &[&ChoiceGroup]) -> Result<(), ValidationError> {
-        if !(1..=999).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Choice group ID must be between 1 and 999".to_string()
-            ));
-        }
+    pub fn validate(&self, other_groups: &[&ChoiceGroup], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::ChoiceGroup, self.id, id_range)?;
 
         for other in other_groups {
             if other.id == self.id {
@@ -108,6 +171,12 @@ pub fn update(
     message: Message,
 ) -> Action<Operation, Message> {
     match message {
+        Message::ExportRecord(id) => {
+            Action::operation(Operation::ExportRecord(id))
+        },
+        Message::CopyJson(id) => {
+            Action::operation(Operation::CopyJson(id))
+        },
         Message::RequestDelete(id) => {
             Action::operation(Operation::RequestDelete(id))
         },
@@ -129,20 +198,250 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::MoveUp(id) => {
+            Action::operation(Operation::MoveUp(id))
+        }
+        Message::MoveDown(id) => {
+            Action::operation(Operation::MoveDown(id))
+        }
+        Message::ToggleSortByUsage(enabled) => {
+            Action::operation(Operation::ToggleSortByUsage(enabled))
+        }
+        Message::ToggleNeverUsed(enabled) => {
+            Action::operation(Operation::ToggleNeverUsed(enabled))
+        }
+        Message::ExportUsage => {
+            Action::operation(Operation::ExportUsage)
+        }
+        Message::UsageExported(result) => {
+            Action::operation(Operation::UsageExported(result))
+        }
+        Message::ShowMembers(id) => {
+            Action::operation(Operation::ShowMembers(id))
+        }
+        Message::SearchMembers(query) => {
+            Action::operation(Operation::SearchMembers(query))
+        }
+        Message::ToggleItemMembership(group_id, item_id) => {
+            Action::operation(Operation::ToggleItemMembership(group_id, item_id))
+        }
+        Message::SelectMerge(selection) => {
+            Action::operation(Operation::SelectMerge(selection))
+        }
+        Message::Merge(from, into) => {
+            Action::operation(Operation::Merge(from, into))
+        }
     }
 }
 
+// Number of items referencing each choice group, groups with no items are included with 0
+pub fn usage_counts(
+    groups: &BTreeMap<EntityId, ChoiceGroup>,
+    items: &BTreeMap<EntityId, Item>,
+) -> BTreeMap<EntityId, usize> {
+    let mut counts: BTreeMap<EntityId, usize> = groups.keys().map(|id| (*id, 0)).collect();
+
+    for item in items.values() {
+        if let Some(item_groups) = &item.choice_groups {
+            for (group_id, _) in item_groups {
+                *counts.entry(*group_id).or_insert(0) += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+// Groups passing the "Never used" filter and the search, in list order
+pub fn shown_groups<'a>(
+    all_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
+    edit_states: &[EditState],
+    usage: &BTreeMap<EntityId, usize>,
+    options: &ListOptions,
+    search: &str,
+) -> Vec<&'a ChoiceGroup> {
+    let mut groups: Vec<&ChoiceGroup> = all_groups.values()
+        .filter(|group| !options.never_used_only || usage.get(&group.id).copied().unwrap_or(0) == 0)
+        .filter(|group| entity_component::matches_search(*group, search, edit_states))
+        .collect();
+    entity_component::display_order(&mut groups);
+    groups
+}
+
 pub fn view<'a>(
     all_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
     edit_states: &'a Vec<EditState>,
+    usage: BTreeMap<EntityId, usize>,
+    items: &'a BTreeMap<EntityId, Item>,
+    options: &'a ListOptions,
+    search: &str,
+    sort: ListSort,
+    merge: MergeSelection,
 ) -> Element<'a, Message> {
-    entity_component::entity_view(
+    let title_row = entity_component::render_title_row(
         "Choice Groups",
         Message::CreateNew,
-        all_groups,
-        edit_states,
-        |choice_group, edit_states| render_choice_group_row(choice_group, edit_states)
+        665.0
+    );
+
+    let options_row = row![
+        checkbox("Sort by usage", options.sort_by_usage)
+            .on_toggle(Message::ToggleSortByUsage)
+            .style(Modern::checkbox()),
+        checkbox("Never used", options.never_used_only)
+            .on_toggle(Message::ToggleNeverUsed)
+            .style(Modern::checkbox()),
+        iced::widget::horizontal_space(),
+        button("Export Usage")
+            .on_press(Message::ExportUsage)
+            .style(Modern::system_button()),
+    ]
+    .spacing(15)
+    .padding([0, 15])
+    .width(Length::Fixed(665.0))
+    .align_y(iced::Alignment::Center);
+
+    let header_row = row![
+        text("ID").width(Length::Fixed(75.0)),
+        text("Name").width(Length::Fixed(250.0)),
+        text("Actions").width(Length::Fixed(230.0)),
+        text("Usage").width(Length::Fixed(80.0)),
+    ]
+    .padding(15);
+
+    let count_of = |id: &EntityId| usage.get(id).copied().unwrap_or(0);
+
+    let mut groups = shown_groups(all_groups, edit_states, &usage, options, search);
+
+    // Stable sorts keep the list order for groups with the same key
+    sort.apply(&mut groups);
+    if options.sort_by_usage {
+        groups.sort_by_key(|group| std::cmp::Reverse(count_of(&group.id)));
+    }
+    let reordering = !options.sort_by_usage && sort == ListSort::Default;
+
+    let groups_list = scrollable(
+        column(
+            groups
+                .into_iter()
+                .map(|group| {
+                    row![
+                        render_choice_group_row(group, edit_states),
+                        // Moving is meaningless while the list is sorted some other way
+                        if reordering {
+                            entity_component::move_buttons(Message::MoveUp(group.id), Message::MoveDown(group.id))
+                        } else {
+                            iced::widget::horizontal_space().width(45).into()
+                        },
+                        iced::widget::horizontal_space().width(20),
+                        button(text(count_of(&group.id).to_string()))
+                            .on_press(Message::ShowMembers(
+                                (options.members_of != Some(group.id)).then_some(group.id)
+                            ))
+                            .style(Modern::gray_button())
+                            .width(Length::Fixed(80.0)),
+                    ]
+                    .align_y(iced::Alignment::Center)
+                    .padding(5)
+                    .into()
+                })
+                .collect::<Vec<_>>()
+        )
+    ).height(Length::Fill);
+
+    let all_content = column![
+        title_row,
+        options_row,
+        text(&options.export_message).size(12).style(Modern::secondary_text()),
+        container(entity_component::merge_picker(all_groups, merge, Message::SelectMerge, Message::Merge))
+            .padding([0, 15]),
+        header_row,
+        groups_list
+    ];
+
+    let members: Element<'a, Message> = match options.members_of.and_then(|id| all_groups.get(&id)) {
+        Some(group) => members_view(group, items, &options.member_search),
+        None => column![].into(),
+    };
+
+    column![
+        container(all_content)
+            .height(Length::Shrink)
+            .style(Modern::card_container()),
+        members,
+    ]
+    .spacing(10)
+    .into()
+}
+
+// Every item with a checkbox for whether it includes the group, ticking one
+// adds the group to the end of that item's choice groups
+fn members_view<'a>(
+    group: &'a ChoiceGroup,
+    items: &'a BTreeMap<EntityId, Item>,
+    search: &'a str,
+) -> Element<'a, Message> {
+    let query = search.trim().to_lowercase();
+
+    let rows = items
+        .values()
+        .filter(|item| query.is_empty() || item.name.to_lowercase().contains(&query) || item.id.to_string() == query)
+        .map(|item| {
+            let member = item.choice_groups
+                .as_ref()
+                .is_some_and(|groups| groups.iter().any(|(id, _)| *id == group.id));
+
+            checkbox(format!("{} ({})", item.name, item.id), member)
+                .on_toggle(move |_| Message::ToggleItemMembership(group.id, item.id))
+                .style(Modern::checkbox())
+                .into()
+        })
+        .collect::<Vec<Element<'a, Message>>>();
+
+    container(
+        column![
+            row![
+                text(format!("Items in {} ({})", group.name, group.id)).style(Modern::primary_text()),
+                iced::widget::horizontal_space(),
+                button("Close")
+                    .on_press(Message::ShowMembers(None))
+                    .style(Modern::system_button()),
+            ]
+            .align_y(iced::Alignment::Center),
+            text_input("Search items", search)
+                .on_input(Message::SearchMembers)
+                .style(Modern::search_input()),
+            scrollable(column(rows).spacing(5)).height(Length::Fixed(300.0)),
+        ]
+        .spacing(10)
     )
+    .padding(10)
+    .width(Length::Fixed(665.0))
+    .style(Modern::card_container())
+    .into()
+}
+
+pub async fn export_usage(rows: Vec<(EntityId, String, usize)>) -> Result<PathBuf, String> {
+    let path = rfd::AsyncFileDialog::new()
+        .add_filter("CSV Files", &["csv"])
+        .set_title("Save Choice Group Usage")
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or("Export canceled".to_string())?;
+
+    let mut content = String::from("\"Choice Group ID\",\"Choice Group Name\",\"Item Count\"\n");
+    for (id, name, count) in rows {
+        content.push_str(&format!("{},\"{}\",{}\n", id, name.replace('"', "\"\""), count));
+    }
+
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| format!("Failed to write usage export: {}", e))?;
+
+    Ok(path)
 }
 
 fn render_choice_group_row<'a>(
@@ -155,9 +454,12 @@ fn render_choice_group_row<'a>(
         Message::EditChoiceGroup,
         Message::SaveAll,
         Message::CopyChoiceGroup,
+        Message::ExportRecord,
+        Message::CopyJson,
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
+        None,
         "Choice Group Name"
     )
 }
@@ -1,7 +1,9 @@
-use std::ops::Range;
-use rust_decimal::Decimal;
+use std::ops::{Range, RangeInclusive};
+use std::fmt;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use std::collections::BTreeMap;
 
 // Custom type for IDs to make it easier to change the underlying type if needed
 pub type EntityId = i32;
@@ -9,12 +11,70 @@ pub type EntityId = i32;
 // Custom type for currency values
 pub type Currency = Decimal;
 
+// Id held as text in an edit state, None if it isn't a number
+pub fn parse_entity_id(id: &str) -> Option<EntityId> {
+    id.trim().parse().ok()
+}
+
 //Convert String to Decimal
 pub fn string_to_decimal(input: &str) -> Result<Decimal, String> {
     Decimal::from_str(input)
         .map_err(|e| format!("Failed to convert '{}' to Decimal: {}", input, e))
 }
 
+// Amount held to exactly `scale` decimal places, halves round up like a till
+// rather than to even, so 0.005 becomes 0.01. The bool is set when digits were dropped
+pub fn round_amount(amount: Decimal, scale: u32) -> (Decimal, bool) {
+    let mut rounded = amount.round_dp_with_strategy(scale, RoundingStrategy::MidpointAwayFromZero);
+    rounded.rescale(scale);
+
+    (rounded, rounded != amount)
+}
+
+// When a record was created and last saved, as unix seconds. Records from
+// files written before these were kept have neither.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timestamps {
+    #[serde(default)]
+    pub created_at: Option<i64>,
+    #[serde(default)]
+    pub modified_at: Option<i64>,
+}
+
+impl Timestamps {
+    pub fn created_now() -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            created_at: Some(now),
+            modified_at: Some(now),
+        }
+    }
+
+    pub fn touch(&mut self) {
+        self.modified_at = Some(chrono::Utc::now().timestamp());
+    }
+
+    // Local time for display and export, empty when the time isn't known
+    pub fn format(time: Option<i64>) -> String {
+        time.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|utc| utc.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+
+    // For showing on screen, where a blank would look like a missing field
+    pub fn display(time: Option<i64>) -> String {
+        match Self::format(time) {
+            formatted if formatted.is_empty() => "Unknown".to_string(),
+            formatted => formatted,
+        }
+    }
+
+    // "Created: 2025-01-02 10:00, Modified: 2025-03-04 12:30"
+    pub fn summary(&self) -> String {
+        format!("Created: {}, Modified: {}", Self::display(self.created_at), Self::display(self.modified_at))
+    }
+}
+
 //Struct to handle PriceLevel: Price pairs
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ItemPrice {
@@ -37,6 +97,8 @@ pub enum ValidationError {
     InvalidPrice(String),
     MissingItemGroup(String),
     MissingRevenueCategory(String),
+    // A warning only, saving goes ahead with the duplicate
+    DuplicateName(String),
 }
 
 impl std::fmt::Display for ValidationError {
@@ -54,14 +116,141 @@ impl std::fmt::Display for ValidationError {
             ValidationError::InvalidPrice(msg)=> write!(f, "Invalid Price: {}", msg),
             ValidationError::MissingItemGroup(msg)=> write!(f, "Missing Item Group: {}", msg),
             ValidationError::MissingRevenueCategory(msg)=> write!(f, "Missing Revenue Group: {}", msg),
+            ValidationError::DuplicateName(msg) => write!(f, "Duplicate name: {}", msg),
+        }
+    }
+}
+
+// Entity modules with their own id space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EntityKind {
+    ItemGroup,
+    PriceLevel,
+    ProductClass,
+    TaxGroup,
+    SecurityLevel,
+    RevenueCategory,
+    ReportCategory,
+    ChoiceGroup,
+    PrinterLogical,
+    Store,
+}
+
+impl EntityKind {
+    pub const ALL: &'static [Self] = &[
+        Self::ItemGroup,
+        Self::PriceLevel,
+        Self::ProductClass,
+        Self::TaxGroup,
+        Self::SecurityLevel,
+        Self::RevenueCategory,
+        Self::ReportCategory,
+        Self::ChoiceGroup,
+        Self::PrinterLogical,
+        Self::Store,
+    ];
+
+    // Ranges accepted by the original POS target, used until overridden in settings
+    pub fn default_id_range(&self) -> RangeInclusive<EntityId> {
+        match self {
+            Self::ItemGroup => 1..=999,
+            Self::PriceLevel => 1..=999,
+            Self::ProductClass => 1..=999,
+            Self::TaxGroup => 1..=99,
+            Self::SecurityLevel => 1..=999,
+            Self::RevenueCategory => 0..=25,
+            Self::ReportCategory => 0..=25,
+            Self::ChoiceGroup => 1..=999,
+            Self::PrinterLogical => 0..=25,
+            Self::Store => 1..=99999,
+        }
+    }
+
+    pub fn default_id_ranges() -> BTreeMap<EntityKind, RangeInclusive<EntityId>> {
+        Self::ALL.iter().map(|kind| (*kind, kind.default_id_range())).collect()
+    }
+}
+
+impl fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ItemGroup => write!(f, "Item Group"),
+            Self::PriceLevel => write!(f, "Price Level"),
+            Self::ProductClass => write!(f, "Product Class"),
+            Self::TaxGroup => write!(f, "Tax Group"),
+            Self::SecurityLevel => write!(f, "Security Level"),
+            Self::RevenueCategory => write!(f, "Revenue Category"),
+            Self::ReportCategory => write!(f, "Report Category"),
+            Self::ChoiceGroup => write!(f, "Choice Group"),
+            Self::PrinterLogical => write!(f, "Printer Logical"),
+            Self::Store => write!(f, "Store"),
+        }
+    }
+}
+
+pub fn check_id_range(kind: EntityKind, id: EntityId, range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+    if range.contains(&id) {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidId(
+            format!("{} ID must be between {} and {}", kind, range.start(), range.end())
+        ))
+    }
+}
+
+// Shown beside a new record's id, so the range is known before a save rejects it
+pub fn id_hint(next_id: EntityId, range: &RangeInclusive<EntityId>) -> String {
+    format!("Next id: {} (valid {}\u{2013}{})", next_id, range.start(), range.end())
+}
+
+// Lowest id in the range that isn't taken, so gaps are reused before the range runs out
+pub fn lowest_free_id<T>(
+    kind: EntityKind,
+    existing: &BTreeMap<EntityId, T>,
+    range: &RangeInclusive<EntityId>,
+) -> Result<EntityId, String> {
+    range
+        .clone()
+        .find(|id| !existing.contains_key(id))
+        .ok_or_else(|| format!("No free {} ids remain ({}-{})", kind, range.start(), range.end()))
+}
+
+// Moves every entity outside the range onto the lowest free ids inside it,
+// returning old id -> new id so references can be rewritten
+pub fn renumber_into_range<T>(
+    kind: EntityKind,
+    entities: &mut BTreeMap<EntityId, T>,
+    range: &RangeInclusive<EntityId>,
+    set_id: impl Fn(&mut T, EntityId),
+) -> Result<BTreeMap<EntityId, EntityId>, String> {
+    let outside: Vec<EntityId> = entities.keys().filter(|id| !range.contains(id)).copied().collect();
+    let free = range.clone().filter(|id| !entities.contains_key(id)).count();
+
+    if outside.len() > free {
+        return Err(format!(
+            "{} {} records are outside {}-{} but only {} IDs are free",
+            outside.len(), kind, range.start(), range.end(), free
+        ));
+    }
+
+    let mut moves = BTreeMap::new();
+    for old_id in outside {
+        let new_id = lowest_free_id(kind, entities, range)?;
+        if let Some(mut entity) = entities.remove(&old_id) {
+            set_id(&mut entity, new_id);
+            entities.insert(new_id, entity);
+            moves.insert(old_id, new_id);
         }
     }
+
+    Ok(moves)
 }
 
 #[derive(Debug, Clone)]
 pub struct DeletionInfo {
     pub entity_type: String,
     pub entity_id: EntityId,
+    pub entity_name: String,
     pub affected_items: Vec<String>,
 }
 
@@ -70,7 +259,119 @@ impl DeletionInfo {
         Self {
             entity_type: String::new(),
             entity_id: 1,
+            entity_name: String::new(),
             affected_items: Vec::new(),
         }
     }
-}
\ No newline at end of file
+
+    // "TaxGroup" -> "tax group"
+    pub fn type_label(&self) -> String {
+        let mut label = String::new();
+        for (i, c) in self.entity_type.chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                label.push(' ');
+            }
+            label.extend(c.to_lowercase());
+        }
+        label
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("Delete {} '{}' (ID {})?", self.type_label(), self.entity_name, self.entity_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deletion_prompt_names_the_record() {
+        let info = DeletionInfo {
+            entity_type: "TaxGroup".to_string(),
+            entity_id: 3,
+            entity_name: "Food".to_string(),
+            affected_items: Vec::new(),
+        };
+
+        assert_eq!(info.type_label(), "tax group");
+        assert_eq!(info.prompt(), "Delete tax group 'Food' (ID 3)?");
+    }
+
+    #[test]
+    fn single_word_types_are_lowercased() {
+        let info = DeletionInfo { entity_type: "Item".to_string(), ..DeletionInfo::new() };
+
+        assert_eq!(info.type_label(), "item");
+    }
+
+    // Records that only know their own id, enough for the range helpers
+    fn records(ids: &[EntityId]) -> BTreeMap<EntityId, EntityId> {
+        ids.iter().map(|id| (*id, *id)).collect()
+    }
+
+    #[test]
+    fn ids_outside_the_range_are_rejected() {
+        assert!(check_id_range(EntityKind::ReportCategory, 1, &(1..=99)).is_ok());
+        assert!(check_id_range(EntityKind::ReportCategory, 99, &(1..=99)).is_ok());
+        assert!(matches!(
+            check_id_range(EntityKind::ReportCategory, 100, &(1..=99)),
+            Err(ValidationError::InvalidId(message)) if message == "Report Category ID must be between 1 and 99"
+        ));
+    }
+
+    #[test]
+    fn lowest_free_id_reuses_gaps() {
+        assert_eq!(lowest_free_id(EntityKind::RevenueCategory, &records(&[10, 12]), &(10..=20)), Ok(11));
+        assert_eq!(lowest_free_id(EntityKind::RevenueCategory, &records(&[1, 50]), &(10..=20)), Ok(10));
+        assert_eq!(
+            lowest_free_id(EntityKind::RevenueCategory, &records(&[1, 2]), &(1..=2)),
+            Err("No free Revenue Category ids remain (1-2)".to_string())
+        );
+    }
+
+    #[test]
+    fn renumbering_moves_only_records_outside_the_range() {
+        let mut categories = records(&[1, 5, 30, 40]);
+
+        let moves = renumber_into_range(EntityKind::ReportCategory, &mut categories, &(1..=10), |id, new_id| *id = new_id);
+
+        assert_eq!(moves, Ok(BTreeMap::from([(30, 2), (40, 3)])));
+        assert_eq!(categories, records(&[1, 2, 3, 5]));
+    }
+
+    #[test]
+    fn renumbering_refuses_when_the_range_is_too_small() {
+        let mut categories = records(&[1, 2, 30]);
+
+        assert!(renumber_into_range(EntityKind::ReportCategory, &mut categories, &(1..=2), |id, new_id| *id = new_id).is_err());
+        assert_eq!(categories, records(&[1, 2, 30]));
+    }
+
+    #[test]
+    fn unreadable_edit_state_ids_parse_to_none() {
+        assert_eq!(parse_entity_id("42"), Some(42));
+        assert_eq!(parse_entity_id(" -1 "), Some(-1));
+        assert_eq!(parse_entity_id(""), None);
+        assert_eq!(parse_entity_id("12a"), None);
+    }
+
+    #[test]
+    fn amounts_round_to_the_price_scale() {
+        let (rounded, changed) = round_amount(Decimal::from_str("1.999").unwrap(), 2);
+        assert_eq!(rounded.to_string(), "2.00");
+        assert!(changed);
+
+        let (rounded, changed) = round_amount(Decimal::from_str("4.5").unwrap(), 2);
+        assert_eq!(rounded.to_string(), "4.50");
+        assert!(!changed);
+    }
+
+    #[test]
+    fn halves_round_away_from_zero() {
+        assert_eq!(round_amount(Decimal::from_str("0.005").unwrap(), 2).0.to_string(), "0.01");
+        assert_eq!(round_amount(Decimal::from_str("0.015").unwrap(), 2).0.to_string(), "0.02");
+        assert_eq!(round_amount(Decimal::from_str("-0.005").unwrap(), 2).0.to_string(), "-0.01");
+        assert_eq!(round_amount(Decimal::from_str("0.0049").unwrap(), 2).0.to_string(), "0.00");
+    }
+}
@@ -1,9 +1,12 @@
-use crate::data_types::{EntityId, ValidationError};
+use crate::accent;
+use crate::data_types::{self, EntityId, EntityKind, Timestamps, ValidationError};
+use crate::hold_to_confirm;
 use crate::icon;
 use iced_modern_theme::Modern;
 use iced::{Element, Length};
-use iced::widget::{button, column, container, row, text, scrollable, text_input, tooltip, TextInput};
+use iced::widget::{button, column, container, pick_list, row, text, scrollable, text_input, tooltip, TextInput};
 use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 
 /// Trait that defines common behavior for entity types
 pub trait Entity: Clone + std::fmt::Display {
@@ -12,6 +15,108 @@ pub trait Entity: Clone + std::fmt::Display {
     fn with_id(&self, id: EntityId) -> Self;
     fn with_name(&self, name: String) -> Self;
     fn default_new() -> Self;
+    fn timestamps(&self) -> Timestamps;
+}
+
+/// Entities the user can put in their own order in a list
+pub trait Ordered: Entity {
+    fn sort_order(&self) -> Option<u32>;
+    fn set_sort_order(&mut self, sort_order: Option<u32>);
+}
+
+/// Sorts a list by its saved order. Entities never moved have no order and
+/// follow the ordered ones by id, which is how every list started out.
+pub fn display_order<T: Ordered>(entities: &mut [&T]) {
+    entities.sort_by_key(|entity| (entity.sort_order().is_none(), entity.sort_order(), entity.id()));
+}
+
+/// How a list screen orders its rows. The default keeps the collection's own
+/// order: by id, or the saved order for lists the user can rearrange.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListSort {
+    #[default]
+    Default,
+    IdAscending,
+    IdDescending,
+    NameAscending,
+    NameDescending,
+}
+
+impl ListSort {
+    pub const ALL: [ListSort; 5] = [
+        ListSort::Default,
+        ListSort::IdAscending,
+        ListSort::IdDescending,
+        ListSort::NameAscending,
+        ListSort::NameDescending,
+    ];
+
+    /// Sorts the rows in place. The sort is stable, so rows with the same key
+    /// keep the order they came in.
+    pub fn apply<T: Entity>(&self, entities: &mut [&T]) {
+        match self {
+            ListSort::Default => {}
+            ListSort::IdAscending => entities.sort_by_key(|entity| entity.id()),
+            ListSort::IdDescending => entities.sort_by_key(|entity| std::cmp::Reverse(entity.id())),
+            ListSort::NameAscending => entities.sort_by_cached_key(|entity| entity.name().to_lowercase()),
+            ListSort::NameDescending => entities.sort_by_cached_key(|entity| std::cmp::Reverse(entity.name().to_lowercase())),
+        }
+    }
+}
+
+impl std::fmt::Display for ListSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListSort::Default => write!(f, "Default order"),
+            ListSort::IdAscending => write!(f, "ID ascending"),
+            ListSort::IdDescending => write!(f, "ID descending"),
+            ListSort::NameAscending => write!(f, "Name A-Z"),
+            ListSort::NameDescending => write!(f, "Name Z-A"),
+        }
+    }
+}
+
+/// Swaps an entity with its neighbour in the list on screen, returning
+/// whether it moved. `shown` is the ids in the order they are listed, so with
+/// a search or filter active the rows hidden between the two stay put. The
+/// whole collection is renumbered so every entity ends up with a distinct
+/// order, including copies that inherited one.
+pub fn move_entity<T: Ordered>(entities: &mut BTreeMap<EntityId, T>, id: EntityId, up: bool, shown: &[EntityId]) -> bool {
+    let Some(position) = shown.iter().position(|shown_id| *shown_id == id) else {
+        return false;
+    };
+    let neighbour = if up { position.checked_sub(1) } else { Some(position + 1) };
+    let Some(&neighbour_id) = neighbour.and_then(|neighbour| shown.get(neighbour)) else {
+        return false;
+    };
+
+    let mut ordered: Vec<&T> = entities.values().collect();
+    display_order(&mut ordered);
+    let mut ids: Vec<EntityId> = ordered.iter().map(|entity| entity.id()).collect();
+
+    let from = ids.iter().position(|entity_id| *entity_id == id);
+    let to = ids.iter().position(|entity_id| *entity_id == neighbour_id);
+    let (Some(from), Some(to)) = (from, to) else {
+        return false;
+    };
+    ids.swap(from, to);
+
+    for (order, entity_id) in (0u32..).zip(ids) {
+        if let Some(entity) = entities.get_mut(&entity_id) {
+            entity.set_sort_order(Some(order));
+        }
+    }
+    true
+}
+
+/// Small up and down arrows for moving a row within its list
+pub fn move_buttons<'a, Message: Clone + 'a>(up: Message, down: Message) -> Element<'a, Message> {
+    row![
+        button(text("▲").size(10)).on_press(up).padding([2, 4]).style(Modern::gray_button()),
+        button(text("▼").size(10)).on_press(down).padding([2, 4]).style(Modern::gray_button()),
+    ]
+    .spacing(2)
+    .into()
 }
 
 /// Generic edit state for editing entities
@@ -22,6 +127,13 @@ pub struct EditState {
     pub id: String,
     pub id_validation_error: Option<String>,
     pub name_validation_error: Option<String>,
+    // Another entity already uses the typed name, shown without blocking the save
+    pub name_warning: Option<String>,
+    // Typed id for a record that hasn't been saved yet. Saved records keep
+    // theirs, since other records may already point at it
+    pub id_input: Option<String>,
+    // Range a new record's id has to fall in, shown under its id until it's saved
+    pub id_range: Option<RangeInclusive<EntityId>>,
 }
 
 impl EditState {
@@ -32,6 +144,9 @@ impl EditState {
             id: entity.id().to_string(),
             id_validation_error: None,
             name_validation_error: None,
+            name_warning: None,
+            id_input: None,
+            id_range: None,
         }
     }
 
@@ -39,6 +154,7 @@ impl EditState {
         self.name = self.original_name.clone();
         self.id_validation_error = None;
         self.name_validation_error = None;
+        self.name_warning = None;
     }
 
     pub fn validate(&self, id_range: std::ops::RangeInclusive<i32>) -> Result<(), ValidationError> {
@@ -70,6 +186,86 @@ impl EditState {
     }
 }
 
+/// Checks an id typed for a new record: a whole number, inside the range and not
+/// used by any other record
+pub fn check_typed_id<T>(
+    entities: &BTreeMap<EntityId, T>,
+    kind: EntityKind,
+    id: EntityId,
+    typed: &str,
+    range: &RangeInclusive<EntityId>,
+) -> Result<EntityId, ValidationError> {
+    let new_id = data_types::parse_entity_id(typed).ok_or_else(|| {
+        ValidationError::InvalidId(format!("{} ID must be a whole number", kind))
+    })?;
+    data_types::check_id_range(kind, new_id, range)?;
+
+    if new_id != id && entities.contains_key(&new_id) {
+        return Err(ValidationError::DuplicateId(
+            format!("{} ID {} is already in use", kind, new_id)
+        ));
+    }
+
+    Ok(new_id)
+}
+
+/// Adds a record with only a name and id, for references created from the item
+/// editor. A blank id takes the lowest free one in the range.
+pub fn create_named<T: Entity>(
+    entities: &mut BTreeMap<EntityId, T>,
+    kind: EntityKind,
+    name: &str,
+    typed_id: &str,
+    range: &RangeInclusive<EntityId>,
+) -> Result<EntityId, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("{} name cannot be empty", kind));
+    }
+    if let Some(existing) = entities.values().find(|entity| entity.name().trim().eq_ignore_ascii_case(name)) {
+        return Err(format!("{} '{}' already exists ({})", kind, existing.name(), existing.id()));
+    }
+
+    let id = if typed_id.trim().is_empty() {
+        data_types::lowest_free_id(kind, entities, range)?
+    } else {
+        let id = data_types::parse_entity_id(typed_id)
+            .ok_or_else(|| format!("{} ID must be a whole number", kind))?;
+        data_types::check_id_range(kind, id, range).map_err(|e| e.to_string())?;
+        if entities.contains_key(&id) {
+            return Err(format!("{} ID {} is already in use", kind, id));
+        }
+        id
+    };
+
+    entities.insert(id, T::default_new().with_id(id).with_name(name.to_string()));
+    Ok(id)
+}
+
+/// Moves a new record onto the id typed in its edit state, returning the id it
+/// ends up under. Records without a typed id are left where they are
+pub fn apply_typed_id<T: Entity>(
+    entities: &mut BTreeMap<EntityId, T>,
+    kind: EntityKind,
+    id: EntityId,
+    edit_state: &mut EditState,
+    range: &RangeInclusive<EntityId>,
+) -> Result<EntityId, ValidationError> {
+    let Some(typed) = edit_state.id_input.as_deref() else {
+        return Ok(id);
+    };
+    let new_id = check_typed_id(entities, kind, id, typed, range)?;
+
+    if new_id != id {
+        if let Some(entity) = entities.remove(&id) {
+            entities.insert(new_id, entity.with_id(new_id));
+        }
+        edit_state.id = new_id.to_string();
+    }
+
+    Ok(new_id)
+}
+
 /// Generic function to render title row
 pub fn render_title_row<'a, Message>(
     title: &'a str, 
@@ -84,7 +280,7 @@ where
         iced::widget::horizontal_space(),
         button(icon::new().size(14))
             .on_press(create_message)
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
     ]
     .width(Length::Fixed(width))
     .padding(15)
@@ -109,6 +305,8 @@ where
 pub fn render_entity_list<'a, T, Message, F>(
     entities: &'a BTreeMap<EntityId, T>,
     edit_states: &'a Vec<EditState>,
+    search: &str,
+    sort: ListSort,
     row_renderer: F
 ) -> Element<'a, Message> 
 where
@@ -116,9 +314,14 @@ where
     Message: 'a,
     F: Fn(&'a T, &'a Vec<EditState>) -> Element<'a, Message> + 'a,
 {
+    let mut rows: Vec<&'a T> = entities.values()
+        .filter(|entity| matches_search(*entity, search, edit_states))
+        .collect();
+    sort.apply(&mut rows);
+
     scrollable(
         column(
-            entities.values()
+            rows.into_iter()
                 .map(|entity| 
                     row![
                         row_renderer(entity, edit_states)
@@ -133,12 +336,166 @@ where
     .into()
 }
 
+/// Whether an entity belongs in a list filtered by `search`, a case-insensitive
+/// match on its name. Rows open for editing always show, so a new blank entry
+/// isn't hidden by the filter.
+pub fn matches_search<'a, T: Entity>(
+    entity: &T,
+    search: &str,
+    edit_states: impl IntoIterator<Item = &'a EditState>,
+) -> bool {
+    let search = search.trim().to_lowercase();
+
+    search.is_empty()
+        || entity.name().to_lowercase().contains(&search)
+        || edit_states.into_iter().any(|state| data_types::parse_entity_id(&state.id) == Some(entity.id()))
+}
+
+/// Another entity in the collection with the same name, ignoring case and surrounding spaces
+pub fn duplicate_name<'a, T: Entity + 'a>(
+    entities: impl IntoIterator<Item = &'a T>,
+    id: EntityId,
+    name: &str,
+) -> Option<&'a T> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+
+    entities
+        .into_iter()
+        .find(|entity| entity.id() != id && entity.name().trim().to_lowercase() == name)
+}
+
+/// Soft check for a name another entity already uses. The error is shown as a
+/// warning under the name and never stops a save.
+pub fn check_duplicate_name<'a, T: Entity + 'a>(
+    entities: impl IntoIterator<Item = &'a T>,
+    id: EntityId,
+    name: &str,
+    label: &str,
+) -> Result<(), ValidationError> {
+    match duplicate_name(entities, id, name) {
+        Some(other) => Err(ValidationError::DuplicateName(format!("also used by {} {}", label, other.id()))),
+        None => Ok(()),
+    }
+}
+
+/// Amber text for warnings that don't block anything
+pub fn warning_text(_theme: &iced::Theme) -> text::Style {
+    text::Style {
+        color: Some(iced::Color::from_rgb8(0xd9, 0x77, 0x06)),
+    }
+}
+
+/// Entities that share a name with at least one other, grouped by name
+pub fn duplicate_name_groups<'a, T: Entity + 'a>(
+    entities: impl IntoIterator<Item = &'a T>,
+) -> Vec<Vec<&'a T>> {
+    let mut by_name: BTreeMap<String, Vec<&'a T>> = BTreeMap::new();
+    for entity in entities {
+        let name = entity.name().trim().to_lowercase();
+        if !name.is_empty() {
+            by_name.entry(name).or_default().push(entity);
+        }
+    }
+
+    by_name.into_values().filter(|group| group.len() > 1).collect()
+}
+
+/// Trash button for a list row. Needs a press-and-hold when hold-to-delete is turned on
+pub fn delete_button<'a, Message: Clone + 'a>(on_delete: Message) -> Element<'a, Message> {
+    hold_to_confirm::hold_to_confirm(
+        button(icon::trash().size(14))
+            .on_press(on_delete.clone())
+            .style(Modern::danger_button()),
+        on_delete,
+    )
+    .into()
+}
+
+/// Two records picked for merging. Items pointing at `from` are moved to `into`,
+/// then `from` is deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeSelection {
+    pub from: Option<EntityId>,
+    pub into: Option<EntityId>,
+}
+
+// An entry in a merge dropdown, with the id so duplicate names can be told apart
+#[derive(Debug, Clone, PartialEq)]
+struct MergeOption {
+    id: EntityId,
+    label: String,
+}
+
+impl std::fmt::Display for MergeOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// "Merge [record] into [record]" dropdowns with a button that asks for the merge
+pub fn merge_picker<'a, T, Message>(
+    entities: &'a BTreeMap<EntityId, T>,
+    selection: MergeSelection,
+    on_select: fn(MergeSelection) -> Message,
+    on_merge: fn(EntityId, EntityId) -> Message,
+) -> Element<'a, Message>
+where
+    T: Entity,
+    Message: Clone + 'a,
+{
+    let options: Vec<MergeOption> = entities
+        .values()
+        .map(|entity| MergeOption { id: entity.id(), label: format!("{} ({})", entity.name(), entity.id()) })
+        .collect();
+    let selected = |id: Option<EntityId>| id.and_then(|id| options.iter().find(|option| option.id == id).cloned());
+
+    let merge = match (selection.from, selection.into) {
+        (Some(from), Some(into)) if from != into => Some(on_merge(from, into)),
+        _ => None,
+    };
+
+    row![
+        text("Merge").size(13),
+        pick_list(options.clone(), selected(selection.from), move |option: MergeOption| {
+            on_select(MergeSelection { from: Some(option.id), ..selection })
+        })
+        .placeholder("Record to remove")
+        .text_size(13)
+        .width(200)
+        .style(Modern::pick_list()),
+        text("into").size(13),
+        pick_list(options.clone(), selected(selection.into), move |option: MergeOption| {
+            on_select(MergeSelection { into: Some(option.id), ..selection })
+        })
+        .placeholder("Record to keep")
+        .text_size(13)
+        .width(200)
+        .style(Modern::pick_list()),
+        button(text("Merge...").size(13))
+            .on_press_maybe(merge)
+            .style(Modern::warning_button()),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Id of a row's name input, so a newly created row can be focused
+pub fn name_input_id(id: EntityId) -> text_input::Id {
+    text_input::Id::new(format!("entity-name-{}", id))
+}
+
 /// Generic function for entity view layout
 pub fn entity_view<'a, T, Message, F>(
     title: &'a str,
     create_message: Message,
     entities: &'a BTreeMap<EntityId, T>,
     edit_states: &'a Vec<EditState>,
+    search: &str,
+    sort: ListSort,
     row_renderer: F,
 ) -> Element<'a, Message> 
 where
@@ -148,7 +505,7 @@ where
 {
     let title_row = render_title_row(title, create_message, 505.0);
     let header_row = render_header_row();
-    let entity_list = render_entity_list(entities, edit_states, row_renderer);
+    let entity_list = render_entity_list(entities, edit_states, search, sort, row_renderer);
 
     let all_content = column![title_row, header_row, entity_list];
 
@@ -167,9 +524,12 @@ pub fn entity_quick_edit_view<'a, T, Message>(
     edit_message: impl Fn(EntityId) -> Message + 'a,
     save_message: impl Fn(EntityId, EditState) -> Message + 'a,
     copy_message: impl Fn(EntityId) -> Message + 'a,
+    export_message: impl Fn(EntityId) -> Message + 'a,
+    copy_json_message: impl Fn(EntityId) -> Message + 'a,
     delete_message: impl Fn(EntityId) -> Message + 'a,
     cancel_message: impl Fn(EntityId) -> Message + 'a,
     update_name_message: impl Fn(EntityId, String) -> Message + 'a,
+    update_id_message: Option<fn(EntityId, String) -> Message>,
     input_placeholder: &'a str
 ) -> Element<'a, Message> 
 where
@@ -178,7 +538,7 @@ where
 {
     // Find edit state for this entity if it exists
     let edit_state = edit_states.iter()
-        .find(|state| state.id.parse::<i32>().unwrap_or(-999) == entity.id());
+        .find(|state| data_types::parse_entity_id(&state.id) == Some(entity.id()));
 
     let editing = edit_state.is_some();
 
@@ -193,17 +553,41 @@ where
     let name_validation_error = edit_state
         .and_then(|state| state.name_validation_error.as_ref());
 
+    let name_warning = edit_state
+        .and_then(|state| state.name_warning.as_ref());
+
+    // Only a new record's id can be typed over, and only where the screen supports it
+    let typed_id = edit_state
+        .and_then(|state| state.id_input.as_ref())
+        .filter(|_| update_id_message.is_some());
+
     let id_input: Element<'_, Message> = {
-        let input: TextInput<'_, Message> = text_input("ID", &entity.id().to_string())
+        let id_value = typed_id.cloned().unwrap_or_else(|| entity.id().to_string());
+        let input: TextInput<'_, Message> = text_input("ID", &id_value)
+            .on_input_maybe(
+                typed_id
+                    .and(update_id_message)
+                    .map(|update_id| move |id: String| update_id(entity.id(), id))
+            )
             .style(Modern::validated_text_input(id_validation_error.is_some()))
             .width(Length::Fixed(75.0));
 
+        let id_hint = edit_state
+            .and_then(|state| state.id_range.as_ref())
+            .map(|range| data_types::id_hint(entity.id(), range));
+
         if let Some(error) = id_validation_error.as_ref() {
             tooltip(
                 input,
                 container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
                 tooltip::Position::Top,
             ).into()
+        } else if let Some(hint) = id_hint {
+            column![
+                input,
+                text(hint).size(11).style(Modern::secondary_text()),
+            ]
+            .into()
         } else {
             input.into()
         }
@@ -211,6 +595,7 @@ where
 
     let name_input: Element<'_, Message> = {
         let input: TextInput<'_, Message> = text_input(input_placeholder, &display_name)
+            .id(name_input_id(entity.id()))
             .on_input_maybe(
                 if editing {
                     Some(move |name| update_name_message(entity.id(), name))
@@ -227,6 +612,19 @@ where
                 container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
                 tooltip::Position::Top,
             ).into()
+        } else if let Some(warning) = name_warning {
+            column![
+                input,
+                text(warning).size(11).style(warning_text),
+            ]
+            .into()
+        } else if !editing {
+            // Hovering a saved row shows when it was created and last changed
+            tooltip(
+                input,
+                container(text(entity.timestamps().summary()).size(12)).padding(10).style(Modern::card_container()),
+                tooltip::Position::Top,
+            ).into()
         } else {
             input.into()
         }
@@ -241,22 +639,37 @@ where
                         edit_message(entity.id()) 
                     }
                 )
-                .style(Modern::primary_button()),
+                .style(accent::primary_button()),
             iced::widget::horizontal_space().width(2),
             button(icon::copy().size(14))
                 .on_press(copy_message(entity.id()))
-                .style(Modern::primary_button()),
+                .style(accent::primary_button()),
             iced::widget::horizontal_space().width(2),
-            button(if editing { icon::cancel().size(14) } else { icon::trash().size(14) })
-                .on_press(
-                    if editing { 
-                        cancel_message(entity.id()) 
-                    } else { 
-                        delete_message(entity.id()) 
-                    }
-                )
-                .style(Modern::danger_button()),
-        ].width(150);
+            tooltip(
+                button(text("{ }").size(14))
+                    .on_press(export_message(entity.id()))
+                    .style(Modern::system_button()),
+                container("Export this record...").padding(10).style(Modern::card_container()),
+                tooltip::Position::Top,
+            ),
+            iced::widget::horizontal_space().width(2),
+            tooltip(
+                button(text("JSON").size(11))
+                    .on_press(copy_json_message(entity.id()))
+                    .style(Modern::system_button()),
+                container("Copy as JSON").padding(10).style(Modern::card_container()),
+                tooltip::Position::Top,
+            ),
+            iced::widget::horizontal_space().width(2),
+            if editing {
+                button(icon::cancel().size(14))
+                    .on_press(cancel_message(entity.id()))
+                    .style(Modern::danger_button())
+                    .into()
+            } else {
+                delete_button(delete_message(entity.id()))
+            },
+        ].width(195);
 
 
         row![
@@ -267,6 +680,265 @@ where
             action_row,
         ]
         .align_y(iced::Alignment::Center)
-        .width(Length::Fixed(495.0))
+        .width(Length::Fixed(540.0))
         .into()
-}
\ No newline at end of file
+}
+/// Filter text and highlighted entry for a two column assignment list
+#[derive(Default, Debug, Clone)]
+pub struct DualListState {
+    pub filter: String,
+    pub highlighted: Option<EntityId>,
+}
+
+#[derive(Debug, Clone)]
+pub enum DualListMessage {
+    Filter(String),
+    Highlight(EntityId),
+    Assign(Vec<EntityId>),
+    Unassign(EntityId),
+}
+
+impl DualListState {
+    // Unassigned entities whose name contains the filter, keeping the given order
+    pub fn filtered<'a, T: Entity + 'a>(
+        &self,
+        entities: impl IntoIterator<Item = &'a T>,
+        assigned: &[EntityId],
+    ) -> Vec<&'a T> {
+        let filter = self.filter.trim().to_lowercase();
+
+        entities
+            .into_iter()
+            .filter(|entity| !assigned.contains(&entity.id()))
+            .filter(|entity| filter.is_empty() || entity.name().to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    // What Enter or Space acts on: the highlighted entry on either side, else the first match
+    pub fn keyboard_move(&self, filtered: &[EntityId], assigned: &[EntityId]) -> Option<DualListMessage> {
+        match self.highlighted {
+            Some(id) if assigned.contains(&id) => Some(DualListMessage::Unassign(id)),
+            Some(id) if filtered.contains(&id) => Some(DualListMessage::Assign(vec![id])),
+            _ => filtered.first().map(|id| DualListMessage::Assign(vec![*id])),
+        }
+    }
+
+    // Only the list state changes here, the caller applies Assign and Unassign
+    // to whatever it stores so the assigned order stays under its control
+    pub fn update(&mut self, message: &DualListMessage) {
+        match message {
+            DualListMessage::Filter(filter) => {
+                self.filter = filter.clone();
+                self.highlighted = None;
+            }
+            DualListMessage::Highlight(id) => {
+                self.highlighted = if self.highlighted == Some(*id) { None } else { Some(*id) };
+            }
+            DualListMessage::Assign(_) | DualListMessage::Unassign(_) => {
+                self.highlighted = None;
+            }
+        }
+    }
+}
+
+/// Available entities on the left, assigned on the right in their assigned order
+pub fn dual_list<'a, T, Message>(
+    title: &'a str,
+    state: &'a DualListState,
+    entities: &'a BTreeMap<EntityId, T>,
+    assigned: Vec<EntityId>,
+    on_message: impl Fn(DualListMessage) -> Message + Clone + 'a,
+) -> Element<'a, Message>
+where
+    T: Entity + 'a,
+    Message: Clone + 'a,
+{
+    dual_list_where(title, state, entities, assigned, |_| true, on_message)
+}
+
+/// dual_list that only offers the entities passing `eligible` on the available side
+pub fn dual_list_where<'a, T, Message>(
+    title: &'a str,
+    state: &'a DualListState,
+    entities: &'a BTreeMap<EntityId, T>,
+    assigned: Vec<EntityId>,
+    eligible: impl Fn(&T) -> bool,
+    on_message: impl Fn(DualListMessage) -> Message + Clone + 'a,
+) -> Element<'a, Message>
+where
+    T: Entity + 'a,
+    Message: Clone + 'a,
+{
+    let filtered = state.filtered(entities.values().filter(|entity| eligible(entity)), &assigned);
+    let filtered_ids: Vec<EntityId> = filtered.iter().map(|entity| entity.id()).collect();
+    let keyboard_move = state.keyboard_move(&filtered_ids, &assigned);
+
+    // A space typed into an empty filter moves the entry instead of filtering on it
+    let filter_empty = state.filter.is_empty();
+    let filter_input = text_input("Type to filter...", &state.filter)
+        .on_input({
+            let on_message = on_message.clone();
+            let keyboard_move = keyboard_move.clone();
+            move |value| match &keyboard_move {
+                Some(next) if filter_empty && value == " " => on_message(next.clone()),
+                _ => on_message(DualListMessage::Filter(value)),
+            }
+        })
+        .on_submit_maybe(keyboard_move.clone().map(&on_message))
+        .style(Modern::search_input())
+        .width(Length::Fixed(410.0));
+
+    let entry = |entity: &'a T| -> Element<'a, Message> {
+        button(text(entity.name()).size(13))
+            .on_press(on_message(DualListMessage::Highlight(entity.id())))
+            .style(Modern::conditional_button_style(
+                state.highlighted == Some(entity.id()),
+                accent::selected_button(),
+                Modern::system_button()
+            ))
+            .width(Length::Fill)
+            .into()
+    };
+
+    let available_column = scrollable(
+        column(filtered.into_iter().map(entry).collect::<Vec<_>>()).spacing(2)
+    )
+    .height(Length::Fixed(150.0))
+    .width(Length::Fixed(180.0));
+
+    let assigned_column = scrollable(
+        column(
+            assigned
+                .iter()
+                .filter_map(|id| entities.get(id))
+                .map(entry)
+                .collect::<Vec<_>>()
+        )
+        .spacing(2)
+    )
+    .height(Length::Fixed(150.0))
+    .width(Length::Fixed(180.0));
+
+    let highlighted_available = state.highlighted.filter(|id| filtered_ids.contains(id));
+    let highlighted_assigned = state.highlighted.filter(|id| assigned.contains(id));
+
+    let move_buttons = column![
+        button(text(">").size(13))
+            .on_press_maybe(highlighted_available.map(|id| on_message(DualListMessage::Assign(vec![id]))))
+            .style(accent::primary_button())
+            .width(Length::Fixed(40.0)),
+        button(text(">>").size(13))
+            .on_press_maybe(
+                (!filtered_ids.is_empty()).then(|| on_message(DualListMessage::Assign(filtered_ids.clone())))
+            )
+            .style(accent::primary_button())
+            .width(Length::Fixed(40.0)),
+        button(text("<").size(13))
+            .on_press_maybe(highlighted_assigned.map(|id| on_message(DualListMessage::Unassign(id))))
+            .style(Modern::danger_button())
+            .width(Length::Fixed(40.0)),
+    ]
+    .spacing(5);
+
+    column![
+        text(title).style(Modern::primary_text()),
+        filter_input,
+        row![available_column, move_buttons, assigned_column]
+            .spacing(5)
+            .align_y(iced::Alignment::Center),
+        text("Enter or Space moves the highlighted entry, Ctrl+A moves everything filtered")
+            .size(11)
+            .style(Modern::secondary_text()),
+    ]
+    .spacing(5)
+    .into()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::choice_groups::ChoiceGroup;
+
+    fn groups() -> Vec<ChoiceGroup> {
+        ["Sides", "Sauces", "Drinks"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| ChoiceGroup::default_new().with_id(i as EntityId + 1).with_name(name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn filter_hides_assigned_entries_and_ignores_case() {
+        let groups = groups();
+        let state = DualListState { filter: " s".to_string(), highlighted: None };
+        let ids = |entries: Vec<&ChoiceGroup>| entries.iter().map(|group| group.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(state.filtered(&groups, &[])), vec![1, 2, 3]);
+        assert_eq!(ids(DualListState { filter: "SA".to_string(), ..state.clone() }.filtered(&groups, &[])), vec![2]);
+        assert_eq!(ids(DualListState::default().filtered(&groups, &[1, 3])), vec![2]);
+    }
+
+    #[test]
+    fn keyboard_move_prefers_the_highlighted_entry() {
+        let highlighted = |id| DualListState { highlighted: Some(id), ..DualListState::default() };
+
+        assert!(matches!(highlighted(5).keyboard_move(&[4, 6], &[5]), Some(DualListMessage::Unassign(5))));
+        assert!(matches!(highlighted(6).keyboard_move(&[4, 6], &[5]), Some(DualListMessage::Assign(ids)) if ids == vec![6]));
+        assert!(matches!(DualListState::default().keyboard_move(&[4, 6], &[5]), Some(DualListMessage::Assign(ids)) if ids == vec![4]));
+        assert!(DualListState::default().keyboard_move(&[], &[5]).is_none());
+    }
+
+    #[test]
+    fn filtering_or_moving_clears_the_highlight() {
+        let mut state = DualListState::default();
+
+        state.update(&DualListMessage::Highlight(2));
+        assert_eq!(state.highlighted, Some(2));
+        state.update(&DualListMessage::Highlight(2));
+        assert_eq!(state.highlighted, None);
+
+        state.update(&DualListMessage::Highlight(3));
+        state.update(&DualListMessage::Filter("dr".to_string()));
+        assert_eq!((state.filter.as_str(), state.highlighted), ("dr", None));
+
+        state.update(&DualListMessage::Highlight(3));
+        state.update(&DualListMessage::Assign(vec![3]));
+        assert_eq!(state.highlighted, None);
+    }
+
+    #[test]
+    fn rows_being_edited_show_through_the_search() {
+        let groups = groups();
+        let editing_sauces = EditState::new(&groups[1]);
+        let unreadable = EditState { id: "not a number".to_string(), ..EditState::new(&groups[2]) };
+        let none: [&EditState; 0] = [];
+
+        assert!(matches_search(&groups[0], "", none));
+        assert!(matches_search(&groups[0], " SID", none));
+        assert!(!matches_search(&groups[1], "sid", none));
+        assert!(matches_search(&groups[1], "sid", [&editing_sauces]));
+        assert!(!matches_search(&groups[2], "sid", [&unreadable]));
+    }
+
+    #[test]
+    fn moving_steps_over_rows_hidden_by_the_search() {
+        let mut groups: BTreeMap<EntityId, ChoiceGroup> = groups()
+            .into_iter()
+            .zip(0u32..)
+            .map(|(group, order)| (group.id, ChoiceGroup { sort_order: Some(order), ..group }))
+            .collect();
+        let listed = |groups: &BTreeMap<EntityId, ChoiceGroup>| {
+            let mut ordered: Vec<&ChoiceGroup> = groups.values().collect();
+            display_order(&mut ordered);
+            ordered.iter().map(|group| group.id).collect::<Vec<_>>()
+        };
+
+        // Sides and Drinks are shown, Sauces between them is filtered out
+        assert!(move_entity(&mut groups, 3, true, &[1, 3]));
+        assert_eq!(listed(&groups), vec![3, 2, 1]);
+
+        assert!(!move_entity(&mut groups, 3, true, &[3, 1]));
+        assert!(!move_entity(&mut groups, 2, true, &[3, 1]));
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use crate::data_types::{EntityId, Timestamps};
+use crate::{
+    item_groups::ItemGroup,
+    price_levels::PriceLevel,
+    product_classes::ProductClass,
+    tax_groups::TaxGroup,
+    security_levels::SecurityLevel,
+    revenue_categories::RevenueCategory,
+    report_categories::ReportCategory,
+    choice_groups::ChoiceGroup,
+    printer_logicals::PrinterLogical,
+    stores::Store,
+};
+
+// CSV export for the lists other than items. Each type names its columns
+// after its struct fields, so a spreadsheet lines up with the saved data.
+
+pub trait CsvExport {
+    const HEADERS: &'static [&'static str];
+
+    fn csv_fields(&self) -> Vec<String>;
+}
+
+// Every type ends with these, empty for records saved before they were kept
+fn timestamp_fields(timestamps: &Timestamps) -> [String; 2] {
+    [Timestamps::format(timestamps.created_at), Timestamps::format(timestamps.modified_at)]
+}
+
+// Id and name only
+macro_rules! name_only_csv {
+    ($($entity:ty),*) => {
+        $(
+            impl CsvExport for $entity {
+                const HEADERS: &'static [&'static str] = &["id", "name", "created_at", "modified_at"];
+
+                fn csv_fields(&self) -> Vec<String> {
+                    let mut fields = vec![self.id.to_string(), self.name.clone()];
+                    fields.extend(timestamp_fields(&self.timestamps));
+                    fields
+                }
+            }
+        )*
+    };
+}
+
+name_only_csv!(ProductClass, SecurityLevel, RevenueCategory, ReportCategory, ChoiceGroup, PrinterLogical, Store);
+
+impl CsvExport for ItemGroup {
+    const HEADERS: &'static [&'static str] = &["id", "name", "id_range_start", "id_range_end", "created_at", "modified_at"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        let mut fields = vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.id_range.start.to_string(),
+            self.id_range.end.to_string(),
+        ];
+        fields.extend(timestamp_fields(&self.timestamps));
+        fields
+    }
+}
+
+impl CsvExport for PriceLevel {
+    const HEADERS: &'static [&'static str] = &["id", "name", "price", "level_type", "store_id", "created_at", "modified_at"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        let mut fields = vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.price.to_string(),
+            self.level_type.to_string(),
+            self.store_id.map(|id| id.to_string()).unwrap_or_default(),
+        ];
+        fields.extend(timestamp_fields(&self.timestamps));
+        fields
+    }
+}
+
+impl CsvExport for TaxGroup {
+    const HEADERS: &'static [&'static str] = &["id", "name", "rate", "created_at", "modified_at"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        let mut fields = vec![self.id.to_string(), self.name.clone(), self.rate.to_string()];
+        fields.extend(timestamp_fields(&self.timestamps));
+        fields
+    }
+}
+
+pub fn to_csv<T: CsvExport>(entities: &BTreeMap<EntityId, T>) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer.write_record(T::HEADERS)
+        .map_err(|e| format!("Failed to write CSV header: {}", e))?;
+    for entity in entities.values() {
+        writer.write_record(entity.csv_fields())
+            .map_err(|e| format!("Failed to write CSV row: {}", e))?;
+    }
+
+    let bytes = writer.into_inner()
+        .map_err(|e| format!("Failed to finish CSV: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("CSV was not valid UTF-8: {}", e))
+}
+
+// e.g. "Tax Groups" -> "Tax_Groups_Export.csv"
+pub fn default_file_name(label: &str) -> String {
+    format!("{}_Export.csv", label.replace(' ', "_"))
+}
+
+pub async fn save_csv(label: String, contents: String) -> Result<PathBuf, String> {
+    let path = rfd::AsyncFileDialog::new()
+        .add_filter("CSV Files", &["csv"])
+        .set_title(format!("Export {}", label))
+        .set_file_name(default_file_name(&label))
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or_else(|| "Export cancelled".to_string())?;
+
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(path)
+}
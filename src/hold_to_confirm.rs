@@ -0,0 +1,185 @@
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{self, tree, Tree, Widget};
+use iced::advanced::{Clipboard, Shell};
+use iced::event::{self, Event};
+use iced::{mouse, window};
+use iced::{Background, Border, Color, Element, Length, Rectangle, Shadow, Size};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// Wrapper for destructive buttons. With hold-to-delete turned on in settings the
+// wrapped button has to be held down for HOLD_DURATION, with a fill showing the
+// progress, before the message is sent; letting go or leaving the button cancels.
+// With it off every event goes straight to the wrapped button.
+
+pub const HOLD_DURATION: Duration = Duration::from_millis(800);
+
+// Mirrors AppSettings.hold_to_delete so views don't each need the setting passed in
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn hold_to_confirm<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+    on_confirm: Message,
+) -> HoldToConfirm<'a, Message> {
+    HoldToConfirm {
+        content: content.into(),
+        on_confirm,
+    }
+}
+
+pub struct HoldToConfirm<'a, Message> {
+    content: Element<'a, Message>,
+    on_confirm: Message,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    pressed_at: Option<Instant>,
+}
+
+impl<'a, Message: Clone> Widget<Message, iced::Theme, iced::Renderer> for HoldToConfirm<'a, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(&self, tree: &mut Tree, renderer: &iced::Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        operation: &mut dyn widget::Operation,
+    ) {
+        self.content.as_widget().operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &iced::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if !is_enabled() {
+            tree.state.downcast_mut::<State>().pressed_at = None;
+            return self.content.as_widget_mut().on_event(
+                &mut tree.children[0], event, layout, cursor, renderer, clipboard, shell, viewport,
+            );
+        }
+
+        let over = cursor.is_over(layout.bounds());
+        let state = tree.state.downcast_mut::<State>();
+
+        match &event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) if over => {
+                // Kept from the wrapped button so only a completed hold fires
+                state.pressed_at = Some(Instant::now());
+                shell.request_redraw(window::RedrawRequest::NextFrame);
+                return event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) if state.pressed_at.is_some() => {
+                state.pressed_at = None;
+                return event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if !over => {
+                state.pressed_at = None;
+            }
+            Event::Window(window::Event::RedrawRequested(now)) => {
+                if let Some(pressed_at) = state.pressed_at {
+                    if now.duration_since(pressed_at) >= HOLD_DURATION {
+                        state.pressed_at = None;
+                        shell.publish(self.on_confirm.clone());
+                    } else {
+                        shell.request_redraw(window::RedrawRequest::NextFrame);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // Hover and redraws still reach the button so it looks the same as usual
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0], event, layout, cursor, renderer, clipboard, shell, viewport,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(&tree.children[0], renderer, theme, style, layout, cursor, viewport);
+
+        if let Some(pressed_at) = tree.state.downcast_ref::<State>().pressed_at {
+            let progress = (pressed_at.elapsed().as_secs_f32() / HOLD_DURATION.as_secs_f32()).min(1.0);
+            let bounds = layout.bounds();
+
+            <iced::Renderer as renderer::Renderer>::fill_quad(
+                renderer,
+                renderer::Quad {
+                    bounds: Rectangle {
+                        width: bounds.width * progress,
+                        ..bounds
+                    },
+                    border: Border::default(),
+                    shadow: Shadow::default(),
+                },
+                Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.35)),
+            );
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(&tree.children[0], layout, cursor, viewport, renderer)
+    }
+}
+
+impl<'a, Message: Clone + 'a> From<HoldToConfirm<'a, Message>> for Element<'a, Message> {
+    fn from(hold: HoldToConfirm<'a, Message>) -> Self {
+        Element::new(hold)
+    }
+}
@@ -1,7 +1,10 @@
-use crate::data_types::{EntityId, ValidationError};
+use crate::accent;
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState as BaseEditState};
+use crate::entity_component::{self, Entity, EditState as BaseEditState, ListSort};
 use crate::icon;
+use crate::items::Item;
 use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
 use iced::{Element, Length};
@@ -20,6 +23,8 @@ pub enum Message {
     UpdateIdRangeEnd(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    // Put every item in the group whose id range holds its id
+    AutoAssign,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +38,7 @@ pub enum Operation {
     UpdateIdRangeEnd(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    AutoAssign,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -91,6 +97,8 @@ pub struct ItemGroup {
     pub id: EntityId,
     pub name: String,
     pub id_range: Range<EntityId>,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for ItemGroup {
@@ -104,7 +112,8 @@ impl Default for ItemGroup {
         Self {
             id: -1,
             name: String::new(),
-            id_range: Range { start: 1, end: 1000 }
+            id_range: Range { start: 1, end: 1000 },
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -131,7 +140,14 @@ impl Entity for ItemGroup {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
     }
 }
 
@@ -140,12 +156,8 @@ impl ItemGroup {
         Self::default()
     }
 
-    pub fn validate(&self, other_groups: &[&ItemGroup]) -> Result<(), ValidationError> {
-        if !(1..=999).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Item group ID must be between 1 and 999".to_string()
-            ));
-        }
+    pub fn validate(&self, other_groups: &[&ItemGroup], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::ItemGroup, self.id, id_range)?;
  
         // Check for duplicate IDs
         for other in other_groups {
@@ -156,6 +168,12 @@ impl ItemGroup {
             }
         }
  
+        if self.id_range.start >= self.id_range.end {
+            return Err(ValidationError::InvalidValue(
+                "Range start must be less than range end".to_string()
+            ));
+        }
+
         // Check for overlapping ranges
         for other in other_groups {
             if self.id == other.id {
@@ -179,6 +197,41 @@ impl ItemGroup {
     }
 }
 
+// Item group changes worked out from the group id ranges
+#[derive(Debug, Clone, Default)]
+pub struct RangeAssignment {
+    // (item id, group id) for items not already in their range's group
+    pub changes: Vec<(EntityId, EntityId)>,
+    // Items whose id falls in more than one range, left as they are. Saving
+    // refuses overlapping ranges, but older data files can still hold them
+    pub conflicts: Vec<(EntityId, Vec<EntityId>)>,
+}
+
+// Items outside every range keep their group
+pub fn assign_by_range(items: &BTreeMap<EntityId, Item>, groups: &BTreeMap<EntityId, ItemGroup>) -> RangeAssignment {
+    let mut assignment = RangeAssignment::default();
+
+    for item in items.values() {
+        let matches: Vec<EntityId> = groups
+            .values()
+            .filter(|group| (group.id_range.start..=group.id_range.end).contains(&item.id))
+            .map(|group| group.id)
+            .collect();
+
+        match matches.as_slice() {
+            [] => {}
+            [group_id] => {
+                if item.item_group != Some(*group_id) {
+                    assignment.changes.push((item.id, *group_id));
+                }
+            }
+            _ => assignment.conflicts.push((item.id, matches)),
+        }
+    }
+
+    assignment
+}
+
 fn ranges_overlap<T: Ord>(range1: &std::ops::RangeInclusive<T>, range2: &std::ops::RangeInclusive<T>) -> bool {
     range1.start() <= range2.end() && range2.start() <= range1.end()
 }
@@ -214,12 +267,17 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::AutoAssign => {
+            Action::operation(Operation::AutoAssign)
+        }
     }
 }
 
 pub fn view<'a>(
     all_groups: &'a BTreeMap<EntityId, ItemGroup>,
     edit_states: &'a Vec<ItemGroupEditState>,
+    search: &str,
+    sort: ListSort,
 ) -> Element<'a, Message> {
     let title_row = entity_component::render_title_row(
         "Item Groups", 
@@ -237,11 +295,17 @@ pub fn view<'a>(
     ]
     .padding(15);
 
+    let mut groups: Vec<&ItemGroup> = all_groups
+        .values()
+        .filter(|group| entity_component::matches_search(*group, search, edit_states.iter().map(|state| &state.base)))
+        .collect();
+    sort.apply(&mut groups);
+
     // List of item groups
     let groups_list = scrollable(
         column(
-            all_groups
-                .values()
+            groups
+                .into_iter()
                 .map(|group| 
                     row![
                         render_item_group_row(group, edit_states)
@@ -253,8 +317,21 @@ pub fn view<'a>(
         )
     ).height(Length::Fill);
 
+    let auto_assign_row = row![
+        tooltip(
+            button("Assign items by id range")
+                .on_press(Message::AutoAssign)
+                .style(Modern::system_button()),
+            container("Sets each item's group to the one whose range holds its id, with a confirmation first")
+                .padding(10)
+                .style(Modern::card_container()),
+            tooltip::Position::Bottom,
+        ),
+    ]
+    .padding([0, 15]);
+
     // Combine all elements
-    let all_content = column![title_row, header_row, groups_list];
+    let all_content = column![title_row, auto_assign_row, header_row, groups_list];
 
     column![
         container(all_content)
@@ -270,7 +347,7 @@ fn render_item_group_row<'a>(
 ) -> Element<'a, Message> {
     // Find edit state for this item_group if it exists
     let edit_state = edit_states.iter()
-        .find(|state| state.base.id.parse::<i32>().unwrap_or(-999) == item_group.id);
+        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(item_group.id));
 
     let editing = edit_state.is_some();
 
@@ -294,6 +371,9 @@ fn render_item_group_row<'a>(
     let name_validation_error = edit_state
         .and_then(|state| state.base.name_validation_error.as_ref());
 
+    let name_warning = edit_state
+        .and_then(|state| state.base.name_warning.as_ref());
+
     let range_validation_error = edit_state
         .and_then(|state| state.range_validation_error.as_ref());
 
@@ -317,6 +397,7 @@ fn render_item_group_row<'a>(
     // Name input with validation
     let name_input: Element<'_, Message> = {
         let input = text_input("Item Group Name", &display_name)
+            .id(entity_component::name_input_id(item_group.id))
             .on_input_maybe(
                 if editing {
                     Some(|name| Message::UpdateName(item_group.id, name))
@@ -333,6 +414,12 @@ fn render_item_group_row<'a>(
                 container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
                 tooltip::Position::Top,
             ).into()
+        } else if let Some(warning) = name_warning {
+            column![
+                input,
+                text(warning).size(11).style(entity_component::warning_text),
+            ]
+            .into()
         } else {
             input.into()
         }
@@ -396,21 +483,20 @@ fn render_item_group_row<'a>(
                     Message::EditItemGroup(item_group.id) 
                 }
             )
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
         iced::widget::horizontal_space().width(2),
         button(icon::copy().size(14))
             .on_press(Message::CopyItemGroup(item_group.id))
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
         iced::widget::horizontal_space().width(2),
-        button(if editing { icon::cancel().size(14) } else { icon::trash().size(14) })
-            .on_press(
-                if editing { 
-                    Message::CancelEdit(item_group.id) 
-                } else { 
-                    Message::RequestDelete(item_group.id) 
-                }
-            )
-            .style(Modern::danger_button()),
+        if editing {
+            button(icon::cancel().size(14))
+                .on_press(Message::CancelEdit(item_group.id))
+                .style(Modern::danger_button())
+                .into()
+        } else {
+            entity_component::delete_button(Message::RequestDelete(item_group.id))
+        },
     ].width(150);
 
 
@@ -2,17 +2,24 @@ pub mod edit;
 pub mod view;
 pub mod import_items;
 pub mod export_items;
+pub mod online_menu;
+pub mod round_trip;
 //pub mod superedit;
 
+use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use crate::data_types::{
-    self, EntityId, ValidationError, ItemPrice
+    self, EntityId, ValidationError, ItemPrice, Timestamps
 };
 use crate::Action;
+use crate::accent;
+use crate::entity_component::{self, DualListMessage, DualListState};
 use iced_modern_theme::Modern;
 use iced::{Alignment, Element, Length};
 use serde::{Serialize, Deserialize};
-use iced::widget::{button, combo_box, container, column, row, text, scrollable};
+use iced::widget::{button, combo_box, container, column, pick_list, row, text, text_editor, scrollable};
 use rust_decimal::Decimal;
 use crate::{
     tax_groups::TaxGroup,
@@ -24,7 +31,9 @@ use crate::{
     choice_groups::ChoiceGroup,
     printer_logicals::PrinterLogical,
     price_levels::PriceLevel,
+    stores::Store,
     icon,
+    validation,
 };
 
 
@@ -37,9 +46,24 @@ pub enum Message {
     SearchItems(String),
     RequestDelete(EntityId),
     CopyItem(EntityId),
+    MoveUp(EntityId),
+    MoveDown(EntityId),
     HideModal,
     ShowModal,
     LaunchMassItemEditWindow,
+    OpenEditorWindow(EntityId),
+    FilterCourse(Option<u8>),
+    FilterPriceLevel(Option<EntityId>, bool),
+    FilterMissingPrice(bool),
+    FilterAttribute(AttributeField, Option<EntityId>),
+    ShowAttributeFilters(bool),
+    ClearFilters,
+    SelectBulkCourse(Option<u8>),
+    ApplyBulkCourse(Vec<EntityId>, Option<u8>),
+    SelectBulkAssignField(AttributeField),
+    SelectBulkAssignValue(Option<EntityId>),
+    ApplyBulkAssign(Vec<EntityId>, AttributeField, EntityId),
+    OpenRecent(validation::Target),
 }
 
 #[derive(Debug, Clone)]
@@ -53,10 +77,319 @@ pub enum Operation {
     UpdateSearchQuery(String),
     RequestDelete(EntityId),
     CopyItem(EntityId),
+    MoveUp(EntityId),
+    MoveDown(EntityId),
     HideModal,
     ShowModal,
     UpdatePrice(EntityId, EntityId, String),
     LaunchMassItemEditWindow,
+    OpenEditorWindow(EntityId),
+    SaveAndAddAnother(Item),
+    UpdateCarryOver(CarryOver),
+    FilterCourse(Option<u8>),
+    FilterPriceLevel(Option<EntityId>, bool),
+    FilterMissingPrice(bool),
+    FilterAttribute(AttributeField, Option<EntityId>),
+    ShowAttributeFilters(bool),
+    ClearFilters,
+    SelectBulkCourse(Option<u8>),
+    ApplyCourse(Vec<EntityId>, Option<u8>),
+    SelectBulkAssignField(AttributeField),
+    SelectBulkAssignValue(Option<EntityId>),
+    ApplyAttribute(Vec<EntityId>, AttributeField, EntityId),
+    // Name and typed id of a reference created from the editor
+    CreateReference(AttributeField, String, String),
+    OpenRecent(validation::Target),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
+    // In the item export layout, one item or every item when None
+    ExportToCsv(Option<EntityId>),
+}
+
+// Filters on the items list, kept across selections
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListFilters {
+    pub course: Option<u8>,
+    pub price_level: Option<EntityId>,
+    // With a price level chosen, show items assigned to it without a price instead of priced ones
+    pub price_level_unpriced: bool,
+    // Only items that can't be rung up yet, see Item::is_missing_price
+    pub missing_price: bool,
+    // Reference the item must have for each chosen field, all of them must match
+    pub attributes: BTreeMap<AttributeField, EntityId>,
+    pub show_attributes: bool,
+    // Course the bulk action will assign to every filtered item
+    pub bulk_course: Option<u8>,
+    // Reference the bulk action will set, or add for printers, on every filtered item
+    pub bulk_assign_field: Option<AttributeField>,
+    pub bulk_assign_value: Option<EntityId>,
+}
+
+impl ListFilters {
+    pub fn matches(&self, item: &Item) -> bool {
+        self.course.is_none_or(|course| item.course == Some(course))
+            && self.price_level.is_none_or(|level| {
+                if self.price_level_unpriced {
+                    item.is_assigned_to(level) && !item.has_price_at(level)
+                } else {
+                    item.has_price_at(level)
+                }
+            })
+            && self.attributes.iter().all(|(field, id)| field.matches(item, *id))
+            && (!self.missing_price || item.is_missing_price())
+    }
+
+    // Drops every filter, the bulk actions and whether the panel is open are kept
+    pub fn clear(&mut self) {
+        *self = Self {
+            show_attributes: self.show_attributes,
+            bulk_course: self.bulk_course,
+            bulk_assign_field: self.bulk_assign_field,
+            bulk_assign_value: self.bulk_assign_value,
+            ..Self::default()
+        };
+    }
+}
+
+// Reference fields the items list can be filtered on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AttributeField {
+    ItemGroup,
+    ProductClass,
+    RevenueCategory,
+    TaxGroup,
+    SecurityLevel,
+    ReportCategory,
+    ChoiceGroup,
+    PrinterLogical,
+}
+
+impl AttributeField {
+    pub const ALL: &'static [Self] = &[
+        Self::ItemGroup,
+        Self::ProductClass,
+        Self::RevenueCategory,
+        Self::TaxGroup,
+        Self::SecurityLevel,
+        Self::ReportCategory,
+        Self::ChoiceGroup,
+        Self::PrinterLogical,
+    ];
+
+    // Fields the bulk assign action offers, the ones that change with tax or kitchen setup
+    pub const BULK_ASSIGNABLE: &'static [Self] = &[
+        Self::TaxGroup,
+        Self::ReportCategory,
+        Self::PrinterLogical,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ItemGroup => "Item group",
+            Self::ProductClass => "Product class",
+            Self::RevenueCategory => "Revenue cat.",
+            Self::TaxGroup => "Tax group",
+            Self::SecurityLevel => "Security",
+            Self::ReportCategory => "Report cat.",
+            Self::ChoiceGroup => "Choice group",
+            Self::PrinterLogical => "Printer",
+        }
+    }
+
+    pub fn kind(&self) -> data_types::EntityKind {
+        match self {
+            Self::ItemGroup => data_types::EntityKind::ItemGroup,
+            Self::ProductClass => data_types::EntityKind::ProductClass,
+            Self::RevenueCategory => data_types::EntityKind::RevenueCategory,
+            Self::TaxGroup => data_types::EntityKind::TaxGroup,
+            Self::SecurityLevel => data_types::EntityKind::SecurityLevel,
+            Self::ReportCategory => data_types::EntityKind::ReportCategory,
+            Self::ChoiceGroup => data_types::EntityKind::ChoiceGroup,
+            Self::PrinterLogical => data_types::EntityKind::PrinterLogical,
+        }
+    }
+
+    // The editor message that picks this record, so a created reference goes
+    // through the same handler as choosing it from the dropdown
+    pub fn select_message(&self, id: EntityId) -> edit::Message {
+        match self {
+            Self::ItemGroup => edit::Message::SelectItemGroup(Some(id)),
+            Self::ProductClass => edit::Message::SelectProductClass(Some(id)),
+            Self::RevenueCategory => edit::Message::SelectRevenueCategory(Some(id)),
+            Self::TaxGroup => edit::Message::SelectTaxGroup(Some(id)),
+            Self::SecurityLevel => edit::Message::SelectSecurityLevel(Some(id)),
+            Self::ReportCategory => edit::Message::SelectReportCategory(Some(id)),
+            Self::ChoiceGroup => edit::Message::AddChoiceGroup(id),
+            Self::PrinterLogical => edit::Message::AddPrinterLogical(id),
+        }
+    }
+
+    // Matches DeletionInfo::entity_type, so a deleted record's filter can be dropped
+    pub fn entity_type(&self) -> &'static str {
+        match self {
+            Self::ItemGroup => "ItemGroup",
+            Self::ProductClass => "ProductClass",
+            Self::RevenueCategory => "RevenueCategory",
+            Self::TaxGroup => "TaxGroup",
+            Self::SecurityLevel => "SecurityLevel",
+            Self::ReportCategory => "ReportCategory",
+            Self::ChoiceGroup => "ChoiceGroup",
+            Self::PrinterLogical => "PrinterLogical",
+        }
+    }
+
+    // Single references must equal the id, choice groups and printers must contain it
+    fn matches(&self, item: &Item, id: EntityId) -> bool {
+        match self {
+            Self::ItemGroup => item.item_group == Some(id),
+            Self::ProductClass => item.product_class == Some(id),
+            Self::RevenueCategory => item.revenue_category == Some(id),
+            Self::TaxGroup => item.tax_group == Some(id),
+            Self::SecurityLevel => item.security_level == Some(id),
+            Self::ReportCategory => item.report_category == Some(id),
+            Self::ChoiceGroup => item.choice_groups
+                .as_ref()
+                .is_some_and(|groups| groups.iter().any(|(group_id, _)| *group_id == id)),
+            Self::PrinterLogical => item.printer_logicals
+                .as_ref()
+                .is_some_and(|printers| printers.iter().any(|(printer_id, _)| *printer_id == id)),
+        }
+    }
+
+    // Single references are replaced, choice groups and printers are added alongside
+    // the existing ones. Returns false when the item already had it
+    pub fn assign(&self, item: &mut Item, id: EntityId) -> bool {
+        if self.matches(item, id) {
+            return false;
+        }
+
+        match self {
+            Self::ItemGroup => item.item_group = Some(id),
+            Self::ProductClass => item.product_class = Some(id),
+            Self::RevenueCategory => item.revenue_category = Some(id),
+            Self::TaxGroup => item.tax_group = Some(id),
+            Self::SecurityLevel => item.security_level = Some(id),
+            Self::ReportCategory => item.report_category = Some(id),
+            Self::ChoiceGroup => {
+                let groups = item.choice_groups.get_or_insert_with(Vec::new);
+                let sequence = groups.iter().map(|(_, seq)| *seq).max().map_or(1, |max| max + 1);
+                groups.push((id, sequence));
+            }
+            Self::PrinterLogical => {
+                // The first printer on an item becomes its primary
+                let printers = item.printer_logicals.get_or_insert_with(Vec::new);
+                let primary = printers.is_empty();
+                printers.push((id, primary));
+            }
+        }
+        true
+    }
+}
+
+impl std::fmt::Display for AttributeField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+// An entry in an attribute filter dropdown
+#[derive(Debug, Clone, PartialEq)]
+struct FilterOption {
+    id: EntityId,
+    name: String,
+}
+
+impl std::fmt::Display for FilterOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+fn filter_options<T: entity_component::Entity>(entities: &BTreeMap<EntityId, T>) -> Vec<FilterOption> {
+    entities
+        .values()
+        .map(|entity| FilterOption { id: entity.id(), name: entity.name().to_string() })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarryField {
+    ItemGroup,
+    TaxGroup,
+    Categories,
+    PrinterLogicals,
+    PriceLevels,
+}
+
+// Which fields "Save & add another" copies into the next draft
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CarryOver {
+    pub item_group: bool,
+    pub tax_group: bool,
+    pub categories: bool,
+    pub printer_logicals: bool,
+    pub price_levels: bool,
+}
+
+impl Default for CarryOver {
+    fn default() -> Self {
+        Self {
+            item_group: true,
+            tax_group: true,
+            categories: true,
+            printer_logicals: true,
+            price_levels: true,
+        }
+    }
+}
+
+impl CarryOver {
+    pub fn set(&mut self, field: CarryField, value: bool) {
+        match field {
+            CarryField::ItemGroup => self.item_group = value,
+            CarryField::TaxGroup => self.tax_group = value,
+            CarryField::Categories => self.categories = value,
+            CarryField::PrinterLogicals => self.printer_logicals = value,
+            CarryField::PriceLevels => self.price_levels = value,
+        }
+    }
+
+    // Build a fresh draft from the previous item, name and prices are cleared
+    pub fn apply(&self, previous: &Item) -> Item {
+        let mut item = Item {
+            name: String::new(),
+            ..Item::default()
+        };
+
+        if self.item_group {
+            item.item_group = previous.item_group;
+        }
+        if self.tax_group {
+            item.tax_group = previous.tax_group;
+        }
+        if self.categories {
+            item.product_class = previous.product_class;
+            item.revenue_category = previous.revenue_category;
+            item.report_category = previous.report_category;
+            item.security_level = previous.security_level;
+        }
+        if self.printer_logicals {
+            item.printer_logicals = previous.printer_logicals.clone();
+        }
+        if self.price_levels {
+            item.price_levels = previous.price_levels.clone();
+            item.item_prices = previous.item_prices.as_ref().map(|prices| {
+                prices.iter()
+                    .map(|price| ItemPrice {
+                        price_level_id: price.price_level_id,
+                        price: Decimal::ZERO,
+                    })
+                    .collect()
+            });
+        }
+
+        item
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -132,11 +465,75 @@ pub struct EditState {
     pub printer_logicals_combo: combo_box::State<PrinterLogical>,
     pub printer_logicals_selection: Option<PrinterLogical>,
 
+    // Online description, edited over several lines
+    pub description: DescriptionEditor,
+
     // Validation
     pub validation_error: Option<String>,
+
+    // Save & add another
+    pub carry_over: CarryOver,
+    pub carried_over: bool,
+
+    // Stores whose price levels are folded away in the editor
+    pub collapsed_stores: Vec<EntityId>,
+
+    // Keyboard assignment lists, Ctrl+A acts on the one last used
+    pub choice_group_list: DualListState,
+    pub printer_logical_list: DualListState,
+    pub price_level_list: DualListState,
+    pub component_list: DualListState,
+    pub active_assign_list: Option<edit::AssignList>,
+
+    // Reference being created without leaving the editor
+    pub inline_create: Option<InlineCreate>,
+}
+
+// text_editor::Content can't be cloned, so a copy starts over from the same
+// text with the cursor at the top
+#[derive(Debug, Default)]
+pub struct DescriptionEditor(pub text_editor::Content);
+
+impl DescriptionEditor {
+    pub fn new(text: &str) -> Self {
+        Self(text_editor::Content::with_text(text))
+    }
+
+    // The editor ends its text with a newline that isn't part of the description
+    pub fn text(&self) -> String {
+        let mut text = self.0.text();
+        if text.ends_with('\n') {
+            text.pop();
+        }
+        text
+    }
+}
+
+impl Clone for DescriptionEditor {
+    fn clone(&self) -> Self {
+        Self::new(&self.text())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineCreate {
+    pub field: AttributeField,
+    pub name: String,
+    // Blank takes the lowest free id
+    pub id: String,
+    pub error: Option<String>,
 }
 
 impl EditState {
+    pub fn dual_list_mut(&mut self, list: edit::AssignList) -> &mut DualListState {
+        match list {
+            edit::AssignList::ChoiceGroups => &mut self.choice_group_list,
+            edit::AssignList::PrinterLogicals => &mut self.printer_logical_list,
+            edit::AssignList::PriceLevels => &mut self.price_level_list,
+            edit::AssignList::Components => &mut self.component_list,
+        }
+    }
+
     pub fn new(item: &Item, choice_group_list: Vec<ChoiceGroup>, printer_logical_list: Vec<PrinterLogical>, price_level_list: Vec<PriceLevel>) -> Self {
         Self {
             name: item.name.clone(),
@@ -193,7 +590,17 @@ impl EditState {
             printer_logicals: item.printer_logicals.clone().unwrap_or_default(),
             printer_logicals_combo: combo_box::State::with_selection(printer_logical_list.clone(), None),
             printer_logicals_selection: None,
+            description: DescriptionEditor::new(item.description.as_deref().unwrap_or_default()),
             validation_error: None,
+            carry_over: CarryOver::default(),
+            carried_over: false,
+            collapsed_stores: Vec::new(),
+            choice_group_list: DualListState::default(),
+            printer_logical_list: DualListState::default(),
+            price_level_list: DualListState::default(),
+            component_list: DualListState::default(),
+            active_assign_list: None,
+            inline_create: None,
         }
     }
 
@@ -311,8 +718,29 @@ pub struct Item {
     pub image_id: i32,
     pub stock_item: bool,
     pub language_iso_code: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub course: Option<u8>,
+    #[serde(default)]
+    pub image_path: Option<PathBuf>,
+    // Items sold together under this one, e.g. burger + fries + drink. Combos
+    // can't contain other combos, which also keeps them free of loops.
+    #[serde(default)]
+    pub components: Option<Vec<EntityId>>,
+    // Position in the items list, items without one follow in id order
+    #[serde(default)]
+    pub sort_order: Option<u32>,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
+// Courses an item can be fired in
+pub const COURSES: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+// Longest guest-facing description accepted for online menus
+pub const DESCRIPTION_MAX_LEN: usize = 500;
+
 impl Default for Item {
     fn default() -> Self {
         Self {
@@ -357,6 +785,12 @@ impl Default for Item {
             image_id: 0,
             stock_item: false,
             language_iso_code: String::new(),
+            description: None,
+            course: None,
+            image_path: None,
+            components: None,
+            sort_order: None,
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -367,12 +801,207 @@ impl std::fmt::Display for Item {
     }
 }
 
+impl entity_component::Entity for Item {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn with_id(&self, id: EntityId) -> Self {
+        let mut clone = self.clone();
+        clone.id = id;
+        clone
+    }
+
+    fn with_name(&self, name: String) -> Self {
+        let mut clone = self.clone();
+        clone.name = name;
+        clone
+    }
+
+    fn default_new() -> Self {
+        Self::default()
+    }
+}
+
+impl entity_component::Ordered for Item {
+    fn sort_order(&self) -> Option<u32> {
+        self.sort_order
+    }
+
+    fn set_sort_order(&mut self, sort_order: Option<u32>) {
+        self.sort_order = sort_order;
+    }
+}
+
 impl Item {
 
     pub fn new() -> Self {
         Self::default()
     }
 
+    // Keeps price_levels and item_prices in sync. Nothing is dropped: an
+    // assigned level without a price gets a $0.00 row, and a price for a level
+    // that isn't assigned adds the assignment. item_prices order wins, extra
+    // assigned levels are appended. Import relies on this to derive
+    // price_levels from the prices in the file.
+    pub fn reconcile_price_levels(&mut self) {
+        let mut prices = self.item_prices.take().unwrap_or_default();
+        let assigned = self.price_levels.take().unwrap_or_default();
+
+        // Drop duplicate price rows, first one wins
+        let mut seen = Vec::new();
+        prices.retain(|price| {
+            if seen.contains(&price.price_level_id) {
+                false
+            } else {
+                seen.push(price.price_level_id);
+                true
+            }
+        });
+
+        for level_id in assigned {
+            if !prices.iter().any(|price| price.price_level_id == level_id) {
+                prices.push(ItemPrice {
+                    price_level_id: level_id,
+                    price: Decimal::new(0, 2),
+                });
+            }
+        }
+
+        if prices.is_empty() {
+            self.price_levels = None;
+            self.item_prices = None;
+        } else {
+            self.price_levels = Some(prices.iter().map(|price| price.price_level_id).collect());
+            self.item_prices = Some(prices);
+        }
+    }
+
+    // Drops assignments and prices for price levels that no longer exist, then
+    // reconciles what is left so every assigned level has a price row. Returns
+    // the ids that were dropped.
+    pub fn drop_missing_price_levels(&mut self, price_levels: &BTreeMap<EntityId, PriceLevel>) -> Vec<EntityId> {
+        let mut missing: Vec<EntityId> = self.price_levels
+            .iter()
+            .flatten()
+            .copied()
+            .chain(self.item_prices.iter().flatten().map(|price| price.price_level_id))
+            .filter(|level_id| !price_levels.contains_key(level_id))
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+
+        for level_id in &missing {
+            self.remove_price_level(*level_id);
+        }
+        self.reconcile_price_levels();
+
+        missing
+    }
+
+    // No prices at all, or only zero ones, the pre-launch pricing check
+    pub fn is_missing_price(&self) -> bool {
+        self.item_prices
+            .as_ref()
+            .is_none_or(|prices| prices.iter().all(|price| price.price.is_zero()))
+    }
+
+    pub fn has_price_at(&self, level_id: EntityId) -> bool {
+        self.item_prices
+            .as_ref()
+            .is_some_and(|prices| prices.iter().any(|price| price.price_level_id == level_id))
+    }
+
+    // Ids on the assigned side of an editor assignment list, in their stored order.
+    // Price levels come from item_prices when present, matching the price editor.
+    pub fn assigned_ids(&self, list: edit::AssignList) -> Vec<EntityId> {
+        match list {
+            edit::AssignList::ChoiceGroups => self.choice_groups
+                .iter()
+                .flatten()
+                .map(|(id, _)| *id)
+                .collect(),
+            edit::AssignList::PrinterLogicals => self.printer_logicals
+                .iter()
+                .flatten()
+                .map(|(id, _)| *id)
+                .collect(),
+            edit::AssignList::PriceLevels => match &self.item_prices {
+                Some(prices) => prices.iter().map(|price| price.price_level_id).collect(),
+                None => self.price_levels.clone().unwrap_or_default(),
+            },
+            edit::AssignList::Components => self.components.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn is_combo(&self) -> bool {
+        self.components.as_ref().is_some_and(|components| !components.is_empty())
+    }
+
+    // Whether this item can be added to the combo with the given id
+    pub fn can_be_component(&self, combo_id: EntityId) -> bool {
+        self.id != combo_id && !self.is_combo()
+    }
+
+    // Drops a component, collapsing to None when empty. Returns whether it was there.
+    pub fn remove_component(&mut self, component_id: EntityId) -> bool {
+        let Some(components) = &mut self.components else {
+            return false;
+        };
+
+        let before = components.len();
+        components.retain(|&id| id != component_id);
+        let removed = components.len() != before;
+
+        if components.is_empty() {
+            self.components = None;
+        }
+        removed
+    }
+
+    pub fn is_assigned_to(&self, level_id: EntityId) -> bool {
+        self.price_levels
+            .as_ref()
+            .is_some_and(|levels| levels.contains(&level_id))
+    }
+
+    // Drops a price level from both the assignment and the prices, collapsing to None when empty
+    pub fn remove_price_level(&mut self, level_id: EntityId) {
+        if let Some(levels) = &mut self.price_levels {
+            levels.retain(|&id| id != level_id);
+            if levels.is_empty() {
+                self.price_levels = None;
+            }
+        }
+
+        if let Some(prices) = &mut self.item_prices {
+            prices.retain(|price| price.price_level_id != level_id);
+            if prices.is_empty() {
+                self.item_prices = None;
+            }
+        }
+    }
+
+    // True when price_levels and item_prices disagree about the assigned levels
+    pub fn has_price_level_mismatch(&self) -> bool {
+        let mut assigned = self.price_levels.clone().unwrap_or_default();
+        let mut priced = self.item_prices
+            .as_ref()
+            .map(|prices| prices.iter().map(|price| price.price_level_id).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        assigned.sort();
+        assigned.dedup();
+        priced.sort();
+        priced.dedup();
+
+        assigned != priced
+    }
+
     pub fn assign_id_from_group(&mut self, item_group: &ItemGroup) -> Result<(), ValidationError> {
         // Find first available ID in the group's range
         let range = item_group.id_range.start..=item_group.id_range.end;
@@ -498,12 +1127,165 @@ impl Item {
     }
 }
 
+// Reads a file in the item export layout back into items
+pub fn import_from_csv(path: &Path) -> Result<Vec<Item>, String> {
+    let path = path.to_path_buf();
+
+    import_items::verify_csv_format(path.clone())
+        .map_err(|e| format!("{} is not in the item export layout: {}", path.display(), e))?;
+
+    import_items::collect_item_information(&path)
+        .map(|items| items.into_values().collect())
+        .map_err(|e| format!("Failed to read items from {}: {}", path.display(), e))
+}
+
+// The selection if it still points at something, a draft (negative id) or an
+// existing item. A deleted item leaves nothing selected.
+pub fn live_selection(selected: Option<EntityId>, items: &BTreeMap<EntityId, Item>) -> Option<EntityId> {
+    selected.filter(|id| *id < 0 || items.contains_key(id))
+}
+
+// Items written by the item view's CSV export, the one item or every item when
+// no id is given. None when the one item has been deleted since.
+pub fn export_selection(only: Option<EntityId>, items: &BTreeMap<EntityId, Item>) -> Option<BTreeMap<EntityId, Item>> {
+    match only {
+        Some(id) => items.get(&id).map(|item| BTreeMap::from([(id, item.clone())])),
+        None => Some(items.clone()),
+    }
+}
+
+// Combos that list the item with this id as a component
+pub fn combos_containing(id: EntityId, items: &BTreeMap<EntityId, Item>) -> Vec<&Item> {
+    items
+        .values()
+        .filter(|combo| combo.id != id && combo.components.as_ref().is_some_and(|c| c.contains(&id)))
+        .collect()
+}
+
+// Follows component links from the item, using its unsaved components rather
+// than the stored ones. Returns the ids around the first loop found, starting
+// and ending on the same id.
+pub fn component_cycle(item: &Item, items: &BTreeMap<EntityId, Item>) -> Option<Vec<EntityId>> {
+    fn visit(
+        id: EntityId,
+        item: &Item,
+        items: &BTreeMap<EntityId, Item>,
+        path: &mut Vec<EntityId>,
+    ) -> Option<Vec<EntityId>> {
+        if let Some(start) = path.iter().position(|&on_path| on_path == id) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(id);
+            return Some(cycle);
+        }
+
+        let components = if id == item.id {
+            item.components.as_ref()
+        } else {
+            items.get(&id).and_then(|other| other.components.as_ref())
+        };
+
+        path.push(id);
+        for &component in components.into_iter().flatten() {
+            if let Some(cycle) = visit(component, item, items, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+
+        None
+    }
+
+    visit(item.id, item, items, &mut Vec::new())
+}
+
+// Copies the editor's price strings into the item's price rows. Each must be a
+// non-negative amount, otherwise nothing should be saved and the bad ones are named.
+// Amounts are rounded to `scale` places, the prices that changed are returned for a note.
+pub fn apply_edit_prices(
+    item: &mut Item,
+    edit_prices: &[(EntityId, String)],
+    price_levels: &BTreeMap<EntityId, PriceLevel>,
+    scale: u32,
+) -> Result<Vec<String>, String> {
+    let mut invalid = Vec::new();
+    let mut rounded = Vec::new();
+
+    for item_price in item.item_prices.iter_mut().flatten() {
+        let Some((_, price)) = edit_prices.iter().find(|(id, _)| *id == item_price.price_level_id) else {
+            continue;
+        };
+        let level_name = price_levels.get(&item_price.price_level_id)
+            .map_or_else(|| format!("Price level {}", item_price.price_level_id), |level| level.name.clone());
+
+        match price.trim().parse::<Decimal>() {
+            Ok(parsed) if !parsed.is_sign_negative() => {
+                let (amount, was_rounded) = data_types::round_amount(parsed, scale);
+                if was_rounded {
+                    rounded.push(format!("{} {} -> {}", level_name, price.trim(), amount));
+                }
+                item_price.price = amount;
+            }
+            _ => invalid.push(format!("{} \"{}\"", level_name, price)),
+        }
+    }
+
+    if invalid.is_empty() {
+        Ok(rounded)
+    } else {
+        Err(format!("Not saved, these prices are not valid amounts: {}", invalid.join(", ")))
+    }
+}
+
+// Checks an item's components before it is saved: they must exist, must not
+// lead back to the item, and combos can't be nested in either direction
+pub fn validate_components(item: &Item, items: &BTreeMap<EntityId, Item>) -> Result<(), String> {
+    if !item.is_combo() {
+        return Ok(());
+    }
+
+    let name_of = |id: EntityId| {
+        if id == item.id {
+            item.name.clone()
+        } else {
+            items.get(&id).map_or(id.to_string(), |other| other.name.clone())
+        }
+    };
+
+    if let Some(cycle) = component_cycle(item, items) {
+        return Err(format!(
+            "Components loop back on themselves: {}",
+            cycle.into_iter().map(name_of).collect::<Vec<_>>().join(" → ")
+        ));
+    }
+
+    for &id in item.components.iter().flatten() {
+        match items.get(&id) {
+            None => return Err(format!("Component {} does not exist", id)),
+            Some(component) if component.is_combo() => {
+                return Err(format!("'{}' is a combo and can't be a component of another combo", component.name));
+            }
+            Some(_) => {}
+        }
+    }
+
+    if let Some(combo) = combos_containing(item.id, items).first() {
+        return Err(format!("'{}' is a component of '{}' and can't be a combo itself", item.name, combo.name));
+    }
+
+    Ok(())
+}
+
 pub fn update(
     item: &mut Item,
     message: Message,
     state: &mut EditState,
-    _context: &ViewContext,
+    context: &ViewContext,
 ) -> Action<Operation, Message> {
+    // Ctrl+A only targets an assignment list while the user is working in it
+    if !matches!(message, Message::Edit(edit::Message::AssignList(..) | edit::Message::AssignAllFiltered)) {
+        state.active_assign_list = None;
+    }
+
     match message {
         Message::Edit(msg) => match msg  {
             // Basic Info
@@ -535,6 +1317,26 @@ pub fn update(
                 item.printer_text = text;
                 Action::none()
             }
+            edit::Message::EditDescription(action) => {
+                // Cursor moves and selections leave the text alone
+                if !action.is_edit() {
+                    state.description.0.perform(action);
+                    return Action::none();
+                }
+
+                let before = state.description.clone();
+                state.description.0.perform(action);
+                let text = state.description.text();
+
+                if text.chars().count() <= DESCRIPTION_MAX_LEN {
+                    item.description = if text.is_empty() { None } else { Some(text) };
+                    state.validation_error = None;
+                } else {
+                    state.description = before;
+                    state.validation_error = Some(format!("Description cannot exceed {} characters", DESCRIPTION_MAX_LEN));
+                }
+                Action::none()
+            }
 
             // Classifications
             edit::Message::SelectItemGroup(group_id) => {
@@ -553,6 +1355,27 @@ pub fn update(
                 item.tax_group = group_id;
                 Action::none()
             }
+            edit::Message::PickImage => {
+                Action::task(
+                    iced::Task::perform(pick_image(), |path| Message::Edit(edit::Message::ImagePicked(path)))
+                )
+            }
+            edit::Message::ImagePicked(path) => {
+                item.image_path = path;
+                Action::none()
+            }
+            edit::Message::SelectCourse(course) => {
+                match course {
+                    Some(c) if !COURSES.contains(&c) => {
+                        state.validation_error = Some("Course must be between 1 and 9".to_string());
+                    }
+                    _ => {
+                        item.course = course;
+                        state.validation_error = None;
+                    }
+                }
+                Action::none()
+            }
             edit::Message::SelectSecurityLevel(level_id) => {
                 item.security_level = level_id;
                 Action::none()
@@ -576,8 +1399,71 @@ pub fn update(
                 }
                 Action::none()
             }
+            edit::Message::AssignList(list, message) => {
+                state.dual_list_mut(list).update(&message);
+                state.active_assign_list = Some(list);
+
+                // Moves reuse the single add/remove handlers so sequences and default prices stay consistent
+                let moves: Vec<edit::Message> = match (list, message) {
+                    (edit::AssignList::ChoiceGroups, DualListMessage::Assign(ids)) => ids.into_iter().map(edit::Message::AddChoiceGroup).collect(),
+                    (edit::AssignList::ChoiceGroups, DualListMessage::Unassign(id)) => vec![edit::Message::RemoveChoiceGroup(id)],
+                    (edit::AssignList::PrinterLogicals, DualListMessage::Assign(ids)) => ids.into_iter().map(edit::Message::AddPrinterLogical).collect(),
+                    (edit::AssignList::PrinterLogicals, DualListMessage::Unassign(id)) => vec![edit::Message::RemovePrinterLogical(id)],
+                    (edit::AssignList::PriceLevels, DualListMessage::Assign(ids)) => ids.into_iter().map(edit::Message::PriceLevelSelected).collect(),
+                    (edit::AssignList::PriceLevels, DualListMessage::Unassign(id)) => vec![edit::Message::RemovePriceLevel(id)],
+                    (edit::AssignList::Components, DualListMessage::Assign(ids)) => ids.into_iter().map(edit::Message::AddComponent).collect(),
+                    (edit::AssignList::Components, DualListMessage::Unassign(id)) => vec![edit::Message::RemoveComponent(id)],
+                    (_, DualListMessage::Filter(_) | DualListMessage::Highlight(_)) => Vec::new(),
+                };
+
+                for next in moves {
+                    let _ = update(item, Message::Edit(next), state, context);
+                }
+                state.active_assign_list = Some(list);
+
+                Action::none()
+            }
+            edit::Message::AssignAllFiltered => {
+                let Some(list) = state.active_assign_list else {
+                    return Action::none();
+                };
+
+                let assigned = item.assigned_ids(list);
+                let ids: Vec<EntityId> = match list {
+                    edit::AssignList::ChoiceGroups => state.choice_group_list
+                        .filtered(context.available_choice_groups.values(), &assigned)
+                        .iter()
+                        .map(|group| group.id)
+                        .collect(),
+                    edit::AssignList::PrinterLogicals => state.printer_logical_list
+                        .filtered(context.available_printer_logicals.values(), &assigned)
+                        .iter()
+                        .map(|printer| printer.id)
+                        .collect(),
+                    edit::AssignList::PriceLevels => state.price_level_list
+                        .filtered(context.available_price_levels.values(), &assigned)
+                        .iter()
+                        .map(|level| level.id)
+                        .collect(),
+                    edit::AssignList::Components => state.component_list
+                        .filtered(
+                            context.available_items.values().filter(|candidate| candidate.can_be_component(item.id)),
+                            &assigned,
+                        )
+                        .iter()
+                        .map(|component| component.id)
+                        .collect(),
+                };
+
+                update(item, Message::Edit(edit::Message::AssignList(list, DualListMessage::Assign(ids))), state, context)
+            }
             edit::Message::PriceLevelSelected(level_id) => {
                 println!("Price level id selected: {}", level_id);
+
+                // Already assigned, a second entry would show as two prices for one level
+                if item.is_assigned_to(level_id) || item.has_price_at(level_id) {
+                    return Action::none();
+                }
                 
                 // Update both price_levels and item_prices
                 match &mut item.price_levels {
@@ -680,6 +1566,14 @@ pub fn update(
                 // Update edit_state.prices for UI
                 Action::operation(Operation::UpdatePrice(item.id, price_level_id, item_price))
             }
+            edit::Message::ToggleStoreGroup(store_id) => {
+                if state.collapsed_stores.contains(&store_id) {
+                    state.collapsed_stores.retain(|id| *id != store_id);
+                } else {
+                    state.collapsed_stores.push(store_id);
+                }
+                Action::none()
+            }
             edit::Message::UpdateStorePriceLevel(level_id) => {
                 if let Some(level_id) = level_id {
                     if let Some(ref mut levels) = item.store_price_level {
@@ -858,7 +1752,11 @@ pub fn update(
                 match &mut item.choice_groups {
                     Some(choice_groups) => {
                         if choice_groups.iter().any(|(id, _)| *id == group_id ) {
-                            choice_groups.retain(|&(id, _)| id != group_id)
+                            choice_groups.retain(|&(id, _)| id != group_id);
+                            // Stored as None once empty, same as after a delete
+                            if choice_groups.is_empty() {
+                                item.choice_groups = None;
+                            }
                         } 
                         else {} //choice group doesn't exist, do nothing
                     }
@@ -889,20 +1787,95 @@ pub fn update(
                         if printers.iter().any(|(id, _)| *id == printer_id ) {
                             // Keep all Ids, not matching the id we want to remove.
                             printers.retain(|&(id, _)| id != printer_id);
+                            if printers.is_empty() {
+                                item.printer_logicals = None;
+                            }
                         } else {} // Printer doesn't exist, do nothing
                     }
                     None => {} // Printer doesn't exist, do nothing
                 }
                 Action::none()
             }
+            edit::Message::AddComponent(component_id) => {
+                match context.available_items.get(&component_id) {
+                    Some(component) if component.can_be_component(item.id) => {
+                        let components = item.components.get_or_insert_with(Vec::new);
+                        if !components.contains(&component_id) {
+                            components.push(component_id);
+                        }
+                        state.validation_error = None;
+                    }
+                    Some(component) => {
+                        state.validation_error = Some(format!("'{}' can't be a component of this combo", component.name));
+                    }
+                    None => {}
+                }
+                Action::none()
+            }
+            edit::Message::RemoveComponent(component_id) => {
+                item.remove_component(component_id);
+                Action::none()
+            }
 
             edit::Message::Save => Action::operation(Operation::Save(item.clone())),
+            edit::Message::SaveAndAddAnother => Action::operation(Operation::SaveAndAddAnother(item.clone())),
             edit::Message::Cancel => Action::operation(Operation::Cancel),
+            edit::Message::StartInlineCreate(field) => {
+                state.inline_create = Some(InlineCreate {
+                    field,
+                    name: String::new(),
+                    id: String::new(),
+                    error: None,
+                });
+                Action::none()
+            }
+            edit::Message::UpdateInlineName(name) => {
+                if let Some(create) = &mut state.inline_create {
+                    create.name = name;
+                    create.error = None;
+                }
+                Action::none()
+            }
+            edit::Message::UpdateInlineId(id) => {
+                if let Some(create) = &mut state.inline_create {
+                    create.id = id;
+                    create.error = None;
+                }
+                Action::none()
+            }
+            edit::Message::ConfirmInlineCreate => match &state.inline_create {
+                Some(create) => Action::operation(Operation::CreateReference(create.field, create.name.clone(), create.id.clone())),
+                None => Action::none(),
+            },
+            edit::Message::CancelInlineCreate => {
+                state.inline_create = None;
+                Action::none()
+            }
+            edit::Message::ToggleCarryOver(field, value) => {
+                state.carry_over.set(field, value);
+                Action::operation(Operation::UpdateCarryOver(state.carry_over.clone()))
+            }
+            edit::Message::ClearCarriedOver => {
+                item.item_group = None;
+                item.tax_group = None;
+                item.product_class = None;
+                item.revenue_category = None;
+                item.report_category = None;
+                item.security_level = None;
+                item.printer_logicals = None;
+                item.price_levels = None;
+                item.item_prices = None;
+                state.prices = None;
+                state.carried_over = false;
+                Action::none()
+            }
         }
         Message::View(msg) => match msg {
             view::Message::Edit => Action::operation(Operation::StartEdit(item.id)),
             view::Message::Back => Action::operation(Operation::Back),
-            view::Message::ExportToCsv => Action::none() //Need to implement export and imports
+            view::Message::ExportToCsv(all) => Action::operation(Operation::ExportToCsv((!all).then_some(item.id))),
+            view::Message::ExportRecord => Action::operation(Operation::ExportRecord(item.id)),
+            view::Message::CopyJson => Action::operation(Operation::CopyJson(item.id)),
         }
         Message::CreateNew => {
             let new_item = Item::default();
@@ -911,6 +1884,9 @@ pub fn update(
         Message::Select(id) => {
             Action::operation(Operation::Select(id))
         },
+        Message::OpenRecent(target) => {
+            Action::operation(Operation::OpenRecent(target))
+        },
         Message::SearchItems(query) => {
             Action::operation(Operation::UpdateSearchQuery(query)) //need to implement search
         }
@@ -920,6 +1896,12 @@ pub fn update(
         Message::CopyItem(id) => {
             Action::operation(Operation::CopyItem(id))
         }
+        Message::MoveUp(id) => {
+            Action::operation(Operation::MoveUp(id))
+        }
+        Message::MoveDown(id) => {
+            Action::operation(Operation::MoveDown(id))
+        }
         Message::HideModal => {
             Action::operation(Operation::HideModal)
         }
@@ -929,14 +1911,113 @@ pub fn update(
         Message::LaunchMassItemEditWindow => {
             Action::operation(Operation::LaunchMassItemEditWindow)
         }
+        Message::OpenEditorWindow(id) => {
+            Action::operation(Operation::OpenEditorWindow(id))
+        }
+        Message::FilterCourse(course) => {
+            Action::operation(Operation::FilterCourse(course))
+        }
+        Message::FilterPriceLevel(level, unpriced) => {
+            Action::operation(Operation::FilterPriceLevel(level, unpriced))
+        }
+        Message::FilterMissingPrice(enabled) => {
+            Action::operation(Operation::FilterMissingPrice(enabled))
+        }
+        Message::FilterAttribute(field, id) => {
+            Action::operation(Operation::FilterAttribute(field, id))
+        }
+        Message::ShowAttributeFilters(show) => {
+            Action::operation(Operation::ShowAttributeFilters(show))
+        }
+        Message::ClearFilters => {
+            Action::operation(Operation::ClearFilters)
+        }
+        Message::SelectBulkCourse(course) => {
+            Action::operation(Operation::SelectBulkCourse(course))
+        }
+        Message::ApplyBulkCourse(ids, course) => {
+            Action::operation(Operation::ApplyCourse(ids, course))
+        }
+        Message::SelectBulkAssignField(field) => {
+            Action::operation(Operation::SelectBulkAssignField(field))
+        }
+        Message::SelectBulkAssignValue(id) => {
+            Action::operation(Operation::SelectBulkAssignValue(id))
+        }
+        Message::ApplyBulkAssign(ids, field, id) => {
+            Action::operation(Operation::ApplyAttribute(ids, field, id))
+        }
     }
 }
 
+async fn pick_image() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Select item image")
+        .add_filter("Images", &["png", "jpg", "jpeg", "gif", "bmp", "webp"])
+        .pick_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+}
+
+// Checked lazily when an item is opened, never while rendering the list
+pub async fn image_exists(path: PathBuf) -> bool {
+    tokio::fs::try_exists(&path).await.unwrap_or(false)
+}
+
+// A record of any type that was saved recently, shown while no item is selected
+#[derive(Debug, Clone)]
+pub struct RecentChange {
+    pub target: validation::Target,
+    // "Tax Group 'Food' (3)"
+    pub record: String,
+    pub modified_at: i64,
+}
+
+fn recent_changes_view<'a>(recent: Vec<RecentChange>) -> iced::widget::Container<'a, Message> {
+    container(
+        column![
+            text("Recently modified").size(18).style(Modern::primary_text()),
+            text("Click a record to open it, or choose an item from the list").style(Modern::secondary_text()),
+            column(
+                recent
+                    .into_iter()
+                    .map(|change| {
+                        row![
+                            text(data_types::Timestamps::format(Some(change.modified_at)))
+                                .size(13)
+                                .width(Length::Fixed(130.0))
+                                .style(Modern::secondary_text()),
+                            button(text(change.record).size(13))
+                                .on_press(Message::OpenRecent(change.target))
+                                .style(Modern::gray_button()),
+                        ]
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .into()
+                    })
+                    .collect::<Vec<_>>()
+            )
+            .spacing(5),
+        ]
+        .spacing(10)
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .style(Modern::card_container())
+}
+
 pub fn view<'a>(
-    item: &'a Item, 
+    item: Option<&'a Item>,
     mode: &'a Mode,
     items: &'a BTreeMap<EntityId, Item>,
     item_search: &'a String,
+    list_filters: &'a ListFilters,
+    search_cache: &'a RefCell<SearchCache>,
+    data_generation: u64,
+    show_search_timing: bool,
     item_edit_state: &'a EditState,
     item_groups: &'a BTreeMap<EntityId, ItemGroup>,
     tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
@@ -947,6 +2028,9 @@ pub fn view<'a>(
     choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
     printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
     price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    stores: &'a BTreeMap<EntityId, Store>,
+    image_exists: Option<bool>,
+    recent: Vec<RecentChange>,
 ) -> Element<'a, Message> {
 
 /*     let search_bar = iced::widget::text_input(
@@ -967,24 +2051,203 @@ pub fn view<'a>(
         .on_input(Message::SearchItems),
         button(icon::superpowers().size(14).center())
             .on_press(Message::LaunchMassItemEditWindow)
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
     ];
 
-    let filtered_items = items.values()
-        .filter(|item| matches_search(
-            item, 
-            &item_search,
-            item_groups,
-            tax_groups,
-            security_levels,
-            revenue_categories,
-            report_categories,
-            product_classes,
-            choice_groups,
-            printer_logicals,
-            price_levels,
-        ))
+    let filtered_ids = search_cache.borrow_mut().filtered_ids(
+        data_generation,
+        item_search,
+        list_filters,
+        items,
+        item_groups,
+        tax_groups,
+        security_levels,
+        report_categories,
+        choice_groups,
+        printer_logicals,
+        price_levels,
+    );
+
+    let mut filtered_items = filtered_ids.iter()
+        .filter_map(|id| items.get(id))
         .collect::<Vec<_>>();
+    entity_component::display_order(&mut filtered_items);
+
+    // Debug overlay for checking the search cache
+    let search_timing = match search_cache.borrow().last_timing {
+        Some((elapsed, cached)) if show_search_timing => text(format!(
+            "{} of {} items in {:.2}ms{}",
+            filtered_ids.len(),
+            items.len(),
+            elapsed.as_secs_f64() * 1000.0,
+            if cached { " (cached)" } else { "" }
+        ))
+        .size(11)
+        .style(Modern::secondary_text()),
+        _ => text(""),
+    };
+
+    let course_filter = row![
+        pick_list(COURSES, list_filters.course, |course| Message::FilterCourse(Some(course)))
+            .placeholder("Any course")
+            .width(120)
+            .style(Modern::pick_list()),
+        button("All")
+            .on_press(Message::FilterCourse(None))
+            .style(Modern::gray_button()),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let price_level_filter = row![
+        pick_list(
+            price_levels.values().cloned().collect::<Vec<_>>(),
+            list_filters.price_level.and_then(|id| price_levels.get(&id).cloned()),
+            move |level: PriceLevel| Message::FilterPriceLevel(Some(level.id), list_filters.price_level_unpriced)
+        )
+            .placeholder("Any price level")
+            .width(120)
+            .style(Modern::pick_list()),
+        iced::widget::checkbox("Unpriced", list_filters.price_level_unpriced)
+            .on_toggle(move |unpriced| Message::FilterPriceLevel(list_filters.price_level, unpriced))
+            .style(Modern::checkbox()),
+        button("All")
+            .on_press(Message::FilterPriceLevel(None, false))
+            .style(Modern::gray_button()),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let missing_price_count = items.values().filter(|item| item.is_missing_price()).count();
+    let missing_price_filter = iced::widget::checkbox(format!("Missing price ({} items)", missing_price_count), list_filters.missing_price)
+        .on_toggle(Message::FilterMissingPrice)
+        .style(Modern::checkbox());
+
+    // Reference filters for auditing, e.g. every item on one tax group
+    let attribute_toggle = row![
+        button(text(match list_filters.attributes.len() {
+            0 => "Attribute filters".to_string(),
+            active => format!("Attribute filters ({})", active),
+        }).size(13))
+            .on_press(Message::ShowAttributeFilters(!list_filters.show_attributes))
+            .style(Modern::conditional_button_style(
+                list_filters.show_attributes,
+                accent::selected_button(),
+                Modern::system_button()
+            )),
+        button(text("Clear filters").size(13))
+            .on_press(Message::ClearFilters)
+            .style(Modern::gray_button()),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let options_for = |field: AttributeField| match field {
+        AttributeField::ItemGroup => filter_options(item_groups),
+        AttributeField::ProductClass => filter_options(product_classes),
+        AttributeField::RevenueCategory => filter_options(revenue_categories),
+        AttributeField::TaxGroup => filter_options(tax_groups),
+        AttributeField::SecurityLevel => filter_options(security_levels),
+        AttributeField::ReportCategory => filter_options(report_categories),
+        AttributeField::ChoiceGroup => filter_options(choice_groups),
+        AttributeField::PrinterLogical => filter_options(printer_logicals),
+    };
+
+    let attribute_filters: Element<'a, Message> = if list_filters.show_attributes {
+        column(
+            AttributeField::ALL.iter().map(|&field| {
+                let options = options_for(field);
+                let selected = list_filters.attributes
+                    .get(&field)
+                    .and_then(|id| options.iter().find(|option| option.id == *id).cloned());
+
+                row![
+                    text(field.label()).size(12).width(85),
+                    pick_list(options, selected, move |option: FilterOption| Message::FilterAttribute(field, Some(option.id)))
+                        .placeholder("Any")
+                        .text_size(12)
+                        .width(115)
+                        .style(Modern::pick_list()),
+                    button(text("All").size(12))
+                        .on_press(Message::FilterAttribute(field, None))
+                        .style(Modern::gray_button()),
+                ]
+                .spacing(5)
+                .align_y(Alignment::Center)
+                .into()
+            })
+        )
+        .spacing(5)
+        .into()
+    } else {
+        column![].into()
+    };
+
+    // Bulk course assignment for whatever the search and filter currently show
+    let bulk_course = column![
+        row![
+            pick_list(COURSES, list_filters.bulk_course, |course| Message::SelectBulkCourse(Some(course)))
+                .placeholder("Set course...")
+                .width(120)
+                .style(Modern::pick_list()),
+            button("Apply")
+                .on_press_maybe(
+                    (!filtered_ids.is_empty() && list_filters.bulk_course.is_some())
+                        .then(|| Message::ApplyBulkCourse(filtered_ids.clone(), list_filters.bulk_course))
+                )
+                .style(accent::primary_button()),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        text(match list_filters.bulk_course {
+            Some(course) => format!("Sets course {} on {} filtered item(s)", course, filtered_ids.len()),
+            None => String::new(),
+        })
+        .size(12)
+        .style(Modern::secondary_text()),
+    ]
+    .spacing(5);
+
+    // Re-tags every filtered item at once, e.g. moving them to a new tax group
+    let assign_options = list_filters.bulk_assign_field.map(options_for).unwrap_or_default();
+    let assign_value = list_filters.bulk_assign_value
+        .and_then(|id| assign_options.iter().find(|option| option.id == id).cloned());
+    let bulk_assign = column![
+        row![
+            pick_list(AttributeField::BULK_ASSIGNABLE, list_filters.bulk_assign_field, Message::SelectBulkAssignField)
+                .placeholder("Assign...")
+                .text_size(12)
+                .width(100)
+                .style(Modern::pick_list()),
+            pick_list(assign_options, assign_value.clone(), |option: FilterOption| Message::SelectBulkAssignValue(Some(option.id)))
+                .placeholder("Value")
+                .text_size(12)
+                .width(95)
+                .style(Modern::pick_list()),
+            button(text("Apply").size(12))
+                .on_press_maybe(
+                    list_filters.bulk_assign_field
+                        .zip(list_filters.bulk_assign_value)
+                        .filter(|_| !filtered_ids.is_empty())
+                        .map(|(field, id)| Message::ApplyBulkAssign(filtered_ids.clone(), field, id))
+                )
+                .style(accent::primary_button()),
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center),
+        text(match (list_filters.bulk_assign_field, &assign_value) {
+            (Some(AttributeField::PrinterLogical), Some(value)) => {
+                format!("Adds printer {} to {} filtered item(s)", value, filtered_ids.len())
+            }
+            (Some(field), Some(value)) => {
+                format!("Sets {} to {} on {} filtered item(s)", field.label().to_lowercase(), value, filtered_ids.len())
+            }
+            _ => String::new(),
+        })
+        .size(12)
+        .style(Modern::secondary_text()),
+    ]
+    .spacing(5);
 
     let header_row = row![
         text("Name").width(Length::Fixed(175.0)),
@@ -1000,17 +2263,17 @@ pub fn view<'a>(
                     button(
                         list_item(
                             an_item.name.as_str(),
+                            an_item.id,
                             button(icon::copy().size(14))
                                 .on_press(Message::CopyItem(an_item.id)),
-                            button(icon::trash().size(14))
-                                .on_press(Message::RequestDelete(an_item.id)),
+                            entity_component::delete_button(Message::RequestDelete(an_item.id)),
                         )
                     )
                     .on_press(Message::Select(an_item.id))
                     .style(
                         Modern::conditional_button_style(
-                            an_item.id == item.id,
-                            Modern::selected_button_style(Modern::system_button()),
+                            item.is_some_and(|selected| selected.id == an_item.id),
+                            accent::selected_button(),
                             Modern::system_button()
                         )
                     ).into()
@@ -1018,9 +2281,63 @@ pub fn view<'a>(
                 .collect::<Vec<_>>()
         )
         .spacing(5)
-        .width(iced::Length::Fixed(250.0))
+        .width(iced::Length::Fixed(290.0))
     ).height(Length::Fill);
 
+    let list_panel = container(
+        column![
+            row![
+                container(text("Items").size(18).style(Modern::primary_text())).padding(5),
+                iced::widget::horizontal_space(),
+                button(icon::new().size(14).center())
+                    .on_press(Message::CreateNew)
+                    .style(accent::primary_button()),
+            ].width(290),
+            search_bar,
+            search_timing,
+            course_filter,
+            price_level_filter,
+            missing_price_filter,
+            attribute_toggle,
+            attribute_filters,
+            bulk_course,
+            bulk_assign,
+            header_row,   
+            items_list,
+        ]
+        .width(310)
+        .spacing(10)
+        .padding(10)
+    )
+    .style(Modern::card_container());
+
+    // Nothing is rendered for editing until the user picks an item
+    let Some(item) = item else {
+        let placeholder = if recent.is_empty() {
+            container(
+                column![
+                    text("Select an item").size(18).style(Modern::primary_text()),
+                    text("Choose an item from the list, or create a new one").style(Modern::secondary_text()),
+                ]
+                .spacing(10)
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .style(Modern::card_container())
+        } else {
+            recent_changes_view(recent)
+        };
+
+        return row![
+            list_panel,
+            placeholder,
+        ]
+        .spacing(20)
+        .into();
+    };
+
     let content = match mode {
         Mode::View => view::view(
             item,
@@ -1033,6 +2350,8 @@ pub fn view<'a>(
             choice_groups,
             printer_logicals,
             price_levels,
+            items,
+            image_exists,
         ).map(Message::View),
         Mode::Edit => {
             edit::view(
@@ -1047,31 +2366,27 @@ pub fn view<'a>(
                 choice_groups,
                 printer_logicals,
                 price_levels,
+                stores,
+                items,
             ).map(Message::Edit)
         }
     };
 
+    // Saved items can be edited in their own window, next to the list on another monitor
+    let detach_button = button(text("Edit in new window").size(12))
+        .on_press_maybe(items.contains_key(&item.id).then_some(Message::OpenEditorWindow(item.id)))
+        .style(Modern::gray_button());
+
     let full_view = row![
+        list_panel,
+
         container(
             column![
-                row![
-                    container(text("Items").size(18).style(Modern::primary_text())).padding(5),
-                    iced::widget::horizontal_space(),
-                    button(icon::new().size(14).center())
-                        .on_press(Message::CreateNew)
-                        .style(Modern::primary_button()),
-                ].width(250),
-                search_bar,
-                header_row,   
-                items_list,
+                row![iced::widget::horizontal_space(), detach_button],
+                content,
             ]
-            .width(270)
-            .spacing(10)
-            .padding(10)
+            .spacing(5)
         )
-        .style(Modern::card_container()),
-
-        container(content)
             .width(iced::Length::Fill)
             .style(Modern::card_container())
 
@@ -1097,121 +2412,385 @@ pub struct ViewContext {
     pub available_price_levels: BTreeMap<EntityId, PriceLevel>,
 }
 
-fn matches_search(
-    item: &Item, 
-    query: &str,
+// Caches the search results of the items list. Each item's searchable text is
+// lowercased once per data generation, and the filtered ids are kept until the
+// query, the filters or the data generation change.
+#[derive(Debug, Default)]
+pub struct SearchCache {
+    index_generation: Option<u64>,
+    index: Vec<(EntityId, String)>,
+    key: Option<(u64, String, ListFilters)>,
+    ids: Vec<EntityId>,
+    // How long the last lookup took and whether it came from the cache
+    pub last_timing: Option<(Duration, bool)>,
+}
+
+impl SearchCache {
+    pub fn filtered_ids(
+        &mut self,
+        generation: u64,
+        query: &str,
+        list_filters: &ListFilters,
+        items: &BTreeMap<EntityId, Item>,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+        tax_groups: &BTreeMap<EntityId, TaxGroup>,
+        security_levels: &BTreeMap<EntityId, SecurityLevel>,
+        report_categories: &BTreeMap<EntityId, ReportCategory>,
+        choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+    ) -> Vec<EntityId> {
+        let started = Instant::now();
+        let key = (generation, query.to_string(), list_filters.clone());
+
+        if self.key.as_ref() == Some(&key) {
+            self.last_timing = Some((started.elapsed(), true));
+            return self.ids.clone();
+        }
+
+        if self.index_generation != Some(generation) {
+            self.index = items.values()
+                .map(|item| (item.id, search_text(
+                    item,
+                    item_groups,
+                    tax_groups,
+                    security_levels,
+                    report_categories,
+                    choice_groups,
+                    printer_logicals,
+                    price_levels,
+                )))
+                .collect();
+            self.index_generation = Some(generation);
+        }
+
+        // An empty search shows every item
+        let show_all = query.trim().is_empty();
+        let query_lower = query.to_lowercase();
+        self.ids = self.index.iter()
+            .filter(|(_, text)| show_all || text.contains(&query_lower))
+            .filter(|(id, _)| items.get(id).is_some_and(|item| list_filters.matches(item)))
+            .map(|(id, _)| *id)
+            .collect();
+
+        self.key = Some(key);
+        self.last_timing = Some((started.elapsed(), false));
+        self.ids.clone()
+    }
+}
+
+// Lowercased names the search matches against: the item's own name and the
+// names of the groups, levels and printers it belongs to
+fn search_text(
+    item: &Item,
     item_groups: &BTreeMap<EntityId, ItemGroup>,
     tax_groups: &BTreeMap<EntityId, TaxGroup>,
     security_levels: &BTreeMap<EntityId, SecurityLevel>,
-    revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
     report_categories: &BTreeMap<EntityId, ReportCategory>,
-    product_classes: &BTreeMap<EntityId, ProductClass>,
     choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
     printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
     price_levels: &BTreeMap<EntityId, PriceLevel>,
-) -> bool {
+) -> String {
+    let mut names = vec![item.name.as_str()];
 
-    // If the search bar is empty, show all items
-    if query.trim().is_empty() {
-        return true;
+    names.extend(item.item_group.and_then(|id| item_groups.get(&id)).map(|e| e.name.as_str()));
+    names.extend(item.tax_group.and_then(|id| tax_groups.get(&id)).map(|e| e.name.as_str()));
+    names.extend(item.security_level.and_then(|id| security_levels.get(&id)).map(|e| e.name.as_str()));
+    names.extend(item.report_category.and_then(|id| report_categories.get(&id)).map(|e| e.name.as_str()));
+
+    for (id, _) in item.choice_groups.iter().flatten() {
+        names.extend(choice_groups.get(id).map(|e| e.name.as_str()));
+    }
+    for (id, _) in item.printer_logicals.iter().flatten() {
+        names.extend(printer_logicals.get(id).map(|e| e.name.as_str()));
+    }
+    for id in item.price_levels.iter().flatten() {
+        names.extend(price_levels.get(id).map(|e| e.name.as_str()));
     }
 
-    // Convert everything to lowercase for case-insensitive matching
-    let query_lower = query.to_lowercase();
+    // Separated by newlines so a query can't match across two names
+    names.join("\n").to_lowercase()
+}
+
 
-    if item.name.to_lowercase().contains(&query_lower) {
-        return true;
+pub fn list_item<'a>(list_text: &'a str, id: EntityId, copy_button: iced::widget::Button<'a, Message>,delete_button: Element<'a, Message>) -> Element<'a, Message> {
+    let button_content = row![
+        text(list_text).size(12).align_x(iced::Alignment::Start).width(150),
+        iced::widget::horizontal_space(),
+        entity_component::move_buttons(Message::MoveUp(id), Message::MoveDown(id)),
+        copy_button.style(accent::primary_button()),
+        delete_button
+    ].align_y(Alignment::Center);
+    
+    button_content.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(level: EntityId, cents: i64) -> ItemPrice {
+        ItemPrice { price_level_id: level, price: Decimal::new(cents, 2) }
     }
 
-    //Match on the item's item_group name
-    if let Some(ig_id) = &item.item_group {
-        if let Some(ig) = item_groups.get(ig_id) {
-            if ig.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
+    #[test]
+    fn reconcile_adds_a_zero_price_for_assigned_levels() {
+        let mut item = Item {
+            price_levels: Some(vec![1, 2]),
+            item_prices: Some(vec![price(1, 500)]),
+            ..Item::default()
+        };
 
-    //Match on the item's tax_group name
-    if let Some(tg_id) = &item.tax_group {
-        if let Some(tg) = tax_groups.get(tg_id) {
-            if tg.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
+        item.reconcile_price_levels();
 
-    //Match on the item's security_level name
-    if let Some(sl_id) = &item.security_level {
-        if let Some(sl) = security_levels.get(sl_id) {
-            if sl.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
+        assert_eq!(item.price_levels, Some(vec![1, 2]));
+        assert_eq!(item.item_prices, Some(vec![price(1, 500), price(2, 0)]));
+        assert!(!item.has_price_level_mismatch());
+    }
 
-    //Match on the item's report_category name
-    if let Some(rc_id) = &item.report_category {
-        if let Some(rc) = report_categories.get(rc_id) {
-            if rc.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
+    #[test]
+    fn reconcile_assigns_priced_levels_and_drops_duplicate_prices() {
+        let mut item = Item {
+            price_levels: None,
+            item_prices: Some(vec![price(3, 250), price(1, 100), price(3, 999)]),
+            ..Item::default()
+        };
 
-    //Match on the item's security_level name
-    if let Some(sl_id) = &item.security_level {
-        if let Some(sl) = security_levels.get(sl_id) {
-            if sl.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
+        item.reconcile_price_levels();
 
-    //Match on each choice_group's name
-    if let Some(cg_ids) = &item.choice_groups {
-        for cg_id in cg_ids {
-            if let Some(cg) = choice_groups.get(&cg_id.0) {
-                if cg.name.to_lowercase().contains(&query_lower) {
-                    return true;
-                }
-            }
-        }
-    } false;
+        assert_eq!(item.price_levels, Some(vec![3, 1]));
+        assert_eq!(item.item_prices, Some(vec![price(3, 250), price(1, 100)]));
+    }
 
-    //Match on each printer_logical's name
-    if let Some(pl_ids) = &item.printer_logicals {
-        for (pl_id, _) in pl_ids {
-            if let Some(pl) = printer_logicals.get(pl_id) {
-                if pl.name.to_lowercase().contains(&query_lower) {
-                    return true;
-                }
-            }
-        }
-    } false;
+    #[test]
+    fn reconcile_clears_both_when_nothing_is_priced() {
+        let mut item = Item {
+            price_levels: Some(Vec::new()),
+            item_prices: Some(Vec::new()),
+            ..Item::default()
+        };
 
-    
-    //Match on each price_level's name
-    if let Some(pl_ids) = &item.price_levels {
-        for pl_id in pl_ids {
-            if let Some(pl) = price_levels.get(pl_id) {
-                if pl.name.to_lowercase().contains(&query_lower) {
-                    return true;
-                }
-            }
+        item.reconcile_price_levels();
+
+        assert_eq!(item.price_levels, None);
+        assert_eq!(item.item_prices, None);
+    }
+
+    #[test]
+    fn mismatch_ignores_order_and_duplicates() {
+        let item = Item {
+            price_levels: Some(vec![2, 1, 1]),
+            item_prices: Some(vec![price(1, 100), price(2, 200)]),
+            ..Item::default()
+        };
+        assert!(!item.has_price_level_mismatch());
+
+        let item = Item { price_levels: Some(vec![1]), ..item };
+        assert!(item.has_price_level_mismatch());
+    }
+
+    #[test]
+    fn removing_the_last_price_level_clears_both_lists() {
+        let mut item = Item {
+            price_levels: Some(vec![1, 2]),
+            item_prices: Some(vec![price(1, 100), price(2, 200)]),
+            ..Item::default()
+        };
+
+        item.remove_price_level(1);
+        assert_eq!(item.price_levels, Some(vec![2]));
+        assert_eq!(item.item_prices, Some(vec![price(2, 200)]));
+
+        item.remove_price_level(2);
+        assert_eq!(item.price_levels, None);
+        assert_eq!(item.item_prices, None);
+    }
+
+    #[test]
+    fn deleted_items_drop_out_of_the_selection() {
+        let items = BTreeMap::from([(1, Item { id: 1, ..Item::default() })]);
+
+        assert_eq!(live_selection(Some(1), &items), Some(1));
+        assert_eq!(live_selection(Some(2), &items), None);
+        assert_eq!(live_selection(Some(-1), &items), Some(-1));
+        assert_eq!(live_selection(None, &items), None);
+    }
+
+    // Searches with only items and item groups to match names against
+    fn search(
+        cache: &mut SearchCache,
+        generation: u64,
+        query: &str,
+        filters: &ListFilters,
+        items: &BTreeMap<EntityId, Item>,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+    ) -> Vec<EntityId> {
+        cache.filtered_ids(
+            generation,
+            query,
+            filters,
+            items,
+            item_groups,
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+        )
+    }
+
+    #[test]
+    fn search_matches_item_and_group_names() {
+        let item_groups = BTreeMap::from([(1, ItemGroup { id: 1, name: "Drinks".to_string(), ..ItemGroup::default() })]);
+        let items = BTreeMap::from([
+            (1, Item { id: 1, name: "Burger".to_string(), ..Item::default() }),
+            (2, Item { id: 2, name: "Cola".to_string(), item_group: Some(1), ..Item::default() }),
+        ]);
+        let mut cache = SearchCache::default();
+        let filters = ListFilters::default();
+
+        assert_eq!(search(&mut cache, 0, "", &filters, &items, &item_groups), vec![1, 2]);
+        assert_eq!(search(&mut cache, 0, "BURG", &filters, &items, &item_groups), vec![1]);
+        assert_eq!(search(&mut cache, 0, "drink", &filters, &items, &item_groups), vec![2]);
+    }
+
+    #[test]
+    fn cached_results_last_until_the_generation_changes() {
+        let mut items = BTreeMap::from([(1, Item { id: 1, name: "Burger".to_string(), ..Item::default() })]);
+        let mut cache = SearchCache::default();
+        let filters = ListFilters::default();
+
+        assert_eq!(search(&mut cache, 0, "fries", &filters, &items, &BTreeMap::new()), Vec::<EntityId>::new());
+        assert_eq!(cache.last_timing.map(|(_, cached)| cached), Some(false));
+
+        // Same query and generation is served from the cache, even though the data moved on
+        items.insert(2, Item { id: 2, name: "Fries".to_string(), ..Item::default() });
+        assert_eq!(search(&mut cache, 0, "fries", &filters, &items, &BTreeMap::new()), Vec::<EntityId>::new());
+        assert_eq!(cache.last_timing.map(|(_, cached)| cached), Some(true));
+
+        assert_eq!(search(&mut cache, 1, "fries", &filters, &items, &BTreeMap::new()), vec![2]);
+        assert_eq!(cache.last_timing.map(|(_, cached)| cached), Some(false));
+    }
+
+    #[test]
+    fn list_filters_must_all_match() {
+        let item = Item {
+            course: Some(2),
+            item_prices: Some(vec![price(1, 0)]),
+            ..Item::default()
+        };
+
+        assert!(ListFilters::default().matches(&item));
+        assert!(ListFilters { course: Some(2), missing_price: true, ..ListFilters::default() }.matches(&item));
+        assert!(!ListFilters { course: Some(3), ..ListFilters::default() }.matches(&item));
+        assert!(ListFilters { price_level: Some(1), ..ListFilters::default() }.matches(&item));
+        assert!(!ListFilters { price_level: Some(2), ..ListFilters::default() }.matches(&item));
+    }
+
+    fn combo(id: EntityId, name: &str, components: &[EntityId]) -> Item {
+        Item {
+            id,
+            name: name.to_string(),
+            components: (!components.is_empty()).then(|| components.to_vec()),
+            ..Item::default()
         }
-    } false
+    }
 
-}
+    #[test]
+    fn component_cycle_finds_loops_through_stored_combos() {
+        let items = BTreeMap::from([
+            (1, combo(1, "Meal", &[2])),
+            (2, combo(2, "Side Combo", &[3])),
+            (3, combo(3, "Fries", &[])),
+        ]);
 
+        assert_eq!(component_cycle(&items[&1], &items), None);
 
-pub fn list_item<'a>(list_text: &'a str, copy_button: iced::widget::Button<'a, Message>,delete_button: iced::widget::Button<'a, Message>) -> Element<'a, Message> {
-    let button_content = row![
-        text(list_text).size(12).align_x(iced::Alignment::Start).width(150),
-        iced::widget::horizontal_space(),
-        copy_button.style(Modern::primary_button()),
-        delete_button.style(Modern::danger_button())
-    ].align_y(Alignment::Center);
-    
-    button_content.into()
-}
\ No newline at end of file
+        // The unsaved edit wins over the stored item
+        let edited = combo(3, "Fries", &[1]);
+        assert_eq!(component_cycle(&edited, &items), Some(vec![3, 1, 2, 3]));
+    }
+
+    #[test]
+    fn components_must_exist_and_combos_cannot_nest() {
+        let items = BTreeMap::from([
+            (1, combo(1, "Burger", &[])),
+            (2, combo(2, "Fries", &[])),
+            (3, combo(3, "Meal", &[1, 2])),
+        ]);
+
+        assert_eq!(validate_components(&combo(4, "Big Meal", &[1, 2]), &items), Ok(()));
+        assert_eq!(
+            validate_components(&combo(4, "Big Meal", &[1, 9]), &items),
+            Err("Component 9 does not exist".to_string())
+        );
+        assert_eq!(
+            validate_components(&combo(4, "Big Meal", &[3]), &items),
+            Err("'Meal' is a combo and can't be a component of another combo".to_string())
+        );
+        assert_eq!(
+            validate_components(&combo(1, "Burger", &[2]), &items),
+            Err("'Burger' is a component of 'Meal' and can't be a combo itself".to_string())
+        );
+    }
+
+    #[test]
+    fn removing_the_last_component_clears_the_combo() {
+        let mut meal = combo(3, "Meal", &[1, 2]);
+
+        assert!(meal.remove_component(1));
+        assert!(!meal.remove_component(1));
+        assert!(meal.remove_component(2));
+        assert_eq!(meal.components, None);
+        assert!(!meal.is_combo());
+    }
+
+    #[test]
+    fn combos_and_the_item_itself_cannot_be_components() {
+        let burger = combo(1, "Burger", &[]);
+
+        assert!(burger.can_be_component(3));
+        assert!(!burger.can_be_component(1));
+        assert!(!combo(3, "Meal", &[1]).can_be_component(4));
+    }
+
+    #[test]
+    fn export_selection_is_the_one_item_or_every_item() {
+        let items = BTreeMap::from([
+            (1, Item { id: 1, name: "Burger".to_string(), ..Item::default() }),
+            (2, Item { id: 2, name: "Fries".to_string(), ..Item::default() }),
+        ]);
+
+        let one = export_selection(Some(2), &items).unwrap();
+        assert_eq!(one.keys().copied().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(export_selection(None, &items), Some(items.clone()));
+        assert_eq!(export_selection(Some(9), &items), None);
+    }
+
+    #[test]
+    fn deleted_price_levels_are_dropped_from_the_draft() {
+        let levels = BTreeMap::from([
+            (1, PriceLevel { id: 1, ..PriceLevel::default() }),
+            (2, PriceLevel { id: 2, ..PriceLevel::default() }),
+        ]);
+        let mut item = Item {
+            price_levels: Some(vec![1, 2, 5]),
+            item_prices: Some(vec![price(1, 500), price(5, 300), price(7, 100)]),
+            ..Item::default()
+        };
+
+        assert_eq!(item.drop_missing_price_levels(&levels), vec![5, 7]);
+        assert_eq!(item.price_levels, Some(vec![1, 2]));
+        assert_eq!(item.item_prices, Some(vec![price(1, 500), price(2, 0)]));
+    }
+
+    #[test]
+    fn description_editor_keeps_line_breaks() {
+        let editor = DescriptionEditor::new("Quarter pound\nOn a brioche bun");
+
+        assert_eq!(editor.text(), "Quarter pound\nOn a brioche bun");
+        assert_eq!(editor.clone().text(), editor.text());
+        assert_eq!(DescriptionEditor::default().text(), "");
+    }
+}
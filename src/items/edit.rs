@@ -1,17 +1,19 @@
 use iced::widget::text::LineHeight;
 use iced::widget::{
-    button, checkbox, column, combo_box, container, pick_list, row, 
-    text, text_input, horizontal_space, scrollable
+    button, checkbox, column, container, pick_list, row, 
+    text, text_editor, text_input, horizontal_space, scrollable, tooltip
 };
 use iced_modern_theme::Modern;
 use iced::{Element, Length};
 use std::collections::BTreeMap;
+use crate::accent;
 use crate::data_types::{EntityId, ItemPrice};
 use crate::data_types;
 use crate::{
     choice_groups::ChoiceGroup,
     item_groups::ItemGroup,
     price_levels::PriceLevel,
+    stores::Store,
     printer_logicals::PrinterLogical,
     product_classes::ProductClass,
     report_categories::ReportCategory,
@@ -21,7 +23,8 @@ use crate::{
     icon,
 };
 use crate::HotKey;
-use super::{Item, Action, Operation, EditState};
+use crate::entity_component::{self, DualListMessage};
+use super::{Item, Action, Operation, EditState, AttributeField};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -30,17 +33,27 @@ pub enum Message {
     UpdateButton1(String),
     UpdateButton2(String),
     UpdatePrinterText(String),
+    EditDescription(text_editor::Action),
+    PickImage,
+    ImagePicked(Option<std::path::PathBuf>),
 
     // Classifications
     SelectItemGroup(Option<EntityId>),
     SelectProductClass(Option<EntityId>),
     SelectRevenueCategory(Option<EntityId>),
     SelectTaxGroup(Option<EntityId>),
+    SelectCourse(Option<u8>),
     SelectSecurityLevel(Option<EntityId>),
     SelectReportCategory(Option<EntityId>),
     ChoiceGroupSelected(EntityId),
     PriceLevelSelected(EntityId),
     PrinterLogicalSelected(EntityId),
+    // Creating a missing reference without leaving the editor
+    StartInlineCreate(super::AttributeField),
+    UpdateInlineName(String),
+    UpdateInlineId(String),
+    ConfirmInlineCreate,
+    CancelInlineCreate,
 
     // Pricing
     UpdateCostAmount(String),
@@ -50,6 +63,9 @@ pub enum Message {
     RemovePriceLevel(EntityId),
     UpdateStorePriceLevel(Option<EntityId>),
     UpdatePrice(EntityId, String),
+    ToggleStoreGroup(EntityId),
+    AssignList(AssignList, DualListMessage),
+    AssignAllFiltered,
 
     // Weight
     ToggleUseWeight(bool),
@@ -87,11 +103,30 @@ pub enum Message {
     RemoveChoiceGroup(EntityId),
     AddPrinterLogical(EntityId),
     RemovePrinterLogical(EntityId),
+    AddComponent(EntityId),
+    RemoveComponent(EntityId),
 
     Save,
+    SaveAndAddAnother,
     Cancel,
+
+    // Carry-over template for "Save & add another"
+    ToggleCarryOver(super::CarryField, bool),
+    ClearCarriedOver,
+}
+
+// The keyboard assignment lists in the editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignList {
+    ChoiceGroups,
+    PrinterLogicals,
+    PriceLevels,
+    Components,
 }
 
+// Id of the item name input so new drafts can focus it
+pub const NAME_INPUT: &str = "item_name_input";
+
 pub fn view<'a>(
     item: &'a Item,
     state: &'a EditState,
@@ -104,15 +139,61 @@ pub fn view<'a>(
     choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
     printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
     price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    stores: &'a BTreeMap<EntityId, Store>,
+    items: &'a BTreeMap<EntityId, Item>,
 ) -> Element<'a, Message> {
     let header = row![
-        button(icon::save().size(14)).on_press(Message::Save).style(Modern::primary_button()),
+        button(icon::save().size(14)).on_press(Message::Save).style(accent::primary_button()),
+        button(text("Save & add another").size(14))
+            .on_press(Message::SaveAndAddAnother)
+            .style(accent::primary_button()),
         button(icon::cancel().size(14)).on_press(Message::Cancel).style(Modern::danger_button()),
         horizontal_space().width(4),
     ]
-    .spacing(10);
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+
+    let carry_over = &state.carry_over;
+    let carry_over_row = row![
+        text("Carry over:").size(12).style(Modern::secondary_text()),
+        checkbox("Item Group", carry_over.item_group)
+            .on_toggle(|b| Message::ToggleCarryOver(super::CarryField::ItemGroup, b))
+            .size(14).text_size(12).style(Modern::checkbox()),
+        checkbox("Tax Group", carry_over.tax_group)
+            .on_toggle(|b| Message::ToggleCarryOver(super::CarryField::TaxGroup, b))
+            .size(14).text_size(12).style(Modern::checkbox()),
+        checkbox("Categories", carry_over.categories)
+            .on_toggle(|b| Message::ToggleCarryOver(super::CarryField::Categories, b))
+            .size(14).text_size(12).style(Modern::checkbox()),
+        checkbox("Printers", carry_over.printer_logicals)
+            .on_toggle(|b| Message::ToggleCarryOver(super::CarryField::PrinterLogicals, b))
+            .size(14).text_size(12).style(Modern::checkbox()),
+        checkbox("Price Levels", carry_over.price_levels)
+            .on_toggle(|b| Message::ToggleCarryOver(super::CarryField::PriceLevels, b))
+            .size(14).text_size(12).style(Modern::checkbox()),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center)
+    .wrap();
 
-    let validation_error = &state.validation_error;
+    let carried_over_note: Element<'a, Message> = if state.carried_over {
+        row![
+            text("Carried over from previous item").size(12).style(Modern::secondary_text()),
+            button(text("Clear all").size(12))
+                .on_press(Message::ClearCarriedOver)
+                .style(Modern::gray_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    } else {
+        row![].into()
+    };
+
+    let validation_error: Element<'a, Message> = match &state.validation_error {
+        Some(error) => text(error).style(Modern::error_text()).into(),
+        None => row![].into(),
+    };
 
     let basic_info = container(
         column![
@@ -120,6 +201,7 @@ pub fn view<'a>(
                 column![
                     text("Item Name").style(Modern::primary_text()),
                     text_input("Item Name", &item.name)
+                        .id(text_input::Id::new(NAME_INPUT))
                         .on_input(Message::UpdateName)
                         .style(Modern::inline_text_input())
                         .width(420)
@@ -190,6 +272,45 @@ pub fn view<'a>(
                     .padding(5)
                 ].spacing(10).padding(10)
             ].wrap(),
+            row![
+                column![
+                    row![
+                        text("Online Description").style(Modern::primary_text()),
+                        horizontal_space(),
+                        text(format!(
+                            "{}/{}",
+                            item.description.as_ref().map_or(0, |d| d.chars().count()),
+                            super::DESCRIPTION_MAX_LEN
+                        )).size(12).style(Modern::secondary_text()),
+                    ].width(630),
+                    text_editor(&state.description.0)
+                        .placeholder("Guest-facing description for online menus")
+                        .on_action(Message::EditDescription)
+                        .width(630)
+                        .height(120)
+                        .padding(5)
+                ].spacing(10).padding(10),
+            ],
+            row![
+                column![
+                    text("Image").style(Modern::primary_text()),
+                    row![
+                        text(
+                            item.image_path
+                                .as_ref()
+                                .map_or("No image assigned".to_string(), |p| p.to_string_lossy().into_owned())
+                        ).width(430),
+                        button("Browse...")
+                            .on_press(Message::PickImage)
+                            .style(Modern::system_button()),
+                        button("Clear")
+                            .on_press(Message::ImagePicked(None))
+                            .style(Modern::gray_button()),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center),
+                ].spacing(10).padding(10),
+            ],
         ]
     )
     .style(Modern::sheet_container())
@@ -209,45 +330,65 @@ pub fn view<'a>(
                 ].spacing(10).padding(10),
                 column![
                     text("Product Class").style(Modern::primary_text()),
-                    pick_list(
-                        product_classes.values().collect::<Vec<_>>(),
-                        item.product_class.and_then(|id| product_classes.get(&id)),
-                        |product_class: &ProductClass| Message::SelectProductClass(Some(product_class.id))
-                    ).width(200).style(Modern::pick_list())
+                    row![
+                        pick_list(
+                            product_classes.values().collect::<Vec<_>>(),
+                            item.product_class.and_then(|id| product_classes.get(&id)),
+                            |product_class: &ProductClass| Message::SelectProductClass(Some(product_class.id))
+                        ).width(200).style(Modern::pick_list()),
+                        create_button(AttributeField::ProductClass),
+                    ].spacing(5),
                 ].spacing(10).padding(10),
                 column![
                     text("Revenue Category").style(Modern::primary_text()),
-                    pick_list(
-                        revenue_categories.values().collect::<Vec<_>>(),
-                        item.revenue_category.and_then(|id| revenue_categories.get(&id)),
-                        |revenue_category: &RevenueCategory| Message::SelectRevenueCategory(Some(revenue_category.id))
-                    ).width(200).style(Modern::pick_list())
+                    row![
+                        pick_list(
+                            revenue_categories.values().collect::<Vec<_>>(),
+                            item.revenue_category.and_then(|id| revenue_categories.get(&id)),
+                            |revenue_category: &RevenueCategory| Message::SelectRevenueCategory(Some(revenue_category.id))
+                        ).width(200).style(Modern::pick_list()),
+                        create_button(AttributeField::RevenueCategory),
+                    ].spacing(5),
                 ].spacing(10).padding(10),
             ].wrap(),
             row![
                 column![
                     text("Tax Group").style(Modern::primary_text()),
-                    pick_list(
-                        tax_groups.values().collect::<Vec<_>>(),
-                        item.tax_group.and_then(|id| tax_groups.get(&id)),
-                        |tax_group: &TaxGroup| Message::SelectTaxGroup(Some(tax_group.id))
-                    ).width(200).style(Modern::pick_list())
+                    row![
+                        pick_list(
+                            tax_groups.values().collect::<Vec<_>>(),
+                            item.tax_group.and_then(|id| tax_groups.get(&id)),
+                            |tax_group: &TaxGroup| Message::SelectTaxGroup(Some(tax_group.id))
+                        ).width(200).style(Modern::pick_list()),
+                        create_button(AttributeField::TaxGroup),
+                    ].spacing(5),
+                    text(
+                        item.tax_group
+                            .and_then(|id| tax_groups.get(&id))
+                            .map_or("Tax: None".to_string(), |g| format!("Tax: {}", g.rate_label()))
+                    ).size(12).style(Modern::secondary_text()),
                 ].spacing(10).padding(10),
                 column![
                     text("Security Level").style(Modern::primary_text()),
-                    pick_list(
-                        security_levels.values().collect::<Vec<_>>(),
-                        item.security_level.and_then(|id| security_levels.get(&id)),
-                        |security_level| Message::SelectSecurityLevel(Some(security_level.id))
-                    ).width(200).style(Modern::pick_list())
+                    row![
+                        pick_list(
+                            security_levels.values().collect::<Vec<_>>(),
+                            item.security_level.and_then(|id| security_levels.get(&id)),
+                            |security_level| Message::SelectSecurityLevel(Some(security_level.id))
+                        ).width(200).style(Modern::pick_list()),
+                        create_button(AttributeField::SecurityLevel),
+                    ].spacing(5),
                 ].spacing(10).padding(10),
                 column![
                     text("Report Category").style(Modern::primary_text()),
-                    pick_list(
-                        report_categories.values().collect::<Vec<_>>(),
-                        item.report_category.and_then(|id| report_categories.get(&id)),
-                        |report_category: &ReportCategory| Message::SelectReportCategory(Some(report_category.id))
-                    ).width(200).style(Modern::pick_list())
+                    row![
+                        pick_list(
+                            report_categories.values().collect::<Vec<_>>(),
+                            item.report_category.and_then(|id| report_categories.get(&id)),
+                            |report_category: &ReportCategory| Message::SelectReportCategory(Some(report_category.id))
+                        ).width(200).style(Modern::pick_list()),
+                        create_button(AttributeField::ReportCategory),
+                    ].spacing(5),
                 ].spacing(10).padding(10),
             ].wrap(),
             row![
@@ -279,6 +420,23 @@ pub fn view<'a>(
                         .width(200)
                     ],
                 ].spacing(10).padding(10),
+
+                column![
+                    text("Course").style(Modern::primary_text()),
+                    row![
+                        pick_list(
+                            super::COURSES,
+                            item.course,
+                            |course| Message::SelectCourse(Some(course))
+                        )
+                        .placeholder("None")
+                        .width(120)
+                        .style(Modern::pick_list()),
+                        button("Clear")
+                            .on_press(Message::SelectCourse(None))
+                            .style(Modern::gray_button()),
+                    ].spacing(10),
+                ].spacing(10).padding(10),
             ]
             .wrap(),
         ]
@@ -459,154 +617,116 @@ pub fn view<'a>(
     .padding(10); */
 
     let choice_groups = container(
-        column![
-            column![
-                text("Choice Groups").style(Modern::primary_text()),
-                iced::widget::horizontal_space().height(5),
-                combo_box(
-                    &state.choice_groups_combo,
-                    "Add Choice Group",
-                    state.choice_group_selection.as_ref(),
-                    |choice_group: ChoiceGroup| Message::ChoiceGroupSelected(choice_group.id)
-                )
-                .input_style(Modern::combo_box())
-                .menu_style(Modern::combo_box_menu())
-                .width(200),
-            ].spacing(5),
-            iced::widget::horizontal_space().height(5),
-            row![ // Display Selected Choice Groups
-                if let Some(selected_groups) = &item.choice_groups {
-                    row(
-                        selected_groups
-                            .iter()
-                            .filter_map(|id| choice_groups.get(&id.0))
-                            .map(|group| {
-                                container(
-                                    button(
-                                        row![
-                                            text(&group.name),
-                                        ].spacing(10)
-                                    )
-                                    .on_press(Message::RemoveChoiceGroup(group.id))
-                                    .style(Modern::gray_button())
-                                    .width(Length::Shrink)
-                                ).padding(5).into()
-                            })
-                            .collect::<Vec<_>>()
-                    ).wrap()
-                } else {
-                    row![].wrap()
-                }
-            ],
-        ],
+        entity_component::dual_list(
+            "Choice Groups",
+            &state.choice_group_list,
+            choice_groups,
+            item.assigned_ids(AssignList::ChoiceGroups),
+            |message| Message::AssignList(AssignList::ChoiceGroups, message),
+        )
     )
     .style(Modern::sheet_container())
     .width(Length::Fill)
     .padding(10);
 
-    let printer_info = container(
+    // An item inside a combo can't become one, so the picker is replaced by where it is used
+    let part_of = super::combos_containing(item.id, items);
+    let components: Element<'a, Message> = if !item.is_combo() && !part_of.is_empty() {
         column![
-            column![
-                text("Printer Logicals").style(Modern::primary_text()),
-                iced::widget::horizontal_space().height(5),
-                combo_box(
-                    &state.printer_logicals_combo,
-                    "Add Printer Logical",
-                    state.printer_logicals_selection.as_ref(),
-                    |printer_logical: PrinterLogical| Message::PrinterLogicalSelected(printer_logical.id)
-                )
-                .input_style(Modern::combo_box())
-                .menu_style(Modern::combo_box_menu())
-                .width(200),
-            ].spacing(5),
-            iced::widget::horizontal_space().height(5),
-            row![ // Display Selected Printer Logicals
-            if let Some(selected_logicals) = &item.printer_logicals {
-                row(
-                    selected_logicals
-                        .iter()
-                        .filter_map(|id| printer_logicals.get(&id.0))
-                        .map(|logical| {
-                            container(
-                                button(
-                                    row![
-                                        text(&logical.name),
-                                    ].spacing(10)
-                                )
-                                .on_press(Message::RemovePrinterLogical(logical.id))
-                                .style(Modern::gray_button())
-                                .width(Length::Shrink)
-                            ).padding(5).into()
-                        })
-                        .collect::<Vec<_>>()
-                ).wrap()
-            } else {
-                row![].wrap()
-            }
-            ],
-        ],
+            text("Combo Components").style(Modern::primary_text()),
+            text(format!(
+                "Part of {}, so it can't be a combo itself",
+                part_of.iter().map(|combo| combo.name.as_str()).collect::<Vec<_>>().join(", ")
+            ))
+            .size(12)
+            .style(Modern::secondary_text()),
+        ]
+        .spacing(5)
+        .into()
+    } else {
+        let item_id = item.id;
+        entity_component::dual_list_where(
+            "Combo Components",
+            &state.component_list,
+            items,
+            item.assigned_ids(AssignList::Components),
+            move |candidate: &Item| candidate.can_be_component(item_id),
+            |message| Message::AssignList(AssignList::Components, message),
+        )
+    };
+    let components = container(components)
+        .style(Modern::sheet_container())
+        .width(Length::Fill)
+        .padding(10);
+
+    let printer_info = container(
+        entity_component::dual_list(
+            "Printer Logicals",
+            &state.printer_logical_list,
+            printer_logicals,
+            item.assigned_ids(AssignList::PrinterLogicals),
+            |message| Message::AssignList(AssignList::PrinterLogicals, message),
+        )
     )
     .style(Modern::sheet_container())
     .width(Length::Fill)
     .padding(10);
 
-// temp variables for pricing
-let assigned_price_level_ids = if let Some(item_prices) = &item.item_prices {
-    // Extract price_level_ids from item_prices
-    item_prices.iter().map(|price| price.price_level_id).collect::<Vec<_>>()
-} else {
-    item.price_levels.clone().unwrap_or_default()
-};
+// Enterprise levels first, then store levels grouped under their store
+let assigned_prices: Element<'a, Message> = 
+        if let Some(item_prices) = &item.item_prices {
+            let priced_levels: Vec<(&ItemPrice, &PriceLevel)> = item_prices
+                .iter()
+                .filter_map(|price| {
+                    // Get the price level from the id
+                    price_levels.get(&price.price_level_id).map(|level| (price, level))
+                })
+                .collect();
+
+            let mut sections: Vec<Element<'a, Message>> = vec![
+                row(
+                    priced_levels
+                        .iter()
+                        .filter(|(_, level)| level.store().is_none_or(|id| !stores.contains_key(&id)))
+                        .map(|&(item_price, price_level)| price_row(state, item_price, price_level))
+                        .collect::<Vec<_>>()
+                ).width(900).wrap().into()
+            ];
 
-let available_price_levels: Vec<PriceLevel> = price_levels.iter()
-    .filter(|(id, _)| !assigned_price_level_ids.contains(id))
-    .map(|(_, price_level)| price_level.clone())
-    .collect();
+            for store in stores.values() {
+                let store_prices: Vec<&(&ItemPrice, &PriceLevel)> = priced_levels
+                    .iter()
+                    .filter(|(_, level)| level.store() == Some(store.id))
+                    .collect();
 
-let pricing = container(
-    column![
-        text("Price Levels").style(Modern::primary_text()),
-        iced::widget::horizontal_space().height(10),
+                if store_prices.is_empty() {
+                    continue;
+                }
 
-        row![ // Display Selected Price Levels
-        if let Some(item_prices) = &item.item_prices {
-            row(
-                item_prices
-                    .iter()
-                    .filter_map(|price| {
-                        // Get the price level from the id
-                        price_levels.get(&price.price_level_id).map(|level| (price, level))
-                    })
-                    .map(|(item_price, price_level)| {
-                        // Start with the actual price from item_prices
-                        let display_price = item_price.price.to_string();
-                        
-                        // Look up any updated price from state.prices
-                        let current_price = state.prices.as_ref()
-                            .and_then(|all_prices| {
-                                all_prices.iter()
-                                    .find(|(id, _)| *id == price_level.id)
-                                    .map(|(_, price_str)| price_str.as_str())
-                            })
-                            .unwrap_or(&display_price);
+                let collapsed = state.collapsed_stores.contains(&store.id);
 
-                        row![
-                            text(&price_level.name).width(100),
-                            text_input("Price", current_price)
-                                .on_input(|price|
-                                    Message::UpdatePrice(price_level.id, price)
-                                )
-                                .style(Modern::inline_text_input())
-                                .width(125),
-                                horizontal_space().width(10),
-                            button(icon::trash().size(14))
-                                .on_press(Message::RemovePriceLevel(price_level.id))
-                                .style(Modern::danger_button()),
-                            horizontal_space().width(10),
-                        ].align_y(iced::Alignment::Center).into()
-                    })
-                    .collect::<Vec<_>>()
-            ).width(900).wrap()
+                sections.push(
+                    button(
+                        text(format!("{} {} ({})", if collapsed { "▸" } else { "▾" }, store.name, store_prices.len())).size(13)
+                    )
+                    .on_press(Message::ToggleStoreGroup(store.id))
+                    .style(Modern::system_button())
+                    .into()
+                );
+
+                if !collapsed {
+                    sections.push(
+                        row(
+                            store_prices
+                                .iter()
+                                .map(|&&(item_price, price_level)| price_row(state, item_price, price_level))
+                                .collect::<Vec<_>>()
+                        ).width(900).wrap().into()
+                    );
+                }
+            }
+
+            column(sections).spacing(5).into()
         } else if let Some(selected_prices) = &item.price_levels {
             // Fall back to price_levels if item_prices is missing
             row(
@@ -641,22 +761,27 @@ let pricing = container(
                         ].align_y(iced::Alignment::Center).into()
                     })
                     .collect::<Vec<_>>()
-            ).width(900).wrap()
+            ).width(900).wrap().into()
         } else {
-            row![button(text("No Price Levels Assigned")).style(Modern::gray_button())].wrap()
-        }
+            row![button(text("No Price Levels Assigned")).style(Modern::gray_button())].wrap().into()
+        };
+
+let pricing = container(
+    column![
+        text("Price Levels").style(Modern::primary_text()),
+        iced::widget::horizontal_space().height(10),
+
+        row![ // Display Selected Price Levels
+            assigned_prices
         ],
         iced::widget::horizontal_space().height(5),
-        row![
-            pick_list(
-                available_price_levels,
-                None::<PriceLevel>,
-                |price_level: PriceLevel| Message::PriceLevelSelected(price_level.id)
-            )
-            .width(100)
-            .placeholder("Add Price Levels")
-            .style(Modern::pick_list())
-        ].spacing(5),
+        entity_component::dual_list(
+            "Assign Price Levels",
+            &state.price_level_list,
+            price_levels,
+            item.assigned_ids(AssignList::PriceLevels),
+            |message| Message::AssignList(AssignList::PriceLevels, message),
+        ),
     ],
 )
 .style(Modern::sheet_container())
@@ -742,16 +867,21 @@ let pricing = container(
     container(
         column![
             header,
+            carry_over_row,
+            carried_over_note,
+            validation_error,
             scrollable(
                 column![
                     basic_info,
                     classifications,
+                    inline_create_form(state),
                     //weight_info,
                     flags,
                     //kitchen_info,
                     //store_info,
                     choice_groups,
                     printer_info,
+                    components,
                     pricing,
                 ]
                 .spacing(20)
@@ -772,3 +902,90 @@ pub fn handle_hotkey(hotkey: HotKey) -> Action<Operation, Message> {
         _ => Action::none(),
     }
 }
+
+// Price input for one assigned level, showing any unsaved edit over the stored price
+fn price_row<'a>(
+    state: &'a EditState,
+    item_price: &ItemPrice,
+    price_level: &'a PriceLevel,
+) -> Element<'a, Message> {
+    let display_price = item_price.price.to_string();
+
+    // Look up any updated price from state.prices
+    let current_price = state.prices.as_ref()
+        .and_then(|all_prices| {
+            all_prices.iter()
+                .find(|(id, _)| *id == price_level.id)
+                .map(|(_, price_str)| price_str.as_str())
+        })
+        .unwrap_or(&display_price);
+
+    row![
+        text(&price_level.name).width(100),
+        text_input("Price", current_price)
+            .on_input(|price|
+                Message::UpdatePrice(price_level.id, price)
+            )
+            .style(Modern::inline_text_input())
+            .width(125),
+            horizontal_space().width(10),
+        button(icon::trash().size(14))
+            .on_press(Message::RemovePriceLevel(price_level.id))
+            .style(Modern::danger_button()),
+        horizontal_space().width(10),
+    ].align_y(iced::Alignment::Center).into()
+}
+
+// "+" beside a reference dropdown, opens the inline create form for it
+fn create_button<'a>(field: AttributeField) -> Element<'a, Message> {
+    tooltip(
+        button(text("+").size(14))
+            .on_press(Message::StartInlineCreate(field))
+            .style(Modern::gray_button()),
+        container(text(format!("New {}", field.kind())).size(12)).padding(5).style(Modern::card_container()),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+// Name and optional id for a reference created on the spot. The new record is
+// selected on the item once it's created.
+fn inline_create_form<'a>(state: &'a EditState) -> Element<'a, Message> {
+    let Some(create) = &state.inline_create else {
+        return column![].into();
+    };
+
+    container(
+        column![
+            text(format!("New {}", create.field.kind())).style(Modern::primary_text()),
+            row![
+                text_input("Name", &create.name)
+                    .on_input(Message::UpdateInlineName)
+                    .on_submit(Message::ConfirmInlineCreate)
+                    .style(Modern::inline_text_input())
+                    .width(250)
+                    .padding(5),
+                text_input("Next free ID", &create.id)
+                    .on_input(Message::UpdateInlineId)
+                    .on_submit(Message::ConfirmInlineCreate)
+                    .style(Modern::inline_text_input())
+                    .width(120)
+                    .padding(5),
+                button("Create")
+                    .on_press(Message::ConfirmInlineCreate)
+                    .style(accent::primary_button()),
+                button("Cancel")
+                    .on_press(Message::CancelInlineCreate)
+                    .style(Modern::gray_button()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            text(create.error.as_deref().unwrap_or_default()).size(12).style(Modern::error_text()),
+        ]
+        .spacing(10)
+    )
+    .style(Modern::sheet_container())
+    .width(Length::Fill)
+    .padding(20)
+    .into()
+}
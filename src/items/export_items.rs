@@ -1,199 +1,407 @@
+use crate::data_types::Timestamps;
 use crate::items::{Item, ItemPrice};
 use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
 
+// Column names for the InfoGenesis item export, prices/choice groups/printers
+// span two spreadsheet columns in the original template but are one field here
+pub const EXPORT_HEADERS: &[&str] = &[
+    "Add", "Item ID", "Item Name", "Button 1", "Button 2", "Kitchen Printer Text",
+    "Default Price & Price Levels", "Product Class ID", "Revenue Category ID", "Tax Group ID",
+    "Security Level ID", "Report Category ID", "Use Weight Flag", "Weight Tare Amount", "SKU #",
+    "Bar Gun Code", "Cost Amount", "Reserved", "Ask Price", "Print on Check", "Discountable",
+    "Voidable", "Not Active (86'd)", "Tax Included", "Item Group ID", "Customer Receipt Text",
+    "Allow Price Override", "Reserved", "Choice Groups", "Kitchen Printers (Logical)", "Covers",
+    "Store ID", "Kitchen Video Text", "KDS Department", "KDS Category", "KDS Cook Time (secs.)",
+    "Store Price Level", "Image ID", "Stock Item Flag", "Language ISO Code", "Reserved", "Reserved",
+];
+
+pub const TAX_RATE_HEADER: &str = "Tax Rate %";
+pub const COURSE_HEADER: &str = "Course";
+pub const COMPONENTS_HEADER: &str = "Combo Components";
+pub const IMAGE_HEADER: &str = "Image";
+pub const TIMESTAMP_HEADERS: [&str; 2] = ["Created", "Modified"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ImagePathFormat {
+    #[default]
+    Omit,
+    FullPath,
+    FileName,
+}
+
+impl crate::persistence::KnownVariants for ImagePathFormat {
+    fn variants() -> &'static [Self] {
+        Self::ALL
+    }
+}
+
+impl ImagePathFormat {
+    pub const ALL: &'static [Self] = &[
+        Self::Omit,
+        Self::FullPath,
+        Self::FileName,
+    ];
+
+    fn format(&self, path: &Path) -> String {
+        match self {
+            Self::Omit => String::new(),
+            Self::FullPath => path.to_string_lossy().into_owned(),
+            Self::FileName => path.file_name()
+                .map_or(String::new(), |name| name.to_string_lossy().into_owned()),
+        }
+    }
+}
+
+impl fmt::Display for ImagePathFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Omit => write!(f, "No image column"),
+            Self::FullPath => write!(f, "Image full path"),
+            Self::FileName => write!(f, "Image file name"),
+        }
+    }
+}
+
+// Optional trailing columns appended after the InfoGenesis layout
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExportOptions {
+    // Indexes into EXPORT_HEADERS in the order they're written, None for the full layout
+    pub columns: Option<Vec<usize>>,
+    pub tax_rate: bool,
+    pub course: bool,
+    pub components: bool,
+    pub image_path: ImagePathFormat,
+    pub timestamps: bool,
+}
+
+// Template columns written, in order. Indexes from an older or hand-edited
+// settings file that are out of range or repeated are dropped.
+pub fn selected_columns(columns: Option<&[usize]>) -> Vec<usize> {
+    match columns {
+        Some(columns) => {
+            let mut seen = Vec::with_capacity(columns.len());
+            for &index in columns {
+                if index < EXPORT_HEADERS.len() && !seen.contains(&index) {
+                    seen.push(index);
+                }
+            }
+            seen
+        }
+        None => (0..EXPORT_HEADERS.len()).collect(),
+    }
+}
+
+// Header line matching the options used by export_lines
+pub fn export_header(options: &ExportOptions) -> String {
+    let mut headers = selected_columns(options.columns.as_deref())
+        .into_iter()
+        .map(|index| format!("\"{}\"", EXPORT_HEADERS[index]))
+        .collect::<Vec<_>>();
+    if options.tax_rate {
+        headers.push(format!("\"{}\"", TAX_RATE_HEADER));
+    }
+    if options.course {
+        headers.push(format!("\"{}\"", COURSE_HEADER));
+    }
+    if options.components {
+        headers.push(format!("\"{}\"", COMPONENTS_HEADER));
+    }
+    if options.image_path != ImagePathFormat::Omit {
+        headers.push(format!("\"{}\"", IMAGE_HEADER));
+    }
+    if options.timestamps {
+        headers.extend(TIMESTAMP_HEADERS.iter().map(|h| format!("\"{}\"", h)));
+    }
+    headers.join(",")
+}
+
+// Builds the export lines for the given items. Both the file export and the
+// preview in settings go through here, so what is previewed is what is written.
+pub fn export_lines<'a>(
+    items: impl Iterator<Item = &'a Item>,
+    tax_rates: &BTreeMap<i32, Decimal>,
+    options: &ExportOptions,
+) -> Vec<String> {
+    let columns = options.columns.as_deref().map(|columns| selected_columns(Some(columns)));
+
+    items
+        .map(|item| {
+            let mut line = match &columns {
+                Some(columns) => {
+                    let fields = item_export_fields(item);
+                    columns.iter().map(|index| fields[*index].as_str()).collect::<Vec<_>>().join(",")
+                }
+                None => item_to_export_string(item),
+            };
+
+            // Optional trailing column with the item's tax rate as a percentage.
+            // Tax groups carry a single rate, so there is nothing to combine here.
+            if options.tax_rate {
+                line.push(',');
+                line.push_str(&prepare_tax_rate(item.tax_group, tax_rates));
+            }
+
+            // Kitchen sequencing, empty when the item is not coursed
+            if options.course {
+                line.push(',');
+                line.push_str(&item.course.map_or(String::new(), |c| c.to_string()));
+            }
+
+            // Component item ids in the same braces as choice groups, {} for non-combos
+            if options.components {
+                line.push(',');
+                line.push_str(&prepare_components(item.components.as_deref()));
+            }
+
+            if options.image_path != ImagePathFormat::Omit {
+                line.push_str(",\"");
+                line.push_str(&item.image_path.as_deref().map_or(String::new(), |p| options.image_path.format(p)));
+                line.push('"');
+            }
+
+            // Local times, empty for items saved before they were recorded
+            if options.timestamps {
+                for time in [item.timestamps.created_at, item.timestamps.modified_at] {
+                    line.push_str(",\"");
+                    line.push_str(&Timestamps::format(time));
+                    line.push('"');
+                }
+            }
+
+            line
+        })
+        .collect()
+}
+
+// Layout written by the item export, picked from the extension of the chosen file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    // Padded columns with no separators, for the InfoGenesis file importer
+    FixedWidth,
+}
+
+impl ExportFormat {
+    pub const FIXED_WIDTH_EXTENSION: &'static str = "prn";
+
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case(Self::FIXED_WIDTH_EXTENSION) => Self::FixedWidth,
+            _ => Self::Csv,
+        }
+    }
+}
+
+// How a fixed-width value fills the rest of its field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pad {
+    // Right aligned with leading zeros, for ids and amounts
+    Zeros,
+    // Left aligned with trailing spaces, for text
+    Spaces,
+}
+
+// Field name, width in characters and padding for the fixed-width layout, in
+// file order. Text longer than its field is cut to fit, ids and prices that
+// don't fit fail the export. Prices are written without the $ and to two decimals. Adjust the widths here if the importer on
+// site is set up differently.
+const FIXED_WIDTH_FIELDS: &[(&str, usize, Pad)] = &[
+    ("Item ID", 10, Pad::Zeros),
+    ("Item Name", 16, Pad::Spaces),
+    ("Button 1", 10, Pad::Spaces),
+    ("Button 2", 10, Pad::Spaces),
+    ("Kitchen Printer Text", 16, Pad::Spaces),
+    ("Default Price", 10, Pad::Zeros),
+    ("Product Class ID", 4, Pad::Zeros),
+    ("Revenue Category ID", 4, Pad::Zeros),
+    ("Tax Group ID", 4, Pad::Zeros),
+    ("Security Level ID", 4, Pad::Zeros),
+    ("Report Category ID", 4, Pad::Zeros),
+    ("Item Group ID", 4, Pad::Zeros),
+    ("Customer Receipt Text", 16, Pad::Spaces),
+    ("Not Active (86'd)", 1, Pad::Zeros),
+    ("Store ID", 5, Pad::Zeros),
+    ("Kitchen Video Text", 16, Pad::Spaces),
+];
+
+// One fixed-width line per item. Price levels, choice groups and printers have
+// no fixed-width field and are left to the CSV export
+pub fn fixed_width_lines<'a>(items: impl Iterator<Item = &'a Item>) -> Result<Vec<String>, String> {
+    items.map(item_to_fixed_width).collect()
+}
+
+fn item_to_fixed_width(item: &Item) -> Result<String, String> {
+    let values = [
+        item.id.to_string(),
+        item.name.clone(),
+        item.button1.clone(),
+        item.button2.clone().unwrap_or_default(),
+        item.printer_text.clone(),
+        item.default_price.unwrap_or_default().round_dp(2).to_string(),
+        item.product_class.unwrap_or_default().to_string(),
+        item.revenue_category.unwrap_or_default().to_string(),
+        item.tax_group.unwrap_or_default().to_string(),
+        item.security_level.unwrap_or_default().to_string(),
+        item.report_category.unwrap_or_default().to_string(),
+        item.item_group.unwrap_or_default().to_string(),
+        item.customer_receipt.clone(),
+        if item.not_active { "1" } else { "0" }.to_string(),
+        item.store_id.to_string(),
+        item.kitchen_video.clone(),
+    ];
+
+    FIXED_WIDTH_FIELDS
+        .iter()
+        .zip(values)
+        .map(|((name, width, pad), value)| {
+            fixed_width_field(&value, *width, *pad).ok_or_else(|| {
+                format!(
+                    "Item {} ({}): {} value {} does not fit in {} characters",
+                    item.id, item.name, name, value, width
+                )
+            })
+        })
+        .collect()
+}
+
+// None when a zero padded value is too long, cutting digits would change the number
+fn fixed_width_field(value: &str, width: usize, pad: Pad) -> Option<String> {
+    match pad {
+        Pad::Zeros if value.chars().count() > width => None,
+        Pad::Zeros => Some(format!("{:0>width$}", value, width = width)),
+        Pad::Spaces => {
+            let value: String = value.chars().take(width).collect();
+            Some(format!("{:<width$}", value, width = width))
+        }
+    }
+}
+
+fn prepare_components(components: Option<&[i32]>) -> String {
+    let ids = components
+        .unwrap_or_default()
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>();
+
+    format!("{{{}}}", ids.join(","))
+}
+
 pub fn item_to_export_string(item: &Item) -> String {
+    let result = item_export_fields(item).join(",");
 
-    // Pre-allocate buffer to avoid reallocations
-    let mut result = String::with_capacity(1024);
-    
-    // Helper function to wrap text in quotes and append to buffer
-    fn append_quoted(buffer: &mut String, text: &str) {
-        buffer.push('"');
-        buffer.push_str(text);
-        buffer.push('"');
+    // Debug print (optional)
+    println!("One Item's Export format:");
+    println!("{}", &result);
+
+    result
+}
+
+// One value per EXPORT_HEADERS column, already quoted where the layout wants quotes
+pub fn item_export_fields(item: &Item) -> Vec<String> {
+    fn quoted(text: &str) -> String {
+        format!("\"{}\"", text)
     }
-    
+
+    fn flag(value: bool) -> String {
+        if value { "1" } else { "0" }.to_string()
+    }
+
+    let mut fields = Vec::with_capacity(EXPORT_HEADERS.len());
+
     // add_edit_delete
-    result.push_str("\"A\"");
-    result.push(',');
-    
+    fields.push(quoted("A"));
     // item_id
-    result.push_str(&item.id.to_string());
-    result.push(',');
-    
+    fields.push(item.id.to_string());
     // item_name
-    append_quoted(&mut result, &item.name);
-    result.push(',');
-    
+    fields.push(quoted(&item.name));
     // button_1
-    append_quoted(&mut result, &item.button1);
-    result.push(',');
-    
+    fields.push(quoted(&item.button1));
     // button_2
-    append_quoted(&mut result, &item.button2.as_deref().unwrap_or(""));
-    result.push(',');
-    
+    fields.push(quoted(item.button2.as_deref().unwrap_or("")));
     // printer_text
-    append_quoted(&mut result, &item.printer_text);
-    result.push(',');
-    
+    fields.push(quoted(&item.printer_text));
     // prices
-    let prices = prepare_item_prices(item.default_price, item.item_prices.clone());
-    result.push_str(&prices);
-    result.push(',');
-    
+    fields.push(prepare_item_prices(item.default_price, item.item_prices.clone()));
     // product_class
-    result.push_str(&item.product_class.unwrap_or_default().to_string());
-    result.push(',');
-    
+    fields.push(item.product_class.unwrap_or_default().to_string());
     // revenue_category
-    result.push_str(&item.revenue_category.unwrap_or_default().to_string());
-    result.push(',');
-    
+    fields.push(item.revenue_category.unwrap_or_default().to_string());
     // tax_group
-    result.push_str(&item.tax_group.unwrap_or_default().to_string());
-    result.push(',');
-    
+    fields.push(item.tax_group.unwrap_or_default().to_string());
     // security_level
-    result.push_str(&item.security_level.unwrap_or_default().to_string());
-    result.push(',');
-    
+    fields.push(item.security_level.unwrap_or_default().to_string());
     // report_category
-    result.push_str(&item.report_category.unwrap_or_default().to_string());
-    result.push(',');
-    
+    fields.push(item.report_category.unwrap_or_default().to_string());
     // weight_flag
-    result.push_str(if item.use_weight { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.use_weight));
     // weight_tar
-    result.push_str(&item.weight_amount.to_string());
-    result.push(',');
-    
+    fields.push(item.weight_amount.to_string());
     // sku
-    result.push_str(&item.sku.as_deref().unwrap_or_default());
-    result.push(',');
-    
+    fields.push(item.sku.clone().unwrap_or_default());
     // bar_gun_code
-    result.push_str(&item.bar_gun_code.as_deref().unwrap_or_default());
-    result.push(',');
-    
+    fields.push(item.bar_gun_code.clone().unwrap_or_default());
     // cost_amount
-    let cost_str = prepare_item_cost(item.cost_amount);
-    result.push_str(&cost_str);
-    result.push(',');
-    
+    fields.push(prepare_item_cost(item.cost_amount));
     // reserved1
-    result.push('0');
-    result.push(',');
-    
+    fields.push("0".to_string());
     // ask_price
-    result.push_str(if item.ask_price { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.ask_price));
     // print_on_check
-    result.push_str(if item.print_on_check { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.print_on_check));
     // discountable
-    result.push_str(if item.discountable { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.discountable));
     // voidable
-    result.push_str(if item.voidable { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.voidable));
     // not_active
-    result.push_str(if item.not_active { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.not_active));
     // tax_included
-    result.push_str(if item.tax_included { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.tax_included));
     // item_group_id
-    result.push_str(&item.item_group.unwrap_or_default().to_string());
-    result.push(',');
-    
+    fields.push(item.item_group.unwrap_or_default().to_string());
     // receipt_text
-    append_quoted(&mut result, &item.customer_receipt);
-    result.push(',');
-    
+    fields.push(quoted(&item.customer_receipt));
     // allow_price_override
-    result.push_str(if item.allow_price_override { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.allow_price_override));
     // reserved2
-    result.push('0');
-    result.push(',');
-    
+    fields.push("0".to_string());
     // choice_groups
-    let choice_str = prepare_choice_groups(item.choice_groups.clone());
-    result.push_str(&choice_str);
-    result.push(',');
-    
+    fields.push(prepare_choice_groups(item.choice_groups.clone()));
     // printer_logicals
-    let printer_str = prepare_kitchen_printers(item.printer_logicals.clone());
-    result.push_str(&printer_str);
-    result.push(',');
-    
+    fields.push(prepare_kitchen_printers(item.printer_logicals.clone()));
     // covers
-    result.push_str(&item.covers.to_string());
-    result.push(',');
-    
+    fields.push(item.covers.to_string());
     // store_id
-    result.push_str(&item.store_id.to_string());
-    result.push(',');
-    
+    fields.push(item.store_id.to_string());
     // kds_text
-    append_quoted(&mut result, &item.kitchen_video);
-    result.push(',');
-    
+    fields.push(quoted(&item.kitchen_video));
     // kds_dept
-    result.push_str(&item.kds_dept.to_string());
-    result.push(',');
-    
+    fields.push(item.kds_dept.to_string());
     // kds_category
-    result.push_str(item.kds_category.as_str());
-    result.push(',');
-    
+    fields.push(item.kds_category.as_str().to_string());
     // kds_time
-    result.push_str(&item.kds_cooktime.to_string());
-    result.push(',');
-    
+    fields.push(item.kds_cooktime.to_string());
     // store_price
-    result.push_str("{}");
-    result.push(',');
-    
+    fields.push("{}".to_string());
     // image_id
-    result.push_str(&item.image_id.to_string());
-    result.push(',');
-    
+    fields.push(item.image_id.to_string());
     // stock_item_flag
-    result.push_str(if item.stock_item { "1" } else { "0" });
-    result.push(',');
-    
+    fields.push(flag(item.stock_item));
     // lang_iso
     if !item.language_iso_code.as_str().is_empty() {
-        result.push_str(item.language_iso_code.as_str());
+        fields.push(item.language_iso_code.as_str().to_string());
     } else {
-        result.push_str("\"\"");
+        fields.push(quoted(""));
     }
-    result.push(',');
-    
     // reserved3
-    result.push('0');
-    result.push(',');
-    
+    fields.push("0".to_string());
     // reserved4
-    result.push_str("\"\"");
-    
-    // Debug print (optional)
-    println!("One Item's Export format:");
-    println!("{}", &result);
-    
-    result
+    fields.push(quoted(""));
+
+    fields
 }
 
 
@@ -332,4 +540,55 @@ pub fn item_to_export_string(item: &Item) -> String {
         }
 
         cost_str
-}
\ No newline at end of file
+}
+
+pub fn prepare_tax_rate(tax_group: Option<i32>, tax_rates: &BTreeMap<i32, Decimal>) -> String {
+    // Rates are stored as fractions (0.0825), export them as percentages (8.25)
+    tax_group
+        .and_then(|id| tax_rates.get(&id))
+        .map_or("0".to_string(), |rate| (rate * Decimal::from(100)).normalize().to_string())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tax_rate_is_exported_as_a_percentage() {
+        let rates = BTreeMap::from([(1, Decimal::new(825, 4)), (2, Decimal::new(10, 2))]);
+
+        assert_eq!(prepare_tax_rate(Some(1), &rates), "8.25");
+        assert_eq!(prepare_tax_rate(Some(2), &rates), "10");
+    }
+
+    #[test]
+    fn missing_tax_group_exports_zero() {
+        let rates = BTreeMap::from([(1, Decimal::new(825, 4))]);
+
+        assert_eq!(prepare_tax_rate(None, &rates), "0");
+        assert_eq!(prepare_tax_rate(Some(9), &rates), "0");
+    }
+
+    #[test]
+    fn components_are_written_as_a_braced_id_list() {
+        assert_eq!(prepare_components(Some(&[3, 7][..])), "{3,7}");
+        assert_eq!(prepare_components(None), "{}");
+    }
+
+    #[test]
+    fn one_line_per_item_in_the_selected_columns() {
+        let items = [
+            Item { id: 4, name: "Burger".to_string(), tax_group: Some(1), ..Item::default() },
+            Item { id: 5, name: "Fries".to_string(), ..Item::default() },
+        ];
+        let rates = BTreeMap::from([(1, Decimal::new(825, 4))]);
+        let options = ExportOptions { columns: Some(vec![1, 2]), tax_rate: true, ..ExportOptions::default() };
+
+        assert_eq!(
+            export_lines(items.iter(), &rates, &options),
+            vec!["4,\"Burger\",8.25".to_string(), "5,\"Fries\",0".to_string()]
+        );
+        assert_eq!(export_header(&options), format!("\"{}\",\"{}\",\"{}\"", EXPORT_HEADERS[1], EXPORT_HEADERS[2], TAX_RATE_HEADER));
+    }
+}
@@ -1,6 +1,6 @@
 use std::fs;
 use std::path::PathBuf;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use csv::ReaderBuilder;
 use rust_decimal::Decimal;
 use crate::{
@@ -14,7 +14,7 @@ use crate::{
     revenue_categories::RevenueCategory,
     security_levels::SecurityLevel,
     tax_groups::TaxGroup, 
-    data_types::EntityId
+    data_types::{EntityId, Timestamps}
 };
 
 
@@ -209,7 +209,7 @@ pub fn collect_item_information(path: &PathBuf) -> Result<BTreeMap<EntityId, Ite
         };
         
         // Create Item from ImportStructure with proper type conversions
-        let item = Item {
+        let mut item = Item {
             id: entity_id,
             name: record.item_name.clone(),
             button1: record.button_1.clone(),
@@ -251,8 +251,17 @@ pub fn collect_item_information(path: &PathBuf) -> Result<BTreeMap<EntityId, Ite
             image_id: parse_i32(&record.image_id),
             stock_item: parse_bool(&record.stock_item_flag),
             language_iso_code: record.language_iso_code.clone(),
+            description: None,
+            course: None,
+            image_path: None,
+            components: None,
+            sort_order: None,
+            timestamps: Timestamps::created_now(),
         };
         //println!("{:?}", &item);
+
+        // The file only carries prices, derive the assigned levels from them
+        item.reconcile_price_levels();
         
         items_map.insert(entity_id, item);
     }
@@ -411,6 +420,74 @@ struct ImportStructure {
     reserved4: String,
 }
 
+/// What to do with an imported row whose id is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictChoice {
+    Overwrite,
+    NewId,
+}
+
+/// Items read from a CSV import into the existing data, waiting on a choice
+/// for each row whose id is already in use
+#[derive(Debug, Clone, Default)]
+pub struct PendingCsvImport {
+    pub ready: Vec<Item>,
+    pub conflicts: VecDeque<Item>,
+    // Unknown references that were cleared, one line per reference
+    pub errors: Vec<String>,
+}
+
+impl PendingCsvImport {
+    pub fn new(
+        imported: Vec<Item>,
+        existing: &BTreeMap<EntityId, Item>,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+        tax_groups: &BTreeMap<EntityId, TaxGroup>,
+    ) -> Self {
+        let mut pending = Self::default();
+
+        for mut item in imported {
+            if let Some(group) = item.item_group.filter(|id| !item_groups.contains_key(id)) {
+                pending.errors.push(format!("Item {} {}: unknown item group {}", item.id, item.name, group));
+                item.item_group = None;
+            }
+            if let Some(group) = item.tax_group.filter(|id| !tax_groups.contains_key(id)) {
+                pending.errors.push(format!("Item {} {}: unknown tax group {}", item.id, item.name, group));
+                item.tax_group = None;
+            }
+
+            if existing.contains_key(&item.id) {
+                pending.conflicts.push_back(item);
+            } else {
+                pending.ready.push(item);
+            }
+        }
+
+        pending
+    }
+
+    // Settles the next conflict, or every remaining one when `all` is set.
+    // New ids continue from the highest id in use or waiting to be imported.
+    pub fn resolve(&mut self, choice: ConflictChoice, all: bool, existing: &BTreeMap<EntityId, Item>) {
+        let count = if all { self.conflicts.len() } else { 1 };
+
+        for _ in 0..count {
+            let Some(mut item) = self.conflicts.pop_front() else {
+                break;
+            };
+
+            if choice == ConflictChoice::NewId {
+                item.id = existing.keys()
+                    .chain(self.ready.iter().map(|item| &item.id))
+                    .chain(self.conflicts.iter().map(|item| &item.id))
+                    .max()
+                    .map_or(1, |max_id| max_id + 1);
+            }
+            self.ready.push(item);
+        }
+    }
+}
+
 /// Ensures all referenced entities exist by creating defaults for missing references
 pub fn ensure_all_referenced_entities_exist(
     items: &BTreeMap<EntityId, Item>,
@@ -489,6 +566,7 @@ fn ensure_price_levels_exist(
             let price_level = PriceLevel {
                 id,
                 name: format!("Price Level {}", id),
+                timestamps: Timestamps::created_now(),
                 // Set other fields to defaults
                 ..PriceLevel::default()
             };
@@ -517,6 +595,7 @@ fn ensure_product_classes_exist(
             let product_class = ProductClass {
                 id,
                 name: format!("Product Class {}", id),
+                timestamps: Timestamps::created_now(),
                 // Other default fields
                 ..ProductClass::default()
             };
@@ -544,6 +623,7 @@ fn ensure_revenue_categories_exist(
             let revenue_category = RevenueCategory {
                 id,
                 name: format!("Revenue Category {}", id),
+                timestamps: Timestamps::created_now(),
                 // Other default fields
                 ..RevenueCategory::default()
             };
@@ -571,6 +651,7 @@ fn ensure_tax_groups_exist(
             let tax_group = TaxGroup {
                 id,
                 name: format!("Tax Group {}", id),
+                timestamps: Timestamps::created_now(),
                 // Other default fields
                 ..TaxGroup::default()
             };
@@ -598,6 +679,7 @@ fn ensure_security_levels_exist(
             let security_level = SecurityLevel {
                 id,
                 name: format!("Security Level {}", id),
+                timestamps: Timestamps::created_now(),
                 // Other default fields
                 ..SecurityLevel::default()
             };
@@ -625,6 +707,7 @@ fn ensure_report_categories_exist(
             let report_category = ReportCategory {
                 id,
                 name: format!("Report Category {}", id),
+                timestamps: Timestamps::created_now(),
                 // Other default fields
                 ..ReportCategory::default()
             };
@@ -652,6 +735,7 @@ fn ensure_item_groups_exist(
             let item_group = ItemGroup {
                 id,
                 name: format!("Item Group {}", id),
+                timestamps: Timestamps::created_now(),
                 // Other default fields
                 ..ItemGroup::default()
             };
@@ -684,6 +768,7 @@ fn ensure_choice_groups_exist(
             let choice_group = ChoiceGroup {
                 id,
                 name: format!("Choice Group {}", id),
+                timestamps: Timestamps::created_now(),
                 // Set other fields to defaults
                 ..ChoiceGroup::default()
             };
@@ -715,7 +800,9 @@ fn ensure_printer_logicals_exist(
         if !printer_logicals.contains_key(&id) {
             let printer_logical = PrinterLogical {
                 id,
-                name: format!("Printer Logical {}", id),
+                // Short enough for the printer name limit
+                name: format!("Printer {}", id),
+                timestamps: Timestamps::created_now(),
                 // Set other fields to defaults
                 ..PrinterLogical::default()
             };
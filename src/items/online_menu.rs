@@ -0,0 +1,129 @@
+use crate::data_types::EntityId;
+use crate::items::Item;
+use crate::report_categories::ReportCategory;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// One entry in the online ordering feed. Items carry no tags or allergens
+// yet, so the feed only has what the POS knows about.
+#[derive(Debug, Clone, Serialize)]
+pub struct OnlineMenuItem {
+    pub id: EntityId,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Option<Decimal>,
+    pub category: Option<String>,
+    pub course: Option<u8>,
+}
+
+impl OnlineMenuItem {
+    // Uses the price at the chosen level, or the default price when no level is chosen
+    pub fn from_item(
+        item: &Item,
+        price_level: Option<EntityId>,
+        report_categories: &BTreeMap<EntityId, ReportCategory>,
+    ) -> Self {
+        let price = match price_level {
+            Some(level) => item.item_prices
+                .as_ref()
+                .and_then(|prices| prices.iter().find(|p| p.price_level_id == level))
+                .map(|p| p.price),
+            None => item.default_price,
+        };
+
+        Self {
+            id: item.id,
+            name: item.name.clone(),
+            description: item.description.clone(),
+            price,
+            category: item.report_category
+                .and_then(|id| report_categories.get(&id))
+                .map(|category| category.name.clone()),
+            course: item.course,
+        }
+    }
+}
+
+// Inactive items are left out of the feed
+pub fn build_online_menu<'a>(
+    items: impl Iterator<Item = &'a Item>,
+    price_level: Option<EntityId>,
+    report_categories: &BTreeMap<EntityId, ReportCategory>,
+) -> Vec<OnlineMenuItem> {
+    items
+        .filter(|item| !item.not_active)
+        .map(|item| OnlineMenuItem::from_item(item, price_level, report_categories))
+        .collect()
+}
+
+pub fn to_json(entries: &[OnlineMenuItem]) -> Result<String, String> {
+    serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize online menu: {}", e))
+}
+
+// Prompts for a location unless one is given, as with the item export
+pub async fn write_online_menu(
+    entries: Vec<OnlineMenuItem>,
+    path: Option<PathBuf>,
+) -> Result<PathBuf, crate::settings::Error> {
+    let path = match path {
+        Some(path) => path,
+        None => rfd::AsyncFileDialog::new()
+            .add_filter("JSON Files", &["json"])
+            .set_title("Save Online Menu")
+            .set_file_name("online_menu.json")
+            .save_file()
+            .await
+            .as_ref()
+            .map(rfd::FileHandle::path)
+            .map(Path::to_owned)
+            .ok_or(crate::settings::Error::DialogClosed)?,
+    };
+
+    let content = to_json(&entries)
+        .map_err(|_| crate::settings::Error::IoError(std::io::ErrorKind::InvalidData))?;
+
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|error| crate::settings::Error::IoError(error.kind()))?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::ItemPrice;
+    use crate::entity_component::Entity;
+
+    #[test]
+    fn feed_uses_the_chosen_price_level_and_skips_inactive_items() {
+        let categories = BTreeMap::from([(4, ReportCategory::default_new().with_id(4).with_name("Mains".to_string()))]);
+        let items = [
+            Item {
+                id: 1,
+                name: "Burger".to_string(),
+                description: Some("Quarter pound".to_string()),
+                default_price: Some(Decimal::new(899, 2)),
+                item_prices: Some(vec![ItemPrice { price_level_id: 2, price: Decimal::new(999, 2) }]),
+                report_category: Some(4),
+                ..Item::default()
+            },
+            Item { id: 2, name: "Retired".to_string(), not_active: true, ..Item::default() },
+        ];
+
+        let at_level = build_online_menu(items.iter(), Some(2), &categories);
+        assert_eq!(at_level.len(), 1);
+        assert_eq!(at_level[0].price, Some(Decimal::new(999, 2)));
+        assert_eq!(at_level[0].category.as_deref(), Some("Mains"));
+        assert_eq!(at_level[0].description.as_deref(), Some("Quarter pound"));
+
+        let default_price = build_online_menu(items.iter(), None, &categories);
+        assert_eq!(default_price[0].price, Some(Decimal::new(899, 2)));
+
+        let unpriced_level = build_online_menu(items.iter(), Some(3), &categories);
+        assert_eq!(unpriced_level[0].price, None);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::items::Item;
+use crate::persistence::{self, AppState};
+use crate::settings::SaveFormat;
+use crate::state_diff::{self, CollectionDiff};
+use std::path::{Path, PathBuf};
+
+const SCRATCH_NAME: &str = "menu_builder_round_trip";
+
+fn scratch_path(dir: &Path, extension: &str) -> PathBuf {
+    dir.join(format!("{}.{}", SCRATCH_NAME, extension))
+}
+
+// One line per record that didn't come back the same, prefixed with the route it took
+fn difference_lines(route: &str, diffs: &[CollectionDiff]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for diff in diffs {
+        lines.extend(diff.removed.iter().map(|record| format!("{}: {} {} missing after import", route, diff.label, record)));
+        lines.extend(diff.added.iter().map(|record| format!("{}: {} {} appeared after import", route, diff.label, record)));
+        lines.extend(diff.changed.iter().map(|record| format!("{}: {} {} changed", route, diff.label, record)));
+    }
+    lines
+}
+
+// The CSV layout has no columns for these, so the re-imported item keeps the
+// live values and only what the file carries is compared
+fn with_uncarried_fields(mut reimported: Item, live: &Item) -> Item {
+    reimported.price_levels = live.price_levels.clone();
+    reimported.description = live.description.clone();
+    reimported.course = live.course;
+    reimported.image_path = live.image_path.clone();
+    reimported.components = live.components.clone();
+    reimported.sort_order = live.sort_order;
+    reimported.timestamps = live.timestamps;
+    reimported
+}
+
+// Items through the InfoGenesis CSV export and import. Items are the only
+// records with a CSV import, the other lists only export.
+pub fn item_csv_round_trip(items: &[Item], dir: &Path) -> Result<Vec<String>, String> {
+    let path = scratch_path(dir, "csv");
+
+    // The optional trailing columns are not part of the import layout, so they are never included here
+    let content = super::export_items::export_lines(
+        items.iter(),
+        &Default::default(),
+        &super::export_items::ExportOptions::default(),
+    ).join("\n");
+
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write scratch export: {}", e))?;
+
+    let reimported = super::import_items::collect_item_information(&path)
+        .map_err(|e| format!("Failed to re-import scratch export: {}", e));
+
+    // Clean up regardless of the import result
+    let _ = std::fs::remove_file(&path);
+
+    let mut reimported = reimported?;
+    let reimported: Vec<Item> = items
+        .iter()
+        .filter_map(|live| reimported.remove(&live.id).map(|item| with_uncarried_fields(item, live)))
+        .chain(reimported.into_values())
+        .collect();
+
+    let diff = state_diff::diff_collection("Items", items, &reimported);
+    Ok(difference_lines("CSV", &[diff]))
+}
+
+// The whole menu through a save and load in one format, every persisted collection
+pub fn save_file_round_trip(state: &AppState, format: SaveFormat, dir: &Path) -> Result<Vec<String>, String> {
+    let path = scratch_path(dir, format.extension());
+    let path_text = path.to_string_lossy().into_owned();
+
+    persistence::save_to_file(state, &path_text, format)?;
+    let loaded = persistence::load_from_file(&path_text);
+    let _ = std::fs::remove_file(&path);
+
+    let (loaded, _warnings) = loaded?;
+    Ok(difference_lines(&format.to_string(), &state_diff::diff_states(state, &loaded)))
+}
+
+// Runs every route on the current data and reports each record that did not
+// survive. An empty list means a clean round trip.
+pub async fn verify(state: AppState) -> Result<Vec<String>, String> {
+    let dir = std::env::temp_dir();
+
+    let mut differences = item_csv_round_trip(&state.items, &dir)?;
+    for format in SaveFormat::ALL {
+        differences.extend(save_file_round_trip(&state, *format, &dir)?);
+    }
+
+    Ok(differences)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity_component::Entity;
+    use crate::{
+        item_groups::ItemGroup, product_classes::ProductClass, report_categories::ReportCategory,
+        revenue_categories::RevenueCategory, security_levels::SecurityLevel, stores::Store, tax_groups::TaxGroup,
+    };
+    use rust_decimal::Decimal;
+
+    // Each test gets its own folder so the scratch files don't collide when tests run in parallel
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", SCRATCH_NAME, test));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // An item as it would be set up for export, with a price and every
+    // classification filled in. The layout writes a missing one as 0.
+    fn classified(id: i32, name: &str, cents: i64) -> Item {
+        Item {
+            id,
+            name: name.to_string(),
+            default_price: Some(Decimal::new(cents, 2)),
+            item_group: Some(1),
+            product_class: Some(1),
+            revenue_category: Some(1),
+            tax_group: Some(1),
+            security_level: Some(1),
+            report_category: Some(1),
+            ..Item::default()
+        }
+    }
+
+    fn sample_state() -> AppState {
+        AppState {
+            items: vec![
+                Item {
+                    description: Some("Quarter pound".to_string()),
+                    course: Some(2),
+                    printer_logicals: Some(vec![(2, true)]),
+                    ..classified(1, "Burger", 899)
+                },
+                Item { components: Some(vec![1]), choice_groups: Some(vec![(3, 1)]), ..classified(2, "Fries", 349) },
+            ],
+            item_groups: vec![ItemGroup::default_new().with_id(1).with_name("Mains".to_string())],
+            product_classes: vec![ProductClass::default_new().with_id(1).with_name("Food".to_string())],
+            revenue_categories: vec![RevenueCategory::default_new().with_id(1).with_name("Food".to_string())],
+            security_levels: vec![SecurityLevel::default_new().with_id(1).with_name("Everyone".to_string())],
+            report_categories: vec![ReportCategory::default_new().with_id(1).with_name("Kitchen".to_string())],
+            tax_groups: vec![TaxGroup { rate: Decimal::new(825, 4), ..TaxGroup::default_new().with_id(1).with_name("Food".to_string()) }],
+            stores: vec![Store::default_new().with_id(1).with_name("Downtown".to_string())],
+            ..AppState::default()
+        }
+    }
+
+    #[test]
+    fn every_collection_survives_each_save_format() {
+        let state = sample_state();
+        let dir = scratch_dir("save_formats");
+
+        for format in SaveFormat::ALL {
+            assert_eq!(save_file_round_trip(&state, *format, &dir), Ok(Vec::new()), "{}", format);
+        }
+    }
+
+    #[test]
+    fn changed_records_are_reported_with_their_route() {
+        let state = sample_state();
+        let mut changed = state.items.clone();
+        changed[0].name = "Cheeseburger".to_string();
+        changed.remove(1);
+
+        let diff = state_diff::diff_collection("Items", &state.items, &changed);
+
+        assert_eq!(
+            difference_lines("CSV", &[diff]),
+            vec![
+                "CSV: Items 2 Fries missing after import".to_string(),
+                "CSV: Items 1 Cheeseburger changed".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn fields_without_csv_columns_come_from_the_live_item() {
+        let state = sample_state();
+        let live = &state.items[0];
+        let reimported = with_uncarried_fields(Item { id: 1, name: "Burger".to_string(), ..Item::default() }, live);
+
+        assert_eq!(reimported.description, live.description);
+        assert_eq!(reimported.course, live.course);
+        assert_eq!(reimported.default_price, None);
+    }
+
+    #[test]
+    fn every_item_survives_the_csv_export_and_import() {
+        let state = sample_state();
+        let dir = scratch_dir("item_csv");
+
+        assert_eq!(item_csv_round_trip(&state.items, &dir), Ok(Vec::new()));
+    }
+}
@@ -6,6 +6,7 @@ use iced::{Element, Length};
 use iced_modern_theme::Modern;
 use std::collections::BTreeMap;
 //use crate::HotKey;
+use crate::accent;
 use crate::{
     items::{Item, EntityId},
     item_groups::ItemGroup,
@@ -26,7 +27,10 @@ use crate::{
 pub enum Message {
     Edit,
     Back,
-    ExportToCsv,
+    // true exports every item, false just this one
+    ExportToCsv(bool),
+    ExportRecord,
+    CopyJson,
 }
 
 pub fn view<'a>(
@@ -40,11 +44,25 @@ pub fn view<'a>(
     choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
     printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
     price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    items: &'a BTreeMap<EntityId, Item>,
+    image_exists: Option<bool>,
 ) -> Element<'a, Message> {
     let header = row![
         button(icon::edit().size(14))
             .on_press(Message::Edit)
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
+        button(text("Export this record...").size(14))
+            .on_press(Message::ExportRecord)
+            .style(Modern::system_button()),
+        button(text("Copy JSON").size(14))
+            .on_press(Message::CopyJson)
+            .style(Modern::system_button()),
+        button(text("Export to CSV...").size(14))
+            .on_press(Message::ExportToCsv(false))
+            .style(Modern::system_button()),
+        button(text("Export all items...").size(14))
+            .on_press(Message::ExportToCsv(true))
+            .style(Modern::system_button()),
         horizontal_space().width(4),
     ]
     .spacing(10);
@@ -79,6 +97,24 @@ pub fn view<'a>(
                         "Kitchen Video Text".to_string(), 
                         item.kitchen_video.clone())
                 ].wrap(),
+                row![
+                    info_column(
+                        "Created".to_string(),
+                        data_types::Timestamps::display(item.timestamps.created_at)),
+                    info_column(
+                        "Last Modified".to_string(),
+                        data_types::Timestamps::display(item.timestamps.modified_at)),
+                ].wrap(),
+                row![
+                    container(
+                        column![
+                            text("Online Description").style(Modern::primary_text()),
+                            text(item.description.clone().unwrap_or("None".to_string())).width(630),
+                        ]
+                        .spacing(10)
+                    )
+                    .padding(10)
+                ],
         ]
     )
     .style(Modern::sheet_container())
@@ -91,41 +127,29 @@ pub fn view<'a>(
             row![
                 info_column(
                     "Item Group".to_string(), 
-                    item.item_group
-                        .and_then(|id| item_groups.get(&id))
-                        .map_or("None".to_string(), |g| g.name.clone())
+                    reference_label(item.item_group, item_groups, |g| g.name.clone())
                 ),
                 info_column(
                     "Product Class".to_string(), 
-                    item.product_class
-                        .and_then(|id| product_classes.get(&id))
-                        .map_or("None".to_string(), |c| c.name.clone())
+                    reference_label(item.product_class, product_classes, |c| c.name.clone())
                 ),
                 info_column(
                     "Revenue Category".to_string(), 
-                    item.revenue_category
-                        .and_then(|id| revenue_categories.get(&id))
-                        .map_or("None".to_string(), |c| c.name.clone())
+                    reference_label(item.revenue_category, revenue_categories, |c| c.name.clone())
                 ),
             ].wrap(),
             row![
                 info_column(
                     "Tax Group".to_string(), 
-                    item.tax_group
-                        .and_then(|id| tax_groups.get(&id))
-                        .map_or("None".to_string(), |g| g.name.clone())
+                    reference_label(item.tax_group, tax_groups, |g| g.rate_label())
                 ),
                 info_column(
                     "Security Level".to_string(), 
-                    item.security_level
-                        .and_then(|id| security_levels.get(&id))
-                        .map_or("None".to_string(), |l| l.name.clone())
+                    reference_label(item.security_level, security_levels, |l| l.name.clone())
                 ),
                 info_column(
                     "Report Category".to_string(), 
-                    item.report_category
-                        .and_then(|id| report_categories.get(&id))
-                        .map_or("None".to_string(), |c| c.name.clone())
+                    reference_label(item.report_category, report_categories, |c| c.name.clone())
                 ),
             ].wrap(),
             row![
@@ -155,6 +179,10 @@ pub fn view<'a>(
                         .width(200)
                     ],
                 ].spacing(10).padding(10),
+                info_column(
+                    "Course".to_string(),
+                    item.course.map_or("None".to_string(), |c| c.to_string())
+                ),
             ]
             .wrap()
         ]
@@ -172,15 +200,14 @@ pub fn view<'a>(
                 if let Some(ref prices) = item.item_prices {
                     row(
                         prices.iter()
-                            .filter_map(|item_price| {
-                                price_levels.get(&item_price.price_level_id).map(|price_level|{
-                                    let label2 = row![
-                                        text(price_level.name.clone() + ": ").style(Modern::primary_text()),
-                                        text("$".to_string() + item_price.price.to_string().as_str()),
-                                        ];
+                            .map(|item_price| {
+                                let level = price_levels.get(&item_price.price_level_id);
+                                let label2 = row![
+                                    text(reference_label(Some(item_price.price_level_id), price_levels, |l| l.name.clone()) + ": ").style(Modern::primary_text()),
+                                    text("$".to_string() + item_price.price.to_string().as_str()),
+                                    ];
 
-                                    button(label2).style(Modern::gray_button()).into()
-                                })
+                                button(label2).style(reference_style(level.is_some())).into()
                             }).collect::<Vec<_>>()
                     ).spacing(10).wrap()
                 } else {
@@ -263,8 +290,11 @@ pub fn view<'a>(
             if let Some(ref printers) = item.printer_logicals {
                 row(
                     printers.iter()
-                        .filter_map(|id| printer_logicals.get(&id.0))
-                        .map(|printer| button( &*printer.name).style(Modern::gray_button()).into())
+                        .map(|(id, _)| {
+                            button(text(reference_label(Some(*id), printer_logicals, |p| p.name.clone())))
+                                .style(reference_style(printer_logicals.contains_key(id)))
+                                .into()
+                        })
                         .collect::<Vec<_>>()
                 ).spacing(10).wrap()
             } else {
@@ -311,8 +341,11 @@ pub fn view<'a>(
             if let Some(ref groups) = item.choice_groups {
                 row(
                     groups.iter()
-                        .filter_map(|id| choice_groups.get(&id.0))
-                        .map(|group| button(&*group.name).style(Modern::gray_button()).into() )
+                        .map(|(id, _)| {
+                            button(text(reference_label(Some(*id), choice_groups, |g| g.name.clone())))
+                                .style(reference_style(choice_groups.contains_key(id)))
+                                .into()
+                        })
                         .collect::<Vec<_>>()
                 ).spacing(10).wrap()
             } else {
@@ -324,12 +357,64 @@ pub fn view<'a>(
     .width(Length::Fill)
     .padding(10);
 
+    // Only combos show their components
+    let components: Element<'a, Message> = match &item.components {
+        Some(components) if !components.is_empty() => container(
+            column![
+                text("Combo Components").style(Modern::primary_text()),
+                iced::widget::horizontal_space().height(5),
+                row(
+                    components.iter()
+                        .map(|id| {
+                            button(text(reference_label(Some(*id), items, |component| component.name.clone())))
+                                .style(reference_style(items.contains_key(id)))
+                                .into()
+                        })
+                        .collect::<Vec<_>>()
+                ).spacing(10).wrap()
+            ]
+        )
+        .style(Modern::sheet_container())
+        .width(Length::Fill)
+        .padding(10)
+        .into(),
+        _ => column![].into(),
+    };
+
+    // Existence is checked when the item is opened, None means not checked yet
+    let image_info = container(
+        column![
+            text("Image").style(Modern::primary_text()),
+            match (&item.image_path, image_exists) {
+                (None, _) => Element::from(text("No image assigned")),
+                (Some(path), Some(true)) => row![
+                    iced::widget::image(path.clone()).width(120).height(120),
+                    text(path.to_string_lossy().into_owned()),
+                ]
+                .spacing(10)
+                .into(),
+                (Some(path), Some(false)) => column![
+                    text(path.to_string_lossy().into_owned()),
+                    text("⚠ Image file not found on disk").size(12).style(Modern::error_text()),
+                ]
+                .spacing(5)
+                .into(),
+                (Some(path), None) => text(path.to_string_lossy().into_owned()).into(),
+            },
+        ]
+        .spacing(10)
+    )
+    .style(Modern::sheet_container())
+    .width(Length::Fill)
+    .padding(10);
+
     container(
         column![
             header,
             scrollable(
                 column![
                     basic_info,
+                    image_info,
                     classifications,
                     //weight_info,
                     flags,
@@ -337,6 +422,7 @@ pub fn view<'a>(
                     //store_info,
                     choice_groups,
                     printer_info,
+                    components,
                     pricing,
                 ]
                 .spacing(20)
@@ -351,6 +437,21 @@ pub fn view<'a>(
     .into()
 }
 
+// "State Tax (12)", or a warning when the id points at a record that no longer exists
+fn reference_label<T>(id: Option<EntityId>, entities: &BTreeMap<EntityId, T>, name: impl Fn(&T) -> String) -> String {
+    match id {
+        None => "None".to_string(),
+        Some(id) => match entities.get(&id) {
+            Some(entity) => format!("{} ({})", name(entity), id),
+            None => format!("⚠ missing ({})", id),
+        },
+    }
+}
+
+fn reference_style(found: bool) -> impl Fn(&iced::Theme, button::Status) -> button::Style {
+    Modern::conditional_button_style(found, Modern::gray_button(), Modern::danger_button())
+}
+
 fn info_column(label: String, value: String) -> Element<'static, Message> {
     container(
         column![
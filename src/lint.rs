@@ -0,0 +1,488 @@
+use crate::data_types::EntityId;
+use crate::items::Item;
+use crate::persistence::AppState;
+use regex::Regex;
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use std::fmt;
+
+// User defined configuration checks. Rules are built from dropdowns in the
+// settings screen and evaluated against the whole menu, every rule is scoped
+// to items with an optional report category filter.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Severity {
+    #[default]
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub const ALL: &'static [Self] = &[
+        Self::Warning,
+        Self::Error,
+    ];
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "Warning"),
+            Self::Error => write!(f, "Error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextField {
+    #[default]
+    Name,
+    Button1,
+    PrinterText,
+    CustomerReceipt,
+}
+
+impl TextField {
+    pub const ALL: &'static [Self] = &[
+        Self::Name,
+        Self::Button1,
+        Self::PrinterText,
+        Self::CustomerReceipt,
+    ];
+
+    fn value<'a>(&self, item: &'a Item) -> &'a str {
+        match self {
+            Self::Name => &item.name,
+            Self::Button1 => &item.button1,
+            Self::PrinterText => &item.printer_text,
+            Self::CustomerReceipt => &item.customer_receipt,
+        }
+    }
+}
+
+impl fmt::Display for TextField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name => write!(f, "Item Name"),
+            Self::Button1 => write!(f, "Button Text"),
+            Self::PrinterText => write!(f, "Printer Text"),
+            Self::CustomerReceipt => write!(f, "Receipt Text"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReferenceField {
+    ItemGroup,
+    ProductClass,
+    RevenueCategory,
+    #[default]
+    TaxGroup,
+    SecurityLevel,
+    ReportCategory,
+}
+
+impl ReferenceField {
+    pub const ALL: &'static [Self] = &[
+        Self::ItemGroup,
+        Self::ProductClass,
+        Self::RevenueCategory,
+        Self::TaxGroup,
+        Self::SecurityLevel,
+        Self::ReportCategory,
+    ];
+
+    pub fn value(&self, item: &Item) -> Option<EntityId> {
+        match self {
+            Self::ItemGroup => item.item_group,
+            Self::ProductClass => item.product_class,
+            Self::RevenueCategory => item.revenue_category,
+            Self::TaxGroup => item.tax_group,
+            Self::SecurityLevel => item.security_level,
+            Self::ReportCategory => item.report_category,
+        }
+    }
+
+    pub fn exists(&self, state: &AppState, id: EntityId) -> bool {
+        match self {
+            Self::ItemGroup => state.item_groups.iter().any(|e| e.id == id),
+            Self::ProductClass => state.product_classes.iter().any(|e| e.id == id),
+            Self::RevenueCategory => state.revenue_categories.iter().any(|e| e.id == id),
+            Self::TaxGroup => state.tax_groups.iter().any(|e| e.id == id),
+            Self::SecurityLevel => state.security_levels.iter().any(|e| e.id == id),
+            Self::ReportCategory => state.report_categories.iter().any(|e| e.id == id),
+        }
+    }
+}
+
+impl fmt::Display for ReferenceField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ItemGroup => write!(f, "Item Group"),
+            Self::ProductClass => write!(f, "Product Class"),
+            Self::RevenueCategory => write!(f, "Revenue Category"),
+            Self::TaxGroup => write!(f, "Tax Group"),
+            Self::SecurityLevel => write!(f, "Security Level"),
+            Self::ReportCategory => write!(f, "Report Category"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    // Flags items whose field contains the text (case insensitive). A plain
+    // substring, not a pattern, so rule text needs no escaping
+    TextContains(TextField, String),
+    // Flags items whose field matches the regular expression. The pattern is
+    // checked when the rule is built, so only a hand-edited file holds a bad one
+    TextMatches(TextField, String),
+    // Flags items with no value, or a value pointing at a deleted entity
+    MissingReference(ReferenceField),
+    // Flags items that do not route to the printer logical
+    MissingPrinter(EntityId),
+    // Flags items with any price outside of min..=max
+    PriceOutside(Decimal, Decimal),
+    // Flags items whose price_levels and item_prices disagree
+    PriceLevelMismatch,
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TextContains(field, value) => write!(f, "{} contains \"{}\"", field, value),
+            Self::TextMatches(field, pattern) => write!(f, "{} matches /{}/", field, pattern),
+            Self::MissingReference(field) => write!(f, "{} is missing", field),
+            Self::MissingPrinter(id) => write!(f, "Not routed to printer {}", id),
+            Self::PriceOutside(min, max) => write!(f, "Price outside ${} - ${}", min, max),
+            Self::PriceLevelMismatch => write!(f, "Price levels out of sync with prices"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConditionKind {
+    #[default]
+    TextContains,
+    TextMatches,
+    MissingReference,
+    MissingPrinter,
+    PriceOutside,
+    PriceLevelMismatch,
+}
+
+impl ConditionKind {
+    pub const ALL: &'static [Self] = &[
+        Self::TextContains,
+        Self::TextMatches,
+        Self::MissingReference,
+        Self::MissingPrinter,
+        Self::PriceOutside,
+        Self::PriceLevelMismatch,
+    ];
+}
+
+impl fmt::Display for ConditionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TextContains => write!(f, "Text contains"),
+            Self::TextMatches => write!(f, "Text matches pattern"),
+            Self::MissingReference => write!(f, "Missing reference"),
+            Self::MissingPrinter => write!(f, "Not routed to printer"),
+            Self::PriceOutside => write!(f, "Price out of bounds"),
+            Self::PriceLevelMismatch => write!(f, "Price levels out of sync"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintRule {
+    pub name: String,
+    pub report_category: Option<EntityId>,
+    pub condition: Condition,
+    pub severity: Severity,
+}
+
+impl LintRule {
+    fn applies_to(&self, item: &Item) -> bool {
+        match self.report_category {
+            Some(category) => item.report_category == Some(category),
+            None => true,
+        }
+    }
+
+    // Compiled once per evaluation rather than once per item. None for other
+    // conditions, and for a pattern that doesn't compile, which flags nothing.
+    fn pattern(&self) -> Option<Regex> {
+        match &self.condition {
+            Condition::TextMatches(_, pattern) => match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    println!("Skipping lint rule '{}', invalid pattern: {}", self.name, e);
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn violated_by(&self, item: &Item, state: &AppState, pattern: Option<&Regex>) -> bool {
+        match &self.condition {
+            Condition::TextContains(field, value) => {
+                !value.is_empty()
+                    && field.value(item).to_lowercase().contains(&value.to_lowercase())
+            }
+            Condition::TextMatches(field, _) => {
+                pattern.is_some_and(|regex| regex.is_match(field.value(item)))
+            }
+            Condition::MissingReference(field) => {
+                match field.value(item) {
+                    Some(id) => !field.exists(state, id),
+                    None => true,
+                }
+            }
+            Condition::MissingPrinter(printer_id) => {
+                !item.printer_logicals
+                    .as_ref()
+                    .is_some_and(|printers| printers.iter().any(|(id, _)| id == printer_id))
+            }
+            Condition::PriceOutside(min, max) => {
+                let outside = |price: &Decimal| price < min || price > max;
+
+                item.default_price.as_ref().is_some_and(outside)
+                    || item.item_prices
+                        .as_ref()
+                        .is_some_and(|prices| prices.iter().any(|p| outside(&p.price)))
+            }
+            Condition::PriceLevelMismatch => item.has_price_level_mismatch(),
+        }
+    }
+}
+
+// Rules shipped with new settings files
+pub fn default_rules() -> Vec<LintRule> {
+    vec![
+        LintRule {
+            name: "No '&' in item names".to_string(),
+            report_category: None,
+            condition: Condition::TextContains(TextField::Name, "&".to_string()),
+            severity: Severity::Warning,
+        },
+        LintRule {
+            name: "Items need a tax group".to_string(),
+            report_category: None,
+            condition: Condition::MissingReference(ReferenceField::TaxGroup),
+            severity: Severity::Error,
+        },
+        LintRule {
+            name: "Prices between $0 and $500".to_string(),
+            report_category: None,
+            condition: Condition::PriceOutside(Decimal::ZERO, Decimal::new(50000, 2)),
+            severity: Severity::Warning,
+        },
+        LintRule {
+            name: "Price levels match prices".to_string(),
+            report_category: None,
+            condition: Condition::PriceLevelMismatch,
+            severity: Severity::Warning,
+        },
+    ]
+}
+
+// Rule currently being assembled in the settings screen
+#[derive(Debug, Clone, Default)]
+pub struct RuleDraft {
+    pub name: String,
+    pub kind: ConditionKind,
+    pub text_field: TextField,
+    pub text: String,
+    pub reference: ReferenceField,
+    pub printer: Option<EntityId>,
+    pub min_price: String,
+    pub max_price: String,
+    pub report_category: Option<EntityId>,
+    pub severity: Severity,
+}
+
+impl RuleDraft {
+    pub fn build(&self) -> Result<LintRule, String> {
+        if self.name.trim().is_empty() {
+            return Err("Rule name cannot be empty".to_string());
+        }
+
+        let condition = match self.kind {
+            ConditionKind::TextContains => {
+                if self.text.is_empty() {
+                    return Err("Enter the text to look for".to_string());
+                }
+                Condition::TextContains(self.text_field, self.text.clone())
+            }
+            ConditionKind::TextMatches => {
+                if self.text.is_empty() {
+                    return Err("Enter the pattern to match".to_string());
+                }
+                Regex::new(&self.text).map_err(|e| format!("Invalid pattern: {}", e))?;
+                Condition::TextMatches(self.text_field, self.text.clone())
+            }
+            ConditionKind::MissingReference => Condition::MissingReference(self.reference),
+            ConditionKind::MissingPrinter => {
+                match self.printer {
+                    Some(id) => Condition::MissingPrinter(id),
+                    None => return Err("Select a printer logical".to_string()),
+                }
+            }
+            ConditionKind::PriceOutside => {
+                let min = self.min_price.trim().parse::<Decimal>()
+                    .map_err(|_| "Invalid minimum price".to_string())?;
+                let max = self.max_price.trim().parse::<Decimal>()
+                    .map_err(|_| "Invalid maximum price".to_string())?;
+
+                if min > max {
+                    return Err("Minimum price must not exceed maximum price".to_string());
+                }
+                Condition::PriceOutside(min, max)
+            }
+            ConditionKind::PriceLevelMismatch => Condition::PriceLevelMismatch,
+        };
+
+        Ok(LintRule {
+            name: self.name.trim().to_string(),
+            report_category: self.report_category,
+            condition,
+            severity: self.severity,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    pub rule: String,
+    pub severity: Severity,
+    pub item_id: EntityId,
+    pub item_name: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {} ({})", self.severity, self.rule, self.item_name, self.item_id)
+    }
+}
+
+pub fn evaluate(rules: &[LintRule], state: &AppState) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    for rule in rules {
+        let pattern = rule.pattern();
+        for item in &state.items {
+            if rule.applies_to(item) && rule.violated_by(item, state, pattern.as_ref()) {
+                issues.push(LintIssue {
+                    rule: rule.name.clone(),
+                    severity: rule.severity,
+                    item_id: item.id,
+                    item_name: item.name.clone(),
+                });
+            }
+        }
+    }
+
+    // Built in check, only active while the settings toggle is on
+    if state.settings.require_descriptions {
+        for item in state.items.iter().filter(|item| !item.not_active) {
+            if item.description.as_ref().is_none_or(|d| d.trim().is_empty()) {
+                issues.push(LintIssue {
+                    rule: "Active items need a description".to_string(),
+                    severity: Severity::Warning,
+                    item_id: item.id,
+                    item_name: item.name.clone(),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::ItemPrice;
+
+    fn rule(condition: Condition) -> LintRule {
+        LintRule {
+            name: "Test rule".to_string(),
+            report_category: None,
+            condition,
+            severity: Severity::Warning,
+        }
+    }
+
+    #[test]
+    fn price_level_mismatch_flags_unpriced_assignments() {
+        let state = AppState::default();
+        let rule = rule(Condition::PriceLevelMismatch);
+        let item = Item {
+            price_levels: Some(vec![1, 2]),
+            item_prices: Some(vec![ItemPrice { price_level_id: 1, price: Decimal::ONE }]),
+            ..Item::default()
+        };
+
+        assert!(rule.violated_by(&item, &state, None));
+        assert!(!rule.violated_by(&Item { price_levels: Some(vec![1]), ..item }, &state, None));
+    }
+
+    #[test]
+    fn required_descriptions_only_flag_active_items() {
+        let mut state = AppState {
+            items: vec![
+                Item { id: 1, description: Some("  ".to_string()), ..Item::default() },
+                Item { id: 2, description: Some("Crispy".to_string()), ..Item::default() },
+                Item { id: 3, not_active: true, ..Item::default() },
+            ],
+            ..AppState::default()
+        };
+        assert!(evaluate(&[], &state).is_empty());
+
+        state.settings.require_descriptions = true;
+        let flagged: Vec<EntityId> = evaluate(&[], &state).iter().map(|issue| issue.item_id).collect();
+        assert_eq!(flagged, vec![1]);
+    }
+
+    #[test]
+    fn text_patterns_flag_matching_fields() {
+        let state = AppState {
+            items: vec![
+                Item { id: 1, name: "Tequila Shot".to_string(), ..Item::default() },
+                Item { id: 2, name: "Shot of Espresso".to_string(), ..Item::default() },
+                Item { id: 3, printer_text: "TEQ SHOT".to_string(), ..Item::default() },
+            ],
+            ..AppState::default()
+        };
+        let rules = [rule(Condition::TextMatches(TextField::Name, "^[A-Z][a-z]+ Shot$".to_string()))];
+
+        let flagged: Vec<EntityId> = evaluate(&rules, &state).iter().map(|issue| issue.item_id).collect();
+        assert_eq!(flagged, vec![1]);
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected_when_building() {
+        let mut draft = RuleDraft {
+            name: "Shots".to_string(),
+            kind: ConditionKind::TextMatches,
+            text: "(shot".to_string(),
+            ..RuleDraft::default()
+        };
+        assert!(draft.build().unwrap_err().starts_with("Invalid pattern"));
+
+        draft.text = "(?i)shot$".to_string();
+        assert_eq!(draft.build().unwrap().condition, Condition::TextMatches(TextField::Name, "(?i)shot$".to_string()));
+    }
+
+    #[test]
+    fn a_bad_saved_pattern_flags_nothing() {
+        let state = AppState {
+            items: vec![Item { id: 1, name: "(".to_string(), ..Item::default() }],
+            ..AppState::default()
+        };
+
+        assert!(evaluate(&[rule(Condition::TextMatches(TextField::Name, "(".to_string()))], &state).is_empty());
+    }
+}
@@ -1,18 +1,20 @@
 // #![windows_subsystem = "windows"]
 use iced::advanced::graphics::core::window;
+use iced::advanced::widget::operation::focusable;
 use iced::event;
 use iced::keyboard::{self, Key, Modifiers};
 use iced::widget::{
     focus_next, focus_previous,
-    button, column, container, row, text, vertical_space, opaque, stack
+    button, checkbox, column, container, pick_list, row, scrollable, text, text_input, horizontal_space, vertical_space, opaque, stack
 };
 use iced::{Element, Length, Size, Subscription, Task, Theme};
 use persistence::FileManager;
 use price_levels::PriceLevelType;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use rust_decimal::Decimal;
 use std::path::PathBuf;
 use std::ops::Range;
+use std::time::{Duration, Instant};
 use iced_modern_theme::Modern;
 
 mod action;
@@ -27,10 +29,21 @@ mod revenue_categories;
 mod report_categories;
 mod choice_groups;
 mod printer_logicals;
+mod stores;
 mod data_types;
 mod persistence;
 mod entity_component;
 mod icon;
+mod lint;
+mod bulk;
+mod accent;
+mod hold_to_confirm;
+mod undo_delete;
+mod record_export;
+mod state_diff;
+mod entity_export;
+mod undo;
+mod validation;
 
 use crate::{
     items::import_items,
@@ -44,12 +57,30 @@ use crate::{
     report_categories::ReportCategory,
     choice_groups::ChoiceGroup,
     printer_logicals::PrinterLogical,
+    stores::Store,
     data_types::ValidationError,
 };
 
-use data_types::{EntityId, ItemPrice};
+use data_types::{EntityId, EntityKind, Timestamps};
 pub use action::Action;
 
+// Main window width below which the sidebar collapses to a rail
+const SIDEBAR_COLLAPSE_WIDTH: f32 = 900.0;
+const SIDEBAR_COMPACT_WIDTH: f32 = 64.0;
+
+// Number of item rows shown in the export preview
+const EXPORT_PREVIEW_ROWS: usize = 20;
+
+// Clicks on Delete sooner than this after the modal opens are ignored
+const DELETE_ARM_DELAY: Duration = Duration::from_millis(500);
+// Referencing items listed in the delete dialog before it says "...and N more"
+const AFFECTED_PREVIEW: usize = 10;
+// How long a notice toast stays up
+const NOTICE_DURATION: Duration = Duration::from_secs(8);
+// Spinner shown while the data file loads, one frame per tick
+const LOADING_FRAMES: &[&str] = &["◐", "◓", "◑", "◒"];
+const LOADING_TICK: Duration = Duration::from_millis(150);
+
 fn main() -> iced::Result {
     
     iced::daemon(MenuBuilder::title, MenuBuilder::update, MenuBuilder::view)
@@ -73,15 +104,65 @@ pub enum Screen {
     ReportCategories,
     ChoiceGroups,
     PrinterLogicals,
+    Stores,
+    Validation,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Navigate(Screen),
+    ConfirmNavigate(Screen),
+    CancelNavigate,
+    DismissIdRangeFull,
+    // Item export held back by error-level configuration rules
+    ExportDespiteRuleErrors,
+    CancelBlockedExport,
     HotKey(HotKey),
+    // Arrow key step through the shown items, sent once no text field has focus
+    StepItemSelection(bool),
     ConfirmDelete(data_types::DeletionInfo),
+    UndoDelete,
+    ExpireUndoDelete(Instant),
+    ExpireNotice(Instant),
     CancelDelete,
+    ConfirmBulk,
+    CancelBulk,
+    LoadComplete(persistence::LoadResult),
+    LoadingTick,
+    RestoreNewestBackup,
+    StartFresh,
+    DismissLoadWarnings,
+    RenumberDuplicateIds,
+    KeepLastDuplicates,
+    ItemImageChecked(EntityId, bool),
+    SelectPriceMigrationTarget(Option<EntityId>),
+    MigratePricesAndDelete(data_types::DeletionInfo, EntityId),
     ToggleTheme(bool),
+    SystemThemeChanged(bool),
+    RecordExported(Result<PathBuf, String>),
+    // A record serialized to pretty JSON, put on the clipboard for support tickets
+    CopyEntityJson(String),
+    // Adds the record on the clipboard to the list screen that's open
+    PasteEntity,
+    EntityPasted(Option<String>),
+    RecordLoaded(Result<record_export::RecordFile, String>),
+    // true creates the missing references, false leaves them off the record
+    ResolveRecordImport(bool),
+    CancelRecordImport,
+    ExportCurrentScreen,
+    CurrentScreenExported(Result<PathBuf, String>),
+    Undo,
+    Redo,
+    AutosaveTick,
+    UpdateListSearch(String),
+    UpdateListSort(entity_component::ListSort),
+    ToggleBulkDeleteMode(bool),
+    ToggleBulkDeleteSelection(EntityId, bool),
+    SetBulkDeleteSelection(BTreeSet<EntityId>),
+    SelectUnused,
+    RetrySave,
+    RequestBulkDelete,
+    ConfirmBulkDelete,
 
     //Message handles
     Settings(settings::Message),
@@ -95,18 +176,36 @@ pub enum Message {
     RevenueCategories(EntityId, revenue_categories::Message),
     ReportCategories(EntityId, report_categories::Message),
     ChoiceGroups(EntityId, choice_groups::Message),
+    Stores(EntityId, stores::Message),
+    Validation(validation::Message),
 
     //import handles
     FileDropped(PathBuf),
     ImportItemsOverwriteExisting,
     ImportItemsIntoExisting,
     CancelItemImport,
+    ImportCSV,
+    ImportCSVSelected(PathBuf),
+    // Copies the whole menu to another file, the data file stays the same
+    SaveAs,
+    SaveAsSelected(PathBuf),
+    // Switches to another menu file, asking first if anything is unsaved
+    OpenFile,
+    OpenFileSelected(PathBuf),
+    // True saves the current file before opening the other one
+    ConfirmOpenFile(bool),
+    CancelOpenFile,
+    // Choice for the conflict shown, true applies it to every remaining conflict
+    ResolveCsvConflict(import_items::ConflictChoice, bool),
+    CancelCsvImport,
 
     //window handles
     WindowClosed(iced::window::Id),
-    WindowResized(iced::Size),
+    WindowResized(window::Id, iced::Size),
     RequestOpenWindow(WindowEnum),
     WindowOpened(iced::window::Id, WindowEnum),
+    OpenEditorWindow(EntityId),
+    CloseWindow(iced::window::Id),
     None,
 }
 
@@ -123,6 +222,7 @@ pub enum Operation {
     ReportCategories(EntityId, report_categories::Operation),
     ChoiceGroups(EntityId, choice_groups::Operation),
     PrinterLogicals(EntityId, printer_logicals::Operation),
+    Stores(EntityId, stores::Operation),
 }
 
 pub struct MenuBuilder {
@@ -130,13 +230,53 @@ pub struct MenuBuilder {
     screen: Screen,
     settings: settings::AppSettings,
     theme: iced::Theme,
+    system_dark: bool,
     file_manager: persistence::FileManager,
     deletion_info: data_types::DeletionInfo,
+    price_migration_target: Option<EntityId>,
+    pending_bulk: Option<bulk::PendingBulk<MenuBuilder, Message>>,
+    pending_restore: Option<undo_delete::PendingRestore>,
+    undo_history: undo::History,
+    // Set when the menu data changed without being saved, for the autosave timer
+    unsaved_changes: std::cell::Cell<bool>,
+    // Screen and selection as last written to the data file
+    saved_view: std::cell::Cell<persistence::LastView>,
+    // Single record import waiting on the create/skip choice for missing references
+    pending_record_import: Option<record_export::PendingImport>,
+    // Short-lived message shown as a toast, with when it was raised
+    notice: Option<(String, Instant)>,
+    show_unused_report_categories: bool,
+    // Printer logical whose routed items are listed below the printer list
+    printer_routing: Option<EntityId>,
+    // Records picked in the merge dropdowns of the current list screen
+    merge_selection: entity_component::MergeSelection,
+    delete_requested_at: Option<Instant>,
     show_modal: bool,
+    // Screen asked for while an edit was open, waiting on discard or stay
+    pending_navigation: Option<Screen>,
+    // Create or copy that found no free id in the kind's range, shown until dismissed
+    id_range_full: Option<String>,
+    // Export path waiting on the user, with the error-level rule failures that stopped it
+    blocked_export: Option<(PathBuf, Vec<lint::LintIssue>)>,
+    // Menu file picked to open while there were unsaved changes
+    pending_open: Option<PathBuf>,
     show_super_edit: bool,
+    // Tracked from resize events, decides whether the sidebar collapses
+    main_window_width: f32,
     show_item_import_confirmation: bool,
+    pending_csv_import: Option<import_items::PendingCsvImport>,
     error_message: Option<String>,
-    toggle_theme: bool,
+    // Why the last write of the data file failed, cleared by the next one that works
+    save_error: std::cell::RefCell<Option<String>>,
+    // Set while the data file is read in the background, nothing but window
+    // events is handled until it completes
+    loading_started: Option<Instant>,
+    // Set when the data file could not be read, saving is held off until resolved
+    load_failure: Option<String>,
+    load_warnings: Vec<String>,
+    // The file as read when it had ids used more than once, until the user
+    // chooses between renumbering them and keeping the last of each
+    pending_duplicates: Option<(persistence::AppState, Vec<persistence::DuplicateIds>)>,
     import_item_path: PathBuf,
 
     // Items
@@ -145,7 +285,29 @@ pub struct MenuBuilder {
     draft_item_id: Option<EntityId>,
     selected_item_id: Option<EntityId>,
     item_edit_state: items::EditState,
+    revenue_category_create_error: Option<String>,
+    report_category_create_error: Option<String>,
     item_search: String,
+    // Filters the entity list on screen by name, cleared when changing screens
+    list_search: String,
+    // Row order of the entity list on screen, kept when changing screens
+    list_sort: entity_component::ListSort,
+    // Checkboxes replace the entity list rows while on, cleared when changing screens
+    bulk_delete_mode: bool,
+    bulk_delete_selection: BTreeSet<EntityId>,
+    // The batch the delete confirmation is showing, empty for a single delete
+    bulk_deletions: Vec<data_types::DeletionInfo>,
+    item_carry_over: items::CarryOver,
+    item_list_filters: items::ListFilters,
+    item_search_cache: std::cell::RefCell<items::SearchCache>,
+    // Bumped whenever item data may have changed, invalidating the search cache
+    item_data_generation: u64,
+    // Last validation report and the generation it was built at, so the
+    // Validation screen isn't rebuilt from a full copy of the menu every frame
+    validation_cache: std::cell::RefCell<Option<(u64, Vec<validation::ValidationIssue>)>>,
+    // Configuration rule results, kept the same way for the settings screen
+    lint_cache: std::cell::RefCell<Option<(u64, Vec<lint::LintIssue>)>>,
+    item_image_exists: Option<(EntityId, bool)>,
  
     // Item Groups 
     item_groups: BTreeMap<EntityId, ItemGroup>,
@@ -154,6 +316,7 @@ pub struct MenuBuilder {
     // Price Levels
     price_levels: BTreeMap<EntityId, PriceLevel>,
     price_level_edit_state_vec: Vec<price_levels::PriceLevelEditState>,
+    price_level_list_options: price_levels::ListOptions,
 
     // Product Classes
     product_classes: BTreeMap<EntityId, ProductClass>,
@@ -178,10 +341,15 @@ pub struct MenuBuilder {
     // Choice Groups
     choice_groups: BTreeMap<EntityId, ChoiceGroup>,
     choice_group_edit_state_vec: Vec<entity_component::EditState>,
+    choice_group_list_options: choice_groups::ListOptions,
  
     // Printer Logicals
     printer_logicals: BTreeMap<EntityId, PrinterLogical>,
     printer_logical_edit_state_vec: Vec<entity_component::EditState>,
+
+    // Stores
+    stores: BTreeMap<EntityId, Store>,
+    store_edit_state_vec: Vec<entity_component::EditState>,
  }
  
  impl Default for MenuBuilder {
@@ -194,18 +362,49 @@ pub struct MenuBuilder {
         file_manager.ensure_data_dir()
             .expect("Failed to create data directory");
 
+        Self::empty(file_manager, accent::system_prefers_dark())
+    }
+}
+
+impl MenuBuilder {
+    // No data and default settings. Nothing is read from or written to disk,
+    // and the OS theme is whatever the caller found.
+    fn empty(file_manager: FileManager, system_dark: bool) -> Self {
         Self {
             windows: BTreeMap::new(),
             screen: Screen::Items(items::Mode::View),
             settings: settings::AppSettings::default(),
             theme: iced_modern_theme::Modern::dark_theme(),
+            system_dark,
             file_manager: file_manager,
             show_item_import_confirmation: false,
+            pending_csv_import: None,
+            pending_bulk: None,
+            pending_restore: None,
+            undo_history: undo::History::default(),
+            unsaved_changes: std::cell::Cell::new(false),
+            saved_view: std::cell::Cell::new(persistence::LastView::default()),
+            pending_record_import: None,
+            notice: None,
+            show_unused_report_categories: false,
+            printer_routing: None,
+            merge_selection: entity_component::MergeSelection::default(),
+            delete_requested_at: None,
             show_modal: false,
+            pending_navigation: None,
+            id_range_full: None,
+            blocked_export: None,
+            pending_open: None,
             show_super_edit: false,
+            main_window_width: 1201.0,
             deletion_info: data_types::DeletionInfo::new(),
+            price_migration_target: None,
             error_message: None,
-            toggle_theme: true,
+            save_error: std::cell::RefCell::new(None),
+            loading_started: None,
+            load_failure: None,
+            load_warnings: Vec::new(),
+            pending_duplicates: None,
             import_item_path: PathBuf::new(),
 
             // Items
@@ -214,7 +413,21 @@ pub struct MenuBuilder {
             draft_item_id: None,
             selected_item_id: None,
             item_edit_state: items::EditState::default(),
+            revenue_category_create_error: None,
+            report_category_create_error: None,
             item_search: String::new(),
+            list_search: String::new(),
+            list_sort: entity_component::ListSort::default(),
+            bulk_delete_mode: false,
+            bulk_delete_selection: BTreeSet::new(),
+            bulk_deletions: Vec::new(),
+            item_carry_over: items::CarryOver::default(),
+            item_list_filters: items::ListFilters::default(),
+            item_search_cache: std::cell::RefCell::new(items::SearchCache::default()),
+            item_data_generation: 0,
+            validation_cache: std::cell::RefCell::new(None),
+            lint_cache: std::cell::RefCell::new(None),
+            item_image_exists: None,
  
             // Item Groups
             item_groups: BTreeMap::new(),
@@ -223,6 +436,7 @@ pub struct MenuBuilder {
             // Price Levels 
             price_levels: BTreeMap::new(),
             price_level_edit_state_vec: Vec::new(),
+            price_level_list_options: price_levels::ListOptions::default(),
  
             // Product Classes
             product_classes: BTreeMap::new(),
@@ -247,10 +461,15 @@ pub struct MenuBuilder {
             // Choice Groups
             choice_groups: BTreeMap::new(),
             choice_group_edit_state_vec: Vec::new(),
+            choice_group_list_options: choice_groups::ListOptions::default(),
  
             // Printer Logicals
             printer_logicals: BTreeMap::new(),
             printer_logical_edit_state_vec: Vec::new(),
+
+            // Stores
+            stores: BTreeMap::new(),
+            store_edit_state_vec: Vec::new(),
         }
     }
  }
@@ -268,32 +487,73 @@ impl MenuBuilder {
     fn new() -> (Self, Task<Message>) {
         
         let mut menu_builder = MenuBuilder::default();
+        let open_window = Task::done(Message::RequestOpenWindow(WindowEnum::MainWindow));
+
+        let path = menu_builder.settings.file_path.clone();
+        if !std::path::Path::new(&path).exists() {
+            println!("No saved data file found at: {}", path);
+            menu_builder.finish_loading(Ok(None));
+            return (menu_builder, open_window);
+        }
+
+        // Parsing a large file takes a while, so the window opens straight away
+        // and the data arrives with LoadComplete
+        menu_builder.loading_started = Some(Instant::now());
+        let load = Task::perform(persistence::load_in_background(path), Message::LoadComplete);
+
+        //(menu_builder, Task::done(Message::RequestOpenWindow(WindowEnum::MainWindow)).chain(Task::done(Message::RequestOpenWindow(WindowEnum::SuperEdit))))
+        (menu_builder, Task::batch([open_window, load]))
+    }
+
+    // Applies the startup load and rebuilds everything derived from the data
+    fn finish_loading(&mut self, loaded: Result<Option<(persistence::AppState, Vec<String>)>, String>) {
+        if let Some(started) = self.loading_started.take() {
+            println!("Data file loaded in {:?}", started.elapsed());
+        }
+
+        match loaded {
+            Ok(loaded) => {
+                let mut repairs = Vec::new();
+                if let Some((state, warnings)) = loaded {
+                    for warning in &warnings {
+                        println!("Load warning: {}", warning);
+                    }
+                    self.load_warnings = warnings;
+
+                    let duplicates = persistence::duplicate_ids(&state);
+                    if !duplicates.is_empty() {
+                        self.pending_duplicates = Some((state.clone(), duplicates));
+                    }
+                    let last_view = state.last_view;
+                    self.apply_state(state);
+                    self.restore_view(last_view);
 
-        let available_choice_groups: Vec<ChoiceGroup> = menu_builder.choice_groups.values().cloned().collect();
-        let available_printer_logicals: Vec<PrinterLogical> = menu_builder.printer_logicals.values().cloned().collect();
-        let available_price_levels: Vec<PriceLevel> = menu_builder.price_levels.values().cloned().collect();
-        // Try to load state from file
-        match menu_builder.load_state() {
-            Ok(()) => {
+                    if self.settings.repair_on_load {
+                        repairs = self.repair_references();
+                    }
+                }
                 println!("Successfully loaded saved data");
-                menu_builder.item_edit_state = items::EditState::new(
-                    &menu_builder.draft_item,
-                    available_choice_groups,
-                    available_printer_logicals,
-                    available_price_levels,
-                );
 
                 // If no items were loaded, create a default one
-                if menu_builder.items.is_empty() {
+                if self.items.is_empty() {
                     let mut default_item = Item::default();
                     default_item.name = "Default".to_string();
-                    menu_builder.items.insert(1, default_item);
-                    menu_builder.selected_item_id = Some(1);
+                    self.items.insert(1, default_item);
+                    self.selected_item_id = Some(1);
                 }
 
-                menu_builder.settings.export_message = "".to_string();
-                menu_builder.settings.export_success = true;
-                menu_builder.error_message = None;
+                self.settings.export_message = "".to_string();
+                self.settings.export_success = true;
+                // Repairs are only in memory until the next save, which the autosave picks up
+                if !repairs.is_empty() {
+                    self.unsaved_changes.set(true);
+                }
+                let problems: Vec<String> = self.pending_duplicates
+                    .iter()
+                    .flat_map(|(_, duplicates)| duplicates.iter().map(|duplicate| duplicate.message()))
+                    .chain(repairs)
+                    .collect();
+                self.error_message = (!problems.is_empty()).then(|| problems.join("; "));
             }
             Err(e) => {
                 eprintln!("Failed to load state: {}", e);
@@ -301,18 +561,44 @@ impl MenuBuilder {
                 // Create a default item for new users
                 let mut default_item = Item::default();
                 default_item.name = "Default".to_string();
-                menu_builder.items.insert(1, default_item);
-                menu_builder.selected_item_id = Some(1);
-                menu_builder.error_message = Some(format!("Failed to load saved data: {}", e));
+                self.items.insert(1, default_item);
+                self.selected_item_id = Some(1);
+                self.load_failure = Some(e);
             }
         }
 
-        //(menu_builder, Task::done(Message::RequestOpenWindow(WindowEnum::MainWindow)).chain(Task::done(Message::RequestOpenWindow(WindowEnum::SuperEdit))))
-        (menu_builder, Task::done(Message::RequestOpenWindow(WindowEnum::MainWindow)))
+        self.item_edit_state = items::EditState::new(
+            &self.draft_item,
+            self.choice_groups.values().cloned().collect(),
+            self.printer_logicals.values().cloned().collect(),
+            self.price_levels.values().cloned().collect(),
+        );
+        *self.item_search_cache.borrow_mut() = items::SearchCache::default();
+        self.item_data_generation += 1;
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
         println!("Update Message received: {:?}", &message);
+
+        // Saves, deletes, imports and edits all go through messages outside
+        // this list, so anything else may have changed what the search matches
+        if !keeps_item_data(&message) {
+            self.item_data_generation += 1;
+        }
+
+        // Nothing may touch the data, or save over the file, before it has loaded
+        if self.loading_started.is_some() && !handled_while_loading(&message) {
+            println!("Ignoring message until the data file has loaded");
+            return Task::none();
+        }
+
+        // Changes to the menu data are recorded so they can be undone
+        if let Some(label) = message_undo_label(&message) {
+            if !self.undo_history.recording {
+                return self.with_undo(label, undo::Scope::ALL, move |app| app.update(message));
+            }
+        }
+
         match message {
             Message::Settings(msg) => {
                 println!("Settings message received: {:?}", &msg);
@@ -330,6 +616,7 @@ impl MenuBuilder {
                 } else {
                     Task::none()
                 };
+                hold_to_confirm::set_enabled(self.settings.hold_to_delete);
 
                 operation_task.chain(action.task)
             }
@@ -368,14 +655,12 @@ impl MenuBuilder {
     
                     operation_task.chain(action.task)
                 } else {
-                    let item = if let Some(draft_id) = self.draft_item_id {
-                        if draft_id == id {
-                            &mut self.draft_item
-                        } else {
-                            self.items.get_mut(&id).expect("Item should exist")
-                        }
-                    } else {
-                        self.items.get_mut(&id).expect("Item should exist")
+                    let item = match self.draft_item_id {
+                        Some(draft_id) if draft_id == id => &mut self.draft_item,
+                        _ => match self.items.get_mut(&id) {
+                            Some(item) => item,
+                            None => return self.missing_record("Item", id),
+                        },
                     };
 
                     let other_items: BTreeMap<EntityId, &Item> = cloned_items
@@ -599,10 +884,72 @@ impl MenuBuilder {
             
                 operation_task.chain(action.task)
             }
+            Message::Stores(id, msg) => {
+                let action = stores::update(msg)
+                    .map_operation(move |o| Operation::Stores(id, o))
+                    .map(move |m| Message::Stores(id, m));
+
+                let operation_task = if let Some(operation) = action.operation {
+                    self.perform(operation)
+                } else {
+                    Task::none()
+                };
+
+                operation_task.chain(action.task)
+            }
+            Message::Validation(validation::Message::Open(target)) => self.open_issue(target),
+            Message::Validation(validation::Message::ExportReport) => {
+                Task::perform(
+                    validation::export_report(self.validate_all()),
+                    |result| Message::Validation(validation::Message::ReportExported(result))
+                )
+            }
+            Message::Validation(validation::Message::ReportExported(result)) => {
+                match result {
+                    Ok(path) => self.show_notice(format!("Validation report saved to {}", path.to_string_lossy())),
+                    Err(e) => self.show_notice(e),
+                }
+            }
             Message::Navigate(screen) => {
-                self.screen = screen;
+                // Leaving an open edit asks first, the edit would otherwise linger half done
+                if self.leaves_edits(&screen) {
+                    self.pending_navigation = Some(screen);
+                    return Task::none();
+                }
+                self.open_screen(screen)
+            },
+            Message::ConfirmNavigate(screen) => {
+                self.pending_navigation = None;
+                self.discard_edits();
+                self.open_screen(screen)
+            },
+            Message::CancelNavigate => {
+                self.pending_navigation = None;
+                Task::none()
+            },
+            Message::DismissIdRangeFull => {
+                self.id_range_full = None;
                 Task::none()
             },
+            Message::ExportDespiteRuleErrors => {
+                match self.blocked_export.take() {
+                    Some((path, _)) => self.export_items_to(path),
+                    None => Task::none(),
+                }
+            }
+            Message::CancelBlockedExport => {
+                if let Some((_, issues)) = self.blocked_export.take() {
+                    self.settings.export_success = false;
+                    self.settings.export_message = format!(
+                        "Export cancelled, {} configuration rule error(s) to fix",
+                        issues.len()
+                    );
+                    if let Screen::Settings(_) = self.screen {
+                        self.screen = Screen::Settings(self.settings.clone());
+                    }
+                }
+                Task::none()
+            }
             Message::HotKey(hotkey) => {
                 match hotkey {
                     HotKey::Tab(modifiers) => {
@@ -612,184 +959,521 @@ impl MenuBuilder {
                             focus_next()
                         }
                     }
-                    HotKey::Escape => Task::none(),
-                }
-            }
-            Message::ConfirmDelete(deletion_info) => {
-                println!("Deleting Type: {}, id: {}", deletion_info.entity_type, deletion_info.entity_id);
-
-                match deletion_info.entity_type.as_str() {
-                    "ChoiceGroup" => {
-                        // Clean up references in all items
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(groups) = &mut item.choice_groups {
-                                // Remove this specific choice group ID from the Item.choice_groups vec
-                                groups.retain(|&group_id| group_id.0 != deletion_info.entity_id);
-                                
-                                // If vec is empty after removal, set to None
-                                if groups.is_empty() {
-                                    item.choice_groups = None;
-                                }
-                            }
+                    HotKey::Escape => {
+                        // The delete confirmation sits over every screen, so it closes first
+                        if self.show_modal {
+                            return self.update(Message::CancelDelete);
                         }
-
-                        // Delete the choice group
-                        self.choice_groups.remove(&deletion_info.entity_id);
-                        self.screen = Screen::ChoiceGroups;
-                    }
-                    "ItemGroup" => {
-                        // Find all items using this item group
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(group_id) = item.item_group {
-                                if group_id == deletion_info.entity_id {
-                                    // This item has this item group, set it to None
-                                    item.item_group = None;
-                                }
-                            }
+                        if self.pending_navigation.is_some() {
+                            return self.update(Message::CancelNavigate);
                         }
 
-                        // Delete the item group
-                        self.item_groups.remove(&deletion_info.entity_id);
-                        self.screen = Screen::ItemGroups;
-                    }
-                    "Item" => {
-                        //Delete the item
-                        if self.items.contains_key(&deletion_info.entity_id) { self.items.remove(&deletion_info.entity_id); }
-                    }
-                    "PriceLevel" => {
-                        // Clean up references in all items
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(price_levels) = &mut item.price_levels {
-                                // Remove this specific price level ID from the Item.price_levels vec
-                                price_levels.retain(|&price_id| price_id != deletion_info.entity_id);
-                                
-                                // If vec is empty after removal, set to None
-                                if price_levels.is_empty() {
-                                    item.price_levels = None;
-                                }
-                            }
-                        }
+                        if let (Screen::Items(items::Mode::Edit), Some(id)) = (&self.screen, self.selected_item_id) {
+                            let action = items::edit::handle_hotkey(HotKey::Escape)
+                                .map_operation(move |o| Operation::Items(id, o))
+                                .map(move |m| Message::Items(id, items::Message::Edit(m)));
 
-                        // Delete the price level
-                        self.price_levels.remove(&deletion_info.entity_id);
-                        self.screen = Screen::PriceLevels;
-                    }
-                    "PrinterLogical" => {
-                        // Clean up references in all items
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(printers) = &mut item.printer_logicals {
-                                // Remove this specific printer logical ID from the Item.printer_logicals vec
-                                printers.retain(|&(printer_id, _)| printer_id != deletion_info.entity_id);
-                                
-                                // If vec is empty after removal, set to None
-                                if printers.is_empty() {
-                                    item.printer_logicals = None;
-                                }
-                            }
+                            let operation_task = match action.operation {
+                                Some(operation) => self.perform(operation),
+                                None => Task::none(),
+                            };
+                            return operation_task.chain(action.task);
                         }
 
-                        // Delete the printer logical
-                        self.printer_logicals.remove(&deletion_info.entity_id);
-                        self.screen = Screen::PrinterLogicals;
-                    }
-                    "ProductClass" => {
-                        // Find all items using this product class
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(pc_id) = item.product_class {
-                                if pc_id == deletion_info.entity_id {
-                                    // This item has this product class, set it to None
-                                    item.product_class = None;
-                                }
-                            }
+                        match self.cancel_hotkey_message() {
+                            Some(message) => self.update(message),
+                            None => Task::none(),
                         }
-
-                        // Delete the product class
-                        self.product_classes.remove(&deletion_info.entity_id);
-                        self.screen = Screen::ProductClasses;
                     }
-                    "ReportCategory" => {
-                        // Find all items using this report category
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(rc_id) = item.report_category {
-                                if rc_id == deletion_info.entity_id {
-                                    // This item has this report category, set it to None
-                                    item.report_category = None;
-                                }
-                            }
-                        }
+                    HotKey::SaveAndAddAnother => {
+                        let editing = matches!(self.screen, Screen::Items(items::Mode::Edit));
 
-                        // Delete the report category
-                        self.report_categories.remove(&deletion_info.entity_id);
-                        self.screen = Screen::ReportCategories;
+                        if let (true, Some(id)) = (editing, self.selected_item_id) {
+                            self.update(Message::Items(id, items::Message::Edit(items::edit::Message::SaveAndAddAnother)))
+                        } else {
+                            Task::none()
+                        }
                     }
-                    "RevenueCategory" => {
-                        // Find all items using this revenue category
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(rc_id) = item.revenue_category {
-                                if rc_id == deletion_info.entity_id {
-                                    // This item has this revenue category, set it to None
-                                    item.revenue_category = None;
-                                }
-                            }
+                    HotKey::Save => match self.save_hotkey_message() {
+                        Some(message) => self.update(message),
+                        None => Task::none(),
+                    },
+                    HotKey::New => {
+                        if self.show_modal || self.pending_navigation.is_some() {
+                            return Task::none();
                         }
+                        let Some(message) = self.new_hotkey_message() else {
+                            return Task::none();
+                        };
 
-                        // Delete the revenue category
-                        self.revenue_categories.remove(&deletion_info.entity_id);
-                        self.screen = Screen::RevenueCategories;
+                        // The new row is the last edit opened, typing goes straight to its name
+                        let create_task = self.update(message);
+                        match self.open_edit_ids().last() {
+                            Some(&id) => create_task.chain(text_input::focus(entity_component::name_input_id(id))),
+                            None => create_task,
+                        }
                     }
-                    "SecurityLevel" => {
-                        // Find all items using this security level
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(sl_id) = item.security_level {
-                                if sl_id == deletion_info.entity_id {
-                                    // This item has this security level, set it to None
-                                    item.security_level = None;
-                                }
-                            }
+                    HotKey::Previous | HotKey::Next => {
+                        // Only while browsing, in the editor the arrows belong to its fields
+                        let browsing = matches!(self.screen, Screen::Items(items::Mode::View));
+                        if !browsing || self.show_modal || self.pending_navigation.is_some() {
+                            return Task::none();
                         }
 
-                        // Delete the security level
-                        self.security_levels.remove(&deletion_info.entity_id);
-                        self.screen = Screen::SecurityLevels;
+                        // Text fields pass the up and down arrows on, so typing in the
+                        // item search would also move the selection. Only step when no
+                        // field has focus.
+                        let forward = matches!(hotkey, HotKey::Next);
+                        iced::advanced::widget::operate(focusable::count()).map(move |count| match count.focused {
+                            Some(_) => Message::None,
+                            None => Message::StepItemSelection(forward),
+                        })
                     }
-                    "TaxGroup" => {
-                        // Find all items using this tax group
-                        for (_, item) in self.items.iter_mut() {
-                            if let Some(tg_id) = item.tax_group {
-                                if tg_id == deletion_info.entity_id {
-                                    // This item has this tax group, set it to None
-                                    item.tax_group = None;
-                                }
-                            }
-                        }
+                    HotKey::AssignAllFiltered => {
+                        let editing = matches!(self.screen, Screen::Items(items::Mode::Edit));
 
-                        // Delete the tax group
-                        self.tax_groups.remove(&deletion_info.entity_id);
-                        self.screen = Screen::TaxGroups;
+                        if let (true, Some(id)) = (editing, self.selected_item_id) {
+                            self.update(Message::Items(id, items::Message::Edit(items::edit::Message::AssignAllFiltered)))
+                        } else {
+                            Task::none()
+                        }
                     }
-                    _ => {println!("Oh No! You've tried to delete an unknown type: {}", deletion_info.entity_type);}
                 }
+            }
+            Message::ConfirmDelete(deletion_info) => {
+                if !self.delete_armed() {
+                    println!("Ignoring delete click, the confirmation was only just opened");
+                    return Task::none();
+                }
+
+                println!(
+                    "Deleting {} '{}' (id: {})",
+                    deletion_info.entity_type, deletion_info.entity_name, deletion_info.entity_id
+                );
+
+                // Taken before anything is stripped so the Undo toast can put it back
+                let before_delete = self.app_state();
+
+                self.delete_entity(&deletion_info);
 
                 self.deletion_info = data_types::DeletionInfo::new();
+                self.price_migration_target = None;
                 self.show_modal = false;
-                self.save_state().expect("Failed to save to file.");
+                self.delete_requested_at = None;
+                if let Err(e) = self.save_state() {
+                    self.handle_save_error(e);
+                }
+
+                // A new delete replaces whatever was waiting to be undone
+                let pending = undo_delete::PendingRestore::new(deletion_info, before_delete, &self.items, &self.price_levels);
+                let deleted_at = pending.deleted_at;
+                println!("{}", pending.summary());
+                self.pending_restore = Some(pending);
+
+                Task::perform(
+                    tokio::time::sleep(undo_delete::UNDO_WINDOW),
+                    move |_| Message::ExpireUndoDelete(deleted_at)
+                )
+            }
+            Message::UndoDelete => {
+                let Some(pending) = self.pending_restore.take() else {
+                    return Task::none();
+                };
+
+                if pending.is_expired() || !pending.still_applies(&self.items, &self.price_levels) {
+                    println!("Not undoing delete, the affected records have changed since");
+                    return Task::none();
+                }
+
+                let id = pending.deletion.entity_id;
+                let before = &pending.before;
+
+                let restored = match pending.deletion.entity_type.as_str() {
+                    "ChoiceGroup" => undo_delete::restore_entity(&mut self.choice_groups, &before.choice_groups, id, |e| e.id),
+                    "ItemGroup" => undo_delete::restore_entity(&mut self.item_groups, &before.item_groups, id, |e| e.id),
+                    "PrinterLogical" => undo_delete::restore_entity(&mut self.printer_logicals, &before.printer_logicals, id, |e| e.id),
+                    "ProductClass" => undo_delete::restore_entity(&mut self.product_classes, &before.product_classes, id, |e| e.id),
+                    "ReportCategory" => undo_delete::restore_entity(&mut self.report_categories, &before.report_categories, id, |e| e.id),
+                    "RevenueCategory" => undo_delete::restore_entity(&mut self.revenue_categories, &before.revenue_categories, id, |e| e.id),
+                    "SecurityLevel" => undo_delete::restore_entity(&mut self.security_levels, &before.security_levels, id, |e| e.id),
+                    "TaxGroup" => undo_delete::restore_entity(&mut self.tax_groups, &before.tax_groups, id, |e| e.id),
+                    "Store" => undo_delete::restore_entity(&mut self.stores, &before.stores, id, |e| e.id),
+                    // Items and price levels come back with the other changed records
+                    "Item" | "PriceLevel" => Ok(()),
+                    other => Err(format!("Can't undo deleting an unknown type: {}", other)),
+                };
+
+                match restored {
+                    Ok(()) => {
+                        let references = pending.restore_references(&mut self.items, &mut self.price_levels);
+                        println!(
+                            "Restored {} '{}' (id: {}) and {} changed record(s)",
+                            pending.deletion.entity_type, pending.deletion.entity_name, id, references
+                        );
+
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                    }
+                    Err(e) => println!("Could not undo delete: {}", e),
+                }
+                Task::none()
+            }
+            Message::ExpireNotice(raised_at) => {
+                if self.notice.as_ref().is_some_and(|(_, at)| *at == raised_at) {
+                    self.notice = None;
+                }
+                Task::none()
+            }
+            Message::ExpireUndoDelete(deleted_at) => {
+                // Only clears the slot if it still holds the delete this timer was started for
+                if self.pending_restore.as_ref().is_some_and(|pending| pending.deleted_at == deleted_at) {
+                    self.pending_restore = None;
+                }
                 Task::none()
             }
             Message::CancelDelete => {
                 println!("Canceling Delete Request");
                 self.deletion_info = data_types::DeletionInfo::new();
+                self.bulk_deletions.clear();
+                self.price_migration_target = None;
                 self.show_modal = false;
+                self.delete_requested_at = None;
+                Task::none()
+            }
+            Message::ConfirmBulk => {
+                match self.pending_bulk.take() {
+                    Some(pending) => pending.execute(self),
+                    None => Task::none(),
+                }
+            }
+            Message::CancelBulk => {
+                self.pending_bulk = None;
+                Task::none()
+            }
+            Message::Undo => {
+                if self.has_open_edits() {
+                    return self.show_notice("Save or cancel the open edit before undoing".to_string());
+                }
+                let undone = undo::undo(self).map(|label| format!("Undid: {}", label));
+                self.finish_history_step(undone, "Nothing to undo")
+            }
+            Message::Redo => {
+                if self.has_open_edits() {
+                    return self.show_notice("Save or cancel the open edit before redoing".to_string());
+                }
+                let redone = undo::redo(self).map(|label| format!("Redid: {}", label));
+                self.finish_history_step(redone, "Nothing to redo")
+            }
+            Message::AutosaveTick => {
+                // Nothing is written over a file that failed to load
+                if self.unsaved_changes.get() && self.load_failure.is_none() {
+                    println!("Autosaving unsaved changes");
+                    if let Err(e) = self.save_state() {
+                        self.handle_save_error(e);
+                    }
+                }
+                Task::none()
+            }
+            Message::UpdateListSearch(search) => {
+                self.list_search = search;
+                Task::none()
+            }
+            Message::UpdateListSort(sort) => {
+                self.list_sort = sort;
+                Task::none()
+            }
+            Message::ToggleBulkDeleteMode(enabled) => {
+                self.bulk_delete_mode = enabled;
+                self.bulk_delete_selection.clear();
                 Task::none()
             }
-            Message::ToggleTheme(bool) => {
-                if bool {
-                    self.theme = iced_modern_theme::Modern::dark_theme()
+            Message::ToggleBulkDeleteSelection(id, selected) => {
+                if selected {
+                    self.bulk_delete_selection.insert(id);
                 } else {
-                    self.theme = iced_modern_theme::Modern::light_theme()
+                    self.bulk_delete_selection.remove(&id);
+                }
+                Task::none()
+            }
+            Message::SetBulkDeleteSelection(selection) => {
+                self.bulk_delete_selection = selection;
+                Task::none()
+            }
+            Message::RetrySave => {
+                match self.save_state() {
+                    Ok(()) => self.show_notice("Saved".to_string()),
+                    Err(e) => {
+                        self.handle_save_error(e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::SelectUnused => {
+                let Some(unused) = self.unused_ids() else {
+                    return Task::none();
+                };
+                if unused.is_empty() {
+                    return self.show_notice("Every record here is used by at least one item".to_string());
+                }
+
+                // Clear the search so nothing selected is hidden from the list
+                self.list_search.clear();
+                self.bulk_delete_mode = true;
+                self.bulk_delete_selection = unused;
+                Task::none()
+            }
+            Message::RequestBulkDelete => {
+                let Some((entity_type, entities)) = self.screen_entities() else {
+                    return Task::none();
+                };
+
+                // A row being edited would come back on save, so it has to be closed first
+                let open_edits = self.open_edit_ids();
+                if let Some(id) = self.bulk_delete_selection.iter().find(|id| open_edits.contains(id)) {
+                    return self.show_notice(format!("Save or cancel the edit on {} before deleting it", id));
+                }
+
+                self.bulk_deletions = entities
+                    .into_iter()
+                    .filter(|(id, _)| self.bulk_delete_selection.contains(id))
+                    .map(|(id, name)| data_types::DeletionInfo {
+                        entity_type: entity_type.to_string(),
+                        entity_id: id,
+                        entity_name: name,
+                        affected_items: self.referencing_items(entity_type, id),
+                    })
+                    .collect();
+
+                if !self.bulk_deletions.is_empty() {
+                    self.show_modal = true;
+                    self.delete_requested_at = Some(Instant::now());
+                }
+                Task::none()
+            }
+            Message::ConfirmBulkDelete => {
+                if !self.delete_armed() {
+                    println!("Ignoring delete click, the confirmation was only just opened");
+                    return Task::none();
+                }
+
+                let deletions = std::mem::take(&mut self.bulk_deletions);
+                for deletion_info in &deletions {
+                    println!(
+                        "Deleting {} '{}' (id: {})",
+                        deletion_info.entity_type, deletion_info.entity_name, deletion_info.entity_id
+                    );
+                    self.delete_entity(deletion_info);
+                }
+
+                self.bulk_delete_selection.clear();
+                self.bulk_delete_mode = false;
+                self.deletion_info = data_types::DeletionInfo::new();
+                self.show_modal = false;
+                self.delete_requested_at = None;
+                if let Err(e) = self.save_state() {
+                    self.handle_save_error(e);
+                }
+
+                self.show_notice(format!("Deleted {} record(s), Ctrl+Z puts them back", deletions.len()))
+            }
+            Message::LoadComplete(result) => {
+                let loaded = std::sync::Arc::unwrap_or_clone(result.0).map(Some);
+                self.finish_loading(loaded);
+                Task::none()
+            }
+            Message::LoadingTick => {
+                // Only redraws the spinner
+                Task::none()
+            }
+            Message::RestoreNewestBackup => {
+                let file_path = self.settings.file_path.clone();
+                let path = std::path::Path::new(&file_path);
+
+                let newest = persistence::FileManager::storage_report(path)
+                    .and_then(|report| report.backups.last().map(|(backup, _)| backup.clone())
+                        .ok_or_else(|| "No backups found".to_string()));
+
+                let restored = newest.and_then(|backup| {
+                    let (state, warnings) = persistence::load_from_file(&backup.to_string_lossy())?;
+                    persistence::set_aside_unreadable(path)?;
+                    Ok((backup, state, warnings))
+                });
+
+                match restored {
+                    Ok((backup, state, warnings)) => {
+                        println!("Restored data from backup: {}", backup.display());
+                        for warning in &warnings {
+                            println!("Load warning: {}", warning);
+                        }
+                        self.apply_state(state);
+                        self.settings.file_path = file_path;
+                        self.selected_item_id = None;
+                        self.load_warnings = warnings;
+                        self.load_failure = None;
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                    }
+                    Err(e) => {
+                        self.load_failure = Some(format!("Could not restore backup: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::StartFresh => {
+                match persistence::set_aside_unreadable(std::path::Path::new(&self.settings.file_path)) {
+                    Ok(aside) => {
+                        println!("Unreadable data file kept at: {}", aside.display());
+                        self.load_failure = None;
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                    }
+                    Err(e) => {
+                        self.load_failure = Some(e);
+                    }
+                }
+                Task::none()
+            }
+            Message::DismissLoadWarnings => {
+                self.load_warnings.clear();
+                Task::none()
+            }
+            Message::RenumberDuplicateIds => {
+                let Some((mut state, _)) = self.pending_duplicates.take() else {
+                    return Task::none();
+                };
+
+                let renumbered = persistence::renumber_duplicates(&mut state);
+                state.settings = self.settings.clone();
+                self.apply_state(state);
+                self.close_edits();
+                self.error_message = None;
+
+                if let Err(e) = self.save_state() {
+                    self.handle_save_error(e);
+                }
+                self.show_notice(format!("Gave {} duplicate record(s) new ids", renumbered))
+            }
+            Message::KeepLastDuplicates => {
+                self.pending_duplicates = None;
+                self.error_message = None;
+                Task::none()
+            }
+            Message::ItemImageChecked(id, exists) => {
+                self.item_image_exists = Some((id, exists));
+                Task::none()
+            }
+            Message::SelectPriceMigrationTarget(target) => {
+                self.price_migration_target = target;
+                Task::none()
+            }
+            Message::MigratePricesAndDelete(deletion_info, target) => {
+                if !self.delete_armed() {
+                    return Task::none();
+                }
+
+                let migration = price_levels::migrate_item_prices(
+                    &mut self.items,
+                    deletion_info.entity_id,
+                    target
+                );
+
+                println!(
+                    "Moved prices for {} item(s) from price level {} to {}, skipped {} item(s) already priced at the target: {:?}",
+                    migration.moved.len(),
+                    deletion_info.entity_id,
+                    target,
+                    migration.skipped.len(),
+                    migration.skipped
+                );
+
+                // Remaining prices at the deleted level (the skipped conflicts) go with it
+                let before_migration = self.app_state();
+                let task = self.update(Message::ConfirmDelete(deletion_info));
+
+                // Undo should also take the moved prices back off the target level
+                if let Some(pending) = self.pending_restore.take() {
+                    let mut restore = undo_delete::PendingRestore::new(
+                        pending.deletion,
+                        before_migration,
+                        &self.items,
+                        &self.price_levels
+                    );
+                    restore.deleted_at = pending.deleted_at;
+                    self.pending_restore = Some(restore);
+                }
+                task
+            }
+            Message::ToggleTheme(dark) => {
+                let choice = if dark { settings::ThemeChoice::Dark } else { settings::ThemeChoice::Light };
+                self.set_theme(choice);
+
+                // The toggle has no Save button of its own, so it's kept straight away
+                if let Err(e) = self.save_state() {
+                    self.handle_save_error(e);
+                }
+                Task::none()
+            }
+            Message::SystemThemeChanged(dark) => {
+                self.system_dark = dark;
+                self.theme = accent::app_theme(self.settings.app_theme, self.settings.accent_color, dark);
+                Task::none()
+            }
+            Message::RecordExported(result) => {
+                match result {
+                    Ok(path) => self.show_notice(format!("Exported record to {}", path.display())),
+                    Err(e) => self.show_notice(e),
+                }
+            }
+            Message::CopyEntityJson(json) => {
+                Task::batch([
+                    iced::clipboard::write(json),
+                    self.show_notice("Copied the record as JSON".to_string()),
+                ])
+            }
+            Message::PasteEntity => iced::clipboard::read().map(Message::EntityPasted),
+            Message::EntityPasted(contents) => self.paste_entity(contents),
+            Message::RecordLoaded(result) => {
+                match result {
+                    Ok(file) => {
+                        let pending = record_export::PendingImport::new(file, &self.record_collections());
+                        if pending.missing.is_empty() {
+                            self.apply_record_import(pending, false)
+                        } else {
+                            self.pending_record_import = Some(pending);
+                            Task::none()
+                        }
+                    }
+                    Err(e) => self.show_notice(e),
                 }
-                self.toggle_theme = !self.toggle_theme;
+            }
+            Message::ResolveRecordImport(create_missing) => {
+                match self.pending_record_import.take() {
+                    Some(pending) => self.apply_record_import(pending, create_missing),
+                    None => Task::none(),
+                }
+            }
+            Message::CancelRecordImport => {
+                self.pending_record_import = None;
                 Task::none()
             }
+            Message::ExportCurrentScreen => {
+                match self.export_entities_to_csv(&self.screen) {
+                    Ok((label, contents)) => Task::perform(
+                        entity_export::save_csv(label, contents),
+                        Message::CurrentScreenExported
+                    ),
+                    Err(e) => self.show_notice(e),
+                }
+            }
+            Message::CurrentScreenExported(result) => {
+                match result {
+                    Ok(path) => self.show_notice(format!("Exported to {}", path.display())),
+                    Err(e) => self.show_notice(e),
+                }
+            }
             Message::FileDropped(path) => {
 
                 println!("File Dropped: {:?}", &path);
@@ -844,24 +1528,148 @@ impl MenuBuilder {
             },
             Message::ImportItemsIntoExisting => {
                 self.show_item_import_confirmation = false;
-                Task::none()
+                Task::done(Message::ImportCSVSelected(self.import_item_path.clone()))
             },
+            Message::ImportCSV => {
+                Task::perform(
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Import Menu Items")
+                        .add_filter("CSV Files", &["csv", "txt"])
+                        .pick_file(),
+                    |file| match file {
+                        Some(file) => Message::ImportCSVSelected(file.path().to_owned()),
+                        None => Message::None,
+                    }
+                )
+            }
+            Message::ImportCSVSelected(path) => {
+                match items::import_from_csv(&path) {
+                    Ok(imported) => {
+                        println!("Read {} items from {}", imported.len(), path.display());
+                        self.pending_csv_import = Some(import_items::PendingCsvImport::new(
+                            imported,
+                            &self.items,
+                            &self.item_groups,
+                            &self.tax_groups,
+                        ));
+                        self.finish_csv_import()
+                    }
+                    Err(e) => {
+                        self.error_message = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::SaveAs => {
+                let file_name = std::path::Path::new(&self.settings.file_path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+
+                Task::perform(
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Save Menu As")
+                        .add_filter("Menu Data", &["ron", "json"])
+                        .set_file_name(file_name)
+                        .save_file(),
+                    |file| match file {
+                        Some(file) => Message::SaveAsSelected(file.path().to_owned()),
+                        None => Message::None,
+                    }
+                )
+            }
+            Message::SaveAsSelected(path) => {
+                // The copy names itself as its data file, so it opens cleanly on its own
+                // and is written in the format its extension names
+                let mut state = self.app_state();
+                state.settings.file_path = path.to_string_lossy().into_owned();
+                state.settings.save_format = settings::SaveFormat::from_path(&path)
+                    .unwrap_or(self.settings.save_format);
+
+                match persistence::save_to_file(&state, &state.settings.file_path, state.settings.save_format) {
+                    Ok(()) => {
+                        println!("Saved a copy of the menu to {}", path.display());
+                        self.show_notice(format!("Saved a copy to {}", path.display()))
+                    }
+                    Err(e) => {
+                        self.error_message = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::OpenFile => {
+                Task::perform(
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Open Menu File")
+                        .add_filter("Menu Data", &["ron", "json"])
+                        .pick_file(),
+                    |file| match file {
+                        Some(file) => Message::OpenFileSelected(file.path().to_owned()),
+                        None => Message::None,
+                    }
+                )
+            }
+            Message::OpenFileSelected(path) => {
+                if self.unsaved_changes.get() || self.has_open_edits() {
+                    self.pending_open = Some(path);
+                    return Task::none();
+                }
+                self.open_menu_file(&path)
+            }
+            Message::ConfirmOpenFile(save_first) => {
+                let Some(path) = self.pending_open.take() else {
+                    return Task::none();
+                };
+                if save_first {
+                    if let Err(e) = self.save_state() {
+                        self.handle_save_error(e);
+                        return Task::none();
+                    }
+                }
+                self.open_menu_file(&path)
+            }
+            Message::CancelOpenFile => {
+                self.pending_open = None;
+                Task::none()
+            }
+            Message::ResolveCsvConflict(choice, all) => {
+                if let Some(pending) = &mut self.pending_csv_import {
+                    pending.resolve(choice, all, &self.items);
+                }
+                self.finish_csv_import()
+            }
+            Message::CancelCsvImport => {
+                self.pending_csv_import = None;
+                Task::none()
+            }
             Message::CancelItemImport => {
                 self.show_item_import_confirmation = false;
                 Task::none()
             },
            Message::WindowClosed(id) => {
                 println!("Window close requested: {:?}", &id);
-                self.windows.remove(&id);
+                let closed = self.windows.remove(&id);
                 println!("Window BTreeMap: {:?}", &self.windows);
 
-                if self.windows.is_empty() {
+                // Closing the main window ends the app even with an editor window still open
+                let main_closed = closed.is_some_and(|window| window.windowtype == WindowEnum::MainWindow);
+                if self.windows.is_empty() || main_closed {
+                    // Only the screen moved since the last save, keep it for the next start
+                    if self.last_view() != self.saved_view.get() {
+                        if let Err(e) = self.save_state() {
+                            println!("Failed to save the open screen on exit: {}", e);
+                        }
+                    }
                     iced::exit()
                 } else {
                     Task::none()
                 }
            },
-            Message::WindowResized(size) => {
+            Message::WindowResized(id, size) => {
+                // Only the main window's width decides the sidebar layout
+                if self.windows.get(&id).is_some_and(|window| window.windowtype == WindowEnum::MainWindow) {
+                    self.main_window_width = size.width;
+                }
                 Task::none()
             },
             Message::RequestOpenWindow(windowenum) => {
@@ -871,7 +1679,7 @@ impl MenuBuilder {
                             iced::window::Settings {
                                 size: Size::new(1201.0, 700.0),
                                 position: window::Position::Centered,
-                                min_size: Some(Size::new( 1250_f32, 700_f32)),
+                                min_size: Some(Size::new( 760_f32, 600_f32)),
                                 exit_on_close_request: true,
                                 icon: settings::load_icon(),
                                 ..iced::window::Settings::default()
@@ -886,12 +1694,38 @@ impl MenuBuilder {
                         });
                         return open.map(|id| Message::WindowOpened(id, WindowEnum::SuperEdit)).chain(Task::done(Message::None))
                     }
+                    WindowEnum::ItemEditor => {
+                        let (_id, open) = iced::window::open(window::Settings {
+                            size: Size::new(900.0, 750.0),
+                            min_size: Some(Size::new(600.0, 500.0)),
+                            icon: settings::load_icon(),
+                            ..window::Settings::default()
+                        });
+                        return open.map(|id| Message::WindowOpened(id, WindowEnum::ItemEditor))
+                    }
+                }
+            }
+            Message::OpenEditorWindow(id) => {
+                // An edit already running on this item carries over into the window
+                let editing = self.draft_item_id == Some(id)
+                    && matches!(self.screen, Screen::Items(items::Mode::Edit));
+                let start = if editing {
+                    Task::none()
+                } else {
+                    self.perform(Operation::Items(id, items::Operation::StartEdit(id)))
+                };
+
+                match self.editor_window() {
+                    Some(window_id) => start.chain(window::gain_focus(window_id)),
+                    None => start.chain(Task::done(Message::RequestOpenWindow(WindowEnum::ItemEditor))),
                 }
             }
+            Message::CloseWindow(id) => window::close(id),
             Message::WindowOpened(id, windowenum) => {
                 let title = match windowenum {
                     WindowEnum::MainWindow => { String::from("Main Window") }
                     WindowEnum::SuperEdit => { String::from("SuperEdit Window") }
+                    WindowEnum::ItemEditor => { String::from("Item Editor") }
                 };
 
                 let new_window = Window::new(id, title, windowenum);
@@ -900,6 +1734,18 @@ impl MenuBuilder {
 
                 Task::none()
             }
+            Message::StepItemSelection(forward) => {
+                let shown = self.shown_item_ids();
+                let Some(position) = self.selected_item_id.and_then(|id| shown.iter().position(|shown_id| *shown_id == id)) else {
+                    return Task::none();
+                };
+                let neighbour = if forward { Some(position + 1) } else { position.checked_sub(1) };
+
+                match neighbour.and_then(|index| shown.get(index)) {
+                    Some(&id) => self.perform(Operation::Items(id, items::Operation::Select(id))),
+                    None => Task::none(),
+                }
+            }
             Message::None => {
                 println!("A Window Open subscription event has occured!");
                 Task::none()
@@ -911,142 +1757,147 @@ impl MenuBuilder {
 
 
                     
+        // Below SIDEBAR_COLLAPSE_WIDTH the sidebar shrinks to a rail of short
+        // labels, with the full name in a tooltip
+        let compact = self.main_window_width < SIDEBAR_COLLAPSE_WIDTH;
+
+        // count is the number of records on the screen, shown as a muted badge
+        let nav_button = |label: &'static str, short: &'static str, screen: Screen, count: Option<usize>| {
+            let selected = std::mem::discriminant(&self.screen) == std::mem::discriminant(&screen);
+            let content: Element<'_, Message> = match count {
+                Some(count) if !compact => row![
+                    text(label),
+                    horizontal_space(),
+                    text(count.to_string()).size(12).style(Modern::secondary_text()),
+                ]
+                .align_y(iced::Alignment::Center)
+                .into(),
+                _ => text(if compact { short } else { label }).into(),
+            };
+            let tooltip_label = match count {
+                Some(count) => format!("{} ({})", label, count),
+                None => label.to_string(),
+            };
+            let nav = button(content)
+                .on_press_maybe(self.navigate(screen))
+                .width(Length::Fill)
+                .style(
+                    Modern::conditional_button_style(
+                        selected,
+                        accent::selected_button(),
+                        Modern::system_button()
+                    )
+                );
+
+            if compact {
+                Element::from(iced::widget::tooltip(
+                    nav,
+                    container(text(tooltip_label)).padding(10).style(Modern::card_container()),
+                    iced::widget::tooltip::Position::Right,
+                ))
+            } else {
+                Element::from(nav)
+            }
+        };
+
+        // Shows the theme in effect, flipping it leaves Match system for an explicit choice
+        let showing_dark = match self.settings.app_theme {
+            settings::ThemeChoice::Light => false,
+            settings::ThemeChoice::Dark => true,
+            settings::ThemeChoice::System => self.system_dark,
+        };
+        let theme_toggle = iced::widget::toggler(showing_dark)
+            .on_toggle_maybe(self.loading_started.is_none().then_some(Message::ToggleTheme));
+
+        let settings_button = button(icon::settings().size(14)) 
+            .on_press_maybe(self.navigate(Screen::Settings(self.settings.clone())))
+            .style(
+                Modern::conditional_button_style(
+                    matches!(self.screen, Screen::Settings(_)),
+                    accent::selected_button(),
+                    Modern::system_button()
+                )
+            );
+
+        let sidebar_footer: Element<'_, Message> = if compact {
+            column![theme_toggle, settings_button].spacing(8).into()
+        } else {
+            row![
+                column![
+                    text("Toggle Theme").size(10),
+                    iced::widget::vertical_space().height(2),
+                    theme_toggle,
+                ],
+                iced::widget::horizontal_space(),
+                settings_button,
+            ]
+            .into()
+        };
+
         let sidebar = container(
             column![
-                button("Items")
-                    .on_press(Message::Navigate(Screen::Items(items::Mode::View)))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::Items(_)),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Item Groups")
-                    .on_press(Message::Navigate(Screen::ItemGroups))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::ItemGroups),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Price Levels")
-                    .on_press(Message::Navigate(Screen::PriceLevels))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::PriceLevels),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Product Classes")
-                    .on_press(Message::Navigate(Screen::ProductClasses))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::ProductClasses),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Tax Groups")
-                    .on_press(Message::Navigate(Screen::TaxGroups))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::TaxGroups),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Security Levels")
-                    .on_press(Message::Navigate(Screen::SecurityLevels))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::SecurityLevels),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Revenue Categories")
-                    .on_press(Message::Navigate(Screen::RevenueCategories))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::RevenueCategories),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Report Categories")
-                    .on_press(Message::Navigate(Screen::ReportCategories))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::ReportCategories),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Choice Groups")
-                    .on_press(Message::Navigate(Screen::ChoiceGroups))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::ChoiceGroups),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Printer Logicals")
-                    .on_press(Message::Navigate(Screen::PrinterLogicals))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::PrinterLogicals),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
+                nav_button("Items", "It", Screen::Items(items::Mode::View), Some(self.items.len())),
+                nav_button("Item Groups", "IG", Screen::ItemGroups, Some(self.item_groups.len())),
+                nav_button("Price Levels", "PL", Screen::PriceLevels, Some(self.price_levels.len())),
+                nav_button("Product Classes", "PC", Screen::ProductClasses, Some(self.product_classes.len())),
+                nav_button("Tax Groups", "TG", Screen::TaxGroups, Some(self.tax_groups.len())),
+                nav_button("Security Levels", "SL", Screen::SecurityLevels, Some(self.security_levels.len())),
+                nav_button("Revenue Categories", "RvC", Screen::RevenueCategories, Some(self.revenue_categories.len())),
+                nav_button("Report Categories", "RpC", Screen::ReportCategories, Some(self.report_categories.len())),
+                nav_button("Choice Groups", "CG", Screen::ChoiceGroups, Some(self.choice_groups.len())),
+                nav_button("Printer Logicals", "PrL", Screen::PrinterLogicals, Some(self.printer_logicals.len())),
+                nav_button("Stores", "St", Screen::Stores, Some(self.stores.len())),
+                nav_button("Validation", "Va", Screen::Validation, None),
 
                 vertical_space(),
-                row![
-                    column![
-                        text("Toggle Theme").size(10),
-                        iced::widget::vertical_space().height(2),
-                        iced::widget::toggler(self.toggle_theme).on_toggle(Message::ToggleTheme),
-                    ],
-                    iced::widget::horizontal_space(),
-                    button(icon::settings().size(14)) 
-                        .on_press(Message::Navigate(Screen::Settings(self.settings.clone())))
-                        //.width(Length::Fixed(40.0))
-                        .style(
-                            Modern::conditional_button_style(
-                                matches!(self.screen, Screen::Settings(_)),
-                                Modern::selected_button_style(Modern::system_button()),
-                                Modern::system_button()
-                            )
-                        ),
-                ]
+                sidebar_footer,
             ]
             .spacing(5)
             .padding(10)
         )
-        .width(Length::Fixed(200.0))
+        .width(Length::Fixed(if compact { SIDEBAR_COMPACT_WIDTH } else { 200.0 }))
         .height(Length::Fill)
         .style(Modern::sidebar_container());
 
         let content = match &self.screen {
-            Screen::Settings(settings) => {
-                settings::view(settings, self.error_message.as_deref()).map(Message::Settings)
+            Screen::Settings(_) => {
+                let lint_issues = self.lint_issues();
+                let tax_rates: BTreeMap<EntityId, Decimal> = self.tax_groups
+                    .iter()
+                    .map(|(id, group)| (*id, group.rate))
+                    .collect();
+                let preview_items: BTreeMap<EntityId, Item> = self.items
+                    .iter()
+                    .take(EXPORT_PREVIEW_ROWS)
+                    .map(|(id, item)| (*id, item.clone()))
+                    .collect();
+
+                settings::view(
+                    &self.settings,
+                    self.error_message.as_deref(),
+                    lint_issues,
+                    self.report_categories.values().cloned().collect(),
+                    self.printer_logicals.values().cloned().collect(),
+                    items::export_items::export_lines(
+                        self.export_items(&preview_items).values(),
+                        &tax_rates,
+                        &self.settings.export_options(),
+                    ),
+                    self.items.len(),
+                    self.price_levels.values().cloned().collect(),
+                    self.stores.values().cloned().collect(),
+                    self.id_range_warnings(),
+                    self.tax_groups.values().cloned().collect(),
+                    self.security_levels.values().cloned().collect(),
+                ).map(Message::Settings)
             },
             Screen::Items(mode) => {
-                if let Some(id) = self.selected_item_id {
+                // With the editor in its own window this one keeps the list and a read-only view
+                let mode = if self.editor_window().is_some() { &items::Mode::View } else { mode };
+                // A stale selection (e.g. a deleted item) falls through to the placeholder
+                let selected_id = items::live_selection(self.selected_item_id, &self.items);
+
+                if let Some(id) = selected_id {
                     // When an item is selected, determine whether it represents a new item
                     // (negative ID) or an existing one, and if there’s a draft override.
                     let item = if id < 0 {
@@ -1063,10 +1914,14 @@ impl MenuBuilder {
                     };
                 
                     items::view(
-                        item,
+                        Some(item),
                         mode,
                         &self.items,
                         &self.item_search,
+                        &self.item_list_filters,
+                        &self.item_search_cache,
+                        self.item_data_generation,
+                        self.settings.show_search_timing,
                         &self.item_edit_state,
                         &self.item_groups,
                         &self.tax_groups,
@@ -1077,15 +1932,23 @@ impl MenuBuilder {
                         &self.choice_groups,
                         &self.printer_logicals,
                         &self.price_levels,
+                        &self.stores,
+                        self.item_image_exists.filter(|(image_id, _)| *image_id == id).map(|(_, exists)| exists),
+                        Vec::new(),
                     )
                     .map(move |msg| Message::Items(id, msg))
-                } else if let Some((&first_id, first_item)) = self.items.iter().next() {
-                    // No selected item, but there is at least one in the collection.
+                } else if !self.items.is_empty() {
+                    // Nothing selected, only the list is interactive. Messages carry
+                    // the draft id so no existing item can be targeted by accident.
                     items::view(
-                        first_item,
+                        None,
                         mode,
                         &self.items,
                         &self.item_search,
+                        &self.item_list_filters,
+                        &self.item_search_cache,
+                        self.item_data_generation,
+                        self.settings.show_search_timing,
                         &self.item_edit_state,
                         &self.item_groups,
                         &self.tax_groups,
@@ -1096,8 +1959,11 @@ impl MenuBuilder {
                         &self.choice_groups,
                         &self.printer_logicals,
                         &self.price_levels,
+                        &self.stores,
+                        None,
+                        self.recently_modified(),
                     )
-                    .map(move |msg| Message::Items(first_id, msg))
+                    .map(move |msg| Message::Items(-1, msg))
                 } else {
                     // No selected item and no items available: show the welcome screen.
                     container(
@@ -1127,59 +1993,182 @@ impl MenuBuilder {
             Screen::ItemGroups => {
                 item_groups::view(
                     &self.item_groups,
-                    &self.item_group_edit_state_vec)
+                    &self.item_group_edit_state_vec,
+                    &self.list_search,
+                    self.list_sort)
                 .map(move |msg| Message::ItemGroups(-1, msg)) // Default ID for new messages
             }
             Screen::PriceLevels => {
                 price_levels::view(
                     &self.price_levels,
-                    &self.price_level_edit_state_vec)
+                    &self.price_level_edit_state_vec,
+                    &self.stores,
+                    price_levels::usage_counts(&self.price_levels, &self.items),
+                    &self.price_level_list_options,
+                    &self.list_search,
+                    self.list_sort)
                 .map(move |msg| Message::PriceLevels(-1, msg))
             }
             Screen::ProductClasses => {
 
                 product_classes::view(
                     &self.product_classes,
-                    &self.product_class_edit_state_vec)
+                    &self.product_class_edit_state_vec,
+                    &self.list_search,
+                    self.list_sort)
                 .map(move |msg| Message::ProductClasses(-1, msg))
             }
             Screen::TaxGroups => {
                 tax_groups::view(
                     &self.tax_groups,
-                    &self.tax_group_edit_state_vec)
+                    &self.tax_group_edit_state_vec,
+                    &self.list_search,
+                    self.list_sort,
+                    self.settings.id_range(EntityKind::TaxGroup))
                 .map(move |msg| Message::TaxGroups(-1, msg))
             }
             Screen::SecurityLevels => {
                 security_levels::view(
                     &self.security_levels,
-                    &self.security_level_edit_state_vec)
+                    &self.security_level_edit_state_vec,
+                    &self.list_search,
+                    self.list_sort)
                 .map(move |msg| Message::SecurityLevels(-1, msg))
             }
             Screen::RevenueCategories => {
                 revenue_categories::view(
                     &self.revenue_categories,
-                    &self.revenue_category_edit_state_vec)
+                    &self.revenue_category_edit_state_vec,
+                    &self.list_search,
+                    self.list_sort,
+                    self.revenue_category_create_error.as_deref())
                 .map(move |msg| Message::RevenueCategories(-1, msg))
             }
             Screen::ReportCategories => {
 
                 report_categories::view(
                     &self.report_categories,
-                    &self.report_category_edit_state_vec)
+                    &self.report_category_edit_state_vec,
+                    &self.list_search,
+                    self.list_sort,
+                    self.report_category_create_error.as_deref(),
+                    self.report_category_capacity(),
+                    self.settings.report_category_warn_percent,
+                    self.show_unused_report_categories
+                        .then(|| report_categories::unused(&self.report_categories, &self.items)),
+                    self.merge_selection)
                 .map(move |msg| Message::ReportCategories(-1, msg))
             }
             Screen::ChoiceGroups => {
                 choice_groups::view(
                     &self.choice_groups,
-                    &self.choice_group_edit_state_vec)
+                    &self.choice_group_edit_state_vec,
+                    choice_groups::usage_counts(&self.choice_groups, &self.items),
+                    &self.items,
+                    &self.choice_group_list_options,
+                    &self.list_search,
+                    self.list_sort,
+                    self.merge_selection)
                 .map(move |msg| Message::ChoiceGroups(-1, msg))
             }
             Screen::PrinterLogicals => {
                 printer_logicals::view(
                     &self.printer_logicals, 
-                    &self.printer_logical_edit_state_vec)
+                    &self.printer_logical_edit_state_vec,
+                    &self.list_search,
+                    self.list_sort,
+                    &self.items,
+                    self.printer_routing)
                 .map(move |msg| Message::PrinterLogicals(-1, msg))
             }
+            Screen::Stores => {
+                stores::view(
+                    &self.stores,
+                    &self.store_edit_state_vec,
+                    &self.list_search,
+                    self.list_sort)
+                .map(move |msg| Message::Stores(-1, msg))
+            }
+            Screen::Validation => validation::view(self.validate_all()).map(Message::Validation),
+        };
+
+        // Price levels with prices on them offer to move the prices elsewhere
+        let priced_items = if self.deletion_info.entity_type == "PriceLevel" {
+            self.items.values()
+                .filter(|item| item.has_price_at(self.deletion_info.entity_id))
+                .count()
+        } else {
+            0
+        };
+
+        let price_migration: Element<'_, Message> = if priced_items > 0 {
+            let targets: Vec<PriceLevel> = self.price_levels.values()
+                .filter(|level| level.id != self.deletion_info.entity_id)
+                .cloned()
+                .collect();
+            let selected = self.price_migration_target
+                .and_then(|id| self.price_levels.get(&id).cloned());
+
+            let conflicts = self.price_migration_target
+                .map(|target| price_levels::plan_price_migration(&self.items, self.deletion_info.entity_id, target).skipped)
+                .unwrap_or_default();
+
+            let conflict_text = if conflicts.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    "Skipped, already priced at target: {}",
+                    conflicts.iter()
+                        .filter_map(|id| self.items.get(id))
+                        .map(|item| item.name.clone())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+
+            column![
+                text(format!("{} item(s) have prices at this level", priced_items)).style(Modern::secondary_text()).size(14),
+                row![
+                    pick_list(targets, selected, |level: PriceLevel| Message::SelectPriceMigrationTarget(Some(level.id)))
+                        .placeholder("Move prices to...")
+                        .style(Modern::pick_list()),
+                    button("Move & Delete")
+                        .on_press_maybe(
+                            self.price_migration_target
+                                .map(|target| Message::MigratePricesAndDelete(self.deletion_info.clone(), target))
+                        )
+                        .style(accent::primary_button()),
+                ].spacing(6),
+                text(conflict_text).style(Modern::error_text()).size(12),
+            ]
+            .spacing(6)
+            .into()
+        } else {
+            column![].into()
+        };
+
+        // Records that lose their reference when this one goes
+        let affected = &self.deletion_info.affected_items;
+        let affected_list: Element<'_, Message> = if affected.is_empty() {
+            text("No items reference this.").style(Modern::secondary_text()).size(12).into()
+        } else {
+            column![
+                text(format!("Referenced by {}:", affected.len())).style(Modern::secondary_text()).size(12),
+                column(
+                    affected.iter()
+                        .take(AFFECTED_PREVIEW)
+                        .map(|name| text(format!("• {}", name)).size(12).into())
+                        .collect::<Vec<_>>()
+                )
+                .spacing(2),
+                text(if affected.len() > AFFECTED_PREVIEW {
+                    format!("...and {} more", affected.len() - AFFECTED_PREVIEW)
+                } else {
+                    String::new()
+                }).style(Modern::secondary_text()).size(12),
+            ]
+            .spacing(4)
+            .into()
         };
 
         let delete_confirmation_popup = container(
@@ -1188,19 +2177,71 @@ impl MenuBuilder {
                     vertical_space().height(10),
                     row![
                         iced::widget::horizontal_space().width(6),
-                        text("Are you sure you want to delete this ".to_string() + &self.deletion_info.entity_type).style(Modern::primary_text()).size(16),
+                        text(self.deletion_info.prompt()).style(Modern::primary_text()).size(16),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        affected_list,
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        price_migration,
                         iced::widget::horizontal_space().width(6),
                     ],
                     
                     iced::widget::vertical_space().height(15),
                     row![
                         iced::widget::horizontal_space().width(6),
-                        button("Delete").on_press(Message::ConfirmDelete(self.deletion_info.clone())).style(Modern::danger_button()),
+                        button(if priced_items > 0 { "Discard Prices & Delete" } else { "Delete" })
+                            .on_press(Message::ConfirmDelete(self.deletion_info.clone()))
+                            .style(Modern::danger_button()),
                         iced::widget::horizontal_space(),
                         button("Cancel").on_press(Message::CancelDelete).style(Modern::system_button()),
                         iced::widget::horizontal_space().width(6),
-                    ]
-                ].width(275).height(100)
+                    ],
+                    vertical_space().height(10),
+                ].width(if priced_items > 0 { 375 } else { 275 }).height(Length::Shrink)
+            ).style(Modern::separated_container())
+        ).padding(250);
+
+        let bulk_reference_count: usize = self.bulk_deletions
+            .iter()
+            .map(|deletion| deletion.affected_items.len())
+            .sum();
+
+        let bulk_delete_popup = container(
+            container(
+                column![
+                    text(format!("Delete {} record(s)?", self.bulk_deletions.len())).style(Modern::primary_text()).size(16),
+                    column(
+                        self.bulk_deletions
+                            .iter()
+                            .take(AFFECTED_PREVIEW)
+                            .map(|deletion| text(format!("• {} {}", deletion.entity_id, deletion.entity_name)).size(13).into())
+                            .collect::<Vec<_>>()
+                    )
+                    .spacing(3),
+                    text(if self.bulk_deletions.len() > AFFECTED_PREVIEW {
+                        format!("...and {} more", self.bulk_deletions.len() - AFFECTED_PREVIEW)
+                    } else {
+                        String::new()
+                    }).size(12).style(Modern::secondary_text()),
+                    text(if bulk_reference_count > 0 {
+                        format!("{} item reference(s) will be removed", bulk_reference_count)
+                    } else {
+                        "No items reference these records".to_string()
+                    }).size(13).style(Modern::secondary_text()),
+                    row![
+                        button("Delete").on_press(Message::ConfirmBulkDelete).style(Modern::danger_button()),
+                        iced::widget::horizontal_space(),
+                        button("Cancel").on_press(Message::CancelDelete).style(Modern::system_button()),
+                    ],
+                ]
+                .spacing(10)
+                .padding(10)
+                .width(375)
             ).style(Modern::separated_container())
         ).padding(250);
 
@@ -1225,7 +2266,7 @@ impl MenuBuilder {
                         iced::widget::horizontal_space().width(6),
                         button("New Database").on_press(Message::ImportItemsOverwriteExisting).style(Modern::warning_button()),
                         iced::widget::horizontal_space(),
-                        button("Add to existing").on_press(Message::ImportItemsIntoExisting).style(Modern::primary_button()),
+                        button("Add to existing").on_press(Message::ImportItemsIntoExisting).style(accent::primary_button()),
                         iced::widget::horizontal_space(),
                         button("Cancel").on_press(Message::CancelItemImport).style(Modern::system_button()),
                         iced::widget::horizontal_space().width(6),
@@ -1234,66 +2275,592 @@ impl MenuBuilder {
             ).style(Modern::accent_container())
         ).padding(250);
 
-        //iced::widget::stack
-        let app_view = row![
-            sidebar,
-            container(content)
-                .width(Length::Fill)
-                .padding(20),
-        ];
-        
-        println!("List of Windows: {:?}", self.windows.get(&window_id));
-        let window_view = match self.windows.get(&window_id) {
-            Some(window) => match window.windowtype {
-                WindowEnum::MainWindow => {
-                    println!("Launched Main Window!");
-                    if self.show_modal { //Show Deletion confirmation popup
-                        stack![
-                            app_view,
-                            opaque(delete_confirmation_popup)
-                        ].into()
-                    } else if self.show_item_import_confirmation { // Show Item Import Confirmation popup
-                        stack![
-                            app_view,
-                            opaque(import_items_confirmation)
-                        ].into()
-                    }
-                    else {
-                        app_view.into()
-                    }
+        let csv_conflict_dialog = container(
+            container(
+                match self.pending_csv_import.as_ref().and_then(|pending| pending.conflicts.front().map(|item| (pending, item))) {
+                    Some((pending, item)) => column![
+                        text("Import Items").style(Modern::primary_text()).size(18),
+                        text(format!(
+                            "Item {} {} is already used by {}",
+                            item.id,
+                            item.name,
+                            self.items.get(&item.id).map(|existing| existing.name.as_str()).unwrap_or_default()
+                        ))
+                        .style(Modern::secondary_text())
+                        .size(14),
+                        text(format!("{} conflict(s) left, {} item(s) ready", pending.conflicts.len(), pending.ready.len()))
+                            .style(Modern::secondary_text())
+                            .size(12),
+                        row![
+                            button("Overwrite").on_press(Message::ResolveCsvConflict(import_items::ConflictChoice::Overwrite, false)).style(Modern::warning_button()),
+                            button("New ID").on_press(Message::ResolveCsvConflict(import_items::ConflictChoice::NewId, false)).style(accent::primary_button()),
+                        ]
+                        .spacing(10),
+                        row![
+                            button("Overwrite All").on_press(Message::ResolveCsvConflict(import_items::ConflictChoice::Overwrite, true)).style(Modern::warning_button()),
+                            button("New ID for All").on_press(Message::ResolveCsvConflict(import_items::ConflictChoice::NewId, true)).style(accent::primary_button()),
+                            iced::widget::horizontal_space(),
+                            button("Cancel").on_press(Message::CancelCsvImport).style(Modern::system_button()),
+                        ]
+                        .spacing(10),
+                    ],
+                    None => column![],
                 }
-                WindowEnum::SuperEdit => {
-                    println!("Launched SuperEdit!");
-                    container(text("super_edit")).into()
+                .spacing(10)
+                .padding(10)
+                .width(420)
+            ).style(Modern::accent_container())
+        ).padding(250);
+
+        let bulk_confirmation = container(
+            container(
+                match &self.pending_bulk {
+                    Some(pending) => column![
+                        text(&pending.description).style(Modern::primary_text()).size(18),
+                        text(format!("This will change {} records", pending.count)).style(Modern::secondary_text()).size(14),
+                        column(
+                            pending.sample
+                                .iter()
+                                .map(|name| text(format!("• {}", name)).size(13).into())
+                                .collect::<Vec<_>>()
+                        )
+                        .spacing(3),
+                        text(if pending.count > pending.sample.len() {
+                            format!("...and {} more", pending.count - pending.sample.len())
+                        } else {
+                            String::new()
+                        }).size(12).style(Modern::secondary_text()),
+                        row![
+                            button("Apply").on_press(Message::ConfirmBulk).style(Modern::warning_button()),
+                            iced::widget::horizontal_space(),
+                            button("Cancel").on_press(Message::CancelBulk).style(Modern::system_button()),
+                        ],
+                    ],
+                    None => column![],
                 }
-            }
-            None => { 
-                let content = column![
-                    text(format!("Something has gone terribly wrong. Window Id: {:?}", window_id)),
-                ];
-                container(
-                    content
-                ).into() 
-            }
-        };
+                .spacing(10)
+                .padding(10)
+                .width(375)
+            ).style(Modern::accent_container())
+        ).padding(250);
 
-        window_view
+        let record_import_dialog = container(
+            container(
+                match &self.pending_record_import {
+                    Some(pending) => column![
+                        text(format!("Import {} \"{}\"", pending.file.record.label(), pending.file.record.name()))
+                            .style(Modern::primary_text())
+                            .size(18),
+                        text(format!("{} references have no match here by id or name", pending.missing.len()))
+                            .style(Modern::secondary_text())
+                            .size(14),
+                        column(
+                            pending.missing
+                                .iter()
+                                .map(|reference| text(format!("• {}: {}", reference.kind, reference.name)).size(13).into())
+                                .collect::<Vec<_>>()
+                        )
+                        .spacing(3),
+                        row![
+                            button("Create missing").on_press(Message::ResolveRecordImport(true)).style(accent::primary_button()),
+                            iced::widget::horizontal_space(),
+                            button("Skip missing").on_press(Message::ResolveRecordImport(false)).style(Modern::warning_button()),
+                            iced::widget::horizontal_space(),
+                            button("Cancel").on_press(Message::CancelRecordImport).style(Modern::system_button()),
+                        ],
+                    ],
+                    None => column![],
+                }
+                .spacing(10)
+                .padding(10)
+                .width(375)
+            ).style(Modern::accent_container())
+        ).padding(250);
 
-     }
+        let load_failure_dialog = container(
+            container(
+                column![
+                    text("Saved data could not be loaded").style(Modern::primary_text()).size(18),
+                    text(self.load_failure.clone().unwrap_or_default()).style(Modern::error_text()).size(13),
+                    text("The file is left untouched and nothing will be saved until you choose how to continue.")
+                        .style(Modern::secondary_text())
+                        .size(13),
+                    row![
+                        button("Open Newest Backup").on_press(Message::RestoreNewestBackup).style(accent::primary_button()),
+                        iced::widget::horizontal_space(),
+                        button("Start Fresh").on_press(Message::StartFresh).style(Modern::warning_button()),
+                    ],
+                ]
+                .spacing(10)
+                .padding(10)
+                .width(425)
+            ).style(Modern::accent_container())
+        ).padding(250);
 
+        let load_warnings_dialog = container(
+            container(
+                column![
+                    text("Some saved values were not recognised").style(Modern::primary_text()).size(18),
+                    text("They were replaced with defaults:").style(Modern::secondary_text()).size(13),
+                    column(
+                        self.load_warnings
+                            .iter()
+                            .map(|warning| text(format!("• {}", warning)).size(13).into())
+                            .collect::<Vec<_>>()
+                    )
+                    .spacing(3),
+                    row![
+                        iced::widget::horizontal_space(),
+                        button("OK").on_press(Message::DismissLoadWarnings).style(accent::primary_button()),
+                    ],
+                ]
+                .spacing(10)
+                .padding(10)
+                .width(425)
+            ).style(Modern::accent_container())
+        ).padding(250);
 
-    fn perform(&mut self, operation: Operation) -> Task<Message> {
-        match operation {
-            Operation::Settings(op) => {
-                match op {
-                    settings::Operation::Save(new_settings) => {
-                        self.settings = new_settings;
+        let duplicates_dialog = container(
+            container(
+                column![
+                    text("Some ids are used more than once").style(Modern::primary_text()).size(18),
+                    column(
+                        self.pending_duplicates
+                            .iter()
+                            .flat_map(|(_, duplicates)| duplicates)
+                            .map(|duplicate| text(format!("• {}", duplicate.message())).style(Modern::error_text()).size(13).into())
+                            .collect::<Vec<_>>()
+                    )
+                    .spacing(3),
+                    text("Only the last record with each id is shown. Renumbering gives the others new ids after the highest in their list, so nothing is dropped.")
+                        .style(Modern::secondary_text())
+                        .size(13),
+                    row![
+                        button("Renumber Duplicates").on_press(Message::RenumberDuplicateIds).style(accent::primary_button()),
+                        iced::widget::horizontal_space(),
+                        button("Keep Last Only").on_press(Message::KeepLastDuplicates).style(Modern::warning_button()),
+                    ],
+                ]
+                .spacing(10)
+                .padding(10)
+                .width(425)
+            ).style(Modern::accent_container())
+        ).padding(250);
 
-                        if let Err(e) = self.save_state() {
-                            self.error_message = Some(e);
-                        } else {
-                            self.error_message = None;
-                        }
+        let navigate_dialog = self.pending_navigation.as_ref().map(|screen| {
+            container(
+                container(
+                    column![
+                        text("Discard unsaved changes?").style(Modern::primary_text()).size(18),
+                        text("An edit is still open. Leaving this screen throws away whatever hasn't been saved.")
+                            .style(Modern::secondary_text())
+                            .size(13),
+                        row![
+                            button("Discard").on_press(Message::ConfirmNavigate(screen.clone())).style(Modern::warning_button()),
+                            iced::widget::horizontal_space(),
+                            button("Stay").on_press(Message::CancelNavigate).style(Modern::system_button()),
+                        ],
+                    ]
+                    .spacing(10)
+                    .padding(10)
+                    .width(375)
+                ).style(Modern::accent_container())
+            ).padding(250)
+        });
+
+        let open_file_dialog = self.pending_open.as_ref().map(|path| {
+            container(
+                container(
+                    column![
+                        text("Open another menu?").style(Modern::primary_text()).size(18),
+                        text(path.display().to_string()).style(Modern::secondary_text()),
+                        text("This menu has unsaved changes. Opening another file replaces everything on screen.")
+                            .style(Modern::secondary_text())
+                            .size(13),
+                        row![
+                            button("Save and Open").on_press(Message::ConfirmOpenFile(true)).style(accent::primary_button()),
+                            button("Discard and Open").on_press(Message::ConfirmOpenFile(false)).style(Modern::warning_button()),
+                            iced::widget::horizontal_space(),
+                            button("Cancel").on_press(Message::CancelOpenFile).style(Modern::system_button()),
+                        ]
+                        .spacing(10),
+                    ]
+                    .spacing(10)
+                    .padding(10)
+                    .width(425)
+                ).style(Modern::accent_container())
+            ).padding(250)
+        });
+
+        let id_range_dialog = self.id_range_full.as_ref().map(|error| {
+            container(
+                container(
+                    column![
+                        text("ID range full").style(Modern::primary_text()).size(18),
+                        text(error).style(Modern::error_text()),
+                        text("Delete unused records or widen the range in Settings to add more.")
+                            .style(Modern::secondary_text())
+                            .size(13),
+                        row![
+                            iced::widget::horizontal_space(),
+                            button("OK").on_press(Message::DismissIdRangeFull).style(Modern::system_button()),
+                        ],
+                    ]
+                    .spacing(10)
+                    .padding(10)
+                    .width(375)
+                ).style(Modern::accent_container())
+            ).padding(250)
+        });
+
+        let blocked_export_dialog = self.blocked_export.as_ref().map(|(_, issues)| {
+            container(
+                container(
+                    column![
+                        text("Configuration rule errors").style(Modern::primary_text()).size(18),
+                        text(format!(
+                            "{} error-level rule failure(s). Fix them under Configuration Rules, or export anyway.",
+                            issues.len()
+                        ))
+                        .style(Modern::error_text()),
+                        column(
+                            issues.iter()
+                                .take(AFFECTED_PREVIEW)
+                                .map(|issue| text(format!("• {}", issue)).size(13).into())
+                                .collect::<Vec<_>>()
+                        )
+                        .spacing(2),
+                        text(if issues.len() > AFFECTED_PREVIEW {
+                            format!("...and {} more", issues.len() - AFFECTED_PREVIEW)
+                        } else {
+                            String::new()
+                        })
+                        .size(12)
+                        .style(Modern::secondary_text()),
+                        row![
+                            iced::widget::horizontal_space(),
+                            button("Export Anyway").on_press(Message::ExportDespiteRuleErrors).style(Modern::warning_button()),
+                            button("Cancel").on_press(Message::CancelBlockedExport).style(Modern::system_button()),
+                        ]
+                        .spacing(10),
+                    ]
+                    .spacing(10)
+                    .padding(10)
+                    .width(450)
+                ).style(Modern::accent_container())
+            ).padding(200)
+        });
+
+        let notice_toast = self.notice.as_ref().map(|(message, _)| {
+            container(
+                container(text(message).style(Modern::primary_text()))
+                    .padding(10)
+                    .style(Modern::accent_container())
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Right)
+            .align_y(iced::alignment::Vertical::Bottom)
+            .padding(20)
+        });
+
+        let undo_toast = self.pending_restore
+            .as_ref()
+            .filter(|pending| !pending.is_expired() && pending.still_applies(&self.items, &self.price_levels))
+            .map(|pending| {
+                container(
+                    container(
+                        row![
+                            text(pending.summary()).style(Modern::primary_text()),
+                            button("Undo").on_press(Message::UndoDelete).style(accent::primary_button()),
+                        ]
+                        .spacing(15)
+                        .align_y(iced::Alignment::Center)
+                    )
+                    .padding(10)
+                    .style(Modern::accent_container())
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Right)
+                .align_y(iced::alignment::Vertical::Bottom)
+                .padding(20)
+            });
+
+        // Entity lists can be searched by name and exported as they are
+        let content: Element<'_, Message> = if let Some((entity_type, entities)) = self.screen_entities() {
+            let search = self.list_search.trim().to_lowercase();
+            let shown: Vec<(EntityId, String)> = entities
+                .into_iter()
+                .filter(|(_, name)| name.to_lowercase().contains(&search))
+                .collect();
+
+            let bulk_controls: Element<'_, Message> = if self.bulk_delete_mode {
+                row![
+                    button("Select all shown")
+                        .on_press(Message::SetBulkDeleteSelection(shown.iter().map(|(id, _)| *id).collect()))
+                        .style(Modern::system_button()),
+                    button("Clear")
+                        .on_press(Message::SetBulkDeleteSelection(BTreeSet::new()))
+                        .style(Modern::system_button()),
+                    button(text(format!("Delete Selected ({})", self.bulk_delete_selection.len())))
+                        .on_press_maybe((!self.bulk_delete_selection.is_empty()).then_some(Message::RequestBulkDelete))
+                        .style(Modern::danger_button()),
+                ]
+                .spacing(10)
+                .into()
+            } else {
+                row![].into()
+            };
+
+            let unused_control: Element<'_, Message> = if item_reference(entity_type).is_some() {
+                button("Select unused")
+                    .on_press(Message::SelectUnused)
+                    .style(Modern::system_button())
+                    .into()
+            } else {
+                row![].into()
+            };
+
+            // While selecting, the rows become checkboxes so the screen's own actions are out of the way
+            let list: Element<'_, Message> = if self.bulk_delete_mode {
+                container(
+                    scrollable(
+                        column(
+                            shown
+                                .into_iter()
+                                .map(|(id, name)| {
+                                    checkbox(format!("{}  {}", id, name), self.bulk_delete_selection.contains(&id))
+                                        .on_toggle(move |selected| Message::ToggleBulkDeleteSelection(id, selected))
+                                        .style(Modern::checkbox())
+                                        .into()
+                                })
+                                .collect::<Vec<_>>()
+                        )
+                        .spacing(6)
+                        .padding(15)
+                    )
+                    .height(Length::Fill)
+                )
+                .width(Length::Fixed(505.0))
+                .style(Modern::card_container())
+                .into()
+            } else {
+                content
+            };
+
+            column![
+                row![
+                    text_input("Search by name...", &self.list_search)
+                        .width(Length::Fixed(215.0))
+                        .style(Modern::search_input())
+                        .on_input(Message::UpdateListSearch),
+                    pick_list(
+                        entity_component::ListSort::ALL,
+                        Some(self.list_sort),
+                        Message::UpdateListSort,
+                    )
+                    .width(Length::Fixed(150.0))
+                    .style(Modern::pick_list()),
+                    button("Export to CSV")
+                        .on_press(Message::ExportCurrentScreen)
+                        .style(Modern::system_button()),
+                    button("Paste")
+                        .on_press(Message::PasteEntity)
+                        .style(Modern::system_button()),
+                    checkbox("Select to delete", self.bulk_delete_mode)
+                        .on_toggle(Message::ToggleBulkDeleteMode)
+                        .style(Modern::checkbox()),
+                    unused_control,
+                    bulk_controls,
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+                list,
+            ]
+            .spacing(10)
+            .into()
+        } else {
+            content
+        };
+
+        // Stands in for the screen until the data file has loaded
+        let content = match self.loading_started {
+            Some(started) => {
+                let frame = (started.elapsed().as_millis() / LOADING_TICK.as_millis()) as usize % LOADING_FRAMES.len();
+                container(
+                    column![
+                        text(format!("{} Loading menu data...", LOADING_FRAMES[frame]))
+                            .style(Modern::primary_text())
+                            .size(18),
+                        text(&self.settings.file_path).style(Modern::secondary_text()).size(13),
+                    ]
+                    .spacing(10)
+                    .align_x(iced::Alignment::Center)
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .into()
+            }
+            None => content,
+        };
+
+        let save_banner: Element<'_, Message> = match self.save_error.borrow().clone() {
+            Some(error) => container(
+                row![
+                    text(format!("Changes are not saved: {}", error)).width(Length::Fill),
+                    button("Retry").on_press(Message::RetrySave).style(Modern::warning_button()),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+            )
+            .padding(10)
+            .width(Length::Fill)
+            .style(Modern::danger_tooltip_container())
+            .into(),
+            None => column![].into(),
+        };
+
+        //iced::widget::stack
+        let app_view = row![
+            sidebar,
+            container(column![save_banner, content].spacing(10))
+                .width(Length::Fill)
+                .padding(20),
+        ];
+        
+        println!("List of Windows: {:?}", self.windows.get(&window_id));
+        let window_view = match self.windows.get(&window_id) {
+            Some(window) => match window.windowtype {
+                WindowEnum::MainWindow => {
+                    println!("Launched Main Window!");
+                    if self.load_failure.is_some() { // Data file could not be read
+                        stack![
+                            app_view,
+                            opaque(load_failure_dialog)
+                        ].into()
+                    } else if !self.load_warnings.is_empty() { // Values that fell back to defaults
+                        stack![
+                            app_view,
+                            opaque(load_warnings_dialog)
+                        ].into()
+                    } else if self.pending_duplicates.is_some() { // Ids used by more than one record
+                        stack![
+                            app_view,
+                            opaque(duplicates_dialog)
+                        ].into()
+                    } else if let Some(dialog) = open_file_dialog { // Switching files with unsaved changes
+                        stack![
+                            app_view,
+                            opaque(dialog)
+                        ].into()
+                    } else if let Some(dialog) = id_range_dialog { // Nothing left to create a record with
+                        stack![
+                            app_view,
+                            opaque(dialog)
+                        ].into()
+                    } else if let Some(dialog) = blocked_export_dialog { // Export stopped by rule errors
+                        stack![
+                            app_view,
+                            opaque(dialog)
+                        ].into()
+                    } else if let Some(dialog) = navigate_dialog { // Leaving with an edit open
+                        stack![
+                            app_view,
+                            opaque(dialog)
+                        ].into()
+                    } else if self.show_modal && !self.bulk_deletions.is_empty() { // Confirmation for the selected batch
+                        stack![
+                            app_view,
+                            opaque(bulk_delete_popup)
+                        ].into()
+                    } else if self.show_modal { //Show Deletion confirmation popup
+                        stack![
+                            app_view,
+                            opaque(delete_confirmation_popup)
+                        ].into()
+                    } else if self.pending_bulk.is_some() { // Show bulk operation summary
+                        stack![
+                            app_view,
+                            opaque(bulk_confirmation)
+                        ].into()
+                    } else if self.pending_record_import.is_some() { // Missing references on a single record import
+                        stack![
+                            app_view,
+                            opaque(record_import_dialog)
+                        ].into()
+                    } else if self.pending_csv_import.is_some() { // Item ids already in use by a CSV import
+                        stack![
+                            app_view,
+                            opaque(csv_conflict_dialog)
+                        ].into()
+                    } else if self.show_item_import_confirmation { // Show Item Import Confirmation popup
+                        stack![
+                            app_view,
+                            opaque(import_items_confirmation)
+                        ].into()
+                    }
+                    else if let Some(toast) = undo_toast { // Offer to undo the last delete
+                        stack![
+                            app_view,
+                            toast
+                        ].into()
+                    }
+                    else if let Some(toast) = notice_toast { // Passing warnings
+                        stack![
+                            app_view,
+                            toast
+                        ].into()
+                    }
+                    else {
+                        app_view.into()
+                    }
+                }
+                WindowEnum::SuperEdit => {
+                    println!("Launched SuperEdit!");
+                    container(text("super_edit")).into()
+                }
+                WindowEnum::ItemEditor => self.item_editor_view(window_id),
+            }
+            None => { 
+                let content = column![
+                    text(format!("Something has gone terribly wrong. Window Id: {:?}", window_id)),
+                ];
+                container(
+                    content
+                ).into() 
+            }
+        };
+
+        window_view
+
+     }
+
+
+    fn perform(&mut self, operation: Operation) -> Task<Message> {
+        match operation_undo_label(&operation) {
+            Some(label) => {
+                let scope = operation_undo_scope(&operation);
+                self.with_undo(label, scope, move |app| app.perform_operation(operation))
+            }
+            None => self.perform_operation(operation),
+        }
+    }
+
+    fn perform_operation(&mut self, operation: Operation) -> Task<Message> {
+        match operation {
+            Operation::Settings(op) => {
+                match op {
+                    settings::Operation::Save(new_settings) => {
+                        self.settings = new_settings;
+
+                        if let Err(e) = self.save_state() {
+                            self.error_message = Some(e);
+                        } else {
+                            self.error_message = None;
+                        }
 
                         self.screen = Screen::Settings(self.settings.clone());
                         Task::none()
@@ -1309,23 +2876,31 @@ impl MenuBuilder {
                         Task::none()
                     }
                     settings::Operation::ThemeChanged(theme) => {
-
-                        self.theme = match theme {
-                            settings::ThemeChoice::Light => Modern::light_theme(),
-                            settings::ThemeChoice::Dark => Modern::dark_theme(),
-                        };
-
+                        self.set_theme(theme);
                         self.screen = Screen::Settings(self.settings.clone());
                         Task::none()
                     }
+                    settings::Operation::AccentChanged(accent) => {
+                        // Applied straight away so the settings screen previews it
+                        self.theme = accent::app_theme(self.settings.app_theme, accent, self.system_dark);
+                        Task::none()
+                    }
                     settings::Operation::RequestItemsList(path) => {
                         println!("Direct handling - bypassing task system");
-    
-                        self.update(
-                            Message::Settings(
-                                settings::Message::ProcessItems(
-                                    ( self.items.clone(), path )))
-                            )
+
+                        // Pre-export check against the configured rules, errors hold the
+                        // export until the user fixes them or exports anyway
+                        let rule_errors: Vec<lint::LintIssue> = self.lint_issues()
+                            .into_iter()
+                            .filter(|issue| issue.severity == lint::Severity::Error)
+                            .collect();
+
+                        if !rule_errors.is_empty() {
+                            self.blocked_export = Some((path, rule_errors));
+                            return Task::none();
+                        }
+
+                        self.export_items_to(path)
                     }
                     settings::Operation::UpdateExportMessage(msg) => {
                         println!("Updating Export Message to: {}", &msg);
@@ -1339,75 +2914,140 @@ impl MenuBuilder {
                         self.screen = Screen::Settings(self.settings.clone());
                         Task::none()
                     }
-                }
-            }
-            Operation::Items(id, op) => {
-                match op {
-                    items::Operation::Save(mut item) => {
-                        println!("Saving Item ID: {}, with prices: {:?}", item.id, item.item_prices);
-                        println!("EditState information: {:?}", self.item_edit_state.prices);
-
-                        let edit_state_prices = self.item_edit_state.prices.clone();
-                        //Copy prices from edit_state,to item
-                        let item_prices = edit_state_prices.unwrap_or(Vec::new()).iter().map(
-                            |price| ItemPrice {
-                                price_level_id: price.0,
-                                price: price.1.parse::<Decimal>().unwrap_or(Decimal::new(0, 2))
-                            }
-                        ).collect::<Vec<_>>();
-
-                        item.item_prices = Some(item_prices);
-
-                        if item.id < 0 {
-                            let next_id = self.items
-                                .keys()
-                                .max()
-                                .map_or(1, |max_id| max_id + 1);
-                            item.id = next_id;
-
-                            self.items.insert(next_id, item.clone());
-                            self.draft_item_id = None;
-                            self.draft_item = Item::default();
-                            self.selected_item_id = Some(next_id);
-                        } else {
-                            self.items.insert(item.id, item.clone());
-                            self.selected_item_id = Some(item.id);
-                        }
-                        self.screen = Screen::Items(items::Mode::View);
+                    settings::Operation::RequestOnlineMenu(path) => {
+                        let entries = items::online_menu::build_online_menu(
+                            self.items.values(),
+                            self.settings.online_menu_price_level,
+                            &self.report_categories,
+                        );
 
-                        if self.settings.auto_save {
-                            if let Err(e) = self.save_state() {
-                                self.handle_save_error(e);
+                        Task::perform(
+                            items::online_menu::write_online_menu(entries, path),
+                            |result| Message::Settings(settings::Message::ExportFinished(settings::ExportKind::OnlineMenu, result))
+                        )
+                    }
+                    settings::Operation::ScanImages => {
+                        let images: Vec<(EntityId, String, PathBuf)> = self.items.values()
+                            .filter_map(|item| item.image_path.clone().map(|path| (item.id, item.name.clone(), path)))
+                            .collect();
+
+                        Task::perform(
+                            async move {
+                                let mut missing = Vec::new();
+                                for (id, name, path) in images {
+                                    if !items::image_exists(path.clone()).await {
+                                        missing.push(format!("{} ({}): {}", name, id, path.to_string_lossy()));
+                                    }
+                                }
+                                missing
+                            },
+                            |missing| Message::Settings(settings::Message::ImagesScanned(missing))
+                        )
+                    }
+                    settings::Operation::EditPriceLevel(id) => {
+                        self.perform(Operation::PriceLevels(id, price_levels::Operation::EditPriceLevel(id)))
+                    }
+                    settings::Operation::RenumberOutOfRange(kind) => {
+                        match self.renumber_out_of_range(kind) {
+                            Ok(moves) => {
+                                println!("Renumbered {} {} records: {:?}", moves.len(), kind, moves);
+                                if let Err(e) = self.save_state() {
+                                    self.error_message = Some(e);
+                                } else {
+                                    self.error_message = None;
+                                }
                             }
+                            Err(e) => self.error_message = Some(e),
                         }
-
-                        if let Err(e) = self.save_state() {
-                            self.error_message = Some(e);
-                        } else {
-                            self.error_message = None;
-                        }
-
                         Task::none()
                     }
+                    settings::Operation::CreateVersion(name) => {
+                        let state = self.app_state();
+                        let path = PathBuf::from(&self.settings.file_path);
+                        let keep = self.settings.version_retention;
+
+                        Task::perform(
+                            async move {
+                                let version = persistence::FileManager::create_version(&path, &name, &state)?;
+                                let pruned = persistence::FileManager::prune_versions(&path, keep)?;
+                                Ok::<_, String>(if pruned > 0 {
+                                    format!("Created version {}, removed {} old version(s)", version.name, pruned)
+                                } else {
+                                    format!("Created version {}", version.name)
+                                })
+                            },
+                            |result| Message::Settings(settings::Message::VersionActionDone(result))
+                        )
+                    }
+                    settings::Operation::DiffVersion(version) => {
+                        let current = self.app_state();
+
+                        Task::perform(
+                            async move {
+                                let (saved, _) = persistence::load_from_file(&version.path.to_string_lossy())?;
+                                let diffs = state_diff::diff_states(&saved, &current);
+                                Ok::<_, String>((
+                                    format!("Changes since {} ({})", version.name, version.created),
+                                    state_diff::report_lines(&diffs),
+                                ))
+                            },
+                            |result| Message::Settings(settings::Message::VersionDetail(result))
+                        )
+                    }
+                    settings::Operation::RestoreVersion(version) => {
+                        let result = self.restore_version(&version);
+                        Task::done(Message::Settings(settings::Message::VersionActionDone(result)))
+                    }
+                    settings::Operation::RestoreBackup(backup) => {
+                        let result = self.restore_backup(&backup);
+                        Task::done(Message::Settings(settings::Message::StorageActionDone(result)))
+                    }
+                    settings::Operation::ImportItems => Task::done(Message::ImportCSV),
+                    settings::Operation::SaveMenuAs => Task::done(Message::SaveAs),
+                    settings::Operation::OpenMenuFile => Task::done(Message::OpenFile),
+                    settings::Operation::ImportRecord => {
+                        Task::perform(record_export::open_record(), Message::RecordLoaded)
+                    }
+                    settings::Operation::VerifyRoundTrip => {
+                        Task::perform(
+                            items::round_trip::verify(self.app_state()),
+                            |result| Message::Settings(settings::Message::RoundTripVerified(result))
+                        )
+                    }
+                }
+            }
+            Operation::Items(id, op) => {
+                match op {
+                    items::Operation::Save(item) => self.save_item(item).unwrap_or_else(Task::none),
                     items::Operation::StartEdit(id) => {
                         // Start editing an existing Item
+                        let Some(item) = self.items.get(&id) else {
+                            return self.missing_record("Item", id);
+                        };
                         self.draft_item_id = Some(id);
-                        self.draft_item = self.items[&id].clone();
+                        self.draft_item = item.clone();
+
+                        // The price grid is built from the draft, so prices left behind by a
+                        // deleted price level go and every assigned level gets a row
+                        let dropped = self.draft_item.drop_missing_price_levels(&self.price_levels);
+                        if !dropped.is_empty() {
+                            println!("Dropped prices for missing price levels: {:?}", dropped);
+                        }
 
-                        let item = self.items.get_mut(&id).expect("Item should exist");
                         let available_choice_groups: Vec<ChoiceGroup> = self.choice_groups.values().cloned().collect();
                         let available_printer_logicals: Vec<PrinterLogical> = self.printer_logicals.values().cloned().collect();
                         let available_price_levels: Vec<PriceLevel> = self.price_levels.values().cloned().collect();
 
                         // We only do this once, when the user explicitly begins editing:
                         self.item_edit_state = items::EditState::new(
-                            item,
+                            &self.draft_item,
                             available_choice_groups,
                             available_printer_logicals,
                             available_price_levels,
                         );
+                        self.item_edit_state.carry_over = self.item_carry_over.clone();
 
-                        println!("Prices: {:?}", item.item_prices);
+                        println!("Prices: {:?}", self.draft_item.item_prices);
                         self.screen = Screen::Items(items::Mode::Edit);
                         Task::none()
                     }
@@ -1429,6 +3069,16 @@ impl MenuBuilder {
                             .max()
                             .map_or(1, |max_id| max_id + 1);
                         item.id = next_id;
+                        item.timestamps = Timestamps::created_now();
+
+                        // Defaults only fill what a carried-over template left empty
+                        let defaults = &self.settings;
+                        item.tax_group = item.tax_group
+                            .or(defaults.new_item_tax_group.filter(|id| self.tax_groups.contains_key(id)));
+                        item.security_level = item.security_level
+                            .or(defaults.new_item_security_level.filter(|id| self.security_levels.contains_key(id)));
+                        item.report_category = item.report_category
+                            .or(defaults.new_item_report_category.filter(|id| self.report_categories.contains_key(id)));
 
                         self.items.insert(next_id, item.clone());
                         self.draft_item = item;
@@ -1438,29 +3088,82 @@ impl MenuBuilder {
                         Task::none()
                     },
                     items::Operation::Select(id) => {
-                        let test = self.items.get(&id).unwrap();
+                        let Some(test) = self.items.get(&id) else {
+                            return self.missing_record("Item", id);
+                        };
                         self.selected_item_id = Some(id);
                         self.screen = Screen::Items(items::Mode::View);
                         items::export_items::item_to_export_string(test);
-                        Task::none()
+                        self.check_item_image(id)
                     },
                     items::Operation::UpdateSearchQuery(query) => {
                         self.item_search = query;
                         Task::none()
+                    }
+                    items::Operation::CreateReference(field, name, typed_id) => {
+                        let kind = field.kind();
+                        let range = self.settings.id_range(kind);
+                        let created = match field {
+                            items::AttributeField::ProductClass => entity_component::create_named(&mut self.product_classes, kind, &name, &typed_id, &range),
+                            items::AttributeField::RevenueCategory => entity_component::create_named(&mut self.revenue_categories, kind, &name, &typed_id, &range),
+                            items::AttributeField::TaxGroup => entity_component::create_named(&mut self.tax_groups, kind, &name, &typed_id, &range),
+                            items::AttributeField::SecurityLevel => entity_component::create_named(&mut self.security_levels, kind, &name, &typed_id, &range),
+                            items::AttributeField::ReportCategory => entity_component::create_named(&mut self.report_categories, kind, &name, &typed_id, &range),
+                            // A new item group would have no id range to hold the item
+                            _ => Err(format!("A {} can't be created from the item editor", kind)),
+                        };
+
+                        match created {
+                            Ok(new_id) => {
+                                println!("Created {} {} from the item editor", kind, new_id);
+                                self.item_edit_state.inline_create = None;
+                                self.update(Message::Items(id, items::Message::Edit(field.select_message(new_id))))
+                            }
+                            Err(e) => {
+                                if let Some(create) = &mut self.item_edit_state.inline_create {
+                                    create.error = Some(e);
+                                }
+                                Task::none()
+                            }
+                        }
+                    }
+                    items::Operation::ExportRecord(id) => {
+                        self.export_record(self.items.get(&id).cloned().map(record_export::Record::Item))
+                    }
+                    items::Operation::CopyJson(id) => {
+                        self.copy_record_json(self.items.get(&id).cloned().map(record_export::Record::Item))
+                    }
+                    items::Operation::ExportToCsv(only) => {
+                        let Some(items) = items::export_selection(only, &self.items) else {
+                            return self.missing_record("Item", only.unwrap_or_default());
+                        };
+                        let tax_rates = self.tax_groups.iter().map(|(id, group)| (*id, group.rate)).collect();
+
+                        // Same file layout as the export in settings, with no path so it asks where to save
+                        Task::perform(
+                            settings::write_to_item_export(self.export_items(&items), None, tax_rates, self.settings.export_options()),
+                            |result| Message::CurrentScreenExported(result.map_err(|e| match e {
+                                settings::Error::DialogClosed => "Export cancelled".to_string(),
+                                settings::Error::IoError(kind) => format!("Failed to write the item export: {}", kind),
+                                settings::Error::InvalidData(msg) => msg,
+                            }))
+                        )
                     }
                      items::Operation::RequestDelete(id) => {
                         println!("Deleting Item id: {}", id);
                         self.deletion_info = data_types::DeletionInfo { 
                             entity_type: "Item".to_string(),
                             entity_id: id,
-                            affected_items: Vec::new()
+                            entity_name: self.items.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                            affected_items: self.referencing_items("Item", id)
                         };
-                        self.show_modal = true;
-                        Task::none()
+                        self.prompt_delete()
                     }
                     items::Operation::CopyItem(id) => {
                         println!("Copying Item: {}", id);
-                        let copy_item = self.items.get(&id).unwrap();
+                        let Some(copy_item) = self.items.get(&id) else {
+                            return self.missing_record("Item", id);
+                        };
                         let next_id = self.items
                             .keys()
                             .max()
@@ -1469,6 +3172,7 @@ impl MenuBuilder {
                         let new_item = Item {
                             id: next_id,
                             name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            timestamps: Timestamps::created_now(),
                             ..copy_item.clone()
                         };
 
@@ -1476,10 +3180,38 @@ impl MenuBuilder {
                         self.draft_item_id = Some(next_id);
                         self.draft_item = new_item;
                         self.selected_item_id = Some(next_id);
+
+                        // The editor keeps its own copy of the prices, so it has to be
+                        // rebuilt from the copy rather than left over from the last edit
+                        self.item_edit_state = items::EditState::new(
+                            &self.draft_item,
+                            self.choice_groups.values().cloned().collect(),
+                            self.printer_logicals.values().cloned().collect(),
+                            self.price_levels.values().cloned().collect(),
+                        );
+                        self.item_edit_state.carry_over = self.item_carry_over.clone();
                         self.screen = Screen::Items(items::Mode::Edit);
 
                         Task::none()
                     }
+                    items::Operation::MoveUp(id) => {
+                        let shown = self.shown_item_ids();
+                        if entity_component::move_entity(&mut self.items, id, true, &shown) {
+                            if let Err(e) = self.save_state() {
+                                self.handle_save_error(e);
+                            }
+                        }
+                        Task::none()
+                    }
+                    items::Operation::MoveDown(id) => {
+                        let shown = self.shown_item_ids();
+                        if entity_component::move_entity(&mut self.items, id, false, &shown) {
+                            if let Err(e) = self.save_state() {
+                                self.handle_save_error(e);
+                            }
+                        }
+                        Task::none()
+                    }
                     items::Operation::HideModal => {
                         self.show_modal = false;
                         Task::none()
@@ -1509,7 +3241,146 @@ impl MenuBuilder {
                         self.show_super_edit = !self.show_super_edit;
                         self.update(Message::RequestOpenWindow(WindowEnum::SuperEdit))
                     }
-                }
+                    items::Operation::OpenEditorWindow(id) => {
+                        self.update(Message::OpenEditorWindow(id))
+                    }
+                    items::Operation::SaveAndAddAnother(item) => {
+                        let template = self.item_carry_over.apply(&item);
+
+                        // Commit through the normal save path first. A refused or failed
+                        // save stays on the item, which the next draft would replace.
+                        let Some(save_task) = self.save_item(item) else {
+                            return Task::none();
+                        };
+                        let create_task = self.perform(Operation::Items(-1, items::Operation::CreateNew(template)));
+
+                        self.item_edit_state = items::EditState::new(
+                            &self.draft_item,
+                            self.choice_groups.values().cloned().collect(),
+                            self.printer_logicals.values().cloned().collect(),
+                            self.price_levels.values().cloned().collect(),
+                        );
+                        self.item_edit_state.carry_over = self.item_carry_over.clone();
+                        self.item_edit_state.carried_over = true;
+
+                        save_task
+                            .chain(create_task)
+                            .chain(iced::widget::text_input::focus(
+                                iced::widget::text_input::Id::new(items::edit::NAME_INPUT)
+                            ))
+                    }
+                    items::Operation::OpenRecent(target) => self.open_issue(target),
+                    items::Operation::UpdateCarryOver(carry_over) => {
+                        self.item_carry_over = carry_over;
+                        Task::none()
+                    }
+                    items::Operation::FilterCourse(course) => {
+                        self.item_list_filters.course = course;
+                        Task::none()
+                    }
+                    items::Operation::FilterPriceLevel(level, unpriced) => {
+                        self.item_list_filters.price_level = level;
+                        self.item_list_filters.price_level_unpriced = unpriced;
+                        Task::none()
+                    }
+                    items::Operation::FilterMissingPrice(enabled) => {
+                        self.item_list_filters.missing_price = enabled;
+                        Task::none()
+                    }
+                    items::Operation::FilterAttribute(field, id) => {
+                        match id {
+                            Some(id) => self.item_list_filters.attributes.insert(field, id),
+                            None => self.item_list_filters.attributes.remove(&field),
+                        };
+                        Task::none()
+                    }
+                    items::Operation::ShowAttributeFilters(show) => {
+                        self.item_list_filters.show_attributes = show;
+                        Task::none()
+                    }
+                    items::Operation::ClearFilters => {
+                        self.item_list_filters.clear();
+                        Task::none()
+                    }
+                    items::Operation::SelectBulkCourse(course) => {
+                        self.item_list_filters.bulk_course = course;
+                        Task::none()
+                    }
+                    items::Operation::ApplyCourse(ids, course) => {
+                        let names = ids.iter()
+                            .filter_map(|id| self.items.get(id))
+                            .map(|item| item.name.clone())
+                            .collect();
+                        let description = match course {
+                            Some(c) => format!("Set course {} on filtered items", c),
+                            None => "Clear course on filtered items".to_string(),
+                        };
+                        self.item_list_filters.bulk_course = None;
+
+                        self.run_bulk(bulk::PendingBulk::new(description, names, move |state: &mut MenuBuilder| {
+                            for id in &ids {
+                                if let Some(item) = state.items.get_mut(id) {
+                                    item.course = course;
+                                }
+                            }
+
+                            if let Err(e) = state.save_state() {
+                                state.handle_save_error(e);
+                            }
+                            Task::none()
+                        }))
+                    }
+                    items::Operation::SelectBulkAssignField(field) => {
+                        // A value picked for another field wouldn't mean anything here
+                        if self.item_list_filters.bulk_assign_field != Some(field) {
+                            self.item_list_filters.bulk_assign_value = None;
+                        }
+                        self.item_list_filters.bulk_assign_field = Some(field);
+                        Task::none()
+                    }
+                    items::Operation::SelectBulkAssignValue(id) => {
+                        self.item_list_filters.bulk_assign_value = id;
+                        Task::none()
+                    }
+                    items::Operation::ApplyAttribute(ids, field, value) => {
+                        let value_name = match field {
+                            items::AttributeField::ItemGroup => self.item_groups.get(&value).map(|e| e.name.clone()),
+                            items::AttributeField::ProductClass => self.product_classes.get(&value).map(|e| e.name.clone()),
+                            items::AttributeField::RevenueCategory => self.revenue_categories.get(&value).map(|e| e.name.clone()),
+                            items::AttributeField::TaxGroup => self.tax_groups.get(&value).map(|e| e.name.clone()),
+                            items::AttributeField::SecurityLevel => self.security_levels.get(&value).map(|e| e.name.clone()),
+                            items::AttributeField::ReportCategory => self.report_categories.get(&value).map(|e| e.name.clone()),
+                            items::AttributeField::ChoiceGroup => self.choice_groups.get(&value).map(|e| e.name.clone()),
+                            items::AttributeField::PrinterLogical => self.printer_logicals.get(&value).map(|e| e.name.clone()),
+                        };
+                        let Some(value_name) = value_name else {
+                            return self.missing_record(field.label(), value);
+                        };
+
+                        let names = ids.iter()
+                            .filter_map(|id| self.items.get(id))
+                            .map(|item| item.name.clone())
+                            .collect();
+                        let description = match field {
+                            items::AttributeField::PrinterLogical => format!("Add printer {} to filtered items", value_name),
+                            _ => format!("Set {} to {} on filtered items", field.label().to_lowercase(), value_name),
+                        };
+
+                        self.run_bulk(bulk::PendingBulk::new(description, names, move |state: &mut MenuBuilder| {
+                            let changed = ids.iter()
+                                .filter_map(|id| state.items.get_mut(id))
+                                .map(|item| field.assign(item, value))
+                                .filter(|changed| *changed)
+                                .count();
+                            println!("Bulk assign changed {} of {} items", changed, ids.len());
+
+                            if let Err(e) = state.save_state() {
+                                state.handle_save_error(e);
+                            }
+                            Task::none()
+                        }))
+                    }
+                }
             } 
             Operation::ItemGroups(id, op) => {
                 match op {
@@ -1517,21 +3388,23 @@ impl MenuBuilder {
                         self.deletion_info = data_types::DeletionInfo { 
                             entity_type: "ItemGroup".to_string(),
                             entity_id: id,
-                            affected_items: Vec::new()
+                            entity_name: self.item_groups.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                            affected_items: self.referencing_items("ItemGroup", id)
                         };
-                        self.show_modal = true;
-                        Task::none()
+                        self.prompt_delete()
                     }
                     item_groups::Operation::CopyItemGroup(id) => {
-                        let copy_item = self.item_groups.get(&id).unwrap();
-                        let next_id = self.item_groups
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        let Some(copy_item) = self.item_groups.get(&id) else {
+                            return self.missing_record("Item group", id);
+                        };
+                        let Some(next_id) = self.next_free_id(EntityKind::ItemGroup) else {
+                            return Task::none();
+                        };
                         
                         let new_item = ItemGroup {
                             id: next_id,
                             name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            timestamps: Timestamps::created_now(),
                             ..copy_item.clone()
                         };
 
@@ -1544,7 +3417,7 @@ impl MenuBuilder {
                         // First check if we already have an edit state for this item_group
                         let already_editing = self.item_group_edit_state_vec
                             .iter()
-                            .any(|state| state.base.id.parse::<i32>().unwrap() == id);
+                            .any(|state| data_types::parse_entity_id(&state.base.id) == Some(id));
 
                         // Only create new edit state if we're not already editing this item_group
                         if !already_editing {
@@ -1562,7 +3435,7 @@ impl MenuBuilder {
                         // First, find the edit state for this item_group
                         if let Some(edit_state) = self.item_group_edit_state_vec
                             .iter()
-                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                            .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
                         {
                             // Parse range values
                             if let (Ok(start), Ok(end)) = (
@@ -1576,7 +3449,8 @@ impl MenuBuilder {
                                     id_range: Range {
                                         start: start,
                                         end: end
-                                    }
+                                    },
+                                    timestamps: Timestamps::default(),
                                 };
                                 
                                 // Get a list of other groups for validation
@@ -1585,11 +3459,12 @@ impl MenuBuilder {
                                     .collect();
                                 
                                 // Validate the updated group
-                                match updated_group.validate(&other_groups) {
+                                match updated_group.validate(&other_groups, &self.settings.id_range(data_types::EntityKind::ItemGroup)) {
                                     Ok(()) => {
                                         // Validation passed, update the item_group
                                         if let Some(item_group) = self.item_groups.get_mut(&id) {
                                             item_group.name = edit_state.base.name.clone();
+                                            item_group.timestamps.touch();
                                             item_group.id_range = Range {
                                                 start: start,
                                                 end: end
@@ -1598,14 +3473,14 @@ impl MenuBuilder {
                                         
                                         // Remove the edit state
                                         self.item_group_edit_state_vec.retain(|edit| {
-                                            edit.base.id.parse::<i32>().unwrap() != id
+                                            data_types::parse_entity_id(&edit.base.id).is_some_and(|state_id| state_id != id)
                                         });
                                     },
                                     Err(error) => {
                                         // Validation failed, update the edit state with the error
                                         if let Some(edit_state) = self.item_group_edit_state_vec
                                             .iter_mut()
-                                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                                            .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
                                         {
                                             match error {
                                                 ValidationError::InvalidId(msg) | 
@@ -1632,24 +3507,29 @@ impl MenuBuilder {
                                 // Invalid range format
                                 if let Some(edit_state) = self.item_group_edit_state_vec
                                     .iter_mut()
-                                    .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                                    .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
                                 {
                                     edit_state.range_validation_error = Some("Invalid range format".to_string());
                                 }
                             }
                         }
 
-                        self.save_state().expect("Failed to save to file.");
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
                         self.screen = Screen::ItemGroups;
                         Task::none()
                     },
                     item_groups::Operation::UpdateName(id, new_name) => {
                         if let Some(edit_state) = self.item_group_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.base.name_warning = entity_component::check_duplicate_name(self.item_groups.values(), id, &new_name, "item group")
+                                    .err()
+                                    .map(|e| e.to_string());
                                 // Update the name
                                 edit_state.base.name = new_name;
                             } else {
@@ -1664,7 +3544,7 @@ impl MenuBuilder {
                     item_groups::Operation::UpdateIdRangeStart(id, new_range) => {
                         if let Some(edit_state) = self.item_group_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         { // Update the range start
                             edit_state.id_range_start = new_range;
                         }
@@ -1675,7 +3555,7 @@ impl MenuBuilder {
                     item_groups::Operation::UpdateIdRangeEnd(id, new_range) => {
                         if let Some(edit_state) = self.item_group_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         { // Update the range end
                             edit_state.id_range_end = new_range;
                         }
@@ -1683,11 +3563,63 @@ impl MenuBuilder {
                         self.screen = Screen::ItemGroups;
                         Task::none()
                     },
+                    item_groups::Operation::AutoAssign => {
+                        let assignment = item_groups::assign_by_range(&self.items, &self.item_groups);
+                        let item_name = |id: &EntityId| self.items.get(id).map(|item| item.name.clone()).unwrap_or_default();
+                        let group_name = |id: &EntityId| self.item_groups.get(id).map(|group| group.name.clone()).unwrap_or_default();
+
+                        let conflicts = assignment.conflicts
+                            .iter()
+                            .map(|(item_id, group_ids)| format!(
+                                "{} ({})",
+                                item_name(item_id),
+                                group_ids.iter().map(group_name).collect::<Vec<_>>().join(", ")
+                            ))
+                            .collect::<Vec<_>>();
+                        let names: Vec<String> = assignment.changes
+                            .iter()
+                            .map(|(item_id, group_id)| format!("{} → {}", item_name(item_id), group_name(group_id)))
+                            .collect();
+
+                        if assignment.changes.is_empty() {
+                            return self.show_notice(if conflicts.is_empty() {
+                                "Every item is already in the group its id range points to".to_string()
+                            } else {
+                                format!("Nothing to assign, ids matching more than one range: {}", conflicts.join("; "))
+                            });
+                        }
+
+                        let mut description = format!(
+                            "Move {} items into the item group their id range points to",
+                            assignment.changes.len()
+                        );
+                        if !conflicts.is_empty() {
+                            description.push_str(&format!(
+                                ". Left unchanged, ids matching more than one range: {}",
+                                conflicts.join("; ")
+                            ));
+                        }
+
+                        let changes = assignment.changes;
+                        self.pending_bulk = Some(bulk::PendingBulk::new(description, names, move |state: &mut MenuBuilder| {
+                            for (item_id, group_id) in &changes {
+                                if let Some(item) = state.items.get_mut(item_id) {
+                                    item.item_group = Some(*group_id);
+                                }
+                            }
+                            println!("Assigned {} items to item groups by id range", changes.len());
+
+                            if let Err(e) = state.save_state() {
+                                state.handle_save_error(e);
+                            }
+                            Task::none()
+                        }));
+                        Task::none()
+                    }
                     item_groups::Operation::CreateNew => {
-                        let next_id = self.item_groups
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        let Some(next_id) = self.next_free_id(EntityKind::ItemGroup) else {
+                            return Task::none();
+                        };
 
                         //Create a new ItemGroup
                         let item_group = ItemGroup {
@@ -1696,7 +3628,8 @@ impl MenuBuilder {
                                 start: 0, 
                                 end: 0 
                                 },
-                            name: String::new()
+                            name: String::new(),
+                            timestamps: Timestamps::created_now(),
                         };
 
                         //Add new ItemGroup to the app state
@@ -1711,12 +3644,14 @@ impl MenuBuilder {
                         Task::none()
                     },
                     item_groups::Operation::CancelEdit(id) => {
-                        let item_group = self.item_groups.get(&id).expect("I created an editstate without an item_group?");
+                        let Some(item_group) = self.item_groups.get(&id) else {
+                            return self.missing_record("Item group", id);
+                        };
 
                         // Find the edit state and reset it before removing
                         if let Some(edit_state) = self.item_group_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         { // If all editable fields are blank, and the ItemGroup never had a name saved, delete ItemGroup without confirmation.
                             if (    !(edit_state.base.name.len()        > 0) 
                                 ||  !(edit_state.id_range_end.len()     > 0) 
@@ -1732,7 +3667,7 @@ impl MenuBuilder {
 
                         // Remove the edit state from the vec
                         self.item_group_edit_state_vec.retain(|state| {
-                        state.base.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&state.base.id).is_some_and(|state_id| state_id != id)
                         });
 
                         self.screen = Screen::ItemGroups;
@@ -1747,21 +3682,23 @@ impl MenuBuilder {
                         self.deletion_info = data_types::DeletionInfo { 
                            entity_type: "TaxGroup".to_string(),
                            entity_id: id,
-                           affected_items: Vec::new()
+                           entity_name: self.tax_groups.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                           affected_items: self.referencing_items("TaxGroup", id)
                        };
-                        self.show_modal = true;
-                       Task::none()
+                       self.prompt_delete()
                    }
                     tax_groups::Operation::CopyTaxGroup(id) => {
-                        let copy_item = self.tax_groups.get(&id).unwrap();
-                        let next_id = self.tax_groups
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        let Some(copy_item) = self.tax_groups.get(&id) else {
+                            return self.missing_record("Tax group", id);
+                        };
+                        let Some(next_id) = self.next_free_id(EntityKind::TaxGroup) else {
+                            return Task::none();
+                        };
                        
                         let new_item = TaxGroup {
                             id: next_id,
                             name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            timestamps: Timestamps::created_now(),
                             ..copy_item.clone()
                         };
 
@@ -1774,7 +3711,7 @@ impl MenuBuilder {
                     // First check if we already have an edit state for this tax_group
                     let already_editing = self.tax_group_edit_state_vec
                         .iter()
-                        .any(|state| state.base.id.parse::<i32>().unwrap() == id);
+                        .any(|state| data_types::parse_entity_id(&state.base.id) == Some(id));
 
                     // Only create new edit state if we're not already editing this tax_group
                     if !already_editing {
@@ -1790,38 +3727,73 @@ impl MenuBuilder {
                     },
                     tax_groups::Operation::SaveAll(id, edit_state) => {
 
+                        let id_range = self.settings.id_range(EntityKind::TaxGroup);
+                        let mut rate_note = None;
+
                         // First, find the edit state for this tax_group
                         if let Some(edit_state) = self.tax_group_edit_state_vec
-                            .iter()
-                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
                         {
+                            // Keep the row open with the error shown rather than saving a bad rate
+                            if let Err(e) = edit_state.validate(&id_range) {
+                                match e {
+                                    ValidationError::InvalidRate(_) => edit_state.rate_validation_error = Some(e.to_string()),
+                                    ValidationError::InvalidId(_) | ValidationError::DuplicateId(_) => edit_state.base.id_validation_error = Some(e.to_string()),
+                                    _ => edit_state.base.name_validation_error = Some(e.to_string()),
+                                }
+                                return Task::none();
+                            }
+
                             // Clone the edit state name since we'll need it after removing the edit state
                             let new_name = edit_state.base.name.clone();
-                            let new_rate = edit_state.rate.clone();
+                            let new_rate = tax_groups::TaxGroupEditState::parse_rate(&edit_state.rate);
                             
                             // Get a mutable reference to the tax_group and update it
                             if let Some(tax_group) = self.tax_groups.get_mut(&id) {
                                 tax_group.name = new_name;
-                                tax_group.rate = data_types::string_to_decimal(&new_rate)
-                                    .expect("Rate should be validated before message is triggered");
+                                tax_group.timestamps.touch();
+                                if let Ok(rate) = new_rate {
+                                    // The scale applies to the percentage as typed, not the stored fraction
+                                    let (percentage, was_rounded) = data_types::round_amount(
+                                        rate * Decimal::from(100),
+                                        self.settings.price_scale,
+                                    );
+                                    tax_group.rate = percentage / Decimal::from(100);
+                                    if was_rounded {
+                                        rate_note = Some(format!(
+                                            "{} rate rounded to {}%",
+                                            tax_group.name,
+                                            percentage
+                                        ));
+                                    }
+                                }
                             }
                         }
 
                         self.tax_group_edit_state_vec.retain(|edit| {
-                            edit.base.id.parse::<i32>().unwrap() != id
+                            data_types::parse_entity_id(&edit.base.id).is_some_and(|state_id| state_id != id)
                         });
 
-                        self.save_state().expect("Failed to save to file.");
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
                         self.screen = Screen::TaxGroups;
-                        Task::none()
+                        match rate_note {
+                            Some(note) => self.show_notice(note),
+                            None => Task::none(),
+                        }
                     },
                     tax_groups::Operation::UpdateName(id, new_name) => {
                         if let Some(edit_state) = self.tax_group_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.base.name_warning = entity_component::check_duplicate_name(self.tax_groups.values(), id, &new_name, "tax group")
+                                    .err()
+                                    .map(|e| e.to_string());
                                 // Update the name
                                 edit_state.base.name = new_name;
                             } else {
@@ -1836,9 +3808,11 @@ impl MenuBuilder {
                     tax_groups::Operation::UpdateTaxRate(id, new_rate) => {
                         if let Some(edit_state) = self.tax_group_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         { 
-                            // Update the tax_rate
+                            edit_state.rate_validation_error = tax_groups::TaxGroupEditState::parse_rate(&new_rate)
+                                .err()
+                                .map(|e| e.to_string());
                             edit_state.rate = new_rate;
                         }
     
@@ -1846,23 +3820,24 @@ impl MenuBuilder {
                         Task::none()
                     },
                     tax_groups::Operation::CreateNew => {
-                        let next_id = self.tax_groups
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        let Some(next_id) = self.next_free_id(EntityKind::TaxGroup) else {
+                            return Task::none();
+                        };
 
                         //Create a new TaxGroup
                         let tax_group = TaxGroup {
                             id: next_id,
                             name: String::new(),
                             rate: Decimal::new( 000, 2),
+                            timestamps: Timestamps::created_now(),
                         };
 
                         //Add new TaxGroup to the app state
                         self.tax_groups.insert(next_id, tax_group.clone());
 
                         //Create a new edit_state for the new choice_group
-                        let edit_state = tax_groups::TaxGroupEditState::new(&tax_group);
+                        let mut edit_state = tax_groups::TaxGroupEditState::new(&tax_group);
+                        edit_state.base.id_range = Some(self.settings.id_range(EntityKind::TaxGroup));
                         
                         //Add new choice_group edit_state to app state
                         self.tax_group_edit_state_vec.push(edit_state);
@@ -1870,13 +3845,15 @@ impl MenuBuilder {
                         Task::none()
                     },
                     tax_groups::Operation::CancelEdit(id) => {
-                        let tax_group = self.tax_groups.get(&id).expect("I created and editstate without a TaxGroup?");
+                        let Some(tax_group) = self.tax_groups.get(&id) else {
+                            return self.missing_record("Tax group", id);
+                        };
 
 
                         // Find the edit state and reset it before removing
                         if let Some(edit_state) = self.tax_group_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         {
                             if (    !(edit_state.base.name.len()    > 0)
                                 ||  !(edit_state.rate.len()         > 0 ))
@@ -1891,7 +3868,7 @@ impl MenuBuilder {
 
                         // Remove the edit state from the vec
                         self.tax_group_edit_state_vec.retain(|state| {
-                        state.base.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&state.base.id).is_some_and(|state_id| state_id != id)
                         });
 
                         self.screen = Screen::TaxGroups;
@@ -1901,25 +3878,33 @@ impl MenuBuilder {
             }    
             Operation::SecurityLevels(id, op) => {
                 match op {
+                    security_levels::Operation::ExportRecord(id) => {
+                        self.export_record(self.security_levels.get(&id).cloned().map(record_export::Record::SecurityLevel))
+                    }
+                    security_levels::Operation::CopyJson(id) => {
+                        self.copy_record_json(self.security_levels.get(&id).cloned().map(record_export::Record::SecurityLevel))
+                    }
                     security_levels::Operation::RequestDelete(id) => {
                         self.deletion_info = data_types::DeletionInfo { 
                            entity_type: "SecurityLevel".to_string(),
                            entity_id: id,
-                           affected_items: Vec::new()
+                           entity_name: self.security_levels.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                           affected_items: self.referencing_items("SecurityLevel", id)
                        };
-                        self.show_modal = true;
-                       Task::none()
+                       self.prompt_delete()
                    }
                     security_levels::Operation::CopySecurityLevel(id) => {
-                        let copy_item = self.security_levels.get(&id).unwrap();
-                       let next_id = self.security_levels
-                           .keys()
-                           .max()
-                           .map_or(1, |max_id| max_id + 1);
+                        let Some(copy_item) = self.security_levels.get(&id) else {
+                            return self.missing_record("Security level", id);
+                        };
+                       let Some(next_id) = self.next_free_id(EntityKind::SecurityLevel) else {
+                           return Task::none();
+                       };
                        
                        let new_item = SecurityLevel {
                            id: next_id,
                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                           timestamps: Timestamps::created_now(),
                            ..copy_item.clone()
                        };
 
@@ -1932,18 +3917,12 @@ impl MenuBuilder {
                         // First check if we already have an edit state for this security_level
                         let already_editing = self.security_level_edit_state_vec
                             .iter()
-                            .any(|state| state.id.parse::<i32>().unwrap() == id);
+                            .any(|state| data_types::parse_entity_id(&state.id) == Some(id));
     
                         // Only create new edit state if we're not already editing this security_level
                         if !already_editing {
                             if let Some(security_level) = self.security_levels.get(&id) {
-                                let edit_state = entity_component::EditState {
-                                    name: security_level.name.clone(),
-                                    original_name: security_level.name.clone(),
-                                    id: security_level.id.to_string(),
-                                    id_validation_error: None,
-                                    name_validation_error: None,
-                                };
+                                let edit_state = entity_component::EditState::new(security_level);
                                 
                                 self.security_level_edit_state_vec.push(edit_state);
                             }
@@ -1953,35 +3932,91 @@ impl MenuBuilder {
                         Task::none()
                     },
                     security_levels::Operation::SaveAll(id, edit_state) => {
+                        // A new record moves onto its typed id before anything else is checked
+                        let range = self.settings.id_range(EntityKind::SecurityLevel);
+                        let id = match self.security_level_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                        {
+                            Some(edit_state) => match entity_component::apply_typed_id(&mut self.security_levels, EntityKind::SecurityLevel, id, edit_state, &range) {
+                                Ok(id) => id,
+                                Err(e) => {
+                                    edit_state.id_validation_error = Some(e.to_string());
+                                    self.screen = Screen::SecurityLevels;
+                                    return Task::none();
+                                }
+                            },
+                            None => id,
+                        };
+
                         // First, find the edit state for this security_level
                         if let Some(edit_state) = self.security_level_edit_state_vec
-                            .iter()
-                            .find(|state| state.id.parse::<i32>().unwrap() == id)
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
                         {
+                            // Invalid levels stay open with the error on the field
+                            let candidate = SecurityLevel { id, name: edit_state.name.clone(), timestamps: Timestamps::default() };
+                            let other_levels: Vec<&SecurityLevel> = self.security_levels
+                                .values()
+                                .filter(|level| level.id != id)
+                                .collect();
+                            if let Err(e) = candidate.validate(&other_levels, &range) {
+                                match e {
+                                    ValidationError::InvalidId(_) | ValidationError::DuplicateId(_) => {
+                                        edit_state.id_validation_error = Some(e.to_string());
+                                    }
+                                    other => edit_state.name_validation_error = Some(other.to_string()),
+                                }
+                                self.screen = Screen::SecurityLevels;
+                                return Task::none();
+                            }
+
                             // Clone the edit state name since we'll need it after removing the edit state
                             let new_name = edit_state.name.clone();
                             
                             // Get a mutable reference to the security_level and update it
                             if let Some(security_group) = self.security_levels.get_mut(&id) {
                                 security_group.name = new_name;
+                                security_group.timestamps.touch();
                             }
                         }
 
                         self.security_level_edit_state_vec.retain(|edit| {
-                            edit.id.parse::<i32>().unwrap() != id
+                            data_types::parse_entity_id(&edit.id).is_some_and(|state_id| state_id != id)
                         });
 
-                        self.save_state().expect("Failed to save to file.");
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                        self.screen = Screen::SecurityLevels;
+                        Task::none()
+                    },
+                    security_levels::Operation::UpdateId(id, new_id) => {
+                        let range = self.settings.id_range(EntityKind::SecurityLevel);
+                        if let Some(edit_state) = self.security_level_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                            .filter(|state| state.id_input.is_some())
+                        {
+                            edit_state.id_validation_error = entity_component::check_typed_id(&self.security_levels, EntityKind::SecurityLevel, id, &new_id, &range)
+                                .err()
+                                .map(|e| e.to_string());
+                            edit_state.id_input = Some(new_id);
+                        }
+
                         self.screen = Screen::SecurityLevels;
                         Task::none()
                     },
                     security_levels::Operation::UpdateName(id, new_name) => {
                         if let Some(edit_state) = self.security_level_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.name_warning = entity_component::check_duplicate_name(self.security_levels.values(), id, &new_name, "security level")
+                                    .err()
+                                    .map(|e| e.to_string());
                                 // Update the name
                                 edit_state.name = new_name;
                             } else {
@@ -1994,15 +4029,15 @@ impl MenuBuilder {
                         Task::none()
                     },
                     security_levels::Operation::CreateNew => {
-                        let next_id = self.security_levels
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        let Some(next_id) = self.next_free_id(EntityKind::SecurityLevel) else {
+                            return Task::none();
+                        };
 
                         //Create a new SecurityLevel
                         let security_level = SecurityLevel {
                             id: next_id,
-                            name: String::new()
+                            name: String::new(),
+                            timestamps: Timestamps::created_now(),
                         };
 
                         //Add new SecurityLevel to the app state
@@ -2010,11 +4045,9 @@ impl MenuBuilder {
 
                         //Create a new edit_state for the new security_level
                         let edit_state = entity_component::EditState {
-                            name: security_level.name.clone(),
-                            original_name: security_level.name.clone(),
-                            id: security_level.id.to_string(),
-                            id_validation_error: None,
-                            name_validation_error: None,
+                            id_input: Some(next_id.to_string()),
+                            id_range: Some(self.settings.id_range(EntityKind::SecurityLevel)),
+                            ..entity_component::EditState::new(&security_level)
                         };
                         
                         //Add new security_level edit_state to app state
@@ -2023,12 +4056,14 @@ impl MenuBuilder {
                         Task::none()
                     },
                     security_levels::Operation::CancelEdit(id) => {
-                        let security_level = self.security_levels.get(&id).expect("I created an editstate without a SecurityLevel?");
+                        let Some(security_level) = self.security_levels.get(&id) else {
+                            return self.missing_record("Security level", id);
+                        };
 
                         // Find the edit state and reset it before removing
                         if let Some(edit_state) = self.security_level_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         {
                             if security_level.name.len() < 1 
                             {
@@ -2040,7 +4075,7 @@ impl MenuBuilder {
 
                         // Remove the edit state from the vec
                         self.security_level_edit_state_vec.retain(|state| {
-                        state.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&state.id).is_some_and(|state_id| state_id != id)
                         });
 
                         self.screen = Screen::SecurityLevels;
@@ -2050,25 +4085,35 @@ impl MenuBuilder {
             }    
             Operation::RevenueCategories(id, op) => {
                 match op {
+                    revenue_categories::Operation::ExportRecord(id) => {
+                        self.export_record(self.revenue_categories.get(&id).cloned().map(record_export::Record::RevenueCategory))
+                    }
+                    revenue_categories::Operation::CopyJson(id) => {
+                        self.copy_record_json(self.revenue_categories.get(&id).cloned().map(record_export::Record::RevenueCategory))
+                    }
                     revenue_categories::Operation::RequestDelete(id) => {
                         self.deletion_info = data_types::DeletionInfo { 
                            entity_type: "RevenueCategory".to_string(),
                            entity_id: id,
-                           affected_items: Vec::new()
+                           entity_name: self.revenue_categories.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                           affected_items: self.referencing_items("RevenueCategory", id)
                        };
-                        self.show_modal = true;
-                       Task::none()
+                       self.prompt_delete()
                    }
                     revenue_categories::Operation::CopyRevenueCategory(id) => {
-                        let copy_item = self.revenue_categories.get(&id).unwrap();
-                       let next_id = self.revenue_categories
-                           .keys()
-                           .max()
-                           .map_or(1, |max_id| max_id + 1);
+                        let Some(copy_item) = self.revenue_categories.get(&id) else {
+                            return self.missing_record("Revenue category", id);
+                        };
+                       let next_id = self.free_id(EntityKind::RevenueCategory);
+                       self.revenue_category_create_error = next_id.as_ref().err().cloned();
+                       let Ok(next_id) = next_id else {
+                           return Task::none();
+                       };
                        
                        let new_item = RevenueCategory {
                            id: next_id,
                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                           timestamps: Timestamps::created_now(),
                            ..copy_item.clone()
                        };
 
@@ -2081,18 +4126,12 @@ impl MenuBuilder {
                     // First check if we already have an edit state for this revenue_category
                     let already_editing = self.revenue_category_edit_state_vec
                         .iter()
-                        .any(|state| state.id.parse::<i32>().unwrap() == id);
+                        .any(|state| data_types::parse_entity_id(&state.id) == Some(id));
 
                     // Only create new edit state if we're not already editing this revenue_category
                     if !already_editing {
                         if let Some(revenue_category) = self.revenue_categories.get(&id) {
-                            let edit_state = entity_component::EditState {
-                                name: revenue_category.name.clone(),
-                                original_name: revenue_category.name.clone(),
-                                id: revenue_category.id.to_string(),
-                                id_validation_error: None,
-                                name_validation_error: None,
-                            };
+                            let edit_state = entity_component::EditState::new(revenue_category);
                             
                             self.revenue_category_edit_state_vec.push(edit_state);
                         }
@@ -2102,10 +4141,40 @@ impl MenuBuilder {
                     Task::none()
                    },
                     revenue_categories::Operation::SaveAll(id, edit_state) => {
+                        // Records outside the id range can't be saved until they're renumbered
+                        let range = self.settings.id_range(data_types::EntityKind::RevenueCategory);
+                        let id = match self.revenue_category_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                        {
+                            Some(edit_state) => match entity_component::apply_typed_id(&mut self.revenue_categories, data_types::EntityKind::RevenueCategory, id, edit_state, &range) {
+                                Ok(id) => id,
+                                Err(e) => {
+                                    edit_state.id_validation_error = Some(e.to_string());
+                                    self.screen = Screen::RevenueCategories;
+                                    return Task::none();
+                                }
+                            },
+                            None => id,
+                        };
+
+                        if let Some(edit_state) = self.revenue_category_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                        {
+                            if let Err(e) = data_types::check_id_range(data_types::EntityKind::RevenueCategory, id, &range) {
+                                edit_state.id_validation_error = Some(format!(
+                                    "{}. Use Renumber under Settings > ID Ranges", e
+                                ));
+                                self.screen = Screen::RevenueCategories;
+                                return Task::none();
+                            }
+                        }
+
                         // First, find the edit state for this revenue_category
                         if let Some(edit_state) = self.revenue_category_edit_state_vec
                         .iter()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id)
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
                         {
                         // Clone the edit state name since we'll need it after removing the edit state
                         let new_name = edit_state.name.clone();
@@ -2113,24 +4182,46 @@ impl MenuBuilder {
                         // Get a mutable reference to the revenue_category and update it
                         if let Some(revenue_category) = self.revenue_categories.get_mut(&id) {
                             revenue_category.name = new_name;
+                            revenue_category.timestamps.touch();
                         }
                         }
 
                         self.revenue_category_edit_state_vec.retain(|edit| {
-                        edit.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&edit.id).is_some_and(|state_id| state_id != id)
                         });
 
-                        self.save_state().expect("Failed to save to file.");
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                        self.screen = Screen::RevenueCategories;
+                        Task::none()
+                    },
+                    revenue_categories::Operation::UpdateId(id, new_id) => {
+                        let range = self.settings.id_range(data_types::EntityKind::RevenueCategory);
+                        if let Some(edit_state) = self.revenue_category_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                            .filter(|state| state.id_input.is_some())
+                        {
+                            edit_state.id_validation_error = entity_component::check_typed_id(&self.revenue_categories, data_types::EntityKind::RevenueCategory, id, &new_id, &range)
+                                .err()
+                                .map(|e| e.to_string());
+                            edit_state.id_input = Some(new_id);
+                        }
+
                         self.screen = Screen::RevenueCategories;
                         Task::none()
                     },
                     revenue_categories::Operation::UpdateName(id, new_name) => {
                         if let Some(edit_state) = self.revenue_category_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.name_warning = entity_component::check_duplicate_name(self.revenue_categories.values(), id, &new_name, "revenue category")
+                                    .err()
+                                    .map(|e| e.to_string());
                                 // Update the name
                                 edit_state.name = new_name;
                             } else {
@@ -2143,15 +4234,18 @@ impl MenuBuilder {
                         Task::none()
                     },
                     revenue_categories::Operation::CreateNew => {
-                        let next_id = self.revenue_categories
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        // Lowest free id inside the configured range, rather than max + 1
+                        let next_id = self.free_id(EntityKind::RevenueCategory);
+                        self.revenue_category_create_error = next_id.as_ref().err().cloned();
+                        let Ok(next_id) = next_id else {
+                            return Task::none();
+                        };
 
                         //Create a new RevenueCategory
                         let revenue_category = RevenueCategory {
                             id: next_id,
-                            name: String::new()
+                            name: String::new(),
+                            timestamps: Timestamps::created_now(),
                         };
 
                         //Add new RevenueCategory to the app state
@@ -2159,11 +4253,9 @@ impl MenuBuilder {
 
                         //Create a new edit_state for the new revenue_category
                         let edit_state = entity_component::EditState {
-                            name: revenue_category.name.clone(),
-                            original_name: revenue_category.name.clone(),
-                            id: revenue_category.id.to_string(),
-                            id_validation_error: None,
-                            name_validation_error: None,
+                            id_input: Some(next_id.to_string()),
+                            id_range: Some(self.settings.id_range(EntityKind::RevenueCategory)),
+                            ..entity_component::EditState::new(&revenue_category)
                         };
                         
                         //Add new revenue_category edit_state to app state
@@ -2172,12 +4264,14 @@ impl MenuBuilder {
                         Task::none()
                     },
                     revenue_categories::Operation::CancelEdit(id) => {
-                        let revenue_category = self.revenue_categories.get(&id).expect("I created an editstate without a RevenueCategory?");
+                        let Some(revenue_category) = self.revenue_categories.get(&id) else {
+                            return self.missing_record("Revenue category", id);
+                        };
 
                         // Find the edit state and reset it before removing
                         if let Some(edit_state) = self.revenue_category_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         {
                             if revenue_category.name.len() < 1 
                             {
@@ -2190,7 +4284,7 @@ impl MenuBuilder {
 
                         // Remove the edit state from the vec
                         self.revenue_category_edit_state_vec.retain(|state| {
-                        state.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&state.id).is_some_and(|state_id| state_id != id)
                         });
 
                         self.screen = Screen::RevenueCategories;
@@ -2200,49 +4294,54 @@ impl MenuBuilder {
             }    
             Operation::ReportCategories(id, op) => {
                 match op {
+                    report_categories::Operation::ExportRecord(id) => {
+                        self.export_record(self.report_categories.get(&id).cloned().map(record_export::Record::ReportCategory))
+                    }
+                    report_categories::Operation::CopyJson(id) => {
+                        self.copy_record_json(self.report_categories.get(&id).cloned().map(record_export::Record::ReportCategory))
+                    }
                     report_categories::Operation::RequestDelete(id) => {
                         self.deletion_info = data_types::DeletionInfo { 
                            entity_type: "ReportCategory".to_string(),
                            entity_id: id,
-                           affected_items: Vec::new()
+                           entity_name: self.report_categories.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                           affected_items: self.referencing_items("ReportCategory", id)
                         };
-                        self.show_modal = true;
-                        Task::none()
+                        self.prompt_delete()
                    }
                     report_categories::Operation::CopyReportCategory(id) => {
-                        let copy_item = self.report_categories.get(&id).unwrap();
-                        let next_id = self.report_categories
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        let Some(copy_item) = self.report_categories.get(&id) else {
+                            return self.missing_record("Report category", id);
+                        };
+                        let next_id = self.free_id(EntityKind::ReportCategory);
+                        self.report_category_create_error = next_id.as_ref().err().cloned();
+                        let Ok(next_id) = next_id else {
+                            return Task::none();
+                        };
                        
                         let new_item = ReportCategory {
                             id: next_id,
                             name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            timestamps: Timestamps::created_now(),
                             ..copy_item.clone()
                         };
 
+                        let capacity_before = self.report_category_capacity();
                         self.report_categories.insert(next_id, new_item.clone());
                         self.screen = Screen::ReportCategories;
 
-                        Task::none()
+                        self.warn_report_category_capacity(capacity_before)
                    }
                     report_categories::Operation::EditReportCategory(id) => {
                         // First check if we already have an edit state for this report_category
                         let already_editing = self.report_category_edit_state_vec
                             .iter()
-                            .any(|state| state.id.parse::<i32>().unwrap() == id);
+                            .any(|state| data_types::parse_entity_id(&state.id) == Some(id));
 
                         // Only create new edit state if we're not already editing this report_category
                         if !already_editing {
                             if let Some(report_category) = self.report_categories.get(&id) {
-                                let edit_state = entity_component::EditState {
-                                    name: report_category.name.clone(),
-                                    original_name: report_category.name.clone(),
-                                    id: report_category.id.to_string(),
-                                    id_validation_error: None,
-                                    name_validation_error: None,
-                                };
+                                let edit_state = entity_component::EditState::new(report_category);
                                 
                                 self.report_category_edit_state_vec.push(edit_state);
                             }
@@ -2252,10 +4351,40 @@ impl MenuBuilder {
                         Task::none()
                     },
                     report_categories::Operation::SaveAll(id, edit_state) => {
+                        // Records outside the id range can't be saved until they're renumbered
+                        let range = self.settings.id_range(data_types::EntityKind::ReportCategory);
+                        let id = match self.report_category_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                        {
+                            Some(edit_state) => match entity_component::apply_typed_id(&mut self.report_categories, data_types::EntityKind::ReportCategory, id, edit_state, &range) {
+                                Ok(id) => id,
+                                Err(e) => {
+                                    edit_state.id_validation_error = Some(e.to_string());
+                                    self.screen = Screen::ReportCategories;
+                                    return Task::none();
+                                }
+                            },
+                            None => id,
+                        };
+
+                        if let Some(edit_state) = self.report_category_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                        {
+                            if let Err(e) = data_types::check_id_range(data_types::EntityKind::ReportCategory, id, &range) {
+                                edit_state.id_validation_error = Some(format!(
+                                    "{}. Use Renumber under Settings > ID Ranges", e
+                                ));
+                                self.screen = Screen::ReportCategories;
+                                return Task::none();
+                            }
+                        }
+
                         // First, find the edit state for this report_category
                         if let Some(edit_state) = self.report_category_edit_state_vec
                             .iter()
-                            .find(|state| state.id.parse::<i32>().unwrap() == id)
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
                         {
                             // Clone the edit state name since we'll need it after removing the edit state
                             let new_name = edit_state.name.clone();
@@ -2263,24 +4392,46 @@ impl MenuBuilder {
                             // Get a mutable reference to the report_category and update it
                             if let Some(report_category) = self.report_categories.get_mut(&id) {
                                 report_category.name = new_name;
+                                report_category.timestamps.touch();
                             }
                         }
 
                         self.report_category_edit_state_vec.retain(|edit| {
-                            edit.id.parse::<i32>().unwrap() != id
+                            data_types::parse_entity_id(&edit.id).is_some_and(|state_id| state_id != id)
                         });
 
-                        self.save_state().expect("Failed to save to file.");
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                        self.screen = Screen::ReportCategories;
+                        Task::none()
+                    },
+                    report_categories::Operation::UpdateId(id, new_id) => {
+                        let range = self.settings.id_range(data_types::EntityKind::ReportCategory);
+                        if let Some(edit_state) = self.report_category_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                            .filter(|state| state.id_input.is_some())
+                        {
+                            edit_state.id_validation_error = entity_component::check_typed_id(&self.report_categories, data_types::EntityKind::ReportCategory, id, &new_id, &range)
+                                .err()
+                                .map(|e| e.to_string());
+                            edit_state.id_input = Some(new_id);
+                        }
+
                         self.screen = Screen::ReportCategories;
                         Task::none()
                     },
                     report_categories::Operation::UpdateName(id, new_name) => {
                         if let Some(edit_state) = self.report_category_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.name_warning = entity_component::check_duplicate_name(self.report_categories.values(), id, &new_name, "report category")
+                                    .err()
+                                    .map(|e| e.to_string());
                                 // Update the name
                                 edit_state.name = new_name;
                             } else {
@@ -2293,41 +4444,83 @@ impl MenuBuilder {
                         Task::none()
                     },
                     report_categories::Operation::CreateNew => {
-                        let next_id = self.report_categories
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        // Lowest free id inside the configured range, rather than max + 1
+                        let next_id = self.free_id(EntityKind::ReportCategory);
+                        self.report_category_create_error = next_id.as_ref().err().cloned();
+                        let Ok(next_id) = next_id else {
+                            return Task::none();
+                        };
 
                         //Create a new ReportCategory
                         let report_category = ReportCategory {
                             id: next_id,
-                            name: String::new()
+                            name: String::new(),
+                            timestamps: Timestamps::created_now(),
                         };
 
                         //Add new ReportCategory to the app state
+                        let capacity_before = self.report_category_capacity();
                         self.report_categories.insert(next_id, report_category.clone());
 
                         //Create a new edit_state for the new report_category
                         let edit_state = entity_component::EditState {
-                            name: report_category.name.clone(),
-                            original_name: report_category.name.clone(),
-                            id: report_category.id.to_string(),
-                            id_validation_error: None,
-                            name_validation_error: None,
+                            id_input: Some(next_id.to_string()),
+                            id_range: Some(self.settings.id_range(EntityKind::ReportCategory)),
+                            ..entity_component::EditState::new(&report_category)
                         };
                         
                         //Add new report_category edit_state to app state
                         self.report_category_edit_state_vec.push(edit_state);
 
+                        self.warn_report_category_capacity(capacity_before)
+                    },
+                    report_categories::Operation::ShowUnused(show) => {
+                        self.show_unused_report_categories = show;
+                        Task::none()
+                    },
+                    report_categories::Operation::SelectMerge(selection) => {
+                        self.merge_selection = selection;
+                        Task::none()
+                    },
+                    report_categories::Operation::Merge(from, into) => {
+                        let (Some(source), Some(target)) = (self.report_categories.get(&from), self.report_categories.get(&into)) else {
+                            let missing = if self.report_categories.contains_key(&from) { into } else { from };
+                            return self.missing_record("Report category", missing);
+                        };
+
+                        let description = format!(
+                            "Merge report category {} ({}) into {} ({}) and delete {}",
+                            source.name, from, target.name, into, source.name
+                        );
+                        let names = self.referencing_items("ReportCategory", from);
+
+                        // Always asked, deleting the category can't be put right by hand afterwards
+                        self.pending_bulk = Some(bulk::PendingBulk::new(description, names, move |state: &mut MenuBuilder| {
+                            for item in state.items.values_mut() {
+                                if item.report_category == Some(from) {
+                                    item.report_category = Some(into);
+                                }
+                            }
+                            state.report_categories.remove(&from);
+                            state.report_category_edit_state_vec.retain(|edit| data_types::parse_entity_id(&edit.id) != Some(from));
+                            state.merge_selection = entity_component::MergeSelection::default();
+
+                            if let Err(e) = state.save_state() {
+                                state.handle_save_error(e);
+                            }
+                            Task::none()
+                        }));
                         Task::none()
                     },
                     report_categories::Operation::CancelEdit(id) => {
-                        let report_category = self.report_categories.get(&id).expect("I created an editstate without a ReportCategory?");
+                        let Some(report_category) = self.report_categories.get(&id) else {
+                            return self.missing_record("Report category", id);
+                        };
 
                         // Find the edit state and reset it before removing
                         if let Some(edit_state) = self.report_category_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         {
                             if report_category.name.len() < 1 
                             {
@@ -2340,7 +4533,7 @@ impl MenuBuilder {
 
                         // Remove the edit state from the vec
                         self.report_category_edit_state_vec.retain(|state| {
-                        state.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&state.id).is_some_and(|state_id| state_id != id)
                         });
 
                         self.screen = Screen::ReportCategories;
@@ -2350,25 +4543,33 @@ impl MenuBuilder {
             }    
             Operation::ProductClasses(id, op) => {
                 match op {
+                    product_classes::Operation::ExportRecord(id) => {
+                        self.export_record(self.product_classes.get(&id).cloned().map(record_export::Record::ProductClass))
+                    }
+                    product_classes::Operation::CopyJson(id) => {
+                        self.copy_record_json(self.product_classes.get(&id).cloned().map(record_export::Record::ProductClass))
+                    }
                     product_classes::Operation::RequestDelete(id) => {
                         self.deletion_info = data_types::DeletionInfo { 
                            entity_type: "ProductClass".to_string(),
                            entity_id: id,
-                           affected_items: Vec::new()
+                           entity_name: self.product_classes.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                           affected_items: self.referencing_items("ProductClass", id)
                         };
-                        self.show_modal = true;
-                        Task::none()
+                        self.prompt_delete()
                    }
                     product_classes::Operation::CopyProductClass(id) => {
-                        let copy_item = self.product_classes.get(&id).unwrap();
-                        let next_id = self.product_classes
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        let Some(copy_item) = self.product_classes.get(&id) else {
+                            return self.missing_record("Product class", id);
+                        };
+                        let Some(next_id) = self.next_free_id(EntityKind::ProductClass) else {
+                            return Task::none();
+                        };
                        
                         let new_item = ProductClass {
                             id: next_id,
                             name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            timestamps: Timestamps::created_now(),
                             ..copy_item.clone()
                         };
 
@@ -2381,18 +4582,12 @@ impl MenuBuilder {
                         // First check if we already have an edit state for this product_class
                         let already_editing = self.product_class_edit_state_vec
                             .iter()
-                            .any(|state| state.id.parse::<i32>().unwrap() == id);
+                            .any(|state| data_types::parse_entity_id(&state.id) == Some(id));
     
                         // Only create new edit state if we're not already editing this product_class
                         if !already_editing {
                             if let Some(product_class) = self.product_classes.get(&id) {
-                                let edit_state = entity_component::EditState {
-                                    name: product_class.name.clone(),
-                                    original_name: product_class.name.clone(),
-                                    id: product_class.id.to_string(),
-                                    id_validation_error: None,
-                                    name_validation_error: None,
-                                };
+                                let edit_state = entity_component::EditState::new(product_class);
                                 
                                 self.product_class_edit_state_vec.push(edit_state);
                             }
@@ -2402,35 +4597,89 @@ impl MenuBuilder {
                         Task::none()
                     },
                     product_classes::Operation::SaveAll(id, edit_state) => {
+                        let range = self.settings.id_range(EntityKind::ProductClass);
+                        let id = match self.product_class_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                        {
+                            Some(edit_state) => match entity_component::apply_typed_id(&mut self.product_classes, EntityKind::ProductClass, id, edit_state, &range) {
+                                Ok(id) => id,
+                                Err(e) => {
+                                    edit_state.id_validation_error = Some(e.to_string());
+                                    self.screen = Screen::ProductClasses;
+                                    return Task::none();
+                                }
+                            },
+                            None => id,
+                        };
+
                         // First, find the edit state for this product_class
                         if let Some(edit_state) = self.product_class_edit_state_vec
-                            .iter()
-                            .find(|state| state.id.parse::<i32>().unwrap() == id)
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
                         {
+                            // Invalid classes stay open with the error on the field
+                            let candidate = ProductClass { id, name: edit_state.name.clone(), timestamps: Timestamps::default() };
+                            let other_classes: Vec<&ProductClass> = self.product_classes
+                                .values()
+                                .filter(|class| class.id != id)
+                                .collect();
+                            if let Err(e) = candidate.validate(&other_classes, &self.settings.id_range(EntityKind::ProductClass)) {
+                                match e {
+                                    ValidationError::InvalidId(_) | ValidationError::DuplicateId(_) => {
+                                        edit_state.id_validation_error = Some(e.to_string());
+                                    }
+                                    other => edit_state.name_validation_error = Some(other.to_string()),
+                                }
+                                return Task::none();
+                            }
+
                             // Clone the edit state name since we'll need it after removing the edit state
                             let new_name = edit_state.name.clone();
                             
                             // Get a mutable reference to the product_class and update it
                             if let Some(product_class) = self.product_classes.get_mut(&id) {
                                 product_class.name = new_name;
+                                product_class.timestamps.touch();
                             }
                         }
 
                         self.product_class_edit_state_vec.retain(|edit| {
-                            edit.id.parse::<i32>().unwrap() != id
+                            data_types::parse_entity_id(&edit.id).is_some_and(|state_id| state_id != id)
                         });
 
-                        self.save_state().expect("Failed to save to file.");
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                        self.screen = Screen::ProductClasses;
+                        Task::none()
+                    },
+                    product_classes::Operation::UpdateId(id, new_id) => {
+                        let range = self.settings.id_range(EntityKind::ProductClass);
+                        if let Some(edit_state) = self.product_class_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                            .filter(|state| state.id_input.is_some())
+                        {
+                            edit_state.id_validation_error = entity_component::check_typed_id(&self.product_classes, EntityKind::ProductClass, id, &new_id, &range)
+                                .err()
+                                .map(|e| e.to_string());
+                            edit_state.id_input = Some(new_id);
+                        }
+
                         self.screen = Screen::ProductClasses;
                         Task::none()
                     },
                     product_classes::Operation::UpdateName(id, new_name) => {
                         if let Some(edit_state) = self.product_class_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.name_warning = entity_component::check_duplicate_name(self.product_classes.values(), id, &new_name, "product class")
+                                    .err()
+                                    .map(|e| e.to_string());
                                 // Update the name
                                 edit_state.name = new_name;
                             } else {
@@ -2443,15 +4692,15 @@ impl MenuBuilder {
                         Task::none()
                     },
                     product_classes::Operation::CreateNew => {
-                        let next_id = self.product_classes
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                        let Some(next_id) = self.next_free_id(EntityKind::ProductClass) else {
+                            return Task::none();
+                        };
 
                         //Create a new ProductClass
                         let product_class = ProductClass {
                             id: next_id,
-                            name: String::new()
+                            name: String::new(),
+                            timestamps: Timestamps::created_now(),
                         };
 
                         //Add new ProductClass to the app state
@@ -2459,11 +4708,9 @@ impl MenuBuilder {
 
                         //Create a new edit_state for the new product_class
                         let edit_state = entity_component::EditState {
-                            name: product_class.name.clone(),
-                            original_name: product_class.name.clone(),
-                            id: product_class.id.to_string(),
-                            id_validation_error: None,
-                            name_validation_error: None,
+                            id_input: Some(next_id.to_string()),
+                            id_range: Some(self.settings.id_range(EntityKind::ProductClass)),
+                            ..entity_component::EditState::new(&product_class)
                         };
                         
                         //Add new product_class edit_state to app state
@@ -2472,12 +4719,14 @@ impl MenuBuilder {
                         Task::none()
                     },
                     product_classes::Operation::CancelEdit(id) => {
-                        let product_class = self.product_classes.get(&id).expect("I created an editstate without a ProductClass?");
+                        let Some(product_class) = self.product_classes.get(&id) else {
+                            return self.missing_record("Product class", id);
+                        };
 
                         // Find the edit state and reset it before removing
                         if let Some(edit_state) = self.product_class_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         {
                             if product_class.name.len() < 1 
                             {
@@ -2490,7 +4739,7 @@ impl MenuBuilder {
 
                         // Remove the edit state from the vec
                         self.product_class_edit_state_vec.retain(|state| {
-                        state.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&state.id).is_some_and(|state_id| state_id != id)
                         });
 
                         self.screen = Screen::ProductClasses;
@@ -2499,26 +4748,133 @@ impl MenuBuilder {
                 }
             }    
             Operation::ChoiceGroups(id, op) => match op {
-                choice_groups::Operation::RequestDelete(id) => {
-
-                    self.deletion_info = data_types::DeletionInfo { 
-                        entity_type: "ChoiceGroup".to_string(),
-                        entity_id: id,
-                        affected_items: Vec::new()
-                    };
-                     self.show_modal = true;
+                choice_groups::Operation::ToggleSortByUsage(enabled) => {
+                    self.choice_group_list_options.sort_by_usage = enabled;
                     Task::none()
-                },
-                choice_groups::Operation::CopyChoiceGroup(id) => {
-                    let copy_item = self.choice_groups.get(&id).unwrap();
-                    let next_id = self.choice_groups
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
-                    
-                    let new_item = ChoiceGroup {
-                        id: next_id,
+                }
+                choice_groups::Operation::ToggleNeverUsed(enabled) => {
+                    self.choice_group_list_options.never_used_only = enabled;
+                    Task::none()
+                }
+                choice_groups::Operation::ShowMembers(group_id) => {
+                    self.choice_group_list_options.members_of = group_id;
+                    self.choice_group_list_options.member_search.clear();
+                    Task::none()
+                }
+                choice_groups::Operation::SearchMembers(query) => {
+                    self.choice_group_list_options.member_search = query;
+                    Task::none()
+                }
+                choice_groups::Operation::ToggleItemMembership(group_id, item_id) => {
+                    let Some(item) = self.items.get_mut(&item_id) else {
+                        return self.missing_record("Item", item_id);
+                    };
+
+                    // Assigning does nothing when the item already has the group, so take it off instead
+                    if !items::AttributeField::ChoiceGroup.assign(item, group_id) {
+                        if let Some(groups) = &mut item.choice_groups {
+                            groups.retain(|(id, _)| *id != group_id);
+                            if groups.is_empty() {
+                                item.choice_groups = None;
+                            }
+                        }
+                    }
+
+                    if let Err(e) = self.save_state() {
+                        self.handle_save_error(e);
+                    }
+                    Task::none()
+                }
+                choice_groups::Operation::SelectMerge(selection) => {
+                    self.merge_selection = selection;
+                    Task::none()
+                }
+                choice_groups::Operation::Merge(from, into) => {
+                    let (Some(source), Some(target)) = (self.choice_groups.get(&from), self.choice_groups.get(&into)) else {
+                        let missing = if self.choice_groups.contains_key(&from) { into } else { from };
+                        return self.missing_record("Choice group", missing);
+                    };
+
+                    let description = format!(
+                        "Merge choice group {} ({}) into {} ({}) and delete {}",
+                        source.name, from, target.name, into, source.name
+                    );
+                    let names = self.referencing_items("ChoiceGroup", from);
+
+                    self.pending_bulk = Some(bulk::PendingBulk::new(description, names, move |state: &mut MenuBuilder| {
+                        for item in state.items.values_mut() {
+                            let Some(groups) = &mut item.choice_groups else {
+                                continue;
+                            };
+                            for (group_id, _) in groups.iter_mut() {
+                                if *group_id == from {
+                                    *group_id = into;
+                                }
+                            }
+                            // An item that had both keeps the first, with its sequence
+                            let mut seen = BTreeSet::new();
+                            groups.retain(|(group_id, _)| seen.insert(*group_id));
+                        }
+                        state.choice_groups.remove(&from);
+                        state.choice_group_edit_state_vec.retain(|edit| data_types::parse_entity_id(&edit.id) != Some(from));
+                        if state.choice_group_list_options.members_of == Some(from) {
+                            state.choice_group_list_options.members_of = None;
+                        }
+                        state.merge_selection = entity_component::MergeSelection::default();
+
+                        if let Err(e) = state.save_state() {
+                            state.handle_save_error(e);
+                        }
+                        Task::none()
+                    }));
+                    Task::none()
+                }
+                choice_groups::Operation::ExportUsage => {
+                    let usage = choice_groups::usage_counts(&self.choice_groups, &self.items);
+                    let rows = self.choice_groups.values()
+                        .map(|group| (group.id, group.name.clone(), usage.get(&group.id).copied().unwrap_or(0)))
+                        .collect::<Vec<_>>();
+
+                    Task::perform(
+                        choice_groups::export_usage(rows),
+                        |result| Message::ChoiceGroups(-1, choice_groups::Message::UsageExported(result))
+                    )
+                }
+                choice_groups::Operation::UsageExported(result) => {
+                    self.choice_group_list_options.export_message = match result {
+                        Ok(path) => format!("Usage exported to {}", path.to_string_lossy()),
+                        Err(e) => e,
+                    };
+                    Task::none()
+                }
+                choice_groups::Operation::ExportRecord(id) => {
+                    self.export_record(self.choice_groups.get(&id).cloned().map(record_export::Record::ChoiceGroup))
+                }
+                choice_groups::Operation::CopyJson(id) => {
+                    self.copy_record_json(self.choice_groups.get(&id).cloned().map(record_export::Record::ChoiceGroup))
+                }
+                choice_groups::Operation::RequestDelete(id) => {
+
+                    self.deletion_info = data_types::DeletionInfo { 
+                        entity_type: "ChoiceGroup".to_string(),
+                        entity_id: id,
+                        entity_name: self.choice_groups.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                        affected_items: self.referencing_items("ChoiceGroup", id)
+                    };
+                    self.prompt_delete()
+                },
+                choice_groups::Operation::CopyChoiceGroup(id) => {
+                    let Some(copy_item) = self.choice_groups.get(&id) else {
+                        return self.missing_record("Choice group", id);
+                    };
+                    let Some(next_id) = self.next_free_id(EntityKind::ChoiceGroup) else {
+                        return Task::none();
+                    };
+                    
+                    let new_item = ChoiceGroup {
+                        id: next_id,
                         name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                        timestamps: Timestamps::created_now(),
                         ..copy_item.clone()
                     };
 
@@ -2531,18 +4887,12 @@ impl MenuBuilder {
                     // First check if we already have an edit state for this choice_group
                     let already_editing = self.choice_group_edit_state_vec
                         .iter()
-                        .any(|state| state.id.parse::<i32>().unwrap() == id);
+                        .any(|state| data_types::parse_entity_id(&state.id) == Some(id));
 
                     // Only create new edit state if we're not already editing this choice_group
                     if !already_editing {
                         if let Some(choice_group) = self.choice_groups.get(&id) {
-                            let edit_state = entity_component::EditState {
-                                name: choice_group.name.clone(),
-                                original_name: choice_group.name.clone(),
-                                id: choice_group.id.to_string(),
-                                id_validation_error: None,
-                                name_validation_error: None,
-                            };
+                            let edit_state = entity_component::EditState::new(choice_group);
                             
                             self.choice_group_edit_state_vec.push(edit_state);
                         }
@@ -2556,7 +4906,7 @@ impl MenuBuilder {
                     // First, find the edit state for this choice_group
                     if let Some(edit_state) = self.choice_group_edit_state_vec
                         .iter()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id)
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
                     {
                         // Clone the edit state name since we'll need it after removing the edit state
                         let new_name = edit_state.name.clone();
@@ -2564,24 +4914,30 @@ impl MenuBuilder {
                         // Get a mutable reference to the choice_group and update it
                         if let Some(choice_group) = self.choice_groups.get_mut(&id) {
                             choice_group.name = new_name;
+                            choice_group.timestamps.touch();
                         }
                     }
 
                     self.choice_group_edit_state_vec.retain(|edit| {
-                        edit.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&edit.id).is_some_and(|state_id| state_id != id)
                     });
 
-                    self.save_state().expect("Failed to save to file.");
+                    if let Err(e) = self.save_state() {
+                        self.handle_save_error(e);
+                    }
                     self.screen = Screen::ChoiceGroups;
                     Task::none()
                 },
                 choice_groups::Operation::UpdateName(id, new_name) => {
                     if let Some(edit_state) = self.choice_group_edit_state_vec
                     .iter_mut()
-                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                    .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                     { 
                         //check if name var is less than 17 characters
                         if new_name.len() < 17 {
+                            edit_state.name_warning = entity_component::check_duplicate_name(self.choice_groups.values(), id, &new_name, "choice group")
+                                .err()
+                                .map(|e| e.to_string());
                             // Update the name
                             edit_state.name = new_name;
                         } else {
@@ -2594,41 +4950,56 @@ impl MenuBuilder {
                     Task::none()
                 },
                 choice_groups::Operation::CreateNew => {
-                    let next_id = self.choice_groups
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
+                    let Some(next_id) = self.next_free_id(EntityKind::ChoiceGroup) else {
+                        return Task::none();
+                    };
 
                     //Create a new ChoiceGroup
                     let choice_group = ChoiceGroup {
                         id: next_id,
-                        name: String::new()
+                        name: String::new(),
+                        sort_order: None,
+                        timestamps: Timestamps::created_now(),
                     };
 
                     //Add new ChoiceGroup to the app state
                     self.choice_groups.insert(next_id, choice_group.clone());
 
                     //Create a new edit_state for the new choice_group
-                    let edit_state = entity_component::EditState {
-                        name: choice_group.name.clone(),
-                        original_name: choice_group.name.clone(),
-                        id: choice_group.id.to_string(),
-                        id_validation_error: None,
-                        name_validation_error: None,
-                    };
+                    let edit_state = entity_component::EditState::new(&choice_group);
                     
                     //Add new choice_group edit_state to app state
                     self.choice_group_edit_state_vec.push(edit_state);
 
                     Task::none()
                 },
+                choice_groups::Operation::MoveUp(id) => {
+                    let shown = self.shown_choice_group_ids();
+                    if entity_component::move_entity(&mut self.choice_groups, id, true, &shown) {
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                    }
+                    Task::none()
+                }
+                choice_groups::Operation::MoveDown(id) => {
+                    let shown = self.shown_choice_group_ids();
+                    if entity_component::move_entity(&mut self.choice_groups, id, false, &shown) {
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                    }
+                    Task::none()
+                }
                 choice_groups::Operation::CancelEdit(id) => {
-                    let choice_group = self.choice_groups.get(&id).expect("I created an editstate without a ChoiceGroup?");
+                    let Some(choice_group) = self.choice_groups.get(&id) else {
+                        return self.missing_record("Choice group", id);
+                    };
 
                     // Find the edit state and reset it before removing
                     if let Some(edit_state) = self.choice_group_edit_state_vec
                     .iter_mut()
-                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                    .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                     {
                         if choice_group.name.len() < 1 
                         {
@@ -2641,7 +5012,7 @@ impl MenuBuilder {
 
                     // Remove the edit state from the vec
                     self.choice_group_edit_state_vec.retain(|state| {
-                    state.id.parse::<i32>().unwrap() != id
+                    data_types::parse_entity_id(&state.id).is_some_and(|state_id| state_id != id)
                     });
 
                     self.screen = Screen::ChoiceGroups;
@@ -2649,26 +5020,34 @@ impl MenuBuilder {
                 },
             },    
             Operation::PrinterLogicals(id, op) => match op {
+                printer_logicals::Operation::ExportRecord(id) => {
+                    self.export_record(self.printer_logicals.get(&id).cloned().map(record_export::Record::PrinterLogical))
+                }
+                printer_logicals::Operation::CopyJson(id) => {
+                    self.copy_record_json(self.printer_logicals.get(&id).cloned().map(record_export::Record::PrinterLogical))
+                }
                 printer_logicals::Operation::RequestDelete(id) => {
                     self.deletion_info = data_types::DeletionInfo { 
                        entity_type: "PrinterLogical".to_string(),
                        entity_id: id,
-                       affected_items: Vec::new()
+                       entity_name: self.printer_logicals.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                       affected_items: self.referencing_items("PrinterLogical", id)
                     };
                     
-                    self.show_modal = true;
-                    Task::none()
+                    self.prompt_delete()
                 }
                 printer_logicals::Operation::CopyPrinterLogical(id) => {
-                    let copy_item = self.printer_logicals.get(&id).unwrap();
-                    let next_id = self.printer_logicals
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
+                    let Some(copy_item) = self.printer_logicals.get(&id) else {
+                        return self.missing_record("Printer logical", id);
+                    };
+                    let Some(next_id) = self.next_free_id(EntityKind::PrinterLogical) else {
+                        return Task::none();
+                    };
                    
                     let new_item = PrinterLogical {
                         id: next_id,
-                        name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                        name: PrinterLogical::copy_name(&copy_item.name, next_id),
+                        timestamps: Timestamps::created_now(),
                         ..copy_item.clone()
                     };
 
@@ -2681,18 +5060,12 @@ impl MenuBuilder {
                     // First check if we already have an edit state for this printer
                     let already_editing = self.printer_logical_edit_state_vec
                         .iter()
-                        .any(|state| state.id.parse::<i32>().unwrap() == id);
+                        .any(|state| data_types::parse_entity_id(&state.id) == Some(id));
 
                     // Only create new edit state if we're not already editing this printer
                     if !already_editing {
                         if let Some(printer) = self.printer_logicals.get(&id) {
-                            let edit_state = entity_component::EditState {
-                                name: printer.name.clone(),
-                                original_name: printer.name.clone(),
-                                id: printer.id.to_string(),
-                                id_validation_error: None,
-                                name_validation_error: None,
-                            };
+                            let edit_state = entity_component::EditState::new(printer);
                             
                             self.printer_logical_edit_state_vec.push(edit_state);
                         }
@@ -2702,28 +5075,22 @@ impl MenuBuilder {
                     Task::none()
                 }
                 printer_logicals::Operation::CreateNew => {
-                    let next_id = self.printer_logicals
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
+                    let Some(next_id) = self.next_free_id(EntityKind::PrinterLogical) else {
+                        return Task::none();
+                    };
 
                     //Create a new PrinterLogical
                     let printer = PrinterLogical {
                         id: next_id,
                         name: String::new(),
+                        timestamps: Timestamps::created_now(),
                     };
 
                     //Add new PrinterLogical to the app state
                     self.printer_logicals.insert(next_id, printer.clone());
 
                     //Create a new edit_state for the new printer
-                    let edit_state = entity_component::EditState {
-                        name: printer.name.clone(),
-                        original_name: printer.name.clone(),
-                        id: printer.id.to_string(),
-                        id_validation_error: None,
-                        name_validation_error: None,
-                    };
+                    let edit_state = entity_component::EditState::new(&printer);
                     
                     //Add new printer edit_state to app state
                     self.printer_logical_edit_state_vec.push(edit_state);
@@ -2734,33 +5101,44 @@ impl MenuBuilder {
 
                     // First, find the edit state for this printer
                     if let Some(edit_state) = self.printer_logical_edit_state_vec
-                        .iter()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id)
+                        .iter_mut()
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
                     {
+                        // A name over the limit stays open for editing with the error shown
+                        if let Err(e) = PrinterLogical::validate_name(&edit_state.name) {
+                            edit_state.name_validation_error = Some(e.to_string());
+                            return Task::none();
+                        }
+
                         // Clone the edit state name since we'll need it after removing the edit state
                         let new_name = edit_state.name.clone();
                         
                         // Get a mutable reference to the printer and update it
                         if let Some(printer) = self.printer_logicals.get_mut(&id) {
                             printer.name = new_name;
+                            printer.timestamps.touch();
                         }
                     }
 
                     self.printer_logical_edit_state_vec.retain(|edit| {
-                        edit.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&edit.id).is_some_and(|state_id| state_id != id)
                     });
 
-                    self.save_state().expect("Failed to save to file.");
+                    if let Err(e) = self.save_state() {
+                        self.handle_save_error(e);
+                    }
                     self.screen = Screen::PrinterLogicals;
                     Task::none()
                 }
                 printer_logicals::Operation::CancelEdit(id) => {
-                    let printer_logical = self.printer_logicals.get(&id).expect("I created an editstate without a PrinterLogical?");
+                    let Some(printer_logical) = self.printer_logicals.get(&id) else {
+                        return self.missing_record("Printer logical", id);
+                    };
 
                     // Find the edit state and reset it before removing
                     if let Some(edit_state) = self.printer_logical_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                         {
                             if printer_logical.name.len() < 1
                             {
@@ -2773,25 +5151,29 @@ impl MenuBuilder {
 
                         // Remove the edit state from the vec
                         self.printer_logical_edit_state_vec.retain(|state| {
-                        state.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&state.id).is_some_and(|state_id| state_id != id)
                     });
 
                     self.screen = Screen::PrinterLogicals;
                     Task::none()
                 }
+                printer_logicals::Operation::ShowRouting(id) => {
+                    self.printer_routing = id;
+                    Task::none()
+                }
                 printer_logicals::Operation::UpdateName(id, new_name) => {
                     if let Some(edit_state) = self.printer_logical_edit_state_vec
                     .iter_mut()
-                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                    .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
                     {
-                        //check if name var is less than 17 characters
-                        if new_name.len() < 17 {
-                            // Update the name
-                            edit_state.name = new_name;
-                        } else {
-                            //set validation error message if it's longer than 16 characters
-                            edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
-                        }
+                        // Kept as typed or pasted so the error shows against the full name
+                        edit_state.name_validation_error = PrinterLogical::validate_name(&new_name)
+                            .err()
+                            .map(|e| e.to_string());
+                        edit_state.name_warning = entity_component::check_duplicate_name(self.printer_logicals.values(), id, &new_name, "printer logical")
+                            .err()
+                            .map(|e| e.to_string());
+                        edit_state.name = new_name;
 
                     }
 
@@ -2799,26 +5181,185 @@ impl MenuBuilder {
                     Task::none()
                 }
             },
+            Operation::Stores(id, op) => {
+                match op {
+                    stores::Operation::ExportRecord(id) => {
+                        self.export_record(self.stores.get(&id).cloned().map(record_export::Record::Store))
+                    }
+                    stores::Operation::CopyJson(id) => {
+                        self.copy_record_json(self.stores.get(&id).cloned().map(record_export::Record::Store))
+                    }
+                    stores::Operation::RequestDelete(id) => {
+                        self.deletion_info = data_types::DeletionInfo { 
+                           entity_type: "Store".to_string(),
+                           entity_id: id,
+                           entity_name: self.stores.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                           affected_items: self.price_levels.values()
+                               .filter(|level| level.store_id == Some(id))
+                               .map(|level| level.name.clone())
+                               .collect()
+                        };
+                        self.prompt_delete()
+                   }
+                    stores::Operation::CopyStore(id) => {
+                        let Some(copy_item) = self.stores.get(&id) else {
+                            return self.missing_record("Store", id);
+                        };
+                        let Some(next_id) = self.next_free_id(EntityKind::Store) else {
+                            return Task::none();
+                        };
+                       
+                        let new_item = Store {
+                            id: next_id,
+                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            timestamps: Timestamps::created_now(),
+                            ..copy_item.clone()
+                        };
+
+                       self.stores.insert(next_id, new_item.clone());
+                       self.screen = Screen::Stores;
+
+                       Task::none()
+                   }
+                    stores::Operation::EditStore(id) => {
+                        // First check if we already have an edit state for this store
+                        let already_editing = self.store_edit_state_vec
+                            .iter()
+                            .any(|state| data_types::parse_entity_id(&state.id) == Some(id));
+    
+                        // Only create new edit state if we're not already editing this store
+                        if !already_editing {
+                            if let Some(store) = self.stores.get(&id) {
+                                let edit_state = entity_component::EditState::new(store);
+                                
+                                self.store_edit_state_vec.push(edit_state);
+                            }
+                        }
+    
+                        self.screen = Screen::Stores;
+                        Task::none()
+                    },
+                    stores::Operation::SaveAll(id, edit_state) => {
+                        // First, find the edit state for this store
+                        if let Some(edit_state) = self.store_edit_state_vec
+                            .iter()
+                            .find(|state| data_types::parse_entity_id(&state.id) == Some(id))
+                        {
+                            // Clone the edit state name since we'll need it after removing the edit state
+                            let new_name = edit_state.name.clone();
+                            
+                            // Get a mutable reference to the store and update it
+                            if let Some(store) = self.stores.get_mut(&id) {
+                                store.name = new_name;
+                                store.timestamps.touch();
+                            }
+                        }
+
+                        self.store_edit_state_vec.retain(|edit| {
+                            data_types::parse_entity_id(&edit.id).is_some_and(|state_id| state_id != id)
+                        });
+
+                        if let Err(e) = self.save_state() {
+                            self.handle_save_error(e);
+                        }
+                        self.screen = Screen::Stores;
+                        Task::none()
+                    },
+                    stores::Operation::UpdateName(id, new_name) => {
+                        if let Some(edit_state) = self.store_edit_state_vec
+                        .iter_mut()
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
+                        { 
+                            //check if name var is less than 17 characters
+                            if new_name.len() < 17 {
+                                edit_state.name_warning = entity_component::check_duplicate_name(self.stores.values(), id, &new_name, "store")
+                                    .err()
+                                    .map(|e| e.to_string());
+                                // Update the name
+                                edit_state.name = new_name;
+                            } else {
+                                //set validation error message if it's longer than 16 characters
+                                edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
+                            }
+                        }
+    
+                        self.screen = Screen::Stores;
+                        Task::none()
+                    },
+                    stores::Operation::CreateNew => {
+                        let Some(next_id) = self.next_free_id(EntityKind::Store) else {
+                            return Task::none();
+                        };
+
+                        //Create a new Store
+                        let store = Store {
+                            id: next_id,
+                            name: String::new(),
+                            timestamps: Timestamps::created_now(),
+                        };
+
+                        //Add new Store to the app state
+                        self.stores.insert(next_id, store.clone());
+
+                        //Create a new edit_state for the new store
+                        let edit_state = entity_component::EditState::new(&store);
+                        
+                        //Add new store edit_state to app state
+                        self.store_edit_state_vec.push(edit_state);
+
+                        Task::none()
+                    },
+                    stores::Operation::CancelEdit(id) => {
+                        let Some(store) = self.stores.get(&id) else {
+                            return self.missing_record("Store", id);
+                        };
+
+                        // Find the edit state and reset it before removing
+                        if let Some(edit_state) = self.store_edit_state_vec
+                        .iter_mut()
+                        .find(|state| data_types::parse_entity_id(&state.id) == Some(id)) 
+                        {
+                            if store.name.len() < 1 
+                            {
+                                self.stores.remove(&id);
+                            }
+
+                            // Reset the data to original values if needed
+                            edit_state.reset();
+                        }
+
+                        // Remove the edit state from the vec
+                        self.store_edit_state_vec.retain(|state| {
+                        data_types::parse_entity_id(&state.id).is_some_and(|state_id| state_id != id)
+                        });
+
+                        self.screen = Screen::Stores;
+                        Task::none()
+                    },
+                }
+            }    
             Operation::PriceLevels(id, op) => match op {
                 price_levels::Operation::RequestDelete(id) => {
                     self.deletion_info = data_types::DeletionInfo { 
                        entity_type: "PriceLevel".to_string(),
                        entity_id: id,
-                       affected_items: Vec::new()
+                       entity_name: self.price_levels.get(&id).map(|e| e.name.clone()).unwrap_or_default(),
+                       affected_items: self.referencing_items("PriceLevel", id)
                     };
-                    self.show_modal = true;
-                    Task::none()
+                    self.prompt_delete()
                }
                 price_levels::Operation::CopyPriceLevel(id) => {
-                    let copy_item = self.price_levels.get(&id).unwrap();
-                    let next_id = self.price_levels
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
+                    let Some(copy_item) = self.price_levels.get(&id) else {
+                        return self.missing_record("Price level", id);
+                    };
+                    let Some(next_id) = self.next_free_id(EntityKind::PriceLevel) else {
+                        return Task::none();
+                    };
                    
                     let new_item = PriceLevel {
                         id: next_id,
                         name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                        timestamps: Timestamps::created_now(),
                         ..copy_item.clone()
                     };
 
@@ -2831,7 +5372,7 @@ impl MenuBuilder {
                     // First check if we already have an edit state for this price_level
                     let already_editing = self.price_level_edit_state_vec
                         .iter()
-                        .any(|state| state.base.id.parse::<i32>().unwrap() == id);
+                        .any(|state| data_types::parse_entity_id(&state.base.id) == Some(id));
 
                     // Only create new edit state if we're not already editing this price_level
                     if !already_editing {
@@ -2846,25 +5387,63 @@ impl MenuBuilder {
                     Task::none()
                 },
                 price_levels::Operation::SaveAll(id, edit_state) => {
+                    if self.settings.block_duplicate_price_level_names {
+                        if let Some(edit_state) = self.price_level_edit_state_vec
+                            .iter_mut()
+                            .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
+                        {
+                            if let Some(other) = entity_component::duplicate_name(self.price_levels.values(), id, &edit_state.base.name) {
+                                edit_state.base.name_validation_error = Some(format!(
+                                    "Price level {} is already named '{}'", other.id, other.name
+                                ));
+                                return Task::none();
+                            }
+                        }
+                    }
+
                     // First, find the edit state for this price_level
                     if let Some(edit_state) = self.price_level_edit_state_vec
-                        .iter()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                        .iter_mut()
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
                     {
+                        // Invalid fields keep the level open with the error on the field
+                        if let Err(e) = edit_state.validate(&self.settings.id_range(EntityKind::PriceLevel)) {
+                            match e {
+                                ValidationError::InvalidPrice(msg) => edit_state.price_validation_error = Some(msg),
+                                ValidationError::InvalidId(msg) => edit_state.base.id_validation_error = Some(msg),
+                                ValidationError::InvalidValue(msg) => edit_state.store_validation_error = Some(msg),
+                                other => edit_state.base.name_validation_error = Some(other.to_string()),
+                            }
+                            return Task::none();
+                        }
+
                         // Clone the edit state name since we'll need it after removing the edit state
                         let new_name = edit_state.base.name.clone();
+                        let new_price = price_levels::PriceLevelEditState::validate_price(&edit_state.price);
                         
                         // Get a mutable reference to the price_level and update it
                         if let Some(price_level) = self.price_levels.get_mut(&id) {
                             price_level.name = new_name;
+                            price_level.timestamps.touch();
+                            if let Ok(price) = new_price {
+                                price_level.price = price;
+                            }
+                            price_level.level_type = edit_state.level_type;
+                            // Enterprise levels never belong to a store
+                            price_level.store_id = match edit_state.level_type {
+                                PriceLevelType::Store => edit_state.store_id,
+                                PriceLevelType::Enterprise => None,
+                            };
                         }
                     }
 
                     self.price_level_edit_state_vec.retain(|edit| {
-                        edit.base.id.parse::<i32>().unwrap() != id
+                        data_types::parse_entity_id(&edit.base.id).is_some_and(|state_id| state_id != id)
                     });
 
-                    self.save_state().expect("Failed to save to file.");
+                    if let Err(e) = self.save_state() {
+                        self.handle_save_error(e);
+                    }
                     self.screen = Screen::PriceLevels;
                     Task::none()
                 },
@@ -2872,10 +5451,15 @@ impl MenuBuilder {
 
                     if let Some(edit_state) = self.price_level_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                // Warn while typing when another level already has this name
+                                edit_state.base.name_warning = entity_component::check_duplicate_name(self.price_levels.values(), id, &new_name, "price level")
+                                    .err()
+                                    .map(|e| e.to_string());
+                                edit_state.base.name_validation_error = None;
                                 // Update the name
                                 edit_state.base.name = new_name;
                             } else {
@@ -2887,17 +5471,65 @@ impl MenuBuilder {
                     self.screen = Screen::PriceLevels;
                     Task::none()
                 },
+                price_levels::Operation::UpdatePrice(id, price) => {
+                    if let Some(edit_state) = self.price_level_edit_state_vec
+                        .iter_mut()
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
+                    {
+                        // Checked as typed, saving checks again
+                        edit_state.price_validation_error = price_levels::PriceLevelEditState::validate_price(&price)
+                            .err()
+                            .map(|e| e.to_string());
+                        edit_state.price = price;
+                    }
+
+                    Task::none()
+                },
+                price_levels::Operation::SelectLevelType(id, level_type) => {
+                    if let Some(edit_state) = self.price_level_edit_state_vec
+                        .iter_mut()
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
+                    {
+                        edit_state.level_type = level_type;
+                        edit_state.store_validation_error = None;
+                    }
+
+                    Task::none()
+                },
+                price_levels::Operation::SelectStore(id, store_id) => {
+                    if let Some(edit_state) = self.price_level_edit_state_vec
+                        .iter_mut()
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id))
+                    {
+                        edit_state.store_id = store_id;
+                        edit_state.store_validation_error = None;
+                    }
+
+                    Task::none()
+                },
+                price_levels::Operation::ToggleUnusedOnly(enabled) => {
+                    self.price_level_list_options.unused_only = enabled;
+                    Task::none()
+                },
+                price_levels::Operation::ShowItems(id, unpriced) => {
+                    self.item_list_filters.price_level = Some(id);
+                    self.item_list_filters.price_level_unpriced = unpriced;
+                    self.selected_item_id = None;
+                    self.screen = Screen::Items(items::Mode::View);
+                    Task::none()
+                },
                 price_levels::Operation::CreateNew => {
-                    let next_id = self.price_levels
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
+                    let Some(next_id) = self.next_free_id(EntityKind::PriceLevel) else {
+                        return Task::none();
+                    };
 
                     let price_level = PriceLevel {
                         id: next_id,
                         name: String::new(),
                         level_type: PriceLevelType::Enterprise,
                         price: Decimal::new(000, 2),
+                        store_id: None,
+                        timestamps: Timestamps::created_now(),
                     };
 
                     self.price_levels.insert(next_id, price_level.clone());
@@ -2909,12 +5541,14 @@ impl MenuBuilder {
                     Task::none()
                 },
                 price_levels::Operation::CancelEdit(id) => {
-                    let price_level = self.price_levels.get(&id).expect("I created an editstate without a PriceLevel?");
+                    let Some(price_level) = self.price_levels.get(&id) else {
+                        return self.missing_record("Price level", id);
+                    };
 
                     // Find the edit state and reset it before removing
                     if let Some(edit_state) = self.price_level_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(id)) 
                         {
                             if price_level.name.len() < 1 
                             {
@@ -2927,7 +5561,7 @@ impl MenuBuilder {
 
                     // Remove the edit state from the vec
                     self.price_level_edit_state_vec.retain(|state| {
-                    state.base.id.parse::<i32>().unwrap() != id
+                    data_types::parse_entity_id(&state.base.id).is_some_and(|state_id| state_id != id)
                     });
 
                     self.screen = Screen::PriceLevels;
@@ -2937,9 +5571,169 @@ impl MenuBuilder {
         }
     }
 
-    pub fn save_state(&self) -> Result<(), String> {
-        //println!("Save State Triggered!");
-        let state = persistence::AppState {
+    fn editor_window(&self) -> Option<window::Id> {
+        self.windows
+            .iter()
+            .find(|(_, window)| window.windowtype == WindowEnum::ItemEditor)
+            .map(|(id, _)| *id)
+    }
+
+    // The edit form of the item being edited, or a way out once that edit is
+    // saved or cancelled from either window
+    fn item_editor_view(&self, window_id: window::Id) -> Element<'_, Message> {
+        let editing = self.selected_item_id
+            .filter(|_| matches!(self.screen, Screen::Items(items::Mode::Edit)));
+
+        let content: Element<'_, Message> = match editing {
+            Some(id) => items::edit::view(
+                &self.draft_item,
+                &self.item_edit_state,
+                &self.item_groups,
+                &self.tax_groups,
+                &self.security_levels,
+                &self.revenue_categories,
+                &self.report_categories,
+                &self.product_classes,
+                &self.choice_groups,
+                &self.printer_logicals,
+                &self.price_levels,
+                &self.stores,
+                &self.items,
+            )
+            .map(move |msg| Message::Items(id, items::Message::Edit(msg))),
+            None => column![
+                text("No item is being edited").size(18).style(Modern::primary_text()),
+                text("Start editing an item in the main window to continue here").style(Modern::secondary_text()),
+                button("Close window")
+                    .on_press(Message::CloseWindow(window_id))
+                    .style(accent::primary_button()),
+            ]
+            .spacing(10)
+            .into(),
+        };
+
+        container(content)
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(Modern::card_container())
+            .into()
+    }
+
+    // Configuration rule results for the current data, rebuilt like validate_all
+    fn lint_issues(&self) -> Vec<lint::LintIssue> {
+        let mut cache = self.lint_cache.borrow_mut();
+        match cache.as_ref() {
+            Some((generation, issues)) if *generation == self.item_data_generation => issues.clone(),
+            _ => {
+                let issues = lint::evaluate(&self.settings.lint_rules, &self.app_state());
+                *cache = Some((self.item_data_generation, issues.clone()));
+                issues
+            }
+        }
+    }
+
+    // Writes the item export to a chosen path through the settings export flow
+    fn export_items_to(&mut self, path: PathBuf) -> Task<Message> {
+        self.update(
+            Message::Settings(
+                settings::Message::ProcessItems(
+                    (
+                        self.export_items(&self.items),
+                        path,
+                        self.tax_groups.iter().map(|(id, group)| (*id, group.rate)).collect()
+                    )))
+            )
+    }
+
+    // Every integrity problem across the menu, for the Validation screen.
+    // Rebuilt only when a message may have changed the data since the last report.
+    fn validate_all(&self) -> Vec<validation::ValidationIssue> {
+        let mut cache = self.validation_cache.borrow_mut();
+        match cache.as_ref() {
+            Some((generation, issues)) if *generation == self.item_data_generation => issues.clone(),
+            _ => {
+                let issues = validation::validate_all(&self.app_state());
+                *cache = Some((self.item_data_generation, issues.clone()));
+                issues
+            }
+        }
+    }
+
+    // The ten records saved most recently across every collection, newest first.
+    // Empty for menus saved before times were kept, which get the usual placeholder
+    fn recently_modified(&self) -> Vec<items::RecentChange> {
+        const SHOWN: usize = 10;
+
+        fn changes<T: entity_component::Entity>(
+            kind: EntityKind,
+            entities: &BTreeMap<EntityId, T>,
+        ) -> impl Iterator<Item = items::RecentChange> + '_ {
+            entities.values().filter_map(move |entity| {
+                Some(items::RecentChange {
+                    target: validation::Target::Entity(kind, entity.id()),
+                    record: format!("{} '{}' ({})", kind, entity.name(), entity.id()),
+                    modified_at: entity.timestamps().modified_at?,
+                })
+            })
+        }
+
+        let item_changes = self.items.values().filter_map(|item| {
+            Some(items::RecentChange {
+                target: validation::Target::Item(item.id),
+                record: format!("Item '{}' ({})", item.name, item.id),
+                modified_at: item.timestamps.modified_at?,
+            })
+        });
+
+        let mut recent: Vec<items::RecentChange> = item_changes
+            .chain(changes(EntityKind::ItemGroup, &self.item_groups))
+            .chain(changes(EntityKind::PriceLevel, &self.price_levels))
+            .chain(changes(EntityKind::ProductClass, &self.product_classes))
+            .chain(changes(EntityKind::TaxGroup, &self.tax_groups))
+            .chain(changes(EntityKind::SecurityLevel, &self.security_levels))
+            .chain(changes(EntityKind::RevenueCategory, &self.revenue_categories))
+            .chain(changes(EntityKind::ReportCategory, &self.report_categories))
+            .chain(changes(EntityKind::ChoiceGroup, &self.choice_groups))
+            .chain(changes(EntityKind::PrinterLogical, &self.printer_logicals))
+            .chain(changes(EntityKind::Store, &self.stores))
+            .collect();
+
+        recent.sort_by_key(|change| std::cmp::Reverse(change.modified_at));
+        recent.truncate(SHOWN);
+        recent
+    }
+
+    // Jumps from a validation issue to its record: items are selected, list
+    // entities get their row opened for editing
+    fn open_issue(&mut self, target: validation::Target) -> Task<Message> {
+        let (kind, id) = match target {
+            validation::Target::Item(id) => {
+                let task = self.open_screen(Screen::Items(items::Mode::View));
+                return task.chain(self.perform(Operation::Items(id, items::Operation::Select(id))));
+            }
+            validation::Target::Entity(kind, id) => (kind, id),
+        };
+
+        let open = match kind {
+            EntityKind::ItemGroup => Operation::ItemGroups(id, item_groups::Operation::EditItemGroup(id)),
+            EntityKind::PriceLevel => Operation::PriceLevels(id, price_levels::Operation::EditPriceLevel(id)),
+            EntityKind::ProductClass => Operation::ProductClasses(id, product_classes::Operation::EditProductClass(id)),
+            EntityKind::TaxGroup => Operation::TaxGroups(id, tax_groups::Operation::EditTaxGroup(id)),
+            EntityKind::SecurityLevel => Operation::SecurityLevels(id, security_levels::Operation::EditSecurityLevel(id)),
+            EntityKind::RevenueCategory => Operation::RevenueCategories(id, revenue_categories::Operation::EditRevenueCategory(id)),
+            EntityKind::ReportCategory => Operation::ReportCategories(id, report_categories::Operation::EditReportCategory(id)),
+            EntityKind::ChoiceGroup => Operation::ChoiceGroups(id, choice_groups::Operation::EditChoiceGroup(id)),
+            EntityKind::PrinterLogical => Operation::PrinterLogicals(id, printer_logicals::Operation::EditPrinterLogical(id)),
+            EntityKind::Store => Operation::Stores(id, stores::Operation::EditStore(id)),
+        };
+
+        let task = self.open_screen(list_screen(kind));
+        task.chain(self.perform(open))
+    }
+
+    fn app_state(&self) -> persistence::AppState {
+        persistence::AppState {
             items: self.items.values().cloned().collect(),
             item_groups: self.item_groups.values().cloned().collect(),
             price_levels: self.price_levels.values().cloned().collect(),
@@ -2950,32 +5744,1322 @@ impl MenuBuilder {
             report_categories: self.report_categories.values().cloned().collect(),
             choice_groups: self.choice_groups.values().cloned().collect(),
             printer_logicals: self.printer_logicals.values().cloned().collect(),
+            stores: self.stores.values().cloned().collect(),
             settings: self.settings.clone(),
-        };
-
-        if self.settings.create_backups {
-            self.file_manager.create_backup(std::path::Path::new(&self.settings.file_path))?;
+            last_view: self.last_view(),
         }
+    }
 
-        persistence::save_to_file(&state, &self.settings.file_path)
+    // Item ids in the order the items list shows them, after the search and filters
+    fn shown_choice_group_ids(&self) -> Vec<EntityId> {
+        choice_groups::shown_groups(
+            &self.choice_groups,
+            &self.choice_group_edit_state_vec,
+            &choice_groups::usage_counts(&self.choice_groups, &self.items),
+            &self.choice_group_list_options,
+            &self.list_search,
+        )
+        .iter()
+        .map(|group| group.id)
+        .collect()
     }
 
-    fn handle_save_error(&mut self, error: String) {
-        self.error_message = Some(error);
-        // Switch to settings screen to show error
-        self.screen = Screen::Settings(self.settings.clone());
+    fn shown_item_ids(&self) -> Vec<EntityId> {
+        let filtered_ids = self.item_search_cache.borrow_mut().filtered_ids(
+            self.item_data_generation,
+            &self.item_search,
+            &self.item_list_filters,
+            &self.items,
+            &self.item_groups,
+            &self.tax_groups,
+            &self.security_levels,
+            &self.report_categories,
+            &self.choice_groups,
+            &self.printer_logicals,
+            &self.price_levels,
+        );
+
+        let mut shown: Vec<&Item> = filtered_ids.iter().filter_map(|id| self.items.get(id)).collect();
+        entity_component::display_order(&mut shown);
+        shown.into_iter().map(|item| item.id).collect()
+    }
+
+    fn last_view(&self) -> persistence::LastView {
+        persistence::LastView {
+            list: list_kind(&self.screen),
+            selected_item_id: self.selected_item_id.filter(|id| *id >= 0),
+        }
+    }
+
+    // Reopens the saved screen. A selection that no longer exists lands on the
+    // items screen with nothing selected instead.
+    fn restore_view(&mut self, view: persistence::LastView) {
+        self.saved_view.set(view);
+
+        match view.selected_item_id {
+            Some(id) if !self.items.contains_key(&id) => {
+                self.screen = Screen::Items(items::Mode::View);
+                self.selected_item_id = None;
+            }
+            selected => {
+                self.screen = view.list.map_or(Screen::Items(items::Mode::View), list_screen);
+                if selected.is_some() {
+                    self.selected_item_id = selected;
+                }
+            }
+        }
     }
 
-    pub fn load_state(&mut self) -> Result<(), String> {
-        // Check if file exists
-        let path = std::path::Path::new(&self.settings.file_path);
+    // Items as they go into the CSV export, narrowed to one store when chosen
+    fn export_items(&self, items: &BTreeMap<EntityId, Item>) -> BTreeMap<EntityId, Item> {
+        match self.settings.export_store.filter(|id| self.stores.contains_key(id)) {
+            Some(store_id) => price_levels::restrict_to_store(items, &self.price_levels, store_id),
+            None => items.clone(),
+        }
+    }
+
+    // Entity type and "id, name" of every record on the current list screen
+    fn screen_entities(&self) -> Option<(&'static str, Vec<(EntityId, String)>)> {
+        fn listed<T: entity_component::Entity>(entities: &BTreeMap<EntityId, T>) -> Vec<(EntityId, String)> {
+            entities.values().map(|entity| (entity.id(), entity.name().to_string())).collect()
+        }
+
+        Some(match &self.screen {
+            Screen::ItemGroups => ("ItemGroup", listed(&self.item_groups)),
+            Screen::PriceLevels => ("PriceLevel", listed(&self.price_levels)),
+            Screen::ProductClasses => ("ProductClass", listed(&self.product_classes)),
+            Screen::TaxGroups => ("TaxGroup", listed(&self.tax_groups)),
+            Screen::SecurityLevels => ("SecurityLevel", listed(&self.security_levels)),
+            Screen::RevenueCategories => ("RevenueCategory", listed(&self.revenue_categories)),
+            Screen::ReportCategories => ("ReportCategory", listed(&self.report_categories)),
+            Screen::ChoiceGroups => ("ChoiceGroup", listed(&self.choice_groups)),
+            Screen::PrinterLogicals => ("PrinterLogical", listed(&self.printer_logicals)),
+            Screen::Stores => ("Store", listed(&self.stores)),
+            Screen::Items(_) | Screen::Settings(_) | Screen::Validation => return None,
+        })
+    }
+
+    // Ids with a row open for editing on the current list screen
+    fn open_edit_ids(&self) -> Vec<EntityId> {
+        fn ids<S>(states: &[S], id_of: impl Fn(&S) -> &str) -> Vec<EntityId> {
+            states.iter().filter_map(|state| data_types::parse_entity_id(id_of(state))).collect()
+        }
+
+        match &self.screen {
+            Screen::ItemGroups => ids(&self.item_group_edit_state_vec, |state| state.base.id.as_str()),
+            Screen::PriceLevels => ids(&self.price_level_edit_state_vec, |state| state.base.id.as_str()),
+            Screen::TaxGroups => ids(&self.tax_group_edit_state_vec, |state| state.base.id.as_str()),
+            Screen::ProductClasses => ids(&self.product_class_edit_state_vec, |state| state.id.as_str()),
+            Screen::SecurityLevels => ids(&self.security_level_edit_state_vec, |state| state.id.as_str()),
+            Screen::RevenueCategories => ids(&self.revenue_category_edit_state_vec, |state| state.id.as_str()),
+            Screen::ReportCategories => ids(&self.report_category_edit_state_vec, |state| state.id.as_str()),
+            Screen::ChoiceGroups => ids(&self.choice_group_edit_state_vec, |state| state.id.as_str()),
+            Screen::PrinterLogicals => ids(&self.printer_logical_edit_state_vec, |state| state.id.as_str()),
+            Screen::Stores => ids(&self.store_edit_state_vec, |state| state.id.as_str()),
+            Screen::Items(_) | Screen::Settings(_) | Screen::Validation => Vec::new(),
+        }
+    }
+
+    // Removes one entity and strips every reference to it
+    // The cleanup delete_entity does, for references whose record is already
+    // gone, e.g. in a file edited by hand. Returns a line per item repaired.
+    fn repair_references(&mut self) -> Vec<String> {
+        let mut repairs = Vec::new();
+        let item_ids: BTreeSet<EntityId> = self.items.keys().copied().collect();
+
+        for item in self.items.values_mut() {
+            let mut removed = Vec::new();
+
+            if let Some(id) = item.item_group.filter(|id| !self.item_groups.contains_key(id)) {
+                item.item_group = None;
+                removed.push(format!("Item Group {}", id));
+            }
+            if let Some(id) = item.product_class.filter(|id| !self.product_classes.contains_key(id)) {
+                item.product_class = None;
+                removed.push(format!("Product Class {}", id));
+            }
+            if let Some(id) = item.revenue_category.filter(|id| !self.revenue_categories.contains_key(id)) {
+                item.revenue_category = None;
+                removed.push(format!("Revenue Category {}", id));
+            }
+            if let Some(id) = item.tax_group.filter(|id| !self.tax_groups.contains_key(id)) {
+                item.tax_group = None;
+                removed.push(format!("Tax Group {}", id));
+            }
+            if let Some(id) = item.security_level.filter(|id| !self.security_levels.contains_key(id)) {
+                item.security_level = None;
+                removed.push(format!("Security Level {}", id));
+            }
+            if let Some(id) = item.report_category.filter(|id| !self.report_categories.contains_key(id)) {
+                item.report_category = None;
+                removed.push(format!("Report Category {}", id));
+            }
+
+            if let Some(groups) = &mut item.choice_groups {
+                groups.retain(|(id, _)| {
+                    let exists = self.choice_groups.contains_key(id);
+                    if !exists {
+                        removed.push(format!("Choice Group {}", id));
+                    }
+                    exists
+                });
+                if groups.is_empty() {
+                    item.choice_groups = None;
+                }
+            }
+            if let Some(printers) = &mut item.printer_logicals {
+                printers.retain(|(id, _)| {
+                    let exists = self.printer_logicals.contains_key(id);
+                    if !exists {
+                        removed.push(format!("Printer Logical {}", id));
+                    }
+                    exists
+                });
+                if printers.is_empty() {
+                    item.printer_logicals = None;
+                }
+            }
+
+            let mut missing_levels: Vec<EntityId> = item.price_levels
+                .iter()
+                .flatten()
+                .copied()
+                .chain(item.item_prices.iter().flatten().map(|price| price.price_level_id))
+                .filter(|id| !self.price_levels.contains_key(id))
+                .collect();
+            missing_levels.sort_unstable();
+            missing_levels.dedup();
+            for id in missing_levels {
+                item.remove_price_level(id);
+                removed.push(format!("Price Level {}", id));
+            }
+
+            let missing_components: Vec<EntityId> = item.components
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|id| !item_ids.contains(id))
+                .collect();
+            for id in missing_components {
+                item.remove_component(id);
+                removed.push(format!("Component item {}", id));
+            }
+
+            if !removed.is_empty() {
+                let repair = format!("Repaired item '{}' ({}): removed {}", item.name, item.id, removed.join(", "));
+                println!("{}", repair);
+                repairs.push(repair);
+            }
+        }
+
+        repairs
+    }
+
+    fn delete_entity(&mut self, deletion_info: &data_types::DeletionInfo) {
+        match deletion_info.entity_type.as_str() {
+            "ChoiceGroup" => {
+                // Clean up references in all items
+                for (_, item) in self.items.iter_mut() {
+                    if let Some(groups) = &mut item.choice_groups {
+                        // Remove this specific choice group ID from the Item.choice_groups vec
+                        groups.retain(|&group_id| group_id.0 != deletion_info.entity_id);
+                        
+                        // If vec is empty after removal, set to None
+                        if groups.is_empty() {
+                            item.choice_groups = None;
+                        }
+                    }
+                }
+
+                // Delete the choice group
+                self.choice_groups.remove(&deletion_info.entity_id);
+                self.screen = Screen::ChoiceGroups;
+            }
+            "ItemGroup" => {
+                // Find all items using this item group
+                for (_, item) in self.items.iter_mut() {
+                    if let Some(group_id) = item.item_group {
+                        if group_id == deletion_info.entity_id {
+                            // This item has this item group, set it to None
+                            item.item_group = None;
+                        }
+                    }
+                }
+
+                // Delete the item group
+                self.item_groups.remove(&deletion_info.entity_id);
+                self.screen = Screen::ItemGroups;
+            }
+            "Item" => {
+                // Combos lose the item from their components
+                let mut combos = Vec::new();
+                for (_, item) in self.items.iter_mut() {
+                    if item.id != deletion_info.entity_id && item.remove_component(deletion_info.entity_id) {
+                        combos.push(item.name.clone());
+                    }
+                }
+                if !combos.is_empty() {
+                    println!(
+                        "Warning: '{}' was removed from combos: {}",
+                        deletion_info.entity_name, combos.join(", ")
+                    );
+                }
+
+                //Delete the item
+                if self.items.contains_key(&deletion_info.entity_id) { self.items.remove(&deletion_info.entity_id); }
+
+                if self.selected_item_id == Some(deletion_info.entity_id) {
+                    self.selected_item_id = None;
+                }
+            }
+            "PriceLevel" => {
+                // Clean up references in all items, prices at this level are discarded
+                for (_, item) in self.items.iter_mut() {
+                    item.remove_price_level(deletion_info.entity_id);
+                }
+
+                if self.item_list_filters.price_level == Some(deletion_info.entity_id) {
+                    self.item_list_filters.price_level = None;
+                }
+
+                // Delete the price level
+                self.price_levels.remove(&deletion_info.entity_id);
+                self.screen = Screen::PriceLevels;
+            }
+            "PrinterLogical" => {
+                // Clean up references in all items
+                for (_, item) in self.items.iter_mut() {
+                    if let Some(printers) = &mut item.printer_logicals {
+                        // Remove this specific printer logical ID from the Item.printer_logicals vec
+                        printers.retain(|&(printer_id, _)| printer_id != deletion_info.entity_id);
+                        
+                        // If vec is empty after removal, set to None
+                        if printers.is_empty() {
+                            item.printer_logicals = None;
+                        }
+                    }
+                }
+
+                // Delete the printer logical
+                self.printer_logicals.remove(&deletion_info.entity_id);
+                self.screen = Screen::PrinterLogicals;
+            }
+            "ProductClass" => {
+                // Find all items using this product class
+                for (_, item) in self.items.iter_mut() {
+                    if let Some(pc_id) = item.product_class {
+                        if pc_id == deletion_info.entity_id {
+                            // This item has this product class, set it to None
+                            item.product_class = None;
+                        }
+                    }
+                }
+
+                // Delete the product class
+                self.product_classes.remove(&deletion_info.entity_id);
+                self.screen = Screen::ProductClasses;
+            }
+            "ReportCategory" => {
+                // Find all items using this report category
+                for (_, item) in self.items.iter_mut() {
+                    if let Some(rc_id) = item.report_category {
+                        if rc_id == deletion_info.entity_id {
+                            // This item has this report category, set it to None
+                            item.report_category = None;
+                        }
+                    }
+                }
+
+                // Delete the report category
+                self.report_categories.remove(&deletion_info.entity_id);
+                self.screen = Screen::ReportCategories;
+            }
+            "RevenueCategory" => {
+                // Find all items using this revenue category
+                for (_, item) in self.items.iter_mut() {
+                    if let Some(rc_id) = item.revenue_category {
+                        if rc_id == deletion_info.entity_id {
+                            // This item has this revenue category, set it to None
+                            item.revenue_category = None;
+                        }
+                    }
+                }
+
+                // Delete the revenue category
+                self.revenue_categories.remove(&deletion_info.entity_id);
+                self.screen = Screen::RevenueCategories;
+            }
+            "SecurityLevel" => {
+                // Find all items using this security level
+                for (_, item) in self.items.iter_mut() {
+                    if let Some(sl_id) = item.security_level {
+                        if sl_id == deletion_info.entity_id {
+                            // This item has this security level, set it to None
+                            item.security_level = None;
+                        }
+                    }
+                }
+
+                // Delete the security level
+                self.security_levels.remove(&deletion_info.entity_id);
+                self.screen = Screen::SecurityLevels;
+            }
+            "TaxGroup" => {
+                // Find all items using this tax group
+                for (_, item) in self.items.iter_mut() {
+                    if let Some(tg_id) = item.tax_group {
+                        if tg_id == deletion_info.entity_id {
+                            // This item has this tax group, set it to None
+                            item.tax_group = None;
+                        }
+                    }
+                }
+
+                // Delete the tax group
+                self.tax_groups.remove(&deletion_info.entity_id);
+                self.screen = Screen::TaxGroups;
+            }
+            "Store" => {
+                // Store price levels at this store fall back to no store
+                for (_, level) in self.price_levels.iter_mut() {
+                    if level.store_id == Some(deletion_info.entity_id) {
+                        println!("Warning: price level '{}' no longer belongs to a store", level.name);
+                        level.store_id = None;
+                    }
+                }
+
+                // Delete the store
+                self.stores.remove(&deletion_info.entity_id);
+                self.screen = Screen::Stores;
+            }
+            _ => {println!("Oh No! You've tried to delete an unknown type: {}", deletion_info.entity_type);}
+        }
+
+        // A filter on the deleted record would leave the items list empty
+        self.item_list_filters.attributes.retain(|field, id| {
+            field.entity_type() != deletion_info.entity_type || *id != deletion_info.entity_id
+        });
+    }
+
+    // Opens the delete dialog for deletion_info. In hold-to-delete mode the hold
+    // already confirmed it, so records nothing references are deleted straight away
+    fn prompt_delete(&mut self) -> Task<Message> {
+        if self.settings.hold_to_delete && self.deletion_references(&self.deletion_info) == 0 {
+            self.delete_requested_at = None;
+            return self.update(Message::ConfirmDelete(self.deletion_info.clone()));
+        }
+
+        self.show_modal = true;
+        self.delete_requested_at = Some(Instant::now());
+        Task::none()
+    }
+
+    // Items pointing at the entity about to be deleted, as "id name"
+    fn referencing_items(&self, entity_type: &str, id: EntityId) -> Vec<String> {
+        let Some(references) = item_reference(entity_type) else {
+            return Vec::new();
+        };
+
+        self.items.values()
+            .filter(|item| references(item, id))
+            .map(|item| format!("{} {}", item.id, item.name))
+            .collect()
+    }
+
+    // Records on the current list screen that no saved item points at, None for
+    // screens whose records items don't reference. Only self.items is scanned, so
+    // a reference that exists only in the open item draft doesn't count as a use
+    fn unused_ids(&self) -> Option<BTreeSet<EntityId>> {
+        let (entity_type, entities) = self.screen_entities()?;
+        let references = item_reference(entity_type)?;
+
+        Some(
+            entities
+                .into_iter()
+                .map(|(id, _)| id)
+                .filter(|id| !self.items.values().any(|item| references(item, *id)))
+                .collect()
+        )
+    }
+
+    // How many records and settings point at the entity about to be deleted
+    fn deletion_references(&self, deletion_info: &data_types::DeletionInfo) -> usize {
+        let id = deletion_info.entity_id;
+
+        match deletion_info.entity_type.as_str() {
+            "PriceLevel" => {
+                self.referencing_items("PriceLevel", id).len()
+                    + usize::from(self.settings.online_menu_price_level == Some(id))
+            }
+            "Store" => {
+                self.price_levels.values().filter(|level| level.store_id == Some(id)).count()
+                    + usize::from(self.settings.export_store == Some(id))
+            }
+            entity_type => match item_reference(entity_type) {
+                Some(references) => self.items.values().filter(|item| references(item, id)).count(),
+                // Unknown types always go through the dialog
+                None => usize::MAX,
+            },
+        }
+    }
+
+    // Guards against a double click landing on the Delete button as the modal opens
+    fn delete_armed(&self) -> bool {
+        self.delete_requested_at
+            .is_none_or(|opened| opened.elapsed() >= DELETE_ARM_DELAY)
+    }
+
+    // Records that fall outside the configured id ranges, shown when a range is shrunk
+    fn id_range_warnings(&self) -> Vec<(data_types::EntityKind, String)> {
+        fn outside<T>(
+            kind: EntityKind,
+            entities: &BTreeMap<EntityId, T>,
+            name: impl Fn(&T) -> &str,
+            settings: &settings::AppSettings,
+        ) -> Vec<(EntityKind, String)> {
+            let range = settings.id_range(kind);
+            entities.iter()
+                .filter(|(id, _)| !range.contains(id))
+                .map(|(id, entity)| (kind, format!(
+                    "{} '{}' ({}) is outside {}-{}",
+                    kind, name(entity), id, range.start(), range.end()
+                )))
+                .collect()
+        }
+
+        let settings = &self.settings;
+        let mut warnings = Vec::new();
+        warnings.extend(outside(EntityKind::ItemGroup, &self.item_groups, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::PriceLevel, &self.price_levels, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::ProductClass, &self.product_classes, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::TaxGroup, &self.tax_groups, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::SecurityLevel, &self.security_levels, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::RevenueCategory, &self.revenue_categories, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::ReportCategory, &self.report_categories, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::ChoiceGroup, &self.choice_groups, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::PrinterLogical, &self.printer_logicals, |e| &e.name, settings));
+        warnings.extend(outside(EntityKind::Store, &self.stores, |e| &e.name, settings));
+        warnings
+    }
+
+    fn report_category_capacity(&self) -> report_categories::Capacity {
+        report_categories::Capacity::new(
+            &self.report_categories,
+            &self.settings.id_range(data_types::EntityKind::ReportCategory),
+        )
+    }
+
+    // Raises a toast when a new report category takes the id range past the warning level
+    fn warn_report_category_capacity(&mut self, before: report_categories::Capacity) -> Task<Message> {
+        let after = self.report_category_capacity();
+
+        if after.crossed(&before, self.settings.report_category_warn_percent) {
+            self.show_notice(format!(
+                "Report category ids are {}% used ({} of {})",
+                after.percent(), after.used, after.total
+            ))
+        } else {
+            Task::none()
+        }
+    }
+
+    fn show_notice(&mut self, message: String) -> Task<Message> {
+        let raised_at = Instant::now();
+        self.notice = Some((message, raised_at));
+
+        Task::perform(
+            tokio::time::sleep(NOTICE_DURATION),
+            move |_| Message::ExpireNotice(raised_at)
+        )
+    }
+
+    fn restore_version(&mut self, version: &persistence::VersionInfo) -> Result<String, String> {
+        self.restore_from_file(&version.path, "version")?;
+        Ok(format!("Restored version {}, the previous data was backed up", version.name))
+    }
+
+    fn restore_backup(&mut self, backup: &std::path::Path) -> Result<String, String> {
+        self.restore_from_file(backup, "backup")?;
+        Ok(format!(
+            "Restored {}, the previous data was backed up",
+            backup.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+        ))
+    }
+
+    // Replaces the menu data with a saved version or backup, read through the
+    // same tolerant load as the data file. The current file is backed up first,
+    // whatever the backup setting, and the current settings are kept.
+    fn restore_from_file(&mut self, path: &std::path::Path, kind: &str) -> Result<(), String> {
+        if self.load_failure.is_some() {
+            return Err(format!("Resolve the unreadable data file before restoring a {}", kind));
+        }
         if !path.exists() {
-            println!("No saved data file found at: {}", self.settings.file_path);
-            return Ok(());  // Not an error if file doesn't exist yet
+            return Err(format!("The {} file is missing: {}", kind, path.display()));
+        }
+
+        let (mut state, warnings) = persistence::load_from_file(&path.to_string_lossy())?;
+        self.file_manager.create_backup(std::path::Path::new(&self.settings.file_path))?;
+
+        for warning in &warnings {
+            println!("Load warning: {}", warning);
+        }
+
+        state.settings = self.settings.clone();
+        self.apply_state(state);
+
+        // Nothing open for editing refers to the restored data
+        self.close_edits();
+        self.load_warnings = warnings;
+
+        // Written out directly, the backup above already covers the old data
+        persistence::save_to_file(&self.app_state(), &self.settings.file_path, self.settings.save_format)?;
+
+        Ok(())
+    }
+
+    // Makes another menu file the data file for the rest of the session. It is
+    // loaded the same way as at startup, and the undo history is dropped since
+    // it describes the previous file.
+    fn open_menu_file(&mut self, path: &std::path::Path) -> Task<Message> {
+        let (state, warnings) = match persistence::load_from_file(&path.to_string_lossy()) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.error_message = Some(format!("Could not open {}: {}", path.display(), e));
+                return Task::none();
+            }
+        };
+
+        self.close_edits();
+        self.undo_history = undo::History::default();
+        self.load_failure = None;
+        // Repairs made while loading are left marked unsaved
+        self.unsaved_changes.set(false);
+        self.finish_loading(Ok(Some((state, warnings))));
+        self.settings.file_path = path.to_string_lossy().into_owned();
+
+        println!("Opened menu file: {}", path.display());
+        self.show_notice(format!("Opened {}", path.display()))
+    }
+
+    fn open_screen(&mut self, screen: Screen) -> Task<Message> {
+        let opening_settings = matches!(screen, Screen::Settings(_));
+        self.screen = screen;
+        self.list_search.clear();
+        self.bulk_delete_mode = false;
+        self.bulk_delete_selection.clear();
+        self.merge_selection = entity_component::MergeSelection::default();
+
+        // Keep the previous item selection only if it still exists
+        if matches!(self.screen, Screen::Items(_)) {
+            self.selected_item_id = items::live_selection(self.selected_item_id, &self.items);
+        }
+
+        if opening_settings {
+            Task::batch([
+                Task::done(Message::Settings(settings::Message::ScanStorage)),
+                Task::done(Message::Settings(settings::Message::ScanVersions)),
+            ])
+        } else {
+            Task::none()
+        }
+    }
+
+    fn has_open_edits(&self) -> bool {
+        self.draft_item_id.is_some()
+            || matches!(self.screen, Screen::Items(items::Mode::Edit))
+            || !self.item_group_edit_state_vec.is_empty()
+            || !self.price_level_edit_state_vec.is_empty()
+            || !self.product_class_edit_state_vec.is_empty()
+            || !self.tax_group_edit_state_vec.is_empty()
+            || !self.security_level_edit_state_vec.is_empty()
+            || !self.revenue_category_edit_state_vec.is_empty()
+            || !self.report_category_edit_state_vec.is_empty()
+            || !self.choice_group_edit_state_vec.is_empty()
+            || !self.printer_logical_edit_state_vec.is_empty()
+            || !self.store_edit_state_vec.is_empty()
+    }
+
+    // Whether going to `screen` would leave an open edit behind. Reopening the
+    // same list keeps its rows, but any move out of the item editor leaves it.
+    fn leaves_edits(&self, screen: &Screen) -> bool {
+        let same_screen = std::mem::discriminant(&self.screen) == std::mem::discriminant(screen)
+            && !matches!(self.screen, Screen::Items(items::Mode::Edit));
+
+        !same_screen && self.has_open_edits()
+    }
+
+    // Drops the item draft and every open row edit without saving them
+    fn discard_edits(&mut self) {
+        self.draft_item_id = None;
+        self.draft_item = Item::default();
+        self.item_group_edit_state_vec.clear();
+        self.price_level_edit_state_vec.clear();
+        self.product_class_edit_state_vec.clear();
+        self.tax_group_edit_state_vec.clear();
+        self.security_level_edit_state_vec.clear();
+        self.revenue_category_edit_state_vec.clear();
+        self.report_category_edit_state_vec.clear();
+        self.choice_group_edit_state_vec.clear();
+        self.printer_logical_edit_state_vec.clear();
+        self.store_edit_state_vec.clear();
+    }
+
+    // An item draft or any entity row being edited
+    fn has_open_edits(&self) -> bool {
+        matches!(self.screen, Screen::Items(items::Mode::Edit))
+            || self.draft_item_id.is_some()
+            || !self.item_group_edit_state_vec.is_empty()
+            || !self.price_level_edit_state_vec.is_empty()
+            || !self.product_class_edit_state_vec.is_empty()
+            || !self.tax_group_edit_state_vec.is_empty()
+            || !self.security_level_edit_state_vec.is_empty()
+            || !self.revenue_category_edit_state_vec.is_empty()
+            || !self.report_category_edit_state_vec.is_empty()
+            || !self.choice_group_edit_state_vec.is_empty()
+            || !self.printer_logical_edit_state_vec.is_empty()
+            || !self.store_edit_state_vec.is_empty()
+    }
+
+    // Drops every open edit and the selection, for when the data underneath
+    // them has been replaced
+    fn close_edits(&mut self) {
+        self.selected_item_id = None;
+        self.discard_edits();
+        self.pending_restore = None;
+        *self.item_search_cache.borrow_mut() = items::SearchCache::default();
+    }
+
+    // Runs an action that changes the menu data and records what it changed
+    // as one undo step. Actions run from inside it fold into the same step.
+    fn with_undo(&mut self, label: &str, scope: undo::Scope, action: impl FnOnce(&mut Self) -> Task<Message>) -> Task<Message> {
+        if self.undo_history.recording {
+            return action(self);
+        }
+
+        let before = undo::Before::capture(self, scope);
+        // Assumed unsaved until the action saves, which clears it
+        let unsaved_before = self.unsaved_changes.replace(true);
+        self.undo_history.recording = true;
+        let task = action(self);
+        self.undo_history.recording = false;
+
+        let changed = undo::record(self, label.to_string(), before);
+        if !changed && self.unsaved_changes.get() {
+            self.unsaved_changes.set(unsaved_before);
+        }
+        task
+    }
+
+    // Saves after an undo or redo. Open edits may refer to records that
+    // were just put back or taken away, so they are closed.
+    fn finish_history_step(&mut self, done: Option<String>, nothing_to_do: &str) -> Task<Message> {
+        let Some(notice) = done else {
+            return self.show_notice(nothing_to_do.to_string());
+        };
+
+        // Undo and redo are refused while anything is being edited, so there
+        // is no draft to drop here. The undo-delete toast would restore stale data.
+        self.pending_restore = None;
+        *self.item_search_cache.borrow_mut() = items::SearchCache::default();
+        if let Err(e) = self.save_state() {
+            self.handle_save_error(e);
+        }
+
+        println!("{}", notice);
+        self.show_notice(notice)
+    }
+
+    // Adds the CSV import once no conflicts are left to decide
+    fn finish_csv_import(&mut self) -> Task<Message> {
+        if self.pending_csv_import.as_ref().is_some_and(|pending| !pending.conflicts.is_empty()) {
+            return Task::none();
+        }
+        let Some(pending) = self.pending_csv_import.take() else {
+            return Task::none();
+        };
+
+        let count = pending.ready.len();
+        for item in pending.ready {
+            self.items.insert(item.id, item);
+        }
+
+        if let Err(e) = self.save_state() {
+            self.handle_save_error(e);
+            return Task::none();
+        }
+
+        if pending.errors.is_empty() {
+            self.error_message = None;
+        } else {
+            self.error_message = Some(format!(
+                "Imported {} items, clearing references that don't exist here:\n{}",
+                count,
+                pending.errors.join("\n")
+            ));
+        }
+        self.show_notice(format!("Imported {} items", count))
+    }
+
+    // The list shown on the screen as CSV, with a label for the file name.
+    // Items have their own export in settings.
+    fn export_entities_to_csv(&self, screen: &Screen) -> Result<(String, String), String> {
+        let (label, contents) = match screen {
+            Screen::ItemGroups => ("Item Groups", entity_export::to_csv(&self.item_groups)),
+            Screen::PriceLevels => ("Price Levels", entity_export::to_csv(&self.price_levels)),
+            Screen::ProductClasses => ("Product Classes", entity_export::to_csv(&self.product_classes)),
+            Screen::TaxGroups => ("Tax Groups", entity_export::to_csv(&self.tax_groups)),
+            Screen::SecurityLevels => ("Security Levels", entity_export::to_csv(&self.security_levels)),
+            Screen::RevenueCategories => ("Revenue Categories", entity_export::to_csv(&self.revenue_categories)),
+            Screen::ReportCategories => ("Report Categories", entity_export::to_csv(&self.report_categories)),
+            Screen::ChoiceGroups => ("Choice Groups", entity_export::to_csv(&self.choice_groups)),
+            Screen::PrinterLogicals => ("Printer Logicals", entity_export::to_csv(&self.printer_logicals)),
+            Screen::Stores => ("Stores", entity_export::to_csv(&self.stores)),
+            Screen::Items(_) | Screen::Settings(_) => {
+                return Err("Items are exported from Settings".to_string());
+            }
+            Screen::Validation => return Err("The validation list can't be exported".to_string()),
+        };
+
+        Ok((label.to_string(), contents?))
+    }
+
+    fn record_collections(&self) -> record_export::Collections<'_> {
+        record_export::Collections {
+            item_groups: &self.item_groups,
+            price_levels: &self.price_levels,
+            product_classes: &self.product_classes,
+            tax_groups: &self.tax_groups,
+            security_levels: &self.security_levels,
+            revenue_categories: &self.revenue_categories,
+            report_categories: &self.report_categories,
+            choice_groups: &self.choice_groups,
+            printer_logicals: &self.printer_logicals,
+            stores: &self.stores,
+        }
+    }
+
+    fn export_record(&mut self, record: Option<record_export::Record>) -> Task<Message> {
+        let Some(record) = record else {
+            return Task::none();
+        };
+
+        let file = record_export::RecordFile::new(record, &self.record_collections());
+        Task::perform(record_export::save_record(file), Message::RecordExported)
+    }
+
+    fn copy_record_json(&mut self, record: Option<record_export::Record>) -> Task<Message> {
+        let Some(record) = record else {
+            return Task::none();
+        };
+
+        match serde_json::to_string_pretty(&record) {
+            Ok(json) => Task::done(Message::CopyEntityJson(json)),
+            Err(e) => self.show_notice(format!("Failed to serialize {} as JSON: {}", record.label(), e)),
+        }
+    }
+
+    // Pasted text is only inserted once it parses as the open screen's type and
+    // passes that type's validation under a free id
+    fn paste_entity(&mut self, contents: Option<String>) -> Task<Message> {
+        use record_export::Record;
+
+        let Some(kind) = list_kind(&self.screen) else {
+            return Task::none();
+        };
+        let range = self.settings.id_range(kind);
+
+        let pasted = contents
+            .filter(|text| !text.trim().is_empty())
+            .ok_or_else(|| "The clipboard is empty".to_string())
+            .and_then(|text| record_export::parse_pasted(&text, kind));
+
+        let inserted = match pasted {
+            Ok(Record::ItemGroup(group)) => record_export::insert_pasted(&mut self.item_groups, group, kind, &range, ItemGroup::validate),
+            Ok(Record::PriceLevel(mut level)) => {
+                // A store id from another menu would point at the wrong store here
+                level.store_id = None;
+                record_export::insert_pasted(&mut self.price_levels, level, kind, &range, PriceLevel::validate)
+            }
+            Ok(Record::ProductClass(class)) => record_export::insert_pasted(&mut self.product_classes, class, kind, &range, ProductClass::validate),
+            Ok(Record::TaxGroup(group)) => record_export::insert_pasted(&mut self.tax_groups, group, kind, &range, TaxGroup::validate),
+            Ok(Record::SecurityLevel(level)) => record_export::insert_pasted(&mut self.security_levels, level, kind, &range, SecurityLevel::validate),
+            Ok(Record::RevenueCategory(category)) => record_export::insert_pasted(&mut self.revenue_categories, category, kind, &range, RevenueCategory::validate),
+            Ok(Record::ReportCategory(category)) => record_export::insert_pasted(&mut self.report_categories, category, kind, &range, ReportCategory::validate),
+            Ok(Record::ChoiceGroup(group)) => record_export::insert_pasted(&mut self.choice_groups, group, kind, &range, ChoiceGroup::validate),
+            Ok(Record::PrinterLogical(printer)) => record_export::insert_pasted(&mut self.printer_logicals, printer, kind, &range, PrinterLogical::validate),
+            Ok(Record::Store(store)) => record_export::insert_pasted(&mut self.stores, store, kind, &range, Store::validate),
+            // parse_pasted only returns the open screen's kind
+            Ok(Record::Item(_)) => Err("Items can't be pasted into a list".to_string()),
+            Err(e) => Err(e),
+        };
+
+        match inserted {
+            Ok(id) => {
+                println!("Pasted {} as ID {}", kind, id);
+                self.error_message = None;
+                if let Err(e) = self.save_state() {
+                    self.handle_save_error(e);
+                }
+                self.show_notice(format!("Pasted {} as ID {}", kind, id))
+            }
+            Err(e) => {
+                println!("Paste failed: {}", e);
+                self.error_message = Some(e.clone());
+                self.show_notice(e)
+            }
+        }
+    }
+
+    // Adds an entity for a reference that had no match, returning its id
+    fn create_missing_reference(&mut self, reference: &record_export::ReferenceSnapshot) -> Result<EntityId, String> {
+        let range = self.settings.id_range(reference.kind);
+
+        match reference.kind {
+            EntityKind::ItemGroup => record_export::create_missing(&mut self.item_groups, reference, &range),
+            EntityKind::PriceLevel => record_export::create_missing(&mut self.price_levels, reference, &range),
+            EntityKind::ProductClass => record_export::create_missing(&mut self.product_classes, reference, &range),
+            EntityKind::TaxGroup => {
+                let id = record_export::create_missing(&mut self.tax_groups, reference, &range)?;
+                let rate = reference.details.get("rate").and_then(|rate| rate.parse::<Decimal>().ok());
+                if let (Some(group), Some(rate)) = (self.tax_groups.get_mut(&id), rate) {
+                    group.rate = rate;
+                }
+                Ok(id)
+            }
+            EntityKind::SecurityLevel => record_export::create_missing(&mut self.security_levels, reference, &range),
+            EntityKind::RevenueCategory => record_export::create_missing(&mut self.revenue_categories, reference, &range),
+            EntityKind::ReportCategory => record_export::create_missing(&mut self.report_categories, reference, &range),
+            EntityKind::ChoiceGroup => record_export::create_missing(&mut self.choice_groups, reference, &range),
+            EntityKind::PrinterLogical => record_export::create_missing(&mut self.printer_logicals, reference, &range),
+            EntityKind::Store => record_export::create_missing(&mut self.stores, reference, &range),
+        }
+    }
+
+    // Adds the imported record with its references pointed at this data and
+    // opens it for editing. Missing references are created or left off.
+    fn apply_record_import(&mut self, pending: record_export::PendingImport, create_missing: bool) -> Task<Message> {
+        use record_export::Record;
+
+        let record_export::PendingImport { file, mut resolved, missing } = pending;
+        let mut left_off = Vec::new();
+
+        for reference in &missing {
+            if !create_missing {
+                left_off.push(format!("{} {}", reference.kind, reference.name));
+                continue;
+            }
+
+            match self.create_missing_reference(reference) {
+                Ok(id) => {
+                    println!("Created {} {} as ID {}", reference.kind, reference.name, id);
+                    resolved.insert((reference.kind, reference.id), id);
+                }
+                Err(e) => {
+                    println!("Could not create {} {}: {}", reference.kind, reference.name, e);
+                    left_off.push(format!("{} {}", reference.kind, reference.name));
+                }
+            }
+        }
+
+        let label = file.record.label();
+        let name = file.record.name().to_string();
+
+        let inserted = match file.record {
+            Record::Item(mut item) => {
+                record_export::remap_item(&mut item, &resolved, &self.items);
+                if self.items.contains_key(&item.id) {
+                    item.id = self.items.keys().max().map_or(1, |max_id| max_id + 1);
+                }
+                let id = item.id;
+                self.items.insert(id, item);
+                Ok(Operation::Items(id, items::Operation::StartEdit(id)))
+            }
+            Record::ItemGroup(group) => {
+                let range = self.settings.id_range(EntityKind::ItemGroup);
+                record_export::insert_entity(&mut self.item_groups, group, EntityKind::ItemGroup, &range)
+                    .map(|id| Operation::ItemGroups(id, item_groups::Operation::EditItemGroup(id)))
+            }
+            Record::PriceLevel(mut level) => {
+                record_export::remap_price_level(&mut level, &resolved);
+                let range = self.settings.id_range(EntityKind::PriceLevel);
+                record_export::insert_entity(&mut self.price_levels, level, EntityKind::PriceLevel, &range)
+                    .map(|id| Operation::PriceLevels(id, price_levels::Operation::EditPriceLevel(id)))
+            }
+            Record::ProductClass(class) => {
+                let range = self.settings.id_range(EntityKind::ProductClass);
+                record_export::insert_entity(&mut self.product_classes, class, EntityKind::ProductClass, &range)
+                    .map(|id| Operation::ProductClasses(id, product_classes::Operation::EditProductClass(id)))
+            }
+            Record::TaxGroup(group) => {
+                let range = self.settings.id_range(EntityKind::TaxGroup);
+                record_export::insert_entity(&mut self.tax_groups, group, EntityKind::TaxGroup, &range)
+                    .map(|id| Operation::TaxGroups(id, tax_groups::Operation::EditTaxGroup(id)))
+            }
+            Record::SecurityLevel(level) => {
+                let range = self.settings.id_range(EntityKind::SecurityLevel);
+                record_export::insert_entity(&mut self.security_levels, level, EntityKind::SecurityLevel, &range)
+                    .map(|id| Operation::SecurityLevels(id, security_levels::Operation::EditSecurityLevel(id)))
+            }
+            Record::RevenueCategory(category) => {
+                let range = self.settings.id_range(EntityKind::RevenueCategory);
+                record_export::insert_entity(&mut self.revenue_categories, category, EntityKind::RevenueCategory, &range)
+                    .map(|id| Operation::RevenueCategories(id, revenue_categories::Operation::EditRevenueCategory(id)))
+            }
+            Record::ReportCategory(category) => {
+                let range = self.settings.id_range(EntityKind::ReportCategory);
+                record_export::insert_entity(&mut self.report_categories, category, EntityKind::ReportCategory, &range)
+                    .map(|id| Operation::ReportCategories(id, report_categories::Operation::EditReportCategory(id)))
+            }
+            Record::ChoiceGroup(group) => {
+                let range = self.settings.id_range(EntityKind::ChoiceGroup);
+                record_export::insert_entity(&mut self.choice_groups, group, EntityKind::ChoiceGroup, &range)
+                    .map(|id| Operation::ChoiceGroups(id, choice_groups::Operation::EditChoiceGroup(id)))
+            }
+            Record::PrinterLogical(printer) => {
+                let range = self.settings.id_range(EntityKind::PrinterLogical);
+                record_export::insert_entity(&mut self.printer_logicals, printer, EntityKind::PrinterLogical, &range)
+                    .map(|id| Operation::PrinterLogicals(id, printer_logicals::Operation::EditPrinterLogical(id)))
+            }
+            Record::Store(store) => {
+                let range = self.settings.id_range(EntityKind::Store);
+                record_export::insert_entity(&mut self.stores, store, EntityKind::Store, &range)
+                    .map(|id| Operation::Stores(id, stores::Operation::EditStore(id)))
+            }
+        };
+
+        let open = match inserted {
+            Ok(open) => open,
+            Err(e) => return self.show_notice(format!("Could not import {} {}: {}", label, name, e)),
+        };
+
+        if let Err(e) = self.save_state() {
+            self.handle_save_error(e);
         }
 
-        let state = persistence::load_from_file(&self.settings.file_path)?;
+        self.screen = match &open {
+            Operation::Items(..) => Screen::Items(items::Mode::View),
+            Operation::ItemGroups(..) => Screen::ItemGroups,
+            Operation::PriceLevels(..) => Screen::PriceLevels,
+            Operation::ProductClasses(..) => Screen::ProductClasses,
+            Operation::TaxGroups(..) => Screen::TaxGroups,
+            Operation::SecurityLevels(..) => Screen::SecurityLevels,
+            Operation::RevenueCategories(..) => Screen::RevenueCategories,
+            Operation::ReportCategories(..) => Screen::ReportCategories,
+            Operation::ChoiceGroups(..) => Screen::ChoiceGroups,
+            Operation::PrinterLogicals(..) => Screen::PrinterLogicals,
+            Operation::Stores(..) => Screen::Stores,
+            Operation::Settings(_) => self.screen.clone(),
+        };
+
+        let summary = if left_off.is_empty() {
+            format!("Imported {} {}", label, name)
+        } else {
+            format!("Imported {} {} without {}", label, name, left_off.join(", "))
+        };
+
+        Task::batch([self.perform(open), self.show_notice(summary)])
+    }
+
+    // Moves out-of-range report or revenue categories onto free ids inside the
+    // range and points every item (and the open item edit) at the new ids
+    fn renumber_out_of_range(
+        &mut self,
+        kind: data_types::EntityKind,
+    ) -> Result<BTreeMap<EntityId, EntityId>, String> {
+        use data_types::EntityKind;
+
+        let range = self.settings.id_range(kind);
+
+        let moves = match kind {
+            EntityKind::ReportCategory => {
+                let moves = data_types::renumber_into_range(kind, &mut self.report_categories, &range, |c, id| c.id = id)?;
+                let remap = |field: &mut Option<EntityId>| {
+                    if let Some(new_id) = field.and_then(|old_id| moves.get(&old_id)) {
+                        *field = Some(*new_id);
+                    }
+                };
+                for item in self.items.values_mut() {
+                    remap(&mut item.report_category);
+                }
+                remap(&mut self.item_edit_state.report_category);
+                self.report_category_edit_state_vec
+                    .retain(|state| state.id.parse::<EntityId>().is_ok_and(|id| !moves.contains_key(&id)));
+                moves
+            }
+            EntityKind::RevenueCategory => {
+                let moves = data_types::renumber_into_range(kind, &mut self.revenue_categories, &range, |c, id| c.id = id)?;
+                let remap = |field: &mut Option<EntityId>| {
+                    if let Some(new_id) = field.and_then(|old_id| moves.get(&old_id)) {
+                        *field = Some(*new_id);
+                    }
+                };
+                for item in self.items.values_mut() {
+                    remap(&mut item.revenue_category);
+                }
+                remap(&mut self.item_edit_state.revenue_category);
+                self.revenue_category_edit_state_vec
+                    .retain(|state| state.id.parse::<EntityId>().is_ok_and(|id| !moves.contains_key(&id)));
+                moves
+            }
+            _ => return Err(format!("{} records can't be renumbered automatically", kind)),
+        };
+
+        Ok(moves)
+    }
+
+    // Looks for the item's image file in the background
+    fn check_item_image(&mut self, id: EntityId) -> Task<Message> {
+        self.item_image_exists = None;
+
+        match self.items.get(&id).and_then(|item| item.image_path.clone()) {
+            Some(path) => Task::perform(
+                items::image_exists(path),
+                move |exists| Message::ItemImageChecked(id, exists)
+            ),
+            None => Task::none(),
+        }
+    }
+
+    // Runs the bulk work now, or holds it for the summary modal when it
+    // touches more records than the configured threshold
+    fn run_bulk(&mut self, pending: bulk::PendingBulk<MenuBuilder, Message>) -> Task<Message> {
+        let threshold = self.settings.bulk_confirm_threshold;
+
+        match bulk::gate(self, threshold, pending) {
+            Ok(task) => task,
+            Err(pending) => {
+                self.pending_bulk = Some(pending);
+                Task::none()
+            }
+        }
+    }
+
+    pub fn save_state(&self) -> Result<(), String> {
+        //println!("Save State Triggered!");
+        // Every change to the data is followed by a save, with or without an
+        // undo step. It stays marked until it is on disk, so a save that fails
+        // or is held back below is picked up again by the autosave.
+        self.unsaved_changes.set(true);
+
+        if self.load_failure.is_some() {
+            println!("Not saving, the data file could not be read and has not been replaced yet");
+            return Ok(());
+        }
+
+        let state = self.app_state();
+
+        if self.settings.create_backups {
+            self.file_manager.create_backup(std::path::Path::new(&self.settings.file_path))?;
+        }
+
+        persistence::save_to_file(&state, &self.settings.file_path, self.settings.save_format)?;
+        self.unsaved_changes.set(false);
+        self.save_error.borrow_mut().take();
+        self.saved_view.set(state.last_view);
+        Ok(())
+    }
+
+    // What Ctrl+S saves: the item being edited, or on a list screen the row
+    // most recently opened for editing. None when nothing there is being edited.
+    fn save_hotkey_message(&self) -> Option<Message> {
+        match &self.screen {
+            Screen::Items(items::Mode::Edit) => self.selected_item_id
+                .map(|id| Message::Items(id, items::Message::Edit(items::edit::Message::Save))),
+            Screen::Items(_) | Screen::Settings(_) | Screen::Validation => None,
+            Screen::ItemGroups => latest_edit(&self.item_group_edit_state_vec, |state| state.base.id.as_str())
+                .map(|(id, state)| Message::ItemGroups(id, item_groups::Message::Save(id, state.clone()))),
+            Screen::PriceLevels => latest_edit(&self.price_level_edit_state_vec, |state| state.base.id.as_str())
+                .map(|(id, state)| Message::PriceLevels(id, price_levels::Message::SaveAll(id, state.clone()))),
+            Screen::TaxGroups => latest_edit(&self.tax_group_edit_state_vec, |state| state.base.id.as_str())
+                .map(|(id, state)| Message::TaxGroups(id, tax_groups::Message::SaveAll(id, state.clone()))),
+            Screen::ProductClasses => latest_edit(&self.product_class_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, state)| Message::ProductClasses(id, product_classes::Message::SaveAll(id, state.clone()))),
+            Screen::SecurityLevels => latest_edit(&self.security_level_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, state)| Message::SecurityLevels(id, security_levels::Message::SaveAll(id, state.clone()))),
+            Screen::RevenueCategories => latest_edit(&self.revenue_category_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, state)| Message::RevenueCategories(id, revenue_categories::Message::SaveAll(id, state.clone()))),
+            Screen::ReportCategories => latest_edit(&self.report_category_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, state)| Message::ReportCategories(id, report_categories::Message::SaveAll(id, state.clone()))),
+            Screen::ChoiceGroups => latest_edit(&self.choice_group_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, state)| Message::ChoiceGroups(id, choice_groups::Message::SaveAll(id, state.clone()))),
+            Screen::PrinterLogicals => latest_edit(&self.printer_logical_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, state)| Message::PrinterLogicals(id, printer_logicals::Message::Save(id, state.clone()))),
+            Screen::Stores => latest_edit(&self.store_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, state)| Message::Stores(id, stores::Message::SaveAll(id, state.clone()))),
+        }
+    }
+
+    // What Ctrl+N creates: a new record on the current screen, nothing in settings
+    fn new_hotkey_message(&self) -> Option<Message> {
+        match &self.screen {
+            Screen::Settings(_) | Screen::Validation => None,
+            Screen::Items(_) => Some(Message::Items(-1, items::Message::CreateNew)),
+            Screen::ItemGroups => Some(Message::ItemGroups(-1, item_groups::Message::CreateNew)),
+            Screen::PriceLevels => Some(Message::PriceLevels(-1, price_levels::Message::CreateNew)),
+            Screen::TaxGroups => Some(Message::TaxGroups(-1, tax_groups::Message::CreateNew)),
+            Screen::ProductClasses => Some(Message::ProductClasses(-1, product_classes::Message::CreateNew)),
+            Screen::SecurityLevels => Some(Message::SecurityLevels(-1, security_levels::Message::CreateNew)),
+            Screen::RevenueCategories => Some(Message::RevenueCategories(-1, revenue_categories::Message::CreateNew)),
+            Screen::ReportCategories => Some(Message::ReportCategories(-1, report_categories::Message::CreateNew)),
+            Screen::ChoiceGroups => Some(Message::ChoiceGroups(-1, choice_groups::Message::CreateNew)),
+            Screen::PrinterLogicals => Some(Message::PrinterLogicals(-1, printer_logicals::Message::CreateNew)),
+            Screen::Stores => Some(Message::Stores(-1, stores::Message::CreateNew)),
+        }
+    }
 
+    // What Escape cancels on a list screen: the row most recently opened for editing
+    fn cancel_hotkey_message(&self) -> Option<Message> {
+        match &self.screen {
+            Screen::Items(_) | Screen::Settings(_) | Screen::Validation => None,
+            Screen::ItemGroups => latest_edit(&self.item_group_edit_state_vec, |state| state.base.id.as_str())
+                .map(|(id, _)| Message::ItemGroups(id, item_groups::Message::CancelEdit(id))),
+            Screen::PriceLevels => latest_edit(&self.price_level_edit_state_vec, |state| state.base.id.as_str())
+                .map(|(id, _)| Message::PriceLevels(id, price_levels::Message::CancelEdit(id))),
+            Screen::TaxGroups => latest_edit(&self.tax_group_edit_state_vec, |state| state.base.id.as_str())
+                .map(|(id, _)| Message::TaxGroups(id, tax_groups::Message::CancelEdit(id))),
+            Screen::ProductClasses => latest_edit(&self.product_class_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, _)| Message::ProductClasses(id, product_classes::Message::CancelEdit(id))),
+            Screen::SecurityLevels => latest_edit(&self.security_level_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, _)| Message::SecurityLevels(id, security_levels::Message::CancelEdit(id))),
+            Screen::RevenueCategories => latest_edit(&self.revenue_category_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, _)| Message::RevenueCategories(id, revenue_categories::Message::CancelEdit(id))),
+            Screen::ReportCategories => latest_edit(&self.report_category_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, _)| Message::ReportCategories(id, report_categories::Message::CancelEdit(id))),
+            Screen::ChoiceGroups => latest_edit(&self.choice_group_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, _)| Message::ChoiceGroups(id, choice_groups::Message::CancelEdit(id))),
+            Screen::PrinterLogicals => latest_edit(&self.printer_logical_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, _)| Message::PrinterLogicals(id, printer_logicals::Message::CancelEdit(id))),
+            Screen::Stores => latest_edit(&self.store_edit_state_vec, |state| state.id.as_str())
+                .map(|(id, _)| Message::Stores(id, stores::Message::CancelEdit(id))),
+        }
+    }
+
+    // settings.app_theme is the only record of the choice, the toggle and the
+    // settings screen both read it
+    // Under System the last known preference is used until the subscription
+    // reports the current one, which it does as soon as it starts
+    fn set_theme(&mut self, choice: settings::ThemeChoice) {
+        self.settings.app_theme = choice;
+        self.theme = accent::app_theme(choice, self.settings.accent_color, self.system_dark);
+    }
+
+    // Saves the item from the editor. None when the save was refused or the
+    // write failed, with the reason shown and the editor left open on the item.
+    fn save_item(&mut self, mut item: Item) -> Option<Task<Message>> {
+        if let Err(e) = items::validate_components(&item, &self.items) {
+            self.item_edit_state.validation_error = Some(e);
+            return None;
+        }
+
+        println!("Saving Item ID: {}, with prices: {:?}", item.id, item.item_prices);
+        println!("EditState information: {:?}", self.item_edit_state.prices);
+
+        let edit_state_prices = self.item_edit_state.prices.clone().unwrap_or_default();
+
+        // The item's assignment is the source of truth, every assigned level gets a row
+        item.reconcile_price_levels();
+
+        //Copy prices from edit_state,to item
+        let rounded = match items::apply_edit_prices(&mut item, &edit_state_prices, &self.price_levels, self.settings.price_scale) {
+            Ok(rounded) => rounded,
+            Err(e) => {
+                self.item_edit_state.validation_error = Some(e);
+                return None;
+            }
+        };
+
+        item.timestamps.touch();
+        if item.id < 0 {
+            let next_id = self.items
+                .keys()
+                .max()
+                .map_or(1, |max_id| max_id + 1);
+            item.id = next_id;
+            item.timestamps = Timestamps::created_now();
+
+            self.items.insert(next_id, item.clone());
+            self.draft_item_id = None;
+            self.draft_item = Item::default();
+            self.selected_item_id = Some(next_id);
+        } else {
+            // The draft was taken before any reordering, keep the list position
+            if let Some(existing) = self.items.get(&item.id) {
+                item.sort_order = existing.sort_order;
+            }
+            self.items.insert(item.id, item.clone());
+            self.selected_item_id = Some(item.id);
+        }
+
+        // Leaving the editor would look like the save worked, so a failed
+        // write keeps it open on the item with the banner up
+        if let Err(e) = self.save_state() {
+            self.handle_save_error(e);
+            self.draft_item_id = Some(item.id);
+            self.draft_item = item;
+            self.screen = Screen::Items(items::Mode::Edit);
+            return None;
+        }
+        self.error_message = None;
+        self.screen = Screen::Items(items::Mode::View);
+
+        let image_task = self.check_item_image(item.id);
+        if rounded.is_empty() {
+            Some(image_task)
+        } else {
+            let notice = format!(
+                "Prices rounded to {} decimal places: {}",
+                self.settings.price_scale,
+                rounded.join(", ")
+            );
+            Some(Task::batch([image_task, self.show_notice(notice)]))
+        }
+    }
+
+    // Lowest free id for a new or copied record of this kind
+    fn free_id(&self, kind: EntityKind) -> Result<EntityId, String> {
+        let range = self.settings.id_range(kind);
+        match kind {
+            EntityKind::ItemGroup => data_types::lowest_free_id(kind, &self.item_groups, &range),
+            EntityKind::PriceLevel => data_types::lowest_free_id(kind, &self.price_levels, &range),
+            EntityKind::ProductClass => data_types::lowest_free_id(kind, &self.product_classes, &range),
+            EntityKind::TaxGroup => data_types::lowest_free_id(kind, &self.tax_groups, &range),
+            EntityKind::SecurityLevel => data_types::lowest_free_id(kind, &self.security_levels, &range),
+            EntityKind::RevenueCategory => data_types::lowest_free_id(kind, &self.revenue_categories, &range),
+            EntityKind::ReportCategory => data_types::lowest_free_id(kind, &self.report_categories, &range),
+            EntityKind::ChoiceGroup => data_types::lowest_free_id(kind, &self.choice_groups, &range),
+            EntityKind::PrinterLogical => data_types::lowest_free_id(kind, &self.printer_logicals, &range),
+            EntityKind::Store => data_types::lowest_free_id(kind, &self.stores, &range),
+        }
+    }
+
+    // As free_id, but a full range opens the dialog explaining it and gives None
+    fn next_free_id(&mut self, kind: EntityKind) -> Option<EntityId> {
+        match self.free_id(kind) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                self.id_range_full = Some(e);
+                None
+            }
+        }
+    }
+
+    // For operations on a record that is gone, or an edit whose id can't be
+    // read. Reported instead of panicking so unsaved work isn't lost.
+    fn missing_record(&mut self, kind: &str, id: EntityId) -> Task<Message> {
+        let error = format!("{} {} no longer exists", kind, id);
+        println!("{}", error);
+        self.error_message = Some(error.clone());
+        self.show_notice(error)
+    }
+
+    // Shown as a banner over every screen until a save works, rather than
+    // switching to settings where it was easy to miss
+    fn handle_save_error(&mut self, error: String) {
+        println!("Save failed: {}", error);
+        *self.save_error.borrow_mut() = Some(error);
+    }
+
+    fn apply_state(&mut self, state: persistence::AppState) {
         // Convert Vec to BTreeMap using id as key
         self.items = state.items.into_iter().map(|i| (i.id, i)).collect();
         self.item_groups = state.item_groups.into_iter().map(|i| (i.id, i)).collect();
@@ -2987,6 +7071,7 @@ impl MenuBuilder {
         self.report_categories = state.report_categories.into_iter().map(|i| (i.id, i)).collect();
         self.choice_groups = state.choice_groups.into_iter().map(|i| (i.id, i)).collect();
         self.printer_logicals = state.printer_logicals.into_iter().map(|i| (i.id, i)).collect();
+        self.stores = state.stores.into_iter().map(|i| (i.id, i)).collect();
         self.settings = state.settings.clone();
 
         // Only update settings if they exist in the loaded state
@@ -2994,40 +7079,337 @@ impl MenuBuilder {
             // Keep current settings if none in file
             println!("No settings found in save file, keeping current settings");
         } else {
-            self.theme =  match &state.settings.app_theme {
-                settings::ThemeChoice::Light => Modern::light_theme(),
-                settings::ThemeChoice::Dark => Modern::dark_theme(),
-            };
-
             self.settings = state.settings;
         }
-
-        Ok(())
+        // Follows whichever settings were kept, so the toggle and the theme agree
+        self.theme = accent::app_theme(self.settings.app_theme, self.settings.accent_color, self.system_dark);
+        hold_to_confirm::set_enabled(self.settings.hold_to_delete);
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        event::listen_with(handle_event)
+        let loading = if self.loading_started.is_some() {
+            iced::time::every(LOADING_TICK).map(|_| Message::LoadingTick)
+        } else {
+            Subscription::none()
+        };
+
+        let autosave = if self.settings.autosave_secs > 0 {
+            iced::time::every(Duration::from_secs(self.settings.autosave_secs)).map(|_| Message::AutosaveTick)
+        } else {
+            Subscription::none()
+        };
+
+        let system_theme = if self.settings.app_theme == settings::ThemeChoice::System {
+            Subscription::run(accent::system_theme_changes).map(Message::SystemThemeChanged)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([event::listen_with(handle_event), loading, autosave, system_theme])
+    }
+
+    fn navigate(&self, screen: Screen) -> Option<Message> {
+        self.loading_started.is_none().then(|| Message::Navigate(screen))
     }
 }
 
+// Messages still handled while the data file is loading
+fn handled_while_loading(message: &Message) -> bool {
+    matches!(
+        message,
+        Message::LoadComplete(_)
+            | Message::LoadingTick
+            | Message::WindowClosed(_)
+            | Message::WindowResized(..)
+            | Message::RequestOpenWindow(_)
+            | Message::WindowOpened(..)
+            | Message::None
+    )
+}
+
+// Whether an item points at the entity of this type and id, None for types
+// items don't reference
+fn item_reference(entity_type: &str) -> Option<fn(&Item, EntityId) -> bool> {
+    let references: fn(&Item, EntityId) -> bool = match entity_type {
+        "ChoiceGroup" => |item, id| item.choice_groups.iter().flatten().any(|(group_id, _)| *group_id == id),
+        "ItemGroup" => |item, id| item.item_group == Some(id),
+        "Item" => |item, id| item.id != id && item.components.iter().flatten().any(|component| *component == id),
+        "PriceLevel" => |item, id| item.is_assigned_to(id) || item.has_price_at(id),
+        "PrinterLogical" => |item, id| item.printer_logicals.iter().flatten().any(|(printer_id, _)| *printer_id == id),
+        "ProductClass" => |item, id| item.product_class == Some(id),
+        "ReportCategory" => |item, id| item.report_category == Some(id),
+        "RevenueCategory" => |item, id| item.revenue_category == Some(id),
+        "SecurityLevel" => |item, id| item.security_level == Some(id),
+        "TaxGroup" => |item, id| item.tax_group == Some(id),
+        _ => return None,
+    };
+
+    Some(references)
+}
+
+// Label for the undo step of messages that change the menu data
+fn message_undo_label(message: &Message) -> Option<&'static str> {
+    match message {
+        Message::ConfirmDelete(_) | Message::MigratePricesAndDelete(..) => Some("Delete"),
+        Message::UndoDelete => Some("Undo delete"),
+        Message::ConfirmBulk => Some("Bulk change"),
+        Message::ConfirmBulkDelete => Some("Delete selected"),
+        Message::EntityPasted(_) => Some("Paste record"),
+        Message::RenumberDuplicateIds => Some("Renumber duplicate ids"),
+        Message::RecordLoaded(_) | Message::ResolveRecordImport(_) => Some("Import record"),
+        Message::ImportItemsOverwriteExisting
+        | Message::ImportCSVSelected(_)
+        | Message::ResolveCsvConflict(..) => Some("Import items"),
+        _ => None,
+    }
+}
+
+// Label for the undo step of operations that change the menu data
+fn operation_undo_label(operation: &Operation) -> Option<&'static str> {
+    match operation {
+        Operation::Settings(op) => match op {
+            settings::Operation::RenumberOutOfRange(_) => Some("Renumber out of range ids"),
+            settings::Operation::RestoreVersion(_) => Some("Restore version"),
+            settings::Operation::RestoreBackup(_) => Some("Restore backup"),
+            _ => None,
+        },
+        Operation::Items(_, op) => match op {
+            items::Operation::Save(_) | items::Operation::SaveAndAddAnother(_) => Some("Save item"),
+            items::Operation::CopyItem(_) => Some("Copy item"),
+            items::Operation::ApplyCourse(..) => Some("Set course"),
+            items::Operation::ApplyAttribute(..) => Some("Bulk assign"),
+            items::Operation::CreateReference(..) => Some("Create from item editor"),
+            items::Operation::MoveUp(_) | items::Operation::MoveDown(_) => Some("Move item"),
+            _ => None,
+        },
+        Operation::ItemGroups(_, op) => match op {
+            item_groups::Operation::Save(..) => Some("Save item group"),
+            item_groups::Operation::CopyItemGroup(_) => Some("Copy item group"),
+            item_groups::Operation::CreateNew => Some("New item group"),
+            _ => None,
+        },
+        Operation::PriceLevels(_, op) => match op {
+            price_levels::Operation::SaveAll(..) => Some("Save price level"),
+            price_levels::Operation::CopyPriceLevel(_) => Some("Copy price level"),
+            price_levels::Operation::CreateNew => Some("New price level"),
+            _ => None,
+        },
+        Operation::ProductClasses(_, op) => match op {
+            product_classes::Operation::SaveAll(..) => Some("Save product class"),
+            product_classes::Operation::CopyProductClass(_) => Some("Copy product class"),
+            product_classes::Operation::CreateNew => Some("New product class"),
+            _ => None,
+        },
+        Operation::TaxGroups(_, op) => match op {
+            tax_groups::Operation::SaveAll(..) => Some("Save tax group"),
+            tax_groups::Operation::CopyTaxGroup(_) => Some("Copy tax group"),
+            tax_groups::Operation::CreateNew => Some("New tax group"),
+            _ => None,
+        },
+        Operation::SecurityLevels(_, op) => match op {
+            security_levels::Operation::SaveAll(..) => Some("Save security level"),
+            security_levels::Operation::CopySecurityLevel(_) => Some("Copy security level"),
+            security_levels::Operation::CreateNew => Some("New security level"),
+            _ => None,
+        },
+        Operation::RevenueCategories(_, op) => match op {
+            revenue_categories::Operation::SaveAll(..) => Some("Save revenue category"),
+            revenue_categories::Operation::CopyRevenueCategory(_) => Some("Copy revenue category"),
+            revenue_categories::Operation::CreateNew => Some("New revenue category"),
+            _ => None,
+        },
+        Operation::ReportCategories(_, op) => match op {
+            report_categories::Operation::SaveAll(..) => Some("Save report category"),
+            report_categories::Operation::CopyReportCategory(_) => Some("Copy report category"),
+            report_categories::Operation::CreateNew => Some("New report category"),
+            _ => None,
+        },
+        Operation::ChoiceGroups(_, op) => match op {
+            choice_groups::Operation::SaveAll(..) => Some("Save choice group"),
+            choice_groups::Operation::CopyChoiceGroup(_) => Some("Copy choice group"),
+            choice_groups::Operation::CreateNew => Some("New choice group"),
+            choice_groups::Operation::MoveUp(_) | choice_groups::Operation::MoveDown(_) => Some("Move choice group"),
+            choice_groups::Operation::ToggleItemMembership(..) => Some("Change choice group items"),
+            _ => None,
+        },
+        Operation::PrinterLogicals(_, op) => match op {
+            printer_logicals::Operation::Save(..) => Some("Save printer logical"),
+            printer_logicals::Operation::CopyPrinterLogical(_) => Some("Copy printer logical"),
+            printer_logicals::Operation::CreateNew => Some("New printer logical"),
+            _ => None,
+        },
+        Operation::Stores(_, op) => match op {
+            stores::Operation::SaveAll(..) => Some("Save store"),
+            stores::Operation::CopyStore(_) => Some("Copy store"),
+            stores::Operation::CreateNew => Some("New store"),
+            _ => None,
+        },
+    }
+}
+
+// Collections an undoable operation can change. Entity edits can repoint
+// item references, so items come along with the entity's own collection.
+fn operation_undo_scope(operation: &Operation) -> undo::Scope {
+    use undo::Scope;
+
+    match operation {
+        Operation::Settings(_) => Scope::ALL,
+        // Creates the referenced record in its own collection
+        Operation::Items(_, items::Operation::CreateReference(..)) => Scope::ALL,
+        Operation::Items(..) => Scope { items: true, ..Scope::NONE },
+        Operation::ItemGroups(..) => Scope { items: true, item_groups: true, ..Scope::NONE },
+        Operation::PriceLevels(..) => Scope { items: true, price_levels: true, ..Scope::NONE },
+        Operation::ProductClasses(..) => Scope { items: true, product_classes: true, ..Scope::NONE },
+        Operation::TaxGroups(..) => Scope { items: true, tax_groups: true, ..Scope::NONE },
+        Operation::SecurityLevels(..) => Scope { items: true, security_levels: true, ..Scope::NONE },
+        Operation::RevenueCategories(..) => Scope { items: true, revenue_categories: true, ..Scope::NONE },
+        Operation::ReportCategories(..) => Scope { items: true, report_categories: true, ..Scope::NONE },
+        Operation::ChoiceGroups(..) => Scope { items: true, choice_groups: true, ..Scope::NONE },
+        Operation::PrinterLogicals(..) => Scope { items: true, printer_logicals: true, ..Scope::NONE },
+        Operation::Stores(..) => Scope { items: true, stores: true, ..Scope::NONE },
+    }
+}
+
+// Messages that only browse the items list and leave item data alone
+fn keeps_item_data(message: &Message) -> bool {
+    matches!(
+        message,
+        Message::Navigate(_)
+            | Message::CancelNavigate
+            | Message::DismissIdRangeFull
+            | Message::ExportDespiteRuleErrors
+            | Message::CancelBlockedExport
+            | Message::HotKey(HotKey::Previous | HotKey::Next)
+            | Message::StepItemSelection(_)
+            | Message::SaveAs
+            | Message::SaveAsSelected(_)
+            | Message::OpenFile
+            | Message::CancelOpenFile
+            | Message::ItemImageChecked(..)
+            | Message::CopyEntityJson(_)
+            | Message::PasteEntity
+            | Message::PrinterLogicals(_, printer_logicals::Message::ShowRouting(_))
+            | Message::Validation(validation::Message::ExportReport | validation::Message::ReportExported(_))
+            | Message::ChoiceGroups(
+                _,
+                choice_groups::Message::ShowMembers(_)
+                    | choice_groups::Message::SearchMembers(_)
+                    | choice_groups::Message::SelectMerge(_)
+                    | choice_groups::Message::Merge(..)
+            )
+            | Message::ReportCategories(
+                _,
+                report_categories::Message::SelectMerge(_) | report_categories::Message::Merge(..)
+            )
+            | Message::ItemGroups(_, item_groups::Message::AutoAssign)
+            | Message::ExpireUndoDelete(_)
+            | Message::ExpireNotice(_)
+            | Message::UpdateListSearch(_)
+            | Message::UpdateListSort(_)
+            | Message::ToggleBulkDeleteMode(_)
+            | Message::ToggleBulkDeleteSelection(..)
+            | Message::SetBulkDeleteSelection(_)
+            | Message::SelectUnused
+            | Message::RetrySave
+            | Message::RequestBulkDelete
+            | Message::AutosaveTick
+            | Message::SystemThemeChanged(_)
+            | Message::LoadingTick
+            | Message::WindowResized(..)
+            | Message::Items(
+                _,
+                items::Message::Select(_)
+                    | items::Message::OpenRecent(_)
+                    | items::Message::SearchItems(_)
+                    | items::Message::FilterCourse(_)
+                    | items::Message::FilterPriceLevel(..)
+                    | items::Message::FilterMissingPrice(_)
+                    | items::Message::FilterAttribute(..)
+                    | items::Message::ShowAttributeFilters(_)
+                    | items::Message::ClearFilters
+                    | items::Message::SelectBulkCourse(_)
+                    | items::Message::SelectBulkAssignField(_)
+                    | items::Message::SelectBulkAssignValue(_)
+            )
+    )
+}
+
 #[derive(Debug, Clone)]
 pub enum HotKey {
     Escape,
     Tab(Modifiers),
+    SaveAndAddAnother,
+    AssignAllFiltered,
+    Save,
+    New,
+    // Arrow keys, moving the item selection through the list
+    Previous,
+    Next,
 }
 
-fn handle_event(event: event::Event, _status: event::Status, id: iced::window::Id) -> Option<Message> {
+// The entity kind listed on a screen, None for items and settings
+fn list_kind(screen: &Screen) -> Option<EntityKind> {
+    match screen {
+        Screen::ItemGroups => Some(EntityKind::ItemGroup),
+        Screen::PriceLevels => Some(EntityKind::PriceLevel),
+        Screen::ProductClasses => Some(EntityKind::ProductClass),
+        Screen::TaxGroups => Some(EntityKind::TaxGroup),
+        Screen::SecurityLevels => Some(EntityKind::SecurityLevel),
+        Screen::RevenueCategories => Some(EntityKind::RevenueCategory),
+        Screen::ReportCategories => Some(EntityKind::ReportCategory),
+        Screen::ChoiceGroups => Some(EntityKind::ChoiceGroup),
+        Screen::PrinterLogicals => Some(EntityKind::PrinterLogical),
+        Screen::Stores => Some(EntityKind::Store),
+        Screen::Items(_) | Screen::Settings(_) | Screen::Validation => None,
+    }
+}
+
+fn list_screen(kind: EntityKind) -> Screen {
+    match kind {
+        EntityKind::ItemGroup => Screen::ItemGroups,
+        EntityKind::PriceLevel => Screen::PriceLevels,
+        EntityKind::ProductClass => Screen::ProductClasses,
+        EntityKind::TaxGroup => Screen::TaxGroups,
+        EntityKind::SecurityLevel => Screen::SecurityLevels,
+        EntityKind::RevenueCategory => Screen::RevenueCategories,
+        EntityKind::ReportCategory => Screen::ReportCategories,
+        EntityKind::ChoiceGroup => Screen::ChoiceGroups,
+        EntityKind::PrinterLogical => Screen::PrinterLogicals,
+        EntityKind::Store => Screen::Stores,
+    }
+}
+
+// The edit state opened last, with its id read back
+fn latest_edit<S>(states: &[S], id_of: impl Fn(&S) -> &str) -> Option<(EntityId, &S)> {
+    let state = states.last()?;
+    Some((data_types::parse_entity_id(id_of(state))?, state))
+}
+
+fn handle_event(event: event::Event, status: event::Status, id: iced::window::Id) -> Option<Message> {
+    // Keys a focused widget handled are its own. Undo and redo in a text field,
+    // Ctrl+A selecting its text or arrows in a list must not also act on the data.
+    let unclaimed = status == event::Status::Ignored;
+
     match event {
         event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
             match key {
                 Key::Named(keyboard::key::Named::Escape) => Some(Message::HotKey(HotKey::Escape)),
                 Key::Named(keyboard::key::Named::Tab) => Some(Message::HotKey(HotKey::Tab(modifiers))),
+                Key::Named(keyboard::key::Named::ArrowUp) if unclaimed => Some(Message::HotKey(HotKey::Previous)),
+                Key::Named(keyboard::key::Named::ArrowDown) if unclaimed => Some(Message::HotKey(HotKey::Next)),
+                Key::Named(keyboard::key::Named::Enter) if modifiers.command() => Some(Message::HotKey(HotKey::SaveAndAddAnother)),
+                Key::Character(c) if unclaimed && c.as_str() == "a" && modifiers.command() => Some(Message::HotKey(HotKey::AssignAllFiltered)),
+                Key::Character(c) if c.as_str() == "s" && modifiers.command() => Some(Message::HotKey(HotKey::Save)),
+                Key::Character(c) if c.as_str() == "n" && modifiers.command() => Some(Message::HotKey(HotKey::New)),
+                Key::Character(c) if unclaimed && c.as_str().eq_ignore_ascii_case("z") && modifiers.command() && modifiers.shift() => Some(Message::Redo),
+                Key::Character(c) if unclaimed && c.as_str() == "z" && modifiers.command() => Some(Message::Undo),
+                Key::Character(c) if unclaimed && c.as_str() == "y" && modifiers.command() => Some(Message::Redo),
                 _ => None,
             }
         }
         event::Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
         event::Event::Window(window::Event::Closed) => Some(Message::WindowClosed(id)),
-        event::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+        event::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(id, size)),
         //event::Event::Window(window::Event::Opened { position: _, size: _ }) => Some(Message::WindowMessage),
         _ => None,
     }
@@ -3038,6 +7420,8 @@ pub enum WindowEnum {
     #[default]
     MainWindow,
     SuperEdit,
+    // The item edit form on its own, working on the same draft as the main window
+    ItemEditor,
 }
 
 #[derive(Debug, Clone,)]
@@ -3054,3 +7438,115 @@ impl Window {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use entity_component::Entity;
+
+    // Saves go to a scratch folder per test, never the user's data file, and
+    // no backups are written
+    fn test_app(test: &str) -> MenuBuilder {
+        let dir = std::env::temp_dir().join(format!("menu_builder_app_{}", test));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut app = MenuBuilder::empty(FileManager::new().unwrap(), true);
+        app.settings.file_path = dir.join("menu_data.ron").to_string_lossy().into_owned();
+        app.settings.create_backups = false;
+        app
+    }
+
+    #[test]
+    fn editing_a_revenue_category_starts_from_that_category() {
+        let mut app = test_app("edit_revenue_category");
+        app.revenue_categories.insert(4, RevenueCategory::default_new().with_id(4).with_name("Beverage".to_string()));
+        app.report_categories.insert(4, ReportCategory::default_new().with_id(4).with_name("Bar Sales".to_string()));
+
+        let _ = app.perform(Operation::RevenueCategories(4, revenue_categories::Operation::EditRevenueCategory(4)));
+
+        assert_eq!(app.revenue_category_edit_state_vec.len(), 1);
+        assert_eq!(app.revenue_category_edit_state_vec[0].name, "Beverage");
+        assert_eq!(data_types::parse_entity_id(&app.revenue_category_edit_state_vec[0].id), Some(4));
+    }
+
+    fn key_press(key: Key, modifiers: Modifiers) -> event::Event {
+        event::Event::Keyboard(keyboard::Event::KeyPressed {
+            modified_key: key.clone(),
+            key,
+            physical_key: keyboard::key::Physical::Unidentified(keyboard::key::NativeCode::Unidentified),
+            location: keyboard::Location::Standard,
+            modifiers,
+            text: None,
+        })
+    }
+
+    #[test]
+    fn select_all_in_a_text_field_is_not_assign_all() {
+        let id = iced::window::Id::unique();
+        let ctrl_a = || key_press(Key::Character("a".into()), Modifiers::COMMAND);
+
+        assert!(matches!(
+            handle_event(ctrl_a(), event::Status::Ignored, id),
+            Some(Message::HotKey(HotKey::AssignAllFiltered))
+        ));
+        assert!(handle_event(ctrl_a(), event::Status::Captured, id).is_none());
+    }
+
+    #[test]
+    fn arrows_handled_by_a_widget_leave_the_selection() {
+        let id = iced::window::Id::unique();
+        let arrow = || key_press(Key::Named(keyboard::key::Named::ArrowDown), Modifiers::default());
+
+        assert!(matches!(
+            handle_event(arrow(), event::Status::Ignored, id),
+            Some(Message::HotKey(HotKey::Next))
+        ));
+        assert!(handle_event(arrow(), event::Status::Captured, id).is_none());
+    }
+
+    #[test]
+    fn saving_beside_an_unreadable_edit_state_id_does_not_panic() {
+        let mut app = test_app("save_unreadable_id");
+        let category = RevenueCategory::default_new().with_id(4).with_name("Beverage".to_string());
+        app.revenue_categories.insert(4, category.clone());
+
+        let renamed = entity_component::EditState { name: "Wine".to_string(), ..entity_component::EditState::new(&category) };
+        let unreadable = entity_component::EditState { id: "not a number".to_string(), ..entity_component::EditState::new(&category) };
+        app.revenue_category_edit_state_vec = vec![unreadable.clone(), renamed.clone()];
+
+        let _ = app.perform(Operation::RevenueCategories(4, revenue_categories::Operation::SaveAll(4, renamed)));
+
+        assert_eq!(app.revenue_categories[&4].name, "Wine");
+        assert!(app.revenue_category_edit_state_vec.is_empty());
+
+        // The only open edit has the bad id, so nothing matches and nothing changes
+        app.revenue_category_edit_state_vec = vec![unreadable.clone()];
+        let _ = app.perform(Operation::RevenueCategories(4, revenue_categories::Operation::SaveAll(4, unreadable)));
+
+        assert_eq!(app.revenue_categories[&4].name, "Wine");
+        assert!(app.revenue_category_edit_state_vec.is_empty());
+    }
+
+    #[test]
+    fn opening_a_file_keeps_load_repairs_unsaved() {
+        let mut app = test_app("open_repaired_file");
+        let dir = std::path::Path::new(&app.settings.file_path).parent().unwrap().to_path_buf();
+        let dangling = items::Item { tax_group: Some(9), ..items::Item::default_new().with_id(1).with_name("Soup".to_string()) };
+        app.items.insert(1, dangling);
+        app.settings.repair_on_load = true;
+
+        let repaired = dir.join("repaired.ron");
+        persistence::save_to_file(&app.app_state(), &repaired.to_string_lossy(), settings::SaveFormat::Ron).unwrap();
+        let _ = app.open_menu_file(&repaired);
+
+        assert_eq!(app.items[&1].tax_group, None);
+        assert!(app.unsaved_changes.get());
+
+        let clean = dir.join("clean.ron");
+        persistence::save_to_file(&app.app_state(), &clean.to_string_lossy(), settings::SaveFormat::Ron).unwrap();
+        let _ = app.open_menu_file(&clean);
+
+        assert!(!app.unsaved_changes.get());
+    }
+}
@@ -1,8 +1,13 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
 use chrono::Local;
 use directories::ProjectDirs;
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
 use crate::{
     items::Item, 
     item_groups::ItemGroup,
@@ -14,10 +19,21 @@ use crate::{
     report_categories::ReportCategory,
     choice_groups::ChoiceGroup,
     printer_logicals::PrinterLogical,
-    settings::AppSettings,
+    stores::Store,
+    settings::{AppSettings, SaveFormat},
+    data_types::{EntityId, EntityKind},
+    entity_component::Entity,
 };
 
-#[derive(Debug, Serialize, Deserialize)]
+// Screen and item selection at the last save, reopened on the next start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LastView {
+    // The entity list that was open, None for the items screen
+    pub list: Option<EntityKind>,
+    pub selected_item_id: Option<EntityId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppState {
     pub items: Vec<Item>,
     pub item_groups: Vec<ItemGroup>,
@@ -29,31 +45,267 @@ pub struct AppState {
     pub report_categories: Vec<ReportCategory>,
     pub choice_groups: Vec<ChoiceGroup>,
     pub printer_logicals: Vec<PrinterLogical>,
+    #[serde(default)]
+    pub stores: Vec<Store>,
     pub settings: AppSettings,
+    #[serde(default)]
+    pub last_view: LastView,
 }
 
-pub fn save_to_file(state: &AppState, path: &str) -> Result<(), String> {
-    let serialized = ron::ser::to_string_pretty(
-        state,
-        ron::ser::PrettyConfig::default(),
-    ).map_err(|e| format!("Failed to serialize state: {}", e))?;
+pub fn save_to_file(state: &AppState, path: &str, format: SaveFormat) -> Result<(), String> {
+    let serialized = match format {
+        SaveFormat::Ron => ron::ser::to_string_pretty(
+            state,
+            ron::ser::PrettyConfig::default(),
+        ).map_err(|e| format!("Failed to serialize state: {}", e))?,
+        SaveFormat::Json => serde_json::to_string_pretty(state)
+            .map_err(|e| format!("Failed to serialize state: {}", e))?,
+    };
+
+    // Written next to the menu and renamed over it, so a crash or full disk
+    // mid-write leaves the previous save untouched instead of a truncated file
+    let temp_path = format!("{}.tmp", path);
+    if let Err(e) = fs::write(&temp_path, serialized) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write file: {}", e));
+    }
 
-    fs::write(path, serialized)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to replace file: {}", e)
+    })?;
 
     Ok(())
 }
 
-pub fn load_from_file(path: &str) -> Result<AppState, String> {
+// Unknown struct fields are skipped by serde as long as no struct opts into
+// deny_unknown_fields. Unknown enum variants are not, so the enums that can
+// safely fall back (theme, level types, export formats) go through
+// `known_variant_or_default` and leave a warning here instead of failing the load.
+thread_local! {
+    static LOAD_WARNINGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+// Unit-only enums that may fall back to their default when a file names a variant we don't know
+pub trait KnownVariants: Sized + Copy + Default + fmt::Debug + 'static {
+    fn variants() -> &'static [Self];
+}
+
+pub fn known_variant_or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: KnownVariants,
+{
+    struct NameVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for NameVisitor {
+        type Value = String;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "an enum variant name")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<String, E> {
+            Ok(value.to_string())
+        }
+    }
+
+    let name = deserializer.deserialize_identifier(NameVisitor)?;
+
+    match T::variants().iter().find(|variant| format!("{:?}", variant) == name) {
+        Some(variant) => Ok(*variant),
+        None => {
+            let fallback = T::default();
+            LOAD_WARNINGS.with(|warnings| warnings.borrow_mut().push(format!(
+                "Unknown value '{}' for {}, using {:?}",
+                name,
+                std::any::type_name::<T>().rsplit("::").next().unwrap_or_default(),
+                fallback
+            )));
+            Ok(fallback)
+        }
+    }
+}
+
+// Loads the state along with any values that had to fall back to defaults
+pub fn load_from_file(path: &str) -> Result<(AppState, Vec<String>), String> {
     if !Path::new(path).exists() {
-        return Ok(AppState::default());
+        return Ok((AppState::default(), Vec::new()));
     }
 
     let content = fs::read_to_string(path)
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
-    ron::from_str(&content)
-        .map_err(|e| format!("Failed to parse file: {}", e))
+    // The format that looks right goes first, the others are tried after it
+    // so a file is never rejected for having the wrong look
+    let detected = detect_format(&content);
+    let formats = std::iter::once(detected)
+        .chain(SaveFormat::ALL.iter().copied().filter(|format| *format != detected));
+
+    let mut first_error = None;
+    for format in formats {
+        LOAD_WARNINGS.with(|warnings| warnings.borrow_mut().clear());
+
+        match parse_state(&content, format) {
+            Ok(state) => {
+                let warnings = LOAD_WARNINGS.with(|warnings| warnings.take());
+                return Ok((state, warnings));
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    Err(first_error.unwrap_or_else(|| "Failed to parse file".to_string()))
+}
+
+// Backups and versions carry the extension of whichever format they were written in
+fn has_save_extension(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| SaveFormat::ALL.iter().any(|format| ext == format.extension()))
+}
+
+// A JSON save is an object, a RON save is a bare struct in parentheses
+fn detect_format(content: &str) -> SaveFormat {
+    if content.trim_start().starts_with('{') {
+        SaveFormat::Json
+    } else {
+        SaveFormat::Ron
+    }
+}
+
+fn parse_state(content: &str, format: SaveFormat) -> Result<AppState, String> {
+    match format {
+        SaveFormat::Ron => ron::from_str(content)
+            .map_err(|e| format!("Failed to parse file: {}", e)),
+        SaveFormat::Json => serde_json::from_str(content)
+            .map_err(|e| format!("Failed to parse file as JSON: {}", e)),
+    }
+}
+
+// Ids used by more than one record in a collection. Building the maps keeps
+// only the last record with each id, so the others would be dropped silently.
+// Merging two saved menus by joining their files is the usual cause.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateIds {
+    pub label: &'static str,
+    pub ids: Vec<EntityId>,
+}
+
+impl DuplicateIds {
+    // e.g. "3 duplicate item ids found: 12, 18, 40"
+    pub fn message(&self) -> String {
+        format!(
+            "{} duplicate {} ids found: {}",
+            self.ids.len(),
+            self.label,
+            self.ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+        )
+    }
+}
+
+fn duplicates_in<T: Entity>(label: &'static str, records: &[T]) -> Option<DuplicateIds> {
+    let mut counts: BTreeMap<EntityId, usize> = BTreeMap::new();
+    for record in records {
+        *counts.entry(record.id()).or_default() += 1;
+    }
+
+    let ids: Vec<EntityId> = counts.into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(id, _)| id)
+        .collect();
+
+    (!ids.is_empty()).then_some(DuplicateIds { label, ids })
+}
+
+// Every collection with ids used more than once
+pub fn duplicate_ids(state: &AppState) -> Vec<DuplicateIds> {
+    [
+        duplicates_in("item", &state.items),
+        duplicates_in("item group", &state.item_groups),
+        duplicates_in("price level", &state.price_levels),
+        duplicates_in("product class", &state.product_classes),
+        duplicates_in("tax group", &state.tax_groups),
+        duplicates_in("security level", &state.security_levels),
+        duplicates_in("revenue category", &state.revenue_categories),
+        duplicates_in("report category", &state.report_categories),
+        duplicates_in("choice group", &state.choice_groups),
+        duplicates_in("printer logical", &state.printer_logicals),
+        duplicates_in("store", &state.stores),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+// The first record with an id keeps it, later ones get the next id after the
+// highest in the collection
+fn renumber_in<T: Entity>(records: &mut [T]) -> usize {
+    let mut next_id = records.iter().map(|record| record.id()).max().map_or(1, |max_id| max_id + 1);
+    let mut seen = BTreeSet::new();
+    let mut renumbered = 0;
+
+    for record in records.iter_mut() {
+        if !seen.insert(record.id()) {
+            *record = record.with_id(next_id);
+            next_id += 1;
+            renumbered += 1;
+        }
+    }
+
+    renumbered
+}
+
+// Gives every duplicate a free id so nothing is lost when the maps are built.
+// References to a duplicated id still point at the record that kept it.
+pub fn renumber_duplicates(state: &mut AppState) -> usize {
+    renumber_in(&mut state.items)
+        + renumber_in(&mut state.item_groups)
+        + renumber_in(&mut state.price_levels)
+        + renumber_in(&mut state.product_classes)
+        + renumber_in(&mut state.tax_groups)
+        + renumber_in(&mut state.security_levels)
+        + renumber_in(&mut state.revenue_categories)
+        + renumber_in(&mut state.report_categories)
+        + renumber_in(&mut state.choice_groups)
+        + renumber_in(&mut state.printer_logicals)
+        + renumber_in(&mut state.stores)
+}
+
+// Outcome of the startup load, carried back in a message. Cheap to clone, and
+// its Debug output is a summary since messages are logged and a state can
+// hold thousands of items.
+#[derive(Clone)]
+pub struct LoadResult(pub Arc<Result<(AppState, Vec<String>), String>>);
+
+impl fmt::Debug for LoadResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.as_ref() {
+            Ok((state, warnings)) => write!(f, "LoadResult({} items, {} warnings)", state.items.len(), warnings.len()),
+            Err(e) => write!(f, "LoadResult(Err({}))", e),
+        }
+    }
+}
+
+// Runs load_from_file on the executor, so the window can open while a large file is parsed
+pub async fn load_in_background(path: String) -> LoadResult {
+    LoadResult(Arc::new(load_from_file(&path)))
+}
+
+// Moves an unreadable data file out of the way so nothing is ever saved over it
+pub fn set_aside_unreadable(path: &Path) -> Result<PathBuf, String> {
+    let aside_name = format!(
+        "{}_unreadable_{}.ron",
+        path.file_stem().unwrap_or_default().to_string_lossy(),
+        Local::now().format("%Y%m%d_%H%M%S")
+    );
+    let aside_path = path.with_file_name(aside_name);
+
+    fs::rename(path, &aside_path)
+        .map_err(|e| format!("Failed to move unreadable file aside: {}", e))?;
+
+    Ok(aside_path)
 }
 
 impl Default for AppState {
@@ -69,7 +321,9 @@ impl Default for AppState {
             report_categories: Vec::new(),
             choice_groups: Vec::new(),
             printer_logicals: Vec::new(),
+            stores: Vec::new(),
             settings: AppSettings::default(),
+            last_view: LastView::default(),
         }
     }
 }
@@ -78,6 +332,41 @@ pub struct FileManager {
     project_dirs: ProjectDirs,
 }
 
+// Sizes of everything stored next to the menu file
+#[derive(Debug, Clone, Default)]
+pub struct StorageReport {
+    pub main_file: u64,
+    pub backups: Vec<(PathBuf, u64)>,
+    pub sidecars: Vec<(PathBuf, u64)>,
+}
+
+// A named snapshot of the whole state, stored under the versions folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionInfo {
+    pub name: String,
+    // When it was taken, as "2024-09-01 14:30"
+    pub created: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+// Separates the timestamp from the name in version file names
+const VERSION_SEPARATOR: &str = "__";
+
+impl StorageReport {
+    pub fn backups_size(&self) -> u64 {
+        self.backups.iter().map(|(_, size)| size).sum()
+    }
+
+    pub fn sidecars_size(&self) -> u64 {
+        self.sidecars.iter().map(|(_, size)| size).sum()
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.main_file + self.backups_size() + self.sidecars_size()
+    }
+}
+
 impl FileManager {
     pub fn new() -> Option<Self> {
         ProjectDirs::from("com", "MenuBuilder", "menu_builder").map(|dirs| Self { project_dirs: dirs })
@@ -96,10 +385,17 @@ impl FileManager {
             return Ok(());
         }
 
+        // Named after the format the file was last saved in, which may not be
+        // the one in settings if the format was just changed
+        let format = fs::read_to_string(path)
+            .map(|content| detect_format(&content))
+            .map_err(|e| format!("Failed to read file for backup: {}", e))?;
+
         let backup_name = format!(
-            "{}_backup_{}.ron",
+            "{}_backup_{}.{}",
             path.file_stem().unwrap().to_string_lossy(),
-            Local::now().format("%Y%m%d_%H%M%S")
+            Local::now().format("%Y%m%d_%H%M%S"),
+            format.extension()
         );
         
         let backup_path = path.with_file_name(backup_name);
@@ -109,6 +405,188 @@ impl FileManager {
         Ok(())
     }
 
+    // Backups are "{stem}_backup_{timestamp}.ron" or ".json", sidecars are leftovers
+    // named after the menu file such as "menu_data.ron.tmp"
+    pub fn storage_report(path: &Path) -> Result<StorageReport, String> {
+        let mut report = StorageReport::default();
+
+        if path.exists() {
+            report.main_file = fs::metadata(path)
+                .map_err(|e| format!("Failed to read file size: {}", e))?
+                .len();
+        }
+
+        let Some(dir) = path.parent() else {
+            return Ok(report);
+        };
+        if !dir.exists() {
+            return Ok(report);
+        }
+
+        let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let file_name = path.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let backup_prefix = format!("{}_backup_", stem);
+        let sidecar_prefix = format!("{}.", file_name);
+
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read data directory: {}", e))?;
+
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+
+            if !entry_path.is_file() {
+                continue;
+            }
+
+            if name.starts_with(&backup_prefix) && has_save_extension(&entry_path) {
+                report.backups.push((entry_path, size));
+            } else if name.starts_with(&sidecar_prefix) {
+                report.sidecars.push((entry_path, size));
+            }
+        }
+
+        // Timestamps in the names sort oldest first
+        report.backups.sort();
+        report.sidecars.sort();
+
+        Ok(report)
+    }
+
+    // Backups with when each was written, newest first
+    pub fn list_backups(path: &Path) -> Result<Vec<(PathBuf, SystemTime)>, String> {
+        let report = Self::storage_report(path)?;
+
+        Ok(report.backups
+            .into_iter()
+            .rev()
+            .map(|(backup, _)| {
+                let modified = fs::metadata(&backup)
+                    .and_then(|m| m.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                (backup, modified)
+            })
+            .collect())
+    }
+
+    // Deletes all but the newest `keep` backups, returns how many were removed
+    pub fn prune_backups(path: &Path, keep: usize) -> Result<usize, String> {
+        let report = Self::storage_report(path)?;
+        let excess = report.backups.len().saturating_sub(keep);
+
+        for (backup, _) in report.backups.iter().take(excess) {
+            fs::remove_file(backup)
+                .map_err(|e| format!("Failed to remove backup {}: {}", backup.display(), e))?;
+        }
+
+        Ok(excess)
+    }
+
+    // Deletes leftover sidecar files, returns how many were removed
+    pub fn delete_sidecars(path: &Path) -> Result<usize, String> {
+        let report = Self::storage_report(path)?;
+
+        for (sidecar, _) in &report.sidecars {
+            fs::remove_file(sidecar)
+                .map_err(|e| format!("Failed to remove {}: {}", sidecar.display(), e))?;
+        }
+
+        Ok(report.sidecars.len())
+    }
+
+    // Versions sit in a "versions" folder beside the menu file, one file per
+    // version named "{timestamp}__{name}.ron" (or .json), so listing them needs no index
+    pub fn versions_dir(path: &Path) -> PathBuf {
+        path.parent().unwrap_or(Path::new(".")).join("versions")
+    }
+
+    pub fn create_version(path: &Path, name: &str, state: &AppState) -> Result<VersionInfo, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Version name cannot be empty".to_string());
+        }
+
+        // Characters that can't go in a file name on some platform
+        let file_safe: String = name
+            .chars()
+            .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '-' } else { c })
+            .collect();
+
+        let dir = Self::versions_dir(path);
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create versions folder: {}", e))?;
+
+        let version_path = dir.join(format!(
+            "{}{}{}.{}",
+            Local::now().format("%Y%m%d_%H%M%S"),
+            VERSION_SEPARATOR,
+            file_safe,
+            state.settings.save_format.extension()
+        ));
+        if version_path.exists() {
+            return Err(format!("A version named {} was already taken this second", name));
+        }
+
+        save_to_file(state, &version_path.to_string_lossy(), state.settings.save_format)?;
+
+        Self::version_info(&version_path)
+            .ok_or_else(|| format!("Saved version could not be read back: {}", version_path.display()))
+    }
+
+    fn version_info(version_path: &Path) -> Option<VersionInfo> {
+        let stem = version_path.file_stem()?.to_string_lossy().into_owned();
+        let (timestamp, name) = stem.split_once(VERSION_SEPARATOR)?;
+        let created = chrono::NaiveDateTime::parse_from_str(timestamp, "%Y%m%d_%H%M%S").ok()?;
+        let size = fs::metadata(version_path).map(|m| m.len()).unwrap_or(0);
+
+        Some(VersionInfo {
+            name: name.to_string(),
+            created: created.format("%Y-%m-%d %H:%M").to_string(),
+            path: version_path.to_path_buf(),
+            size,
+        })
+    }
+
+    // Newest first. Files in the folder that aren't versions are ignored.
+    pub fn list_versions(path: &Path) -> Result<Vec<VersionInfo>, String> {
+        let dir = Self::versions_dir(path);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read versions folder: {}", e))?;
+
+        let mut versions: Vec<VersionInfo> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|entry| entry.is_file() && has_save_extension(entry))
+            .filter_map(|entry| Self::version_info(&entry))
+            .collect();
+
+        // Timestamps lead the file names, so reverse name order is newest first
+        versions.sort_by(|a, b| b.path.cmp(&a.path));
+
+        Ok(versions)
+    }
+
+    pub fn delete_version(version_path: &Path) -> Result<(), String> {
+        fs::remove_file(version_path)
+            .map_err(|e| format!("Failed to delete version {}: {}", version_path.display(), e))
+    }
+
+    // Deletes all but the newest `keep` versions, returns how many were removed
+    pub fn prune_versions(path: &Path, keep: usize) -> Result<usize, String> {
+        let versions = Self::list_versions(path)?;
+
+        for version in versions.iter().skip(keep) {
+            Self::delete_version(&version.path)?;
+        }
+
+        Ok(versions.len().saturating_sub(keep))
+    }
+
     pub fn validate_path(&self, path: &str) -> Result<(), String> {
         let path = Path::new(path);
         
@@ -130,4 +608,198 @@ impl FileManager {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh folder per test, so tests running in parallel don't see each other's files
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("menu_builder_persistence_{}", test));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn storage_report_sorts_backups_and_sidecars() {
+        let dir = scratch_dir("storage_report");
+        let menu = dir.join("menu_data.ron");
+        fs::write(&menu, "12345").unwrap();
+        fs::write(dir.join("menu_data_backup_20240102_000000.ron"), "12").unwrap();
+        fs::write(dir.join("menu_data_backup_20240101_000000.json"), "1").unwrap();
+        fs::write(dir.join("menu_data.ron.tmp"), "123").unwrap();
+        fs::write(dir.join("menu_data_backup_notes.txt"), "ignored").unwrap();
+        fs::write(dir.join("other.ron"), "ignored").unwrap();
+
+        let report = FileManager::storage_report(&menu).unwrap();
+
+        assert_eq!(report.main_file, 5);
+        assert_eq!(
+            report.backups,
+            vec![
+                (dir.join("menu_data_backup_20240101_000000.json"), 1),
+                (dir.join("menu_data_backup_20240102_000000.ron"), 2),
+            ]
+        );
+        assert_eq!(report.sidecars, vec![(dir.join("menu_data.ron.tmp"), 3)]);
+        assert_eq!(report.total_size(), 11);
+    }
+
+    #[test]
+    fn prune_backups_keeps_the_newest() {
+        let dir = scratch_dir("prune_backups");
+        let menu = dir.join("menu_data.ron");
+        for day in 1..=4 {
+            fs::write(dir.join(format!("menu_data_backup_2024010{}_000000.ron", day)), "").unwrap();
+        }
+
+        assert_eq!(FileManager::prune_backups(&menu, 2), Ok(2));
+
+        let remaining: Vec<PathBuf> = FileManager::storage_report(&menu).unwrap()
+            .backups
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![
+                dir.join("menu_data_backup_20240103_000000.ron"),
+                dir.join("menu_data_backup_20240104_000000.ron"),
+            ]
+        );
+        assert_eq!(FileManager::prune_backups(&menu, 5), Ok(0));
+    }
+
+    #[derive(Deserialize)]
+    struct LevelHolder {
+        #[serde(deserialize_with = "known_variant_or_default")]
+        level_type: crate::price_levels::PriceLevelType,
+    }
+
+    fn take_warnings() -> Vec<String> {
+        LOAD_WARNINGS.with(|warnings| warnings.take())
+    }
+
+    #[test]
+    fn known_variants_load_in_either_format() {
+        take_warnings();
+
+        let from_ron: LevelHolder = ron::from_str("(level_type: Store)").unwrap();
+        let from_json: LevelHolder = serde_json::from_str(r#"{"level_type": "Store"}"#).unwrap();
+
+        assert_eq!(from_ron.level_type, crate::price_levels::PriceLevelType::Store);
+        assert_eq!(from_json.level_type, crate::price_levels::PriceLevelType::Store);
+        assert!(take_warnings().is_empty());
+    }
+
+    #[test]
+    fn unknown_variants_fall_back_with_a_warning() {
+        take_warnings();
+
+        let holder: LevelHolder = ron::from_str("(level_type: Regional)").unwrap();
+
+        assert_eq!(holder.level_type, crate::price_levels::PriceLevelType::default());
+        assert_eq!(
+            take_warnings(),
+            vec!["Unknown value 'Regional' for PriceLevelType, using Enterprise".to_string()]
+        );
+    }
+
+    #[test]
+    fn unreadable_files_are_moved_aside() {
+        let dir = scratch_dir("set_aside");
+        let menu = dir.join("menu_data.ron");
+        fs::write(&menu, "not a menu").unwrap();
+
+        let aside = set_aside_unreadable(&menu).unwrap();
+
+        assert!(!menu.exists());
+        assert_eq!(fs::read_to_string(&aside).unwrap(), "not a menu");
+        assert!(aside.file_name().unwrap().to_string_lossy().starts_with("menu_data_unreadable_"));
+    }
+
+    #[test]
+    fn versions_are_saved_under_a_file_safe_name() {
+        let dir = scratch_dir("create_version");
+        let menu = dir.join("menu_data.ron");
+
+        assert!(FileManager::create_version(&menu, "   ", &AppState::default()).is_err());
+
+        let version = FileManager::create_version(&menu, " Before 5/1 launch ", &AppState::default()).unwrap();
+
+        assert_eq!(version.name, "Before 5-1 launch");
+        assert_eq!(version.path.parent(), Some(FileManager::versions_dir(&menu).as_path()));
+        assert_eq!(version.path.extension().and_then(|ext| ext.to_str()), Some("ron"));
+        assert_eq!(FileManager::list_versions(&menu), Ok(vec![version.clone()]));
+        assert!(load_from_file(&version.path.to_string_lossy()).is_ok());
+    }
+
+    #[test]
+    fn versions_list_newest_first_and_prune_the_oldest() {
+        let dir = scratch_dir("prune_versions");
+        let menu = dir.join("menu_data.ron");
+        let versions = FileManager::versions_dir(&menu);
+        fs::create_dir_all(&versions).unwrap();
+        fs::write(versions.join("20240101_090000__Opening.ron"), "").unwrap();
+        fs::write(versions.join("20240301_090000__Spring.json"), "").unwrap();
+        fs::write(versions.join("20240201_090000__Winter.ron"), "").unwrap();
+        fs::write(versions.join("notes.txt"), "").unwrap();
+        fs::write(versions.join("no_separator.ron"), "").unwrap();
+
+        let names = |listed: Vec<VersionInfo>| listed.into_iter().map(|version| version.name).collect::<Vec<_>>();
+
+        let listed = FileManager::list_versions(&menu).unwrap();
+        assert_eq!(listed[0].created, "2024-03-01 09:00");
+        assert_eq!(names(listed), vec!["Spring", "Winter", "Opening"]);
+
+        assert_eq!(FileManager::prune_versions(&menu, 2), Ok(1));
+        assert_eq!(names(FileManager::list_versions(&menu).unwrap()), vec!["Spring", "Winter"]);
+    }
+
+    fn menu_named(name: &str) -> AppState {
+        AppState {
+            items: vec![Item { id: 1, name: name.to_string(), ..Item::default() }],
+            ..AppState::default()
+        }
+    }
+
+    #[test]
+    fn save_replaces_the_menu_and_leaves_no_temp_file() {
+        let dir = scratch_dir("atomic_save");
+        let path = dir.join("menu_data.ron").to_string_lossy().into_owned();
+
+        save_to_file(&menu_named("Burger"), &path, SaveFormat::Ron).unwrap();
+        save_to_file(&menu_named("Fries"), &path, SaveFormat::Ron).unwrap();
+
+        let (loaded, warnings) = load_from_file(&path).unwrap();
+        assert_eq!(loaded.items[0].name, "Fries");
+        assert!(warnings.is_empty());
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+    }
+
+    #[test]
+    fn failed_save_keeps_the_previous_menu() {
+        let dir = scratch_dir("failed_save");
+        let path = dir.join("menu_data.ron").to_string_lossy().into_owned();
+        save_to_file(&menu_named("Burger"), &path, SaveFormat::Ron).unwrap();
+
+        // A folder where the temp file goes makes the write fail
+        fs::create_dir(format!("{}.tmp", path)).unwrap();
+
+        assert!(save_to_file(&menu_named("Fries"), &path, SaveFormat::Ron).is_err());
+        assert_eq!(load_from_file(&path).unwrap().0.items[0].name, "Burger");
+    }
+
+    #[test]
+    fn json_saved_under_a_ron_name_still_loads() {
+        let dir = scratch_dir("json_as_ron");
+        let path = dir.join("menu_data.ron").to_string_lossy().into_owned();
+
+        save_to_file(&menu_named("Burger"), &path, SaveFormat::Json).unwrap();
+
+        assert_eq!(detect_format(&fs::read_to_string(&path).unwrap()), SaveFormat::Json);
+        assert_eq!(load_from_file(&path).unwrap().0.items[0].name, "Burger");
+    }
+}
@@ -1,11 +1,15 @@
-use crate::data_types::{ EntityId, ValidationError, Currency };
+use crate::accent;
+use crate::data_types::{self, EntityId, ValidationError, Currency, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState as BaseEditState};
+use crate::entity_component::{self, Entity, EditState as BaseEditState, ListSort};
 use crate::icon;
+use crate::items::Item;
+use crate::stores::Store;
 use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
 use iced::{Element, Length};
-use iced::widget::{button, row, column, container, text, text_input, scrollable, tooltip};
+use iced::widget::{button, checkbox, row, column, container, text, text_input, scrollable, tooltip, pick_list};
 use std::collections::BTreeMap;
 use rust_decimal::Decimal;
 
@@ -16,8 +20,13 @@ pub enum Message {
     EditPriceLevel(EntityId),
     SaveAll(EntityId, PriceLevelEditState),
     UpdateName(EntityId, String),
+    UpdatePrice(EntityId, String),
+    SelectLevelType(EntityId, PriceLevelType),
+    SelectStore(EntityId, Option<EntityId>),
     CreateNew,
     CancelEdit(EntityId),
+    ToggleUnusedOnly(bool),
+    ShowItems(EntityId, bool),
 }
 
 #[derive(Debug, Clone)]
@@ -27,16 +36,61 @@ pub enum Operation {
     EditPriceLevel(EntityId),
     SaveAll(EntityId, PriceLevelEditState),
     UpdateName(EntityId, String),
+    UpdatePrice(EntityId, String),
+    SelectLevelType(EntityId, PriceLevelType),
+    SelectStore(EntityId, Option<EntityId>),
     CreateNew,
     CancelEdit(EntityId),
+    ToggleUnusedOnly(bool),
+    ShowItems(EntityId, bool),
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+// Store price levels are numbered by the POS from their own, wider range
+pub const STORE_LEVEL_ID_RANGE: RangeInclusive<EntityId> = 1..=99999;
+
+// List display options for the price level screen
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    pub unused_only: bool,
+}
+
+// How items use a price level: with an explicit price, or only assigned to it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelUsage {
+    pub priced: usize,
+    pub assigned_only: usize,
+}
+
+impl LevelUsage {
+    pub fn is_unused(&self) -> bool {
+        self.priced == 0 && self.assigned_only == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PriceLevelType {
     Enterprise,
     Store
 }
 
+impl PriceLevelType {
+    pub const ALL: &'static [Self] = &[Self::Enterprise, Self::Store];
+
+    // Ids a level of this type may use, enterprise levels follow the price level setting
+    pub fn id_range(&self, enterprise_range: &RangeInclusive<EntityId>) -> RangeInclusive<EntityId> {
+        match self {
+            Self::Enterprise => enterprise_range.clone(),
+            Self::Store => STORE_LEVEL_ID_RANGE,
+        }
+    }
+}
+
+impl crate::persistence::KnownVariants for PriceLevelType {
+    fn variants() -> &'static [Self] {
+        Self::ALL
+    }
+}
+
 impl Default for PriceLevelType {
     fn default() -> Self {
         Self::Enterprise
@@ -58,7 +112,10 @@ pub struct PriceLevelEditState {
     pub price: String,
     pub original_price: String,
     pub level_type: PriceLevelType,
+    pub store_id: Option<EntityId>,
     pub range_validation_error: Option<String>,
+    pub price_validation_error: Option<String>,
+    pub store_validation_error: Option<String>,
 }
 
 impl PriceLevelEditState {
@@ -68,7 +125,10 @@ impl PriceLevelEditState {
             price: price_level.price.to_string(),
             original_price: price_level.price.to_string(),
             level_type: price_level.level_type.clone(),
+            store_id: price_level.store_id,
             range_validation_error: None,
+            price_validation_error: None,
+            store_validation_error: None,
         }
     }
 
@@ -76,10 +136,13 @@ impl PriceLevelEditState {
         self.base.reset();
         self.price = self.original_price.clone();
         self.level_type = PriceLevelType::default();
+        self.store_id = None;
         self.range_validation_error = None;
+        self.price_validation_error = None;
+        self.store_validation_error = None;
     }
  
-    pub fn validate(&self) -> Result<(), ValidationError> {
+    pub fn validate(&self, id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
         if self.base.name.trim().is_empty() {
             return Err(ValidationError::EmptyName(
                 "Price level name cannot be empty".to_string()
@@ -87,25 +150,35 @@ impl PriceLevelEditState {
         }
 
         if let Ok(id) = self.base.id.parse::<EntityId>() {
-            if !(1..=999).contains(&id) {
-                return Err(ValidationError::InvalidId(
-                    "Price level ID must be between 1 and 999".to_string()
-                ));
-            }
+            data_types::check_id_range(EntityKind::PriceLevel, id, &self.level_type.id_range(id_range))?;
         } else {
             return Err(ValidationError::InvalidId(
                 "Invalid ID format".to_string()
             ));
         }
 
-        if let Err(_) = self.price.parse::<Decimal>() {
+        // A store level with no store would never apply anywhere
+        if self.level_type == PriceLevelType::Store && self.store_id.is_none() {
             return Err(ValidationError::InvalidValue(
-                "Invalid price format".to_string()
+                "Store price levels need a store".to_string()
             ));
         }
 
+        Self::validate_price(&self.price)?;
+
         Ok(())
     }
+
+    pub fn validate_price(price: &str) -> Result<Decimal, ValidationError> {
+        let price = data_types::string_to_decimal(price.trim())
+            .map_err(|_| ValidationError::InvalidPrice("Price must be a number, e.g. 4.50".to_string()))?;
+
+        if price.is_sign_negative() {
+            return Err(ValidationError::InvalidPrice("Price cannot be negative".to_string()));
+        }
+
+        Ok(price)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -113,7 +186,13 @@ pub struct PriceLevel {
     pub id: EntityId,
     pub name: String,
     pub price: Currency,
+    #[serde(deserialize_with = "crate::persistence::known_variant_or_default")]
     pub level_type: PriceLevelType,
+    // Only meaningful for store price levels
+    #[serde(default)]
+    pub store_id: Option<EntityId>,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for PriceLevel {
@@ -129,6 +208,8 @@ impl Default for PriceLevel {
             name: String::new(),
             price: Decimal::ZERO,
             level_type: PriceLevelType::default(),
+            store_id: None,
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -155,7 +236,14 @@ impl Entity for PriceLevel {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
     }
 }
 
@@ -164,12 +252,16 @@ impl PriceLevel {
         Self::default()
     }
 
-    fn validate(&self, other_groups: &[&PriceLevel]) -> Result<(), ValidationError> {
-        if !(1..=999).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Item group ID must be between 1 and 999".to_string()
-            ));
+    // The store this level prices for, enterprise levels have none
+    pub fn store(&self) -> Option<EntityId> {
+        match self.level_type {
+            PriceLevelType::Store => self.store_id,
+            PriceLevelType::Enterprise => None,
         }
+    }
+
+    pub fn validate(&self, other_groups: &[&PriceLevel], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::PriceLevel, self.id, id_range)?;
  
         // Check for duplicate IDs
         for other in other_groups {
@@ -190,6 +282,122 @@ impl PriceLevel {
     }
 }
 
+// Result of moving item prices from one price level to another
+#[derive(Debug, Clone, Default)]
+pub struct PriceMigration {
+    pub moved: Vec<EntityId>,
+    // Items that already have a price at the target level, left untouched
+    pub skipped: Vec<EntityId>,
+}
+
+// Works out which items would move and which conflict, without changing anything
+pub fn plan_price_migration(
+    items: &BTreeMap<EntityId, Item>,
+    from: EntityId,
+    to: EntityId,
+) -> PriceMigration {
+    let mut migration = PriceMigration::default();
+
+    for item in items.values() {
+        if !item.has_price_at(from) {
+            continue;
+        }
+
+        if item.has_price_at(to) {
+            migration.skipped.push(item.id);
+        } else {
+            migration.moved.push(item.id);
+        }
+    }
+
+    migration
+}
+
+// Moves prices at `from` onto `to` for every item that has no price at `to` yet.
+// Conflicting items keep their existing price at `to`, the `from` price is left
+// in place for the caller to remove along with the level.
+pub fn migrate_item_prices(
+    items: &mut BTreeMap<EntityId, Item>,
+    from: EntityId,
+    to: EntityId,
+) -> PriceMigration {
+    let migration = plan_price_migration(items, from, to);
+
+    for id in &migration.moved {
+        if let Some(item) = items.get_mut(id) {
+            if let Some(prices) = &mut item.item_prices {
+                for price in prices.iter_mut().filter(|p| p.price_level_id == from) {
+                    price.price_level_id = to;
+                }
+            }
+
+            if let Some(levels) = &mut item.price_levels {
+                levels.retain(|&level_id| level_id != from);
+                if !levels.contains(&to) {
+                    levels.push(to);
+                }
+            } else {
+                item.price_levels = Some(vec![to]);
+            }
+        }
+    }
+
+    migration
+}
+
+// Usage of every level, levels no item touches are included with zero counts.
+// Prices count even when the level is missing from the item's assignment list.
+pub fn usage_counts(
+    price_levels: &BTreeMap<EntityId, PriceLevel>,
+    items: &BTreeMap<EntityId, Item>,
+) -> BTreeMap<EntityId, LevelUsage> {
+    let mut counts: BTreeMap<EntityId, LevelUsage> = price_levels
+        .keys()
+        .map(|id| (*id, LevelUsage::default()))
+        .collect();
+
+    for item in items.values() {
+        if let Some(prices) = &item.item_prices {
+            for price in prices {
+                counts.entry(price.price_level_id).or_default().priced += 1;
+            }
+        }
+
+        if let Some(levels) = &item.price_levels {
+            for level_id in levels.iter().filter(|id| !item.has_price_at(**id)) {
+                counts.entry(*level_id).or_default().assigned_only += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+// Copy of the items as one store sees them: enterprise prices plus that
+// store's own levels, with every other store's prices dropped
+pub fn restrict_to_store(
+    items: &BTreeMap<EntityId, Item>,
+    price_levels: &BTreeMap<EntityId, PriceLevel>,
+    store_id: EntityId,
+) -> BTreeMap<EntityId, Item> {
+    let excluded: Vec<EntityId> = price_levels
+        .values()
+        .filter(|level| level.level_type == PriceLevelType::Store && level.store_id != Some(store_id))
+        .map(|level| level.id)
+        .collect();
+
+    items
+        .iter()
+        .map(|(id, item)| {
+            let mut item = item.clone();
+            for level_id in &excluded {
+                item.remove_price_level(*level_id);
+            }
+            (*id, item)
+        })
+        .collect()
+}
+
 pub fn update(
     message: Message,
 ) -> Action<Operation, Message> {
@@ -212,6 +420,21 @@ pub fn update(
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
         }
+        Message::UpdatePrice(id, price) => {
+            Action::operation(Operation::UpdatePrice(id, price))
+        }
+        Message::SelectLevelType(id, level_type) => {
+            Action::operation(Operation::SelectLevelType(id, level_type))
+        }
+        Message::SelectStore(id, store_id) => {
+            Action::operation(Operation::SelectStore(id, store_id))
+        }
+        Message::ToggleUnusedOnly(enabled) => {
+            Action::operation(Operation::ToggleUnusedOnly(enabled))
+        }
+        Message::ShowItems(id, unpriced) => {
+            Action::operation(Operation::ShowItems(id, unpriced))
+        }
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
@@ -221,39 +444,80 @@ pub fn update(
 pub fn view<'a>(
     all_prices: &'a BTreeMap<EntityId, PriceLevel>,
     edit_states: &'a Vec<PriceLevelEditState>,
+    stores: &'a BTreeMap<EntityId, Store>,
+    usage: BTreeMap<EntityId, LevelUsage>,
+    options: &'a ListOptions,
+    search: &str,
+    sort: ListSort,
 ) -> Element<'a, Message> {
     let title_row = entity_component::render_title_row(
         "Price Levels", 
         Message::CreateNew,
-        505.0 // view width
+        1150.0 // view width
     );
 
+    let options_row = row![
+        checkbox("Unused only", options.unused_only)
+            .on_toggle(Message::ToggleUnusedOnly)
+            .style(Modern::checkbox()),
+    ]
+    .spacing(15)
+    .padding([0, 15])
+    .align_y(iced::Alignment::Center);
+
+    let usage_of = |id: &EntityId| usage.get(id).copied().unwrap_or_default();
+
     // Custom header row for columns including range fields
     let header_row = row![
         text("ID").width(Length::Fixed(75.0)),
         text("Name").width(Length::Fixed(250.0)),
+        text("Price").width(Length::Fixed(105.0)),
+        text("Type").width(Length::Fixed(200.0)),
+        text("Store").width(Length::Fixed(160.0)),
         text("Actions").width(Length::Fixed(150.0)),
+        text("Priced").width(Length::Fixed(80.0)),
+        text("Unpriced").width(Length::Fixed(80.0)),
     ]
     .padding(15);
 
+    let mut levels: Vec<&PriceLevel> = all_prices
+        .values()
+        .filter(|level| !options.unused_only || usage_of(&level.id).is_unused())
+        .filter(|level| entity_component::matches_search(*level, search, edit_states.iter().map(|state| &state.base)))
+        .collect();
+    sort.apply(&mut levels);
+
     // List of price levels
     let price_list = scrollable(
         column(
-            all_prices
-                .values()
-                .map(|group| 
+            levels
+                .into_iter()
+                .map(|group| {
+                    let level_usage = usage_of(&group.id);
+
+                    // Counts open the items list filtered to those items
                     row![
-                        render_price_level_row(group, edit_states)
+                        render_price_level_row(group, edit_states, stores),
+                        iced::widget::horizontal_space().width(10),
+                        button(text(level_usage.priced.to_string()))
+                            .on_press(Message::ShowItems(group.id, false))
+                            .style(Modern::gray_button())
+                            .width(Length::Fixed(80.0)),
+                        button(text(level_usage.assigned_only.to_string()))
+                            .on_press(Message::ShowItems(group.id, true))
+                            .style(Modern::gray_button())
+                            .width(Length::Fixed(80.0)),
                     ]
+                    .align_y(iced::Alignment::Center)
                     .padding(5)
                     .into()
-                )
+                })
                 .collect::<Vec<_>>()
         )
     ).height(Length::Fill);
 
     // Combine all elements
-    let all_content = column![title_row, header_row, price_list];
+    let all_content = column![title_row, options_row, header_row, price_list];
 
     column![
         container(all_content)
@@ -265,11 +529,12 @@ pub fn view<'a>(
 
 fn render_price_level_row<'a>(
     price_level: &'a PriceLevel,
-    edit_states: &'a Vec<PriceLevelEditState>
+    edit_states: &'a Vec<PriceLevelEditState>,
+    stores: &'a BTreeMap<EntityId, Store>,
 ) -> Element<'a, Message> {
     // Find edit state for this price_level if it exists
     let edit_state = edit_states.iter()
-        .find(|state| state.base.id.parse::<i32>().unwrap_or(-999) == price_level.id);
+        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(price_level.id));
 
     let editing = edit_state.is_some();
 
@@ -302,9 +567,13 @@ fn render_price_level_row<'a>(
         }
     };
 
+    let name_warning = edit_state
+        .and_then(|state| state.base.name_warning.as_ref());
+
     // Name input with validation
     let name_input: Element<'_, Message> = {
         let input = text_input("Price Level Name", &display_name)
+            .id(entity_component::name_input_id(price_level.id))
             .on_input_maybe(
                 if editing {
                     Some(|name| Message::UpdateName(price_level.id, name))
@@ -321,9 +590,97 @@ fn render_price_level_row<'a>(
                 container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
                 tooltip::Position::Top,
             ).into()
+        } else if let Some(warning) = name_warning {
+            column![
+                input,
+                text(warning).size(11).style(entity_component::warning_text),
+            ]
+            .into()
+        } else {
+            input.into()
+        }
+    };
+
+    let price_validation_error = edit_state
+        .and_then(|state| state.price_validation_error.as_ref());
+
+    let display_price = edit_state
+        .map(|state| state.price.clone())
+        .unwrap_or_else(|| price_level.price.to_string());
+
+    let price_input: Element<'_, Message> = {
+        let input = text_input("0.00", &display_price)
+            .on_input_maybe(
+                if editing {
+                    Some(|price| Message::UpdatePrice(price_level.id, price))
+                } else {
+                    None
+                }
+            )
+            .style(Modern::validated_text_input(price_validation_error.is_some()))
+            .width(Length::Fixed(100.0));
+
+        if let Some(error) = price_validation_error {
+            tooltip(
+                input,
+                container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
+                tooltip::Position::Top,
+            ).into()
+        } else {
+            input.into()
+        }
+    };
+
+    let level_type = edit_state
+        .map(|state| state.level_type)
+        .unwrap_or(price_level.level_type);
+
+    let store_id = edit_state
+        .map(|state| state.store_id)
+        .unwrap_or(price_level.store_id);
+
+    let type_input: Element<'_, Message> = if editing {
+        pick_list(
+            PriceLevelType::ALL,
+            Some(level_type),
+            |level_type| Message::SelectLevelType(price_level.id, level_type)
+        )
+        .style(Modern::pick_list())
+        .width(Length::Fixed(200.0))
+        .into()
+    } else {
+        text(level_type.to_string()).width(Length::Fixed(200.0)).into()
+    };
+
+    let store_validation_error = edit_state
+        .and_then(|state| state.store_validation_error.as_ref());
+
+    // Enterprise levels apply everywhere, so only store levels pick a store
+    let store_input: Element<'_, Message> = if editing && level_type == PriceLevelType::Store {
+        let input = pick_list(
+            stores.values().cloned().collect::<Vec<_>>(),
+            store_id.and_then(|id| stores.get(&id).cloned()),
+            |store: Store| Message::SelectStore(price_level.id, Some(store.id))
+        )
+        .placeholder("Select store")
+        .style(Modern::pick_list())
+        .width(Length::Fixed(160.0));
+
+        if let Some(error) = store_validation_error {
+            tooltip(
+                input,
+                container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
+                tooltip::Position::Top,
+            ).into()
         } else {
             input.into()
         }
+    } else {
+        let store_name = store_id
+            .and_then(|id| stores.get(&id))
+            .map(|store| store.name.clone())
+            .unwrap_or_default();
+        text(store_name).width(Length::Fixed(160.0)).into()
     };
 
     // Action buttons
@@ -336,21 +693,20 @@ fn render_price_level_row<'a>(
                     Message::EditPriceLevel(price_level.id) 
                 }
             )
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
         iced::widget::horizontal_space().width(2),
         button(icon::copy().size(14))
             .on_press(Message::CopyPriceLevel(price_level.id))
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
         iced::widget::horizontal_space().width(2),
-        button(if editing { icon::cancel().size(14) } else { icon::trash().size(14) })
-            .on_press(
-                if editing { 
-                    Message::CancelEdit(price_level.id) 
-                } else { 
-                    Message::RequestDelete(price_level.id) 
-                }
-            )
-            .style(Modern::danger_button()),
+        if editing {
+            button(icon::cancel().size(14))
+                .on_press(Message::CancelEdit(price_level.id))
+                .style(Modern::danger_button())
+                .into()
+        } else {
+            entity_component::delete_button(Message::RequestDelete(price_level.id))
+        },
     ].width(150);
 
     // Combine all elements
@@ -359,9 +715,62 @@ fn render_price_level_row<'a>(
         id_input,
         name_input,
         iced::widget::horizontal_space().width(5),
+        price_input,
+        iced::widget::horizontal_space().width(5),
+        type_input,
+        iced::widget::horizontal_space().width(5),
+        store_input,
+        iced::widget::horizontal_space().width(5),
         action_row,
     ]
     .align_y(iced::Alignment::Center)
-    .width(Length::Fixed(495.0))
+    .width(Length::Fixed(960.0))
     .into()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::ItemPrice;
+    use rust_decimal::Decimal;
+
+    fn priced_item(id: EntityId, levels: &[EntityId]) -> Item {
+        Item {
+            id,
+            price_levels: Some(levels.to_vec()),
+            item_prices: Some(
+                levels
+                    .iter()
+                    .map(|&level| ItemPrice { price_level_id: level, price: Decimal::new(id as i64 * 100, 2) })
+                    .collect()
+            ),
+            ..Item::default()
+        }
+    }
+
+    #[test]
+    fn plan_skips_items_already_priced_at_the_target() {
+        let items = BTreeMap::from([
+            (1, priced_item(1, &[10])),
+            (2, priced_item(2, &[10, 20])),
+            (3, priced_item(3, &[30])),
+        ]);
+
+        let plan = plan_price_migration(&items, 10, 20);
+
+        assert_eq!(plan.moved, vec![1]);
+        assert_eq!(plan.skipped, vec![2]);
+    }
+
+    #[test]
+    fn migration_moves_the_price_and_the_assignment() {
+        let mut items = BTreeMap::from([(1, priced_item(1, &[10])), (2, priced_item(2, &[10, 20]))]);
+
+        migrate_item_prices(&mut items, 10, 20);
+
+        assert_eq!(items[&1].price_levels, Some(vec![20]));
+        assert!(items[&1].has_price_at(20) && !items[&1].has_price_at(10));
+        // The conflicting item keeps both, the caller drops the old level
+        assert_eq!(items[&2], priced_item(2, &[10, 20]));
+    }
+}
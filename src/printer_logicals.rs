@@ -1,36 +1,52 @@
-use crate::data_types::{EntityId, ValidationError};
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState};
+use crate::accent;
+use crate::entity_component::{self, Entity, EditState, ListSort};
 use serde::{Serialize, Deserialize};
-use iced::Element;
+use crate::items::Item;
+use iced::{Alignment, Element};
+use iced::widget::{button, column, container, row, text};
+use iced_modern_theme::Modern;
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyPrinterLogical(EntityId),
     EditPrinterLogical(EntityId),
     Save(EntityId, EditState),
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ShowRouting(Option<EntityId>),
 }
 
 #[derive(Debug, Clone)]
 pub enum Operation {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyPrinterLogical(EntityId),
     EditPrinterLogical(EntityId),
     Save(EntityId, EditState),
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ShowRouting(Option<EntityId>),
 }
 
+// Longest name the POS printer hardware accepts
+pub const MAX_NAME_LENGTH: usize = 16;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PrinterLogical {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for PrinterLogical {
@@ -44,6 +60,7 @@ impl Default for PrinterLogical {
         Self {
             id: -1,
             name: String::new(),
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -70,7 +87,14 @@ impl Entity for PrinterLogical {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
     }
 }
 
@@ -79,13 +103,8 @@ impl PrinterLogical {
         Self::default()
     }
 
-    fn validate(&self, other_printers: &[&PrinterLogical]) -> Result<(), ValidationError> {
-        // Validate ID range (0-25 based on your screenshot)
-        if !(0..=25).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Printer Logical ID must be between 0 and 25".to_string()
-            ));
-        }
+    pub fn validate(&self, other_printers: &[&PrinterLogical], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::PrinterLogical, self.id, id_range)?;
 
         // Check for duplicate IDs
         for other in other_printers {
@@ -103,21 +122,39 @@ impl PrinterLogical {
             ));
         }
 
-        // Validate name is not more than 16 Characters
-        if self.name.len() > 16 {
+        Self::validate_name(&self.name)
+    }
+
+    // The length limit on its own, checked as the name is typed and again on save
+    pub fn validate_name(name: &str) -> Result<(), ValidationError> {
+        if name.chars().count() > MAX_NAME_LENGTH {
             return Err(ValidationError::NameTooLong(
-                "Printer Logical name cannot be more than 16 Characters".to_string()
+                format!("Printer Logical name cannot be more than {} characters", MAX_NAME_LENGTH)
             ));
         }
 
         Ok(())
     }
+
+    // Name for a copy, "(id)" on the end with the original cut short to stay within the limit
+    pub fn copy_name(name: &str, id: EntityId) -> String {
+        let suffix = format!("({})", id);
+        let keep = MAX_NAME_LENGTH.saturating_sub(suffix.chars().count());
+
+        name.chars().take(keep).collect::<String>() + &suffix
+    }
 }
 
 pub fn update(
     message: Message,
 ) -> Action<Operation, Message> {
     match message {
+        Message::ExportRecord(id) => {
+            Action::operation(Operation::ExportRecord(id))
+        },
+        Message::CopyJson(id) => {
+            Action::operation(Operation::CopyJson(id))
+        },
         Message::RequestDelete(id) => {
             Action::operation(Operation::RequestDelete(id))
         },
@@ -139,20 +176,114 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::ShowRouting(id) => {
+            Action::operation(Operation::ShowRouting(id))
+        }
     }
 }
 
+// Items that print to the given printer, with whether it is their primary printer
+pub fn routed_items(
+    printer_id: EntityId,
+    items: &BTreeMap<EntityId, Item>,
+) -> Vec<(&Item, bool)> {
+    items
+        .values()
+        .filter_map(|item| {
+            item.printer_logicals
+                .as_ref()?
+                .iter()
+                .find(|(id, _)| *id == printer_id)
+                .map(|(_, primary)| (item, *primary))
+        })
+        .collect()
+}
+
 pub fn view<'a>(
     all_printers: &'a BTreeMap<EntityId, PrinterLogical>,
     edit_states: &'a Vec<EditState>,
+    search: &str,
+    sort: ListSort,
+    items: &'a BTreeMap<EntityId, Item>,
+    routing: Option<EntityId>,
 ) -> Element<'a, Message> {
-    entity_component::entity_view(
+    let list = entity_component::entity_view(
         "Printer Logicals",
         Message::CreateNew,
         all_printers,
         edit_states,
-        |printer, edit_states| render_printer_row(printer, edit_states),
-    )
+        search,
+        sort,
+        move |printer, edit_states| {
+            let count = routed_items(printer.id, items).len();
+            let showing = routing == Some(printer.id);
+
+            // Clicking the count opens the routing panel for this printer, clicking again closes it
+            let badge = button(text(format!("{} items", count)).size(12))
+                .on_press(Message::ShowRouting((!showing).then_some(printer.id)));
+            let badge = if showing {
+                badge.style(accent::primary_button())
+            } else {
+                badge.style(Modern::gray_button())
+            };
+
+            row![render_printer_row(printer, edit_states), badge]
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .into()
+        },
+    );
+
+    let routing_panel: Element<'a, Message> = match routing.and_then(|id| all_printers.get(&id)) {
+        Some(printer) => {
+            let routed = routed_items(printer.id, items);
+
+            container(
+                column![
+                    row![
+                        text(format!("Items printing to {} ({})", printer.name, printer.id))
+                            .style(Modern::primary_text()),
+                        iced::widget::horizontal_space(),
+                        button("Close")
+                            .on_press(Message::ShowRouting(None))
+                            .style(Modern::system_button()),
+                    ]
+                    .align_y(Alignment::Center),
+                    if routed.is_empty() {
+                        column![
+                            text("No items are routed to this printer")
+                                .style(Modern::secondary_text())
+                        ]
+                    } else {
+                        column(
+                            routed
+                                .into_iter()
+                                .map(|(item, primary)| {
+                                    row![
+                                        text(item.id.to_string()).width(75),
+                                        text(&item.name).width(250),
+                                        text(if primary { "Primary" } else { "" })
+                                            .style(Modern::secondary_text()),
+                                    ]
+                                    .spacing(10)
+                                    .into()
+                                })
+                                .collect::<Vec<_>>()
+                        )
+                        .spacing(5)
+                    },
+                ]
+                .spacing(10)
+            )
+            .padding(10)
+            .width(505)
+            .style(Modern::card_container())
+            .into()
+        }
+        None => column![].into(),
+    };
+
+    column![list, routing_panel].spacing(10).into()
 }
 
 fn render_printer_row<'a>(
@@ -165,9 +296,12 @@ fn render_printer_row<'a>(
         Message::EditPrinterLogical,
         Message::Save,
         Message::CopyPrinterLogical,
+        Message::ExportRecord,
+        Message::CopyJson,
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
+        None,
         "Printer Name"
     )
 }
\ No newline at end of file
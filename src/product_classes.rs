@@ -1,6 +1,7 @@
-use crate::data_types::{ EntityId, ValidationError };
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState};
+use crate::entity_component::{self, Entity, EditState, ListSort};
 use serde::{Serialize, Deserialize};
 use iced::Element;
 use std::collections::BTreeMap;
@@ -8,10 +9,13 @@ use std::collections::BTreeMap;
 #[derive(Debug, Clone)]
 pub enum Message {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyProductClass(EntityId),
     EditProductClass(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
+    UpdateId(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
 }
@@ -19,10 +23,13 @@ pub enum Message {
 #[derive(Debug, Clone)]
 pub enum Operation {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyProductClass(EntityId),
     EditProductClass(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
+    UpdateId(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
 }
@@ -31,6 +38,8 @@ pub enum Operation {
 pub struct ProductClass {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for ProductClass {
@@ -44,6 +53,7 @@ impl Default for ProductClass {
         Self {
             id: -1,
             name: String::new(),
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -70,7 +80,14 @@ impl Entity for ProductClass {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
     }
 }
 
@@ -79,12 +96,8 @@ impl ProductClass {
         Self::default()
     }
 
-    fn validate(&self, other_classes: &[&ProductClass]) -> Result<(), ValidationError> {
-        if !(1..=999).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Product class ID must be between 1 and 999".to_string()
-            ));
-        }
+    pub fn validate(&self, other_classes: &[&ProductClass], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::ProductClass, self.id, id_range)?;
 
         for other in other_classes {
             if other.id == self.id {
@@ -111,6 +124,12 @@ pub fn update(
     other_classes: &[&ProductClass] */
 ) -> Action<Operation, Message> {
     match message {
+        Message::ExportRecord(id) => {
+            Action::operation(Operation::ExportRecord(id))
+        },
+        Message::CopyJson(id) => {
+            Action::operation(Operation::CopyJson(id))
+        },
         Message::RequestDelete(id) => {
             Action::operation(Operation::RequestDelete(id))
         },
@@ -129,6 +148,9 @@ pub fn update(
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
         }
+        Message::UpdateId(id, new_id) => {
+            Action::operation(Operation::UpdateId(id, new_id))
+        }
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
@@ -138,12 +160,16 @@ pub fn update(
 pub fn view<'a>(
     all_groups: &'a BTreeMap<EntityId, ProductClass>,
     edit_states: &'a Vec<EditState>,
+    search: &str,
+    sort: ListSort,
 ) -> Element<'a, Message> {
     entity_component::entity_view(
         "Product classes",
         Message::CreateNew,
         all_groups,
         edit_states,
+        search,
+        sort,
         |product_class, edit_states| render_product_class_row(product_class, edit_states),
     )
 }
@@ -158,9 +184,12 @@ fn render_product_class_row<'a>(
         Message::EditProductClass,
         Message::SaveAll,
         Message::CopyProductClass,
+        Message::ExportRecord,
+        Message::CopyJson,
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
+        Some(Message::UpdateId),
         "Product class Name"
     )
 }
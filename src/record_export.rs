@@ -0,0 +1,573 @@
+use chrono::Local;
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use crate::data_types::{self, EntityId, EntityKind, ValidationError};
+use crate::entity_component::Entity;
+use crate::{
+    items::Item,
+    item_groups::ItemGroup,
+    price_levels::PriceLevel,
+    product_classes::ProductClass,
+    tax_groups::TaxGroup,
+    security_levels::SecurityLevel,
+    revenue_categories::RevenueCategory,
+    report_categories::ReportCategory,
+    choice_groups::ChoiceGroup,
+    printer_logicals::PrinterLogical,
+    stores::Store,
+};
+
+// A single record as standalone JSON, for sharing one problem record with a
+// vendor. The file carries a snapshot of everything the record points at, so
+// it reads on its own and can be matched up again when imported elsewhere.
+
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data")]
+pub enum Record {
+    Item(Item),
+    ItemGroup(ItemGroup),
+    PriceLevel(PriceLevel),
+    ProductClass(ProductClass),
+    TaxGroup(TaxGroup),
+    SecurityLevel(SecurityLevel),
+    RevenueCategory(RevenueCategory),
+    ReportCategory(ReportCategory),
+    ChoiceGroup(ChoiceGroup),
+    PrinterLogical(PrinterLogical),
+    Store(Store),
+}
+
+impl Record {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Item(_) => "Item".to_string(),
+            Self::ItemGroup(_) => EntityKind::ItemGroup.to_string(),
+            Self::PriceLevel(_) => EntityKind::PriceLevel.to_string(),
+            Self::ProductClass(_) => EntityKind::ProductClass.to_string(),
+            Self::TaxGroup(_) => EntityKind::TaxGroup.to_string(),
+            Self::SecurityLevel(_) => EntityKind::SecurityLevel.to_string(),
+            Self::RevenueCategory(_) => EntityKind::RevenueCategory.to_string(),
+            Self::ReportCategory(_) => EntityKind::ReportCategory.to_string(),
+            Self::ChoiceGroup(_) => EntityKind::ChoiceGroup.to_string(),
+            Self::PrinterLogical(_) => EntityKind::PrinterLogical.to_string(),
+            Self::Store(_) => EntityKind::Store.to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Item(item) => &item.name,
+            Self::ItemGroup(entity) => entity.name(),
+            Self::PriceLevel(entity) => entity.name(),
+            Self::ProductClass(entity) => entity.name(),
+            Self::TaxGroup(entity) => entity.name(),
+            Self::SecurityLevel(entity) => entity.name(),
+            Self::RevenueCategory(entity) => entity.name(),
+            Self::ReportCategory(entity) => entity.name(),
+            Self::ChoiceGroup(entity) => entity.name(),
+            Self::PrinterLogical(entity) => entity.name(),
+            Self::Store(entity) => entity.name(),
+        }
+    }
+}
+
+// What a reference pointed at when the record was exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferenceSnapshot {
+    pub kind: EntityKind,
+    pub id: EntityId,
+    pub name: String,
+    // Key fields besides the name, e.g. a tax rate or the item's price at a level
+    #[serde(default)]
+    pub details: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordFile {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub record: Record,
+    #[serde(default)]
+    pub references: Vec<ReferenceSnapshot>,
+}
+
+// The collections a record can point into
+pub struct Collections<'a> {
+    pub item_groups: &'a BTreeMap<EntityId, ItemGroup>,
+    pub price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    pub product_classes: &'a BTreeMap<EntityId, ProductClass>,
+    pub tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
+    pub security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
+    pub revenue_categories: &'a BTreeMap<EntityId, RevenueCategory>,
+    pub report_categories: &'a BTreeMap<EntityId, ReportCategory>,
+    pub choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
+    pub printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
+    pub stores: &'a BTreeMap<EntityId, Store>,
+}
+
+impl RecordFile {
+    pub fn new(record: Record, collections: &Collections) -> Self {
+        let references = references_of(&record, collections);
+
+        Self {
+            format_version: FORMAT_VERSION,
+            exported_at: Local::now().to_rfc3339(),
+            record,
+            references,
+        }
+    }
+
+    // Suggested file name, e.g. "item_1042_cheeseburger.json"
+    pub fn file_name(&self) -> String {
+        let id = match &self.record {
+            Record::Item(item) => item.id,
+            Record::ItemGroup(entity) => entity.id(),
+            Record::PriceLevel(entity) => entity.id(),
+            Record::ProductClass(entity) => entity.id(),
+            Record::TaxGroup(entity) => entity.id(),
+            Record::SecurityLevel(entity) => entity.id(),
+            Record::RevenueCategory(entity) => entity.id(),
+            Record::ReportCategory(entity) => entity.id(),
+            Record::ChoiceGroup(entity) => entity.id(),
+            Record::PrinterLogical(entity) => entity.id(),
+            Record::Store(entity) => entity.id(),
+        };
+
+        let slug = |text: &str| {
+            text.to_lowercase()
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("_")
+        };
+
+        format!("{}_{}_{}.json", slug(&self.record.label()), id, slug(self.record.name()))
+    }
+}
+
+fn snapshot<T: Entity>(
+    kind: EntityKind,
+    entities: &BTreeMap<EntityId, T>,
+    id: EntityId,
+    details: &[(&str, String)],
+) -> Option<ReferenceSnapshot> {
+    entities.get(&id).map(|entity| ReferenceSnapshot {
+        kind,
+        id,
+        name: entity.name().to_string(),
+        details: details.iter().map(|(key, value)| (key.to_string(), value.clone())).collect(),
+    })
+}
+
+// Snapshots of everything the record points at. References that don't
+// resolve in the exporting data are left out.
+pub fn references_of(record: &Record, c: &Collections) -> Vec<ReferenceSnapshot> {
+    let mut references = Vec::new();
+
+    match record {
+        Record::Item(item) => {
+            references.extend(item.item_group.and_then(|id| snapshot(EntityKind::ItemGroup, c.item_groups, id, &[])));
+            references.extend(item.product_class.and_then(|id| snapshot(EntityKind::ProductClass, c.product_classes, id, &[])));
+            references.extend(item.revenue_category.and_then(|id| snapshot(EntityKind::RevenueCategory, c.revenue_categories, id, &[])));
+            references.extend(item.report_category.and_then(|id| snapshot(EntityKind::ReportCategory, c.report_categories, id, &[])));
+            references.extend(item.security_level.and_then(|id| snapshot(EntityKind::SecurityLevel, c.security_levels, id, &[])));
+            references.extend(item.tax_group.and_then(|id| {
+                let rate = c.tax_groups.get(&id).map(|group| group.rate.to_string()).unwrap_or_default();
+                snapshot(EntityKind::TaxGroup, c.tax_groups, id, &[("rate", rate)])
+            }));
+
+            // Priced levels carry the price, levels assigned without one follow
+            let mut levels: Vec<(EntityId, Option<String>)> = item.item_prices
+                .iter()
+                .flatten()
+                .map(|price| (price.price_level_id, Some(price.price.to_string())))
+                .collect();
+            for &id in item.price_levels.iter().flatten() {
+                if !levels.iter().any(|(level, _)| *level == id) {
+                    levels.push((id, None));
+                }
+            }
+            references.extend(levels.into_iter().filter_map(|(id, price)| {
+                let details: Vec<(&str, String)> = price.map(|price| ("price", price)).into_iter().collect();
+                snapshot(EntityKind::PriceLevel, c.price_levels, id, &details)
+            }));
+
+            references.extend(item.choice_groups.iter().flatten().filter_map(|(id, sequence)| {
+                snapshot(EntityKind::ChoiceGroup, c.choice_groups, *id, &[("sequence", sequence.to_string())])
+            }));
+            references.extend(item.printer_logicals.iter().flatten().filter_map(|(id, primary)| {
+                snapshot(EntityKind::PrinterLogical, c.printer_logicals, *id, &[("primary", primary.to_string())])
+            }));
+        }
+        Record::PriceLevel(level) => {
+            references.extend(level.store_id.and_then(|id| snapshot(EntityKind::Store, c.stores, id, &[])));
+        }
+        _ => {}
+    }
+
+    references
+}
+
+fn find<T: Entity>(entities: &BTreeMap<EntityId, T>, reference: &ReferenceSnapshot) -> Option<EntityId> {
+    let same_name = |entity: &T| entity.name().trim().eq_ignore_ascii_case(reference.name.trim());
+
+    match entities.get(&reference.id) {
+        Some(entity) if same_name(entity) => Some(reference.id),
+        _ => entities.values().find(|entity| same_name(entity)).map(|entity| entity.id()),
+    }
+}
+
+// The id a reference has in this data: the same id when the name agrees,
+// otherwise whichever entity carries the name. None when neither matches.
+pub fn resolve(reference: &ReferenceSnapshot, c: &Collections) -> Option<EntityId> {
+    match reference.kind {
+        EntityKind::ItemGroup => find(c.item_groups, reference),
+        EntityKind::PriceLevel => find(c.price_levels, reference),
+        EntityKind::ProductClass => find(c.product_classes, reference),
+        EntityKind::TaxGroup => find(c.tax_groups, reference),
+        EntityKind::SecurityLevel => find(c.security_levels, reference),
+        EntityKind::RevenueCategory => find(c.revenue_categories, reference),
+        EntityKind::ReportCategory => find(c.report_categories, reference),
+        EntityKind::ChoiceGroup => find(c.choice_groups, reference),
+        EntityKind::PrinterLogical => find(c.printer_logicals, reference),
+        EntityKind::Store => find(c.stores, reference),
+    }
+}
+
+// Exported id -> local id, per kind
+pub type IdMap = BTreeMap<(EntityKind, EntityId), EntityId>;
+
+// A record file read from disk, waiting on the create/skip choice when some
+// references have nothing to match in this data
+#[derive(Debug, Clone)]
+pub struct PendingImport {
+    pub file: RecordFile,
+    pub resolved: IdMap,
+    pub missing: Vec<ReferenceSnapshot>,
+}
+
+impl PendingImport {
+    pub fn new(file: RecordFile, c: &Collections) -> Self {
+        let mut resolved = IdMap::new();
+        let mut missing = Vec::new();
+
+        for reference in &file.references {
+            match resolve(reference, c) {
+                Some(id) => {
+                    resolved.insert((reference.kind, reference.id), id);
+                }
+                None => missing.push(reference.clone()),
+            }
+        }
+
+        Self { file, resolved, missing }
+    }
+}
+
+// Points the item's references at local ids. References missing from the
+// map were skipped and are dropped, as are components that don't exist here.
+pub fn remap_item(item: &mut Item, ids: &IdMap, items: &BTreeMap<EntityId, Item>) {
+    let local = |kind: EntityKind, id: EntityId| ids.get(&(kind, id)).copied();
+
+    item.item_group = item.item_group.and_then(|id| local(EntityKind::ItemGroup, id));
+    item.product_class = item.product_class.and_then(|id| local(EntityKind::ProductClass, id));
+    item.revenue_category = item.revenue_category.and_then(|id| local(EntityKind::RevenueCategory, id));
+    item.report_category = item.report_category.and_then(|id| local(EntityKind::ReportCategory, id));
+    item.security_level = item.security_level.and_then(|id| local(EntityKind::SecurityLevel, id));
+    item.tax_group = item.tax_group.and_then(|id| local(EntityKind::TaxGroup, id));
+
+    item.price_levels = item.price_levels.take().map(|levels| {
+        levels.into_iter().filter_map(|id| local(EntityKind::PriceLevel, id)).collect()
+    });
+    item.item_prices = item.item_prices.take().map(|prices| {
+        prices.into_iter()
+            .filter_map(|mut price| {
+                price.price_level_id = local(EntityKind::PriceLevel, price.price_level_id)?;
+                Some(price)
+            })
+            .collect()
+    });
+    item.store_price_level = item.store_price_level.take().map(|levels| {
+        levels.into_iter().filter_map(|id| local(EntityKind::PriceLevel, id)).collect()
+    });
+    item.choice_groups = item.choice_groups.take().map(|groups| {
+        groups.into_iter().filter_map(|(id, sequence)| Some((local(EntityKind::ChoiceGroup, id)?, sequence))).collect()
+    });
+    item.printer_logicals = item.printer_logicals.take().map(|printers| {
+        printers.into_iter().filter_map(|(id, primary)| Some((local(EntityKind::PrinterLogical, id)?, primary))).collect()
+    });
+    item.components = item.components.take().map(|components| {
+        components.into_iter().filter(|id| items.contains_key(id)).collect()
+    });
+
+    // Empty collections are stored as None
+    if item.price_levels.as_ref().is_some_and(|v| v.is_empty()) { item.price_levels = None; }
+    if item.item_prices.as_ref().is_some_and(|v| v.is_empty()) { item.item_prices = None; }
+    if item.store_price_level.as_ref().is_some_and(|v| v.is_empty()) { item.store_price_level = None; }
+    if item.choice_groups.as_ref().is_some_and(|v| v.is_empty()) { item.choice_groups = None; }
+    if item.printer_logicals.as_ref().is_some_and(|v| v.is_empty()) { item.printer_logicals = None; }
+    if item.components.as_ref().is_some_and(|v| v.is_empty()) { item.components = None; }
+}
+
+pub fn remap_price_level(level: &mut PriceLevel, ids: &IdMap) {
+    level.store_id = level.store_id.and_then(|id| ids.get(&(EntityKind::Store, id)).copied());
+}
+
+// Creates a bare entity named after a reference that had no match, on the
+// lowest free id in the kind's range
+pub fn create_missing<T: Entity>(
+    entities: &mut BTreeMap<EntityId, T>,
+    reference: &ReferenceSnapshot,
+    range: &RangeInclusive<EntityId>,
+) -> Result<EntityId, String> {
+    let id = data_types::lowest_free_id(reference.kind, entities, range)?;
+    entities.insert(id, T::default_new().with_id(id).with_name(reference.name.clone()));
+    Ok(id)
+}
+
+// Adds an imported entity under its exported id when that id is free and in
+// range, otherwise under the lowest free id
+pub fn insert_entity<T: Entity>(
+    entities: &mut BTreeMap<EntityId, T>,
+    entity: T,
+    kind: EntityKind,
+    range: &RangeInclusive<EntityId>,
+) -> Result<EntityId, String> {
+    let id = if range.contains(&entity.id()) && !entities.contains_key(&entity.id()) {
+        entity.id()
+    } else {
+        data_types::lowest_free_id(kind, entities, range)?
+    };
+
+    entities.insert(id, entity.with_id(id));
+    Ok(id)
+}
+
+// Reads pasted text as a record of `kind`, either in the tagged form Copy JSON
+// puts on the clipboard or as the bare entity
+pub fn parse_pasted(text: &str, kind: EntityKind) -> Result<Record, String> {
+    let record = match serde_json::from_str::<Record>(text) {
+        Ok(record) => record,
+        Err(_) => {
+            let bare = match kind {
+                EntityKind::ItemGroup => serde_json::from_str(text).map(Record::ItemGroup),
+                EntityKind::PriceLevel => serde_json::from_str(text).map(Record::PriceLevel),
+                EntityKind::ProductClass => serde_json::from_str(text).map(Record::ProductClass),
+                EntityKind::TaxGroup => serde_json::from_str(text).map(Record::TaxGroup),
+                EntityKind::SecurityLevel => serde_json::from_str(text).map(Record::SecurityLevel),
+                EntityKind::RevenueCategory => serde_json::from_str(text).map(Record::RevenueCategory),
+                EntityKind::ReportCategory => serde_json::from_str(text).map(Record::ReportCategory),
+                EntityKind::ChoiceGroup => serde_json::from_str(text).map(Record::ChoiceGroup),
+                EntityKind::PrinterLogical => serde_json::from_str(text).map(Record::PrinterLogical),
+                EntityKind::Store => serde_json::from_str(text).map(Record::Store),
+            };
+            bare.map_err(|e| format!("The clipboard doesn't hold a {} as JSON: {}", kind, e))?
+        }
+    };
+
+    if record.label() != kind.to_string() {
+        return Err(format!("The clipboard holds a {}, not a {}", record.label(), kind));
+    }
+
+    Ok(record)
+}
+
+// Adds a pasted entity under the lowest free id, once it passes its type's
+// own checks against the records already there
+pub fn insert_pasted<T: Entity>(
+    entities: &mut BTreeMap<EntityId, T>,
+    entity: T,
+    kind: EntityKind,
+    range: &RangeInclusive<EntityId>,
+    validate: fn(&T, &[&T], &RangeInclusive<EntityId>) -> Result<(), ValidationError>,
+) -> Result<EntityId, String> {
+    let id = data_types::lowest_free_id(kind, entities, range)?;
+    let entity = entity.with_id(id);
+
+    let others: Vec<&T> = entities.values().collect();
+    validate(&entity, &others, range).map_err(|e| e.to_string())?;
+
+    entities.insert(id, entity);
+    Ok(id)
+}
+
+pub async fn save_record(file: RecordFile) -> Result<PathBuf, String> {
+    let path = rfd::AsyncFileDialog::new()
+        .add_filter("JSON Files", &["json"])
+        .set_title("Export Record")
+        .set_file_name(file.file_name())
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or_else(|| "Export cancelled".to_string())?;
+
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize record: {}", e))?;
+
+    tokio::fs::write(&path, json)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(path)
+}
+
+pub async fn open_record() -> Result<RecordFile, String> {
+    let path = rfd::AsyncFileDialog::new()
+        .add_filter("JSON Files", &["json"])
+        .set_title("Import Single Record")
+        .pick_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or_else(|| "Import cancelled".to_string())?;
+
+    let json = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let file: RecordFile = serde_json::from_str(&json)
+        .map_err(|e| format!("Not a record export: {}", e))?;
+
+    if file.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "The record was exported by a newer version (format {}, this version reads {})",
+            file.format_version, FORMAT_VERSION
+        ));
+    }
+
+    Ok(file)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::ItemPrice;
+    use rust_decimal::Decimal;
+
+    // Owns the maps a Collections borrows
+    #[derive(Default)]
+    struct Data {
+        item_groups: BTreeMap<EntityId, ItemGroup>,
+        price_levels: BTreeMap<EntityId, PriceLevel>,
+        product_classes: BTreeMap<EntityId, ProductClass>,
+        tax_groups: BTreeMap<EntityId, TaxGroup>,
+        security_levels: BTreeMap<EntityId, SecurityLevel>,
+        revenue_categories: BTreeMap<EntityId, RevenueCategory>,
+        report_categories: BTreeMap<EntityId, ReportCategory>,
+        choice_groups: BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: BTreeMap<EntityId, PrinterLogical>,
+        stores: BTreeMap<EntityId, Store>,
+    }
+
+    impl Data {
+        fn collections(&self) -> Collections<'_> {
+            Collections {
+                item_groups: &self.item_groups,
+                price_levels: &self.price_levels,
+                product_classes: &self.product_classes,
+                tax_groups: &self.tax_groups,
+                security_levels: &self.security_levels,
+                revenue_categories: &self.revenue_categories,
+                report_categories: &self.report_categories,
+                choice_groups: &self.choice_groups,
+                printer_logicals: &self.printer_logicals,
+                stores: &self.stores,
+            }
+        }
+    }
+
+    fn tax_group(id: EntityId, name: &str) -> TaxGroup {
+        TaxGroup { id, name: name.to_string(), rate: Decimal::new(825, 4), ..TaxGroup::default() }
+    }
+
+    fn cheeseburger() -> Item {
+        Item {
+            id: 1042,
+            name: "Cheeseburger".to_string(),
+            tax_group: Some(5),
+            price_levels: Some(vec![7]),
+            item_prices: Some(vec![ItemPrice { price_level_id: 7, price: Decimal::new(899, 2) }]),
+            ..Item::default()
+        }
+    }
+
+    // The exporting side, with the tax group and price level the item uses
+    fn exporting_data() -> Data {
+        Data {
+            tax_groups: BTreeMap::from([(5, tax_group(5, "Food"))]),
+            price_levels: BTreeMap::from([(7, PriceLevel { id: 7, name: "Happy Hour".to_string(), ..PriceLevel::default() })]),
+            ..Data::default()
+        }
+    }
+
+    #[test]
+    fn record_files_carry_their_references_through_json() {
+        let data = exporting_data();
+        let file = RecordFile::new(Record::Item(cheeseburger()), &data.collections());
+
+        let names: Vec<&str> = file.references.iter().map(|reference| reference.name.as_str()).collect();
+        assert_eq!(names, vec!["Food", "Happy Hour"]);
+        assert_eq!(file.references[1].details.get("price").map(String::as_str), Some("8.99"));
+        assert_eq!(file.file_name(), "item_1042_cheeseburger.json");
+
+        let json = serde_json::to_string(&file).unwrap();
+        assert_eq!(serde_json::from_str::<RecordFile>(&json).unwrap(), file);
+    }
+
+    #[test]
+    fn references_resolve_by_id_then_by_name() {
+        let local = Data {
+            tax_groups: BTreeMap::from([(2, tax_group(2, "food")), (5, tax_group(5, "Liquor"))]),
+            ..Data::default()
+        };
+        let reference = |name: &str| ReferenceSnapshot {
+            kind: EntityKind::TaxGroup,
+            id: 5,
+            name: name.to_string(),
+            details: BTreeMap::new(),
+        };
+
+        assert_eq!(resolve(&reference("Liquor"), &local.collections()), Some(5));
+        assert_eq!(resolve(&reference("Food"), &local.collections()), Some(2));
+        assert_eq!(resolve(&reference("Retail"), &local.collections()), None);
+    }
+
+    #[test]
+    fn importing_remaps_matches_and_drops_skipped_references() {
+        let exported = exporting_data();
+        let file = RecordFile::new(Record::Item(cheeseburger()), &exported.collections());
+        let local = Data {
+            tax_groups: BTreeMap::from([(2, tax_group(2, "Food"))]),
+            ..Data::default()
+        };
+
+        let pending = PendingImport::new(file, &local.collections());
+        assert_eq!(pending.missing.len(), 1);
+        assert_eq!(pending.missing[0].name, "Happy Hour");
+
+        let mut item = cheeseburger();
+        remap_item(&mut item, &pending.resolved, &BTreeMap::new());
+
+        assert_eq!(item.tax_group, Some(2));
+        assert_eq!(item.price_levels, None);
+        assert_eq!(item.item_prices, None);
+    }
+
+    #[test]
+    fn imported_entities_keep_a_free_id_in_range() {
+        let mut groups = BTreeMap::from([(1, tax_group(1, "Food"))]);
+
+        assert_eq!(insert_entity(&mut groups, tax_group(5, "Liquor"), EntityKind::TaxGroup, &(1..=10)), Ok(5));
+        assert_eq!(insert_entity(&mut groups, tax_group(5, "Retail"), EntityKind::TaxGroup, &(1..=10)), Ok(2));
+        assert_eq!(insert_entity(&mut groups, tax_group(50, "Wine"), EntityKind::TaxGroup, &(1..=10)), Ok(3));
+        assert_eq!(groups[&3].id, 3);
+    }
+}
@@ -1,36 +1,102 @@
-use crate::data_types::{EntityId, ValidationError };
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState};
+use crate::entity_component::{self, Entity, EditState, ListSort, MergeSelection};
+use crate::hold_to_confirm;
 use serde::{Serialize, Deserialize};
-use iced::Element;
+use crate::items::Item;
+use iced::{Alignment, Element};
+use iced::widget::{button, column, container, progress_bar, row, text};
+use iced_modern_theme::Modern;
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyReportCategory(EntityId),
     EditReportCategory(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
+    UpdateId(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ShowUnused(bool),
+    SelectMerge(MergeSelection),
+    // Moves the items of the first category to the second, then deletes the first
+    Merge(EntityId, EntityId),
 }
 
 #[derive(Debug, Clone)]
 pub enum Operation {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyReportCategory(EntityId),
     EditReportCategory(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
+    UpdateId(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ShowUnused(bool),
+    SelectMerge(MergeSelection),
+    Merge(EntityId, EntityId),
+}
+
+// How much of the configured id range is taken
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capacity {
+    pub used: usize,
+    pub total: usize,
+}
+
+impl Capacity {
+    pub fn new(categories: &BTreeMap<EntityId, ReportCategory>, id_range: &RangeInclusive<EntityId>) -> Self {
+        Self {
+            used: categories.keys().filter(|id| id_range.contains(id)).count(),
+            total: (id_range.end() - id_range.start() + 1).max(0) as usize,
+        }
+    }
+
+    pub fn percent(&self) -> usize {
+        if self.total == 0 {
+            100
+        } else {
+            self.used * 100 / self.total
+        }
+    }
+
+    // True when growing from `before` to this takes usage to or past the threshold
+    pub fn crossed(&self, before: &Capacity, threshold_percent: u8) -> bool {
+        let threshold = threshold_percent as usize;
+        before.percent() < threshold && self.percent() >= threshold
+    }
+}
+
+// Categories no item points at, the candidates for consolidating
+pub fn unused<'a>(
+    categories: &'a BTreeMap<EntityId, ReportCategory>,
+    items: &BTreeMap<EntityId, Item>,
+) -> Vec<&'a ReportCategory> {
+    let mut references: BTreeMap<EntityId, usize> = BTreeMap::new();
+    for id in items.values().filter_map(|item| item.report_category) {
+        *references.entry(id).or_default() += 1;
+    }
+
+    categories
+        .values()
+        .filter(|category| !references.contains_key(&category.id))
+        .collect()
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReportCategory {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for ReportCategory {
@@ -44,6 +110,7 @@ impl Default for ReportCategory {
         Self {
             id: -1,
             name: String::new(),
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -70,7 +137,14 @@ impl Entity for ReportCategory {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
     }
 }
 
@@ -79,13 +153,8 @@ impl ReportCategory {
         Self::default()
     }
 
-    fn validate(&self, other_printers: &[&ReportCategory]) -> Result<(), ValidationError> {
-        // Validate ID range (0-25 based on your screenshot)
-        if !(0..=25).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Report Category ID must be between 0 and 25".to_string()
-            ));
-        }
+    pub fn validate(&self, other_printers: &[&ReportCategory], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::ReportCategory, self.id, id_range)?;
 
         // Check for duplicate IDs
         for other in other_printers {
@@ -118,6 +187,12 @@ pub fn update(
     message: Message,
 ) -> Action<Operation, Message> {
     match message {
+        Message::ExportRecord(id) => {
+            Action::operation(Operation::ExportRecord(id))
+        },
+        Message::CopyJson(id) => {
+            Action::operation(Operation::CopyJson(id))
+        },
         Message::RequestDelete(id) => {
             Action::operation(Operation::RequestDelete(id))
         },
@@ -137,23 +212,114 @@ pub fn update(
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
         }
+        Message::UpdateId(id, new_id) => {
+            Action::operation(Operation::UpdateId(id, new_id))
+        }
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::ShowUnused(show) => {
+            Action::operation(Operation::ShowUnused(show))
+        }
+        Message::SelectMerge(selection) => {
+            Action::operation(Operation::SelectMerge(selection))
+        }
+        Message::Merge(from, into) => {
+            Action::operation(Operation::Merge(from, into))
+        }
     }
 }
 
 pub fn view<'a>(
     all_categories: &'a BTreeMap<EntityId, ReportCategory>,
     edit_states: &'a Vec<EditState>,
+    search: &str,
+    sort: ListSort,
+    create_error: Option<&'a str>,
+    capacity: Capacity,
+    warn_percent: u8,
+    unused_categories: Option<Vec<&'a ReportCategory>>,
+    merge: MergeSelection,
 ) -> Element<'a, Message> {
-    entity_component::entity_view(
+    let list = entity_component::entity_view(
         "Report Categories",
         Message::CreateNew,
         all_categories,
         edit_states,
+        search,
+        sort,
         |category, edit_states| render_category_row(category, edit_states),
-    )
+    );
+
+    let near_full = capacity.percent() >= warn_percent as usize;
+
+    let meter = row![
+        text(format!("{} of {} ids used", capacity.used, capacity.total))
+            .style(Modern::validated_text(near_full))
+            .width(150),
+        progress_bar(0.0..=capacity.total.max(1) as f32, capacity.used as f32)
+            .width(200)
+            .height(8),
+        button(if unused_categories.is_some() { "Hide unused" } else { "Find unused categories" })
+            .on_press(Message::ShowUnused(unused_categories.is_none()))
+            .style(Modern::system_button()),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let unused_list: Element<'a, Message> = match unused_categories {
+        Some(categories) if categories.is_empty() => {
+            text("Every report category is used by at least one item")
+                .style(Modern::secondary_text())
+                .into()
+        }
+        Some(categories) => container(
+            column![
+                text(format!("{} categories have no items, consider merging or deleting them", categories.len()))
+                    .style(Modern::secondary_text()),
+                column(
+                    categories
+                        .into_iter()
+                        .map(|category| {
+                            row![
+                                text(format!("{} ({})", category.name, category.id)).width(250),
+                                hold_to_confirm::hold_to_confirm(
+                                    button("Delete")
+                                        .on_press(Message::RequestDelete(category.id))
+                                        .style(Modern::danger_button()),
+                                    Message::RequestDelete(category.id),
+                                ),
+                            ]
+                            .spacing(10)
+                            .align_y(Alignment::Center)
+                            .into()
+                        })
+                        .collect::<Vec<_>>()
+                )
+                .spacing(5),
+            ]
+            .spacing(10)
+        )
+        .padding(10)
+        .style(Modern::card_container())
+        .into(),
+        None => column![].into(),
+    };
+
+    column![
+        meter,
+        // Shown when the configured id range has no free ids left
+        if let Some(error) = create_error {
+            text(error).style(Modern::error_text())
+        } else {
+            text("")
+        },
+        unused_list,
+        entity_component::merge_picker(all_categories, merge, Message::SelectMerge, Message::Merge),
+        list,
+    ]
+    .spacing(10)
+    .into()
 }
 
 fn render_category_row<'a>(
@@ -166,9 +332,55 @@ fn render_category_row<'a>(
         Message::EditReportCategory,
         Message::SaveAll,
         Message::CopyReportCategory,
+        Message::ExportRecord,
+        Message::CopyJson,
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
+        Some(Message::UpdateId),
         "Report Category Name"
     )
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories(ids: &[EntityId]) -> BTreeMap<EntityId, ReportCategory> {
+        ids.iter()
+            .map(|id| (*id, ReportCategory { id: *id, name: format!("Category {}", id), ..ReportCategory::default() }))
+            .collect()
+    }
+
+    #[test]
+    fn capacity_counts_only_ids_inside_the_range() {
+        let capacity = Capacity::new(&categories(&[1, 2, 3, 50]), &(1..=10));
+
+        assert_eq!(capacity, Capacity { used: 3, total: 10 });
+        assert_eq!(capacity.percent(), 30);
+        assert_eq!(Capacity::new(&categories(&[]), &(5..=4)).percent(), 100);
+    }
+
+    #[test]
+    fn crossing_the_threshold_is_reported_once() {
+        let before = Capacity { used: 8, total: 10 };
+        let after = Capacity { used: 9, total: 10 };
+
+        assert!(after.crossed(&before, 90));
+        assert!(!Capacity { used: 10, total: 10 }.crossed(&after, 90));
+        assert!(!after.crossed(&before, 95));
+    }
+
+    #[test]
+    fn unused_lists_categories_no_item_points_at() {
+        let categories = categories(&[1, 2, 3]);
+        let items = BTreeMap::from([
+            (10, Item { id: 10, report_category: Some(2), ..Item::default() }),
+            (11, Item { id: 11, report_category: None, ..Item::default() }),
+        ]);
+
+        let unused_ids: Vec<EntityId> = unused(&categories, &items).iter().map(|category| category.id).collect();
+
+        assert_eq!(unused_ids, vec![1, 3]);
+    }
+}
@@ -1,17 +1,23 @@
-use crate::data_types::{ EntityId, ValidationError };
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState};
+use crate::entity_component::{self, Entity, EditState, ListSort};
 use serde::{Serialize, Deserialize};
 use iced::Element;
+use iced::widget::{column, text};
+use iced_modern_theme::Modern;
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyRevenueCategory(EntityId),
     EditRevenueCategory(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
+    UpdateId(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
 }
@@ -19,10 +25,13 @@ pub enum Message {
 #[derive(Debug, Clone)]
 pub enum Operation {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopyRevenueCategory(EntityId),
     EditRevenueCategory(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
+    UpdateId(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
 }
@@ -31,6 +40,8 @@ pub enum Operation {
 pub struct RevenueCategory {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for RevenueCategory {
@@ -44,6 +55,7 @@ impl Default for RevenueCategory {
         Self {
             id: -1,
             name: String::new(),
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -70,7 +82,14 @@ impl Entity for RevenueCategory {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
     }
 }
 
@@ -79,13 +98,8 @@ impl RevenueCategory {
         Self::default()
     }
 
-    fn validate(&self, other_printers: &[&RevenueCategory]) -> Result<(), ValidationError> {
-        // Validate ID range (0-25 based on your screenshot)
-        if !(0..=25).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Revenue Category ID must be between 0 and 25".to_string()
-            ));
-        }
+    pub fn validate(&self, other_printers: &[&RevenueCategory], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::RevenueCategory, self.id, id_range)?;
 
         // Check for duplicate IDs
         for other in other_printers {
@@ -118,6 +132,12 @@ pub fn update(
     message: Message,
 ) -> Action<Operation, Message> {
     match message {
+        Message::ExportRecord(id) => {
+            Action::operation(Operation::ExportRecord(id))
+        },
+        Message::CopyJson(id) => {
+            Action::operation(Operation::CopyJson(id))
+        },
         Message::RequestDelete(id) => {
             Action::operation(Operation::RequestDelete(id))
         },
@@ -137,6 +157,9 @@ pub fn update(
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
         }
+        Message::UpdateId(id, new_id) => {
+            Action::operation(Operation::UpdateId(id, new_id))
+        }
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
@@ -146,14 +169,25 @@ pub fn update(
 pub fn view<'a>(
     all_categories: &'a BTreeMap<EntityId, RevenueCategory>,
     edit_states: &'a Vec<EditState>,
+    search: &str,
+    sort: ListSort,
+    create_error: Option<&'a str>,
 ) -> Element<'a, Message> {
-    entity_component::entity_view(
+    let list = entity_component::entity_view(
         "Revenue Categories",
         Message::CreateNew,
         all_categories,
         edit_states,
+        search,
+        sort,
         |category, edit_states| render_category_row(category, edit_states),
-    )
+    );
+
+    // Shown when the configured id range has no free ids left
+    match create_error {
+        Some(error) => column![text(error).style(Modern::error_text()), list].spacing(10).into(),
+        None => list,
+    }
 }
 
 fn render_category_row<'a>(
@@ -166,9 +200,12 @@ fn render_category_row<'a>(
         Message::EditRevenueCategory,
         Message::SaveAll,
         Message::CopyRevenueCategory,
+        Message::ExportRecord,
+        Message::CopyJson,
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
+        Some(Message::UpdateId),
         "Revenue Category Name"
     )
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
-use crate::data_types::{ EntityId, ValidationError };
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState};
+use crate::entity_component::{self, Entity, EditState, ListSort};
 use serde::{Serialize, Deserialize};
 use iced::Element;
 use std::collections::BTreeMap;
@@ -8,10 +9,13 @@ use std::collections::BTreeMap;
 #[derive(Debug, Clone)]
 pub enum Message {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopySecurityLevel(EntityId),
     EditSecurityLevel(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
+    UpdateId(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
 }
@@ -19,10 +23,13 @@ pub enum Message {
 #[derive(Debug, Clone)]
 pub enum Operation {
     RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
     CopySecurityLevel(EntityId),
     EditSecurityLevel(EntityId),
     SaveAll(EntityId, EditState),
     UpdateName(EntityId, String),
+    UpdateId(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
 }
@@ -31,6 +38,8 @@ pub enum Operation {
 pub struct SecurityLevel {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for SecurityLevel {
@@ -44,6 +53,7 @@ impl Default for SecurityLevel {
         Self {
             id: -1,
             name: String::new(),
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -70,7 +80,14 @@ impl Entity for SecurityLevel {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
     }
 }
 
@@ -79,24 +96,20 @@ impl SecurityLevel {
         Self::default()
     }
 
-    fn validate(&self, other_levels: &[&SecurityLevel]) -> Result<(), ValidationError> {
-        if !(1..=999).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Price Levels ID must be between 1 and 999".to_string()
-            ));
-        }
+    pub fn validate(&self, other_levels: &[&SecurityLevel], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::SecurityLevel, self.id, id_range)?;
 
         for other in other_levels {
             if other.id == self.id {
                 return Err(ValidationError::DuplicateId(
-                    format!("Price Levels with ID {} already exists", self.id)
+                    format!("Security Level with ID {} already exists", self.id)
                 ));
             }
         }
 
         if self.name.trim().is_empty() {
             return Err(ValidationError::EmptyName(
-                "Price Levels name cannot be empty".to_string()
+                "Security Level name cannot be empty".to_string()
             ));
         }
 
@@ -108,6 +121,12 @@ pub fn update(
     message: Message,
 ) -> Action<Operation, Message> {
     match message {
+        Message::ExportRecord(id) => {
+            Action::operation(Operation::ExportRecord(id))
+        },
+        Message::CopyJson(id) => {
+            Action::operation(Operation::CopyJson(id))
+        },
         Message::RequestDelete(id) => {
             Action::operation(Operation::RequestDelete(id))
         },
@@ -126,6 +145,9 @@ pub fn update(
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
         }
+        Message::UpdateId(id, new_id) => {
+            Action::operation(Operation::UpdateId(id, new_id))
+        }
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
@@ -135,12 +157,16 @@ pub fn update(
 pub fn view<'a>(
     all_levels: &'a BTreeMap<EntityId, SecurityLevel>,
     edit_states: &'a Vec<EditState>,
+    search: &str,
+    sort: ListSort,
 ) -> Element<'a, Message> {
     entity_component::entity_view(
         "Security Levels",
         Message::CreateNew,
         all_levels,
         edit_states,
+        search,
+        sort,
         |security_level, edit_states| render_security_level_row(security_level, edit_states),
     )
 }
@@ -155,9 +181,12 @@ fn render_security_level_row<'a>(
         Message::EditSecurityLevel,
         Message::SaveAll,
         Message::CopySecurityLevel,
+        Message::ExportRecord,
+        Message::CopyJson,
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
+        Some(Message::UpdateId),
         "Security Level Name"
     )
 }
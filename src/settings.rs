@@ -1,5 +1,5 @@
-use iced::widget::{button, checkbox, column, container, row, text, text_input};
-use iced::{Element, Task};
+use iced::widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_input, tooltip};
+use iced::{Element, Length, Task};
 pub use iced::window::Settings;
 use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
@@ -8,24 +8,179 @@ use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use std::io;
+use rust_decimal::Decimal;
+use crate::data_types::{EntityId, EntityKind};
+use std::ops::RangeInclusive;
+use crate::lint;
+use crate::accent;
+use crate::entity_component::{self, Entity};
+use crate::items::export_items::{self, ExportFormat, ExportOptions, ImagePathFormat};
+use crate::price_levels::PriceLevel;
+use crate::printer_logicals::PrinterLogical;
+use crate::report_categories::ReportCategory;
+use crate::stores::Store;
+use crate::security_levels::SecurityLevel;
+use crate::tax_groups::TaxGroup;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     UpdateFilePath(String),
     ToggleAutoSave(bool),
+    UpdateAutosaveInterval(String),
+    SelectSaveFormat(SaveFormat),
     ToggleBackups(bool),
+    ToggleHoldToDelete(bool),
+    ToggleRepairOnLoad(bool),
+    UpdateBulkThreshold(String),
+    UpdateReportCategoryWarnPercent(String),
+    UpdatePriceScale(String),
     ValidateAndSave,
     Back,
     ShowError(String),
     ThemeChanged(ThemeChoice),
+    SelectAccent(Option<[u8; 3]>),
+    UpdateAccentInput(String),
     ExportItemsToCSV,
+    // Column picker shown before the item export asks where to save
+    ChooseExportColumns,
+    ToggleExportColumn(usize, bool),
+    SelectAllExportColumns(bool),
+    MoveExportColumnUp(usize),
+    MoveExportColumnDown(usize),
+    ConfirmExportColumns,
+    CancelExportColumns,
     OpenFile,
     FileOpened(Result<(PathBuf, Option<Arc<String>>), Error>),
-    ProcessItems((BTreeMap<i32, crate::items::Item>, PathBuf)),
+    ProcessItems((BTreeMap<i32, crate::items::Item>, PathBuf, BTreeMap<i32, Decimal>)),
     ExportMessage(Result<PathBuf, Error>),
+    ExportFinished(ExportKind, Result<PathBuf, Error>),
+    ReExport(ExportKind),
+    ClearExportPath(ExportKind),
+    ToggleKeepPreviousExport(bool),
     UpdateExportSuccess(bool),
     UpdateExportMessage(String),
+    ToggleExportTaxRate(bool),
+    ToggleExportCourse(bool),
+    ToggleExportTimestamps(bool),
+    ToggleExportComponents(bool),
+    SelectExportImagePath(ImagePathFormat),
+    SelectExportStore(Option<EntityId>),
+    ToggleRequireDescriptions(bool),
+    ToggleBlockDuplicatePriceLevelNames(bool),
+    FixPriceLevel(EntityId),
+    SelectOnlineMenuPriceLevel(Option<EntityId>),
+    SelectNewItemTaxGroup(Option<EntityId>),
+    SelectNewItemSecurityLevel(Option<EntityId>),
+    SelectNewItemReportCategory(Option<EntityId>),
+    ExportOnlineMenu,
+    ImportItems,
+    ImportRecord,
+    SaveMenuAs,
+    OpenMenuFile,
+
+    // Developer tools
+    ToggleAdvanced(bool),
+    ToggleSearchTiming(bool),
+    UpdateIdRangeStart(EntityKind, String),
+    UpdateIdRangeEnd(EntityKind, String),
+    ApplyIdRanges,
+    ResetIdRanges,
+    RenumberOutOfRange(EntityKind),
+    VerifyRoundTrip,
+    RoundTripVerified(Result<Vec<String>, String>),
+
+    // Lint rules
+    UpdateRuleName(String),
+    SelectRuleKind(lint::ConditionKind),
+    SelectRuleTextField(lint::TextField),
+    UpdateRuleText(String),
+    SelectRuleReference(lint::ReferenceField),
+    SelectRulePrinter(EntityId),
+    UpdateRuleMinPrice(String),
+    UpdateRuleMaxPrice(String),
+    SelectRuleReportCategory(Option<EntityId>),
+    SelectRuleSeverity(lint::Severity),
+    AddRule,
+    RemoveRule(usize),
+    ScanImages,
+    ImagesScanned(Vec<String>),
+
+    // Storage
+    ScanStorage,
+    StorageScanned(Result<persistence::StorageReport, String>),
+    UpdateBackupRetention(String),
+    RequestStorageAction(StorageAction),
+    ConfirmStorageAction,
+    CancelStorageAction,
+    StorageActionDone(Result<String, String>),
+    BackupsScanned(Result<Vec<(PathBuf, SystemTime)>, String>),
+    RequestRestoreBackup(PathBuf),
+    ConfirmRestoreBackup,
+    CancelRestoreBackup,
+
+    // Versions
+    ScanVersions,
+    VersionsScanned(Result<Vec<persistence::VersionInfo>, String>),
+    UpdateVersionName(String),
+    CreateVersion,
+    UpdateVersionRetention(String),
+    ShowVersionSummary(persistence::VersionInfo),
+    DiffVersion(persistence::VersionInfo),
+    // Title and lines for the panel under the versions list
+    VersionDetail(Result<(String, Vec<String>), String>),
+    CloseVersionDetail,
+    RequestVersionAction(VersionAction, persistence::VersionInfo),
+    ConfirmVersionAction,
+    CancelVersionAction,
+    VersionActionDone(Result<String, String>),
+}
+
+// Exports whose destination is remembered for re-exporting without the dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ExportKind {
+    Items,
+    OnlineMenu,
+}
+
+impl fmt::Display for ExportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Items => write!(f, "item export"),
+            Self::OnlineMenu => write!(f, "online menu"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageAction {
+    PruneBackups,
+    DeleteSidecars,
+}
+
+impl fmt::Display for StorageAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PruneBackups => write!(f, "prune old backups"),
+            Self::DeleteSidecars => write!(f, "delete leftover files"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionAction {
+    Restore,
+    Delete,
+}
+
+impl fmt::Display for VersionAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Restore => write!(f, "restore"),
+            Self::Delete => write!(f, "delete"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -34,19 +189,206 @@ pub enum Operation {
     Back,
     ShowError(String),
     ThemeChanged(ThemeChoice),
+    AccentChanged(Option<[u8; 3]>),
     RequestItemsList(PathBuf),
     UpdateExportSuccess(bool),
     UpdateExportMessage(String),
+    RequestOnlineMenu(Option<PathBuf>),
+    RenumberOutOfRange(EntityKind),
+    VerifyRoundTrip,
+    ScanImages,
+    ImportItems,
+    ImportRecord,
+    SaveMenuAs,
+    OpenMenuFile,
+    EditPriceLevel(EntityId),
+    CreateVersion(String),
+    DiffVersion(persistence::VersionInfo),
+    RestoreVersion(persistence::VersionInfo),
+    RestoreBackup(PathBuf),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub file_path: String,
+    // How the data file, backups and versions are written, any of them loads
+    #[serde(default, deserialize_with = "persistence::known_variant_or_default")]
+    pub save_format: SaveFormat,
     pub auto_save: bool,
+    // Seconds between saves of unsaved changes, 0 turns the timer off
+    #[serde(default)]
+    pub autosave_secs: u64,
     pub create_backups: bool,
+    // Delete buttons need a press-and-hold instead of the confirmation dialog,
+    // for records nothing else references
+    #[serde(default)]
+    pub hold_to_delete: bool,
+    // Strip item references to records that don't exist when the data file loads
+    #[serde(default)]
+    pub repair_on_load: bool,
+    #[serde(default = "crate::bulk::default_threshold")]
+    pub bulk_confirm_threshold: usize,
+    // Warn when this share of the report category id range is in use
+    #[serde(default = "default_report_category_warn_percent")]
+    pub report_category_warn_percent: u8,
+    // Decimal places prices are rounded to when saved, tax rate percentages use the same
+    #[serde(default = "default_price_scale")]
+    pub price_scale: u32,
+    // Decimal places being typed, applied once they're in range
+    #[serde(skip)]
+    pub price_scale_input: Option<String>,
+    #[serde(skip)]
+    pub price_scale_error: Option<String>,
+    #[serde(deserialize_with = "persistence::known_variant_or_default")]
     pub app_theme: ThemeChoice,
+    // Brand color for primary and selected buttons, theme default when None
+    #[serde(default)]
+    pub accent_color: Option<[u8; 3]>,
+    // Hex text being typed, applied once it parses
+    #[serde(skip)]
+    pub accent_input: Option<String>,
+    #[serde(skip)]
+    pub accent_error: Option<String>,
     pub export_success: bool,
     pub export_message: String,
+    #[serde(default)]
+    pub export_tax_rate: bool,
+    #[serde(default)]
+    pub export_course: bool,
+    #[serde(default)]
+    pub export_timestamps: bool,
+    #[serde(default)]
+    pub export_components: bool,
+    #[serde(default, deserialize_with = "persistence::known_variant_or_default")]
+    pub export_image_path: ImagePathFormat,
+    // Limits the item export to one store's price levels
+    #[serde(default)]
+    pub export_store: Option<EntityId>,
+    // Template columns last picked for the item export, None writes them all
+    #[serde(default)]
+    pub export_columns: Option<Vec<usize>>,
+    // Every template column in picker order, with whether it's written
+    #[serde(skip)]
+    pub column_draft: Option<Vec<(usize, bool)>>,
+    #[serde(default)]
+    pub last_export_paths: BTreeMap<ExportKind, PathBuf>,
+    // Copy the file being replaced by a re-export to a timestamped name first
+    #[serde(default)]
+    pub keep_previous_export: bool,
+    #[serde(default)]
+    pub require_descriptions: bool,
+    // Refuse to save a price level whose name another level already uses
+    #[serde(default)]
+    pub block_duplicate_price_level_names: bool,
+    #[serde(default)]
+    pub online_menu_price_level: Option<EntityId>,
+    // Filled in on new items, the editor can still change them
+    #[serde(default)]
+    pub new_item_tax_group: Option<EntityId>,
+    #[serde(default)]
+    pub new_item_security_level: Option<EntityId>,
+    #[serde(default)]
+    pub new_item_report_category: Option<EntityId>,
+    #[serde(default)]
+    pub show_advanced: bool,
+    // Shows how long the items list search took, and whether it was cached
+    #[serde(default)]
+    pub show_search_timing: bool,
+    #[serde(default = "EntityKind::default_id_ranges")]
+    pub id_ranges: BTreeMap<EntityKind, RangeInclusive<EntityId>>,
+    // Text being typed into the range table, applied together
+    #[serde(skip)]
+    pub id_range_inputs: BTreeMap<EntityKind, (String, String)>,
+    #[serde(skip)]
+    pub id_range_error: Option<String>,
+    #[serde(skip)]
+    pub round_trip_report: Option<Result<Vec<String>, String>>,
+    #[serde(default = "lint::default_rules")]
+    pub lint_rules: Vec<lint::LintRule>,
+    #[serde(skip)]
+    pub rule_draft: lint::RuleDraft,
+    #[serde(skip)]
+    pub rule_error: Option<String>,
+    #[serde(skip)]
+    pub missing_images: Option<Vec<String>>,
+    #[serde(default = "default_backup_retention")]
+    pub backup_retention: usize,
+    #[serde(skip)]
+    pub storage_report: Option<persistence::StorageReport>,
+    #[serde(skip)]
+    pub storage_pending: Option<StorageAction>,
+    #[serde(skip)]
+    pub storage_message: String,
+    #[serde(skip)]
+    pub backups: Option<Vec<(PathBuf, SystemTime)>>,
+    #[serde(skip)]
+    pub backup_pending: Option<PathBuf>,
+    // Oldest versions beyond this are pruned when a new one is created
+    #[serde(default = "default_version_retention")]
+    pub version_retention: usize,
+    #[serde(skip)]
+    pub versions: Option<Vec<persistence::VersionInfo>>,
+    #[serde(skip)]
+    pub version_name: String,
+    #[serde(skip)]
+    pub version_detail: Option<(String, Vec<String>)>,
+    #[serde(skip)]
+    pub version_pending: Option<(VersionAction, persistence::VersionInfo)>,
+    #[serde(skip)]
+    pub version_message: String,
+}
+
+impl AppSettings {
+    // Effective id range for a module, falling back to the POS defaults
+    pub fn id_range(&self, kind: EntityKind) -> RangeInclusive<EntityId> {
+        self.id_ranges
+            .get(&kind)
+            .cloned()
+            .unwrap_or_else(|| kind.default_id_range())
+    }
+
+    fn id_range_input(&self, kind: EntityKind) -> (String, String) {
+        self.id_range_inputs.get(&kind).cloned().unwrap_or_else(|| {
+            let range = self.id_range(kind);
+            (range.start().to_string(), range.end().to_string())
+        })
+    }
+
+    pub fn export_options(&self) -> ExportOptions {
+        ExportOptions {
+            columns: self.export_columns.clone(),
+            tax_rate: self.export_tax_rate,
+            course: self.export_course,
+            timestamps: self.export_timestamps,
+            components: self.export_components,
+            image_path: self.export_image_path,
+        }
+    }
+}
+
+fn default_report_category_warn_percent() -> u8 {
+    90
+}
+
+fn default_price_scale() -> u32 {
+    2
+}
+
+pub const MAX_PRICE_SCALE: u32 = 4;
+
+fn parse_price_scale(input: &str) -> Result<u32, String> {
+    match input.trim().parse::<u32>() {
+        Ok(scale) if scale <= MAX_PRICE_SCALE => Ok(scale),
+        _ => Err(format!("Enter a whole number from 0 to {}", MAX_PRICE_SCALE)),
+    }
+}
+
+fn default_backup_retention() -> usize {
+    10
+}
+
+fn default_version_retention() -> usize {
+    20
 }
 
 impl Default for AppSettings {
@@ -58,11 +400,61 @@ impl Default for AppSettings {
             file_path: file_manager.get_default_path()
                 .to_string_lossy()
                 .into_owned(),
+            save_format: SaveFormat::default(),
             auto_save: true,
+            autosave_secs: 0,
             create_backups: true,
+            hold_to_delete: false,
+            repair_on_load: false,
+            bulk_confirm_threshold: crate::bulk::default_threshold(),
+            report_category_warn_percent: default_report_category_warn_percent(),
+            price_scale: default_price_scale(),
+            price_scale_input: None,
+            price_scale_error: None,
             app_theme: ThemeChoice::Dark,
+            accent_color: None,
+            accent_input: None,
+            accent_error: None,
             export_success: true,
             export_message: String::new(),
+            export_tax_rate: false,
+            export_course: false,
+            export_timestamps: false,
+            export_components: false,
+            export_image_path: ImagePathFormat::default(),
+            export_store: None,
+            export_columns: None,
+            column_draft: None,
+            last_export_paths: BTreeMap::new(),
+            keep_previous_export: false,
+            require_descriptions: false,
+            block_duplicate_price_level_names: false,
+            online_menu_price_level: None,
+            new_item_tax_group: None,
+            new_item_security_level: None,
+            new_item_report_category: None,
+            show_advanced: false,
+            show_search_timing: false,
+            id_ranges: EntityKind::default_id_ranges(),
+            id_range_inputs: BTreeMap::new(),
+            id_range_error: None,
+            round_trip_report: None,
+            lint_rules: lint::default_rules(),
+            rule_draft: lint::RuleDraft::default(),
+            rule_error: None,
+            missing_images: None,
+            backup_retention: default_backup_retention(),
+            storage_report: None,
+            storage_pending: None,
+            storage_message: String::new(),
+            backups: None,
+            backup_pending: None,
+            version_retention: default_version_retention(),
+            versions: None,
+            version_name: String::new(),
+            version_detail: None,
+            version_pending: None,
+            version_message: String::new(),
         }
     }
 }
@@ -81,10 +473,54 @@ pub fn update(
             settings.auto_save = enabled;
             crate::Action::none()
         }
+        Message::SelectSaveFormat(format) => {
+            settings.save_format = format;
+            crate::Action::none()
+        }
+        Message::UpdateAutosaveInterval(value) => {
+            let value = value.trim();
+            if value.is_empty() {
+                settings.autosave_secs = 0;
+            } else if let Ok(secs) = value.parse::<u64>() {
+                settings.autosave_secs = secs;
+            }
+            crate::Action::none()
+        }
         Message::ToggleBackups(enabled) => {
             settings.create_backups = enabled;
             crate::Action::none()
         }
+        Message::ToggleHoldToDelete(enabled) => {
+            settings.hold_to_delete = enabled;
+            crate::Action::none()
+        }
+        Message::ToggleRepairOnLoad(enabled) => {
+            settings.repair_on_load = enabled;
+            crate::Action::none()
+        }
+        Message::UpdateBulkThreshold(value) => {
+            if let Ok(threshold) = value.trim().parse::<usize>() {
+                settings.bulk_confirm_threshold = threshold;
+            }
+            crate::Action::none()
+        }
+        Message::UpdateReportCategoryWarnPercent(value) => {
+            if let Ok(percent) = value.trim().parse::<u8>() {
+                settings.report_category_warn_percent = percent.min(100);
+            }
+            crate::Action::none()
+        }
+        Message::UpdatePriceScale(value) => {
+            match parse_price_scale(&value) {
+                Ok(scale) => {
+                    settings.price_scale = scale;
+                    settings.price_scale_error = None;
+                }
+                Err(e) => settings.price_scale_error = Some(e),
+            }
+            settings.price_scale_input = Some(value);
+            crate::Action::none()
+        }
         Message::ValidateAndSave => {
             match file_manager.validate_path(&settings.file_path) {
                 Ok(()) => crate::Action::operation(Operation::Save(settings.clone())),
@@ -101,9 +537,96 @@ pub fn update(
             settings.app_theme = theme.clone();
             crate::Action::operation(Operation::ThemeChanged(theme))
         }
+        Message::SelectAccent(accent) => {
+            settings.accent_color = accent;
+            settings.accent_input = None;
+            settings.accent_error = None;
+            crate::Action::operation(Operation::AccentChanged(accent))
+        }
+        Message::UpdateAccentInput(input) => {
+            // Clearing the field goes back to the theme's own color
+            let parsed = if input.trim().is_empty() {
+                Ok(None)
+            } else {
+                accent::parse_hex(&input).map(Some)
+            };
+            settings.accent_input = Some(input);
+
+            match parsed {
+                Ok(accent) => {
+                    settings.accent_color = accent;
+                    settings.accent_error = None;
+                    crate::Action::operation(Operation::AccentChanged(accent))
+                }
+                Err(e) => {
+                    settings.accent_error = Some(e);
+                    crate::Action::none()
+                }
+            }
+        }
         Message::ExportItemsToCSV => {
             crate::Action::none()
         }
+        Message::ChooseExportColumns => {
+            // Last picked columns first in their order, then the rest unticked
+            let selected = export_items::selected_columns(settings.export_columns.as_deref());
+            let unselected = (0..export_items::EXPORT_HEADERS.len())
+                .filter(|index| !selected.contains(index))
+                .map(|index| (index, false));
+            settings.column_draft = Some(
+                selected.iter().map(|index| (*index, true)).chain(unselected).collect()
+            );
+            crate::Action::none()
+        }
+        Message::ToggleExportColumn(column, enabled) => {
+            if let Some(entry) = settings.column_draft.iter_mut().flatten().find(|(index, _)| *index == column) {
+                entry.1 = enabled;
+            }
+            crate::Action::none()
+        }
+        Message::SelectAllExportColumns(enabled) => {
+            for entry in settings.column_draft.iter_mut().flatten() {
+                entry.1 = enabled;
+            }
+            crate::Action::none()
+        }
+        Message::MoveExportColumnUp(position) => {
+            if let Some(draft) = settings.column_draft.as_mut() {
+                if position > 0 && position < draft.len() {
+                    draft.swap(position - 1, position);
+                }
+            }
+            crate::Action::none()
+        }
+        Message::MoveExportColumnDown(position) => {
+            if let Some(draft) = settings.column_draft.as_mut() {
+                if position + 1 < draft.len() {
+                    draft.swap(position, position + 1);
+                }
+            }
+            crate::Action::none()
+        }
+        Message::ConfirmExportColumns => {
+            let Some(draft) = settings.column_draft.take() else {
+                return crate::Action::none();
+            };
+
+            let columns: Vec<usize> = draft.iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(index, _)| *index)
+                .collect();
+            // The full layout in template order is stored as None, so files keep
+            // following the template if it ever gains columns
+            let is_full_layout = columns.len() == export_items::EXPORT_HEADERS.len()
+                && columns.iter().enumerate().all(|(position, index)| position == *index);
+            settings.export_columns = if is_full_layout { None } else { Some(columns) };
+
+            crate::Action::operation(Operation::Save(settings.clone())).with_task(Task::done(Message::OpenFile))
+        }
+        Message::CancelExportColumns => {
+            settings.column_draft = None;
+            crate::Action::none()
+        }
         Message::OpenFile => {
             let task = Task::perform(open_or_create_file(), Message::FileOpened);
 
@@ -115,156 +638,1428 @@ pub fn update(
                 Ok( (path, _err) ) => {
                     let update_message = Task::done(Message::UpdateExportSuccess(true));
 
-                    println!("Exporting item export to: {:?}", path);
+                    println!("Exporting item export to: {:?}", path);
+
+                    return crate::Action::operation(Operation::RequestItemsList(path)).with_task(update_message)
+                }
+                Err(e) => {
+                    println!("Error with the path: {:?}", e);
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Error with the path: {:?}", e)));
+
+                    let combined_task = update_success_task.chain(update_message_task);
+
+                    return crate::Action::none().with_task(combined_task)
+                }
+            }
+        }
+        Message::ProcessItems( (items, path, tax_rates) ) => {
+            println!("Processing Items!");
+            println!("Item Count: {}", &items.len());
+            println!("Path: {:?}", &path);
+
+            let task = Task::perform(
+                write_to_item_export(items, Some(path), tax_rates, settings.export_options()),
+                |result| Message::ExportFinished(ExportKind::Items, result)
+            );
+            println!("Task Created");
+            println!("export message: {:?}", settings.export_message);
+            println!("export success: {:?}", settings.export_success);
+
+            return crate::Action::none().with_task(task)
+        }
+        Message::ExportMessage(result) => {
+            println!("Export Message triggered: {:?}", &result);
+            match result {
+                Ok(saved_path) => {
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(true));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Items successfully exported to {}", saved_path.to_string_lossy().to_string())));
+
+                    let combined_task = update_success_task.chain(update_message_task);
+
+                    return crate::Action::none().with_task(combined_task)
+                }
+                Err(e) => {
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let message = match e {
+                        Error::InvalidData(msg) => format!("Export failed: {}", msg),
+                        e => format!("Export failed: {:?}", e),
+                    };
+                    let update_message_task = Task::done(Message::UpdateExportMessage(message));
+
+                    let combined_task = update_success_task.chain(update_message_task);
+
+                    return crate::Action::none().with_task(combined_task)
+                }
+            }
+        }
+        Message::ExportFinished(kind, result) => {
+            let saved_path = result.as_ref().ok().cloned();
+            let action = update(settings, Message::ExportMessage(result), file_manager);
+
+            match saved_path {
+                Some(path) => {
+                    settings.last_export_paths.insert(kind, path);
+                    crate::Action::operation(Operation::Save(settings.clone())).with_task(action.task)
+                }
+                None => action,
+            }
+        }
+        Message::ReExport(kind) => {
+            let Some(path) = settings.last_export_paths.get(&kind).cloned() else {
+                return crate::Action::none();
+            };
+
+            if settings.keep_previous_export {
+                if let Err(e) = keep_previous_export(&path) {
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(e));
+                    return crate::Action::none().with_task(update_success_task.chain(update_message_task));
+                }
+            }
+
+            match kind {
+                ExportKind::Items => crate::Action::operation(Operation::RequestItemsList(path)),
+                ExportKind::OnlineMenu => crate::Action::operation(Operation::RequestOnlineMenu(Some(path))),
+            }
+        }
+        Message::ClearExportPath(kind) => {
+            settings.last_export_paths.remove(&kind);
+            crate::Action::operation(Operation::Save(settings.clone()))
+        }
+        Message::ToggleKeepPreviousExport(enabled) => {
+            settings.keep_previous_export = enabled;
+            crate::Action::none()
+        }
+        Message::UpdateExportSuccess(b) => crate::Action::operation(Operation::UpdateExportSuccess(b)),
+        Message::UpdateExportMessage(msg) => crate::Action::operation(Operation::UpdateExportMessage(msg)),
+        Message::ToggleExportTaxRate(enabled) => {
+            settings.export_tax_rate = enabled;
+            crate::Action::none()
+        }
+        Message::ToggleExportCourse(enabled) => {
+            settings.export_course = enabled;
+            crate::Action::none()
+        }
+        Message::ToggleExportTimestamps(enabled) => {
+            settings.export_timestamps = enabled;
+            crate::Action::none()
+        }
+        Message::ToggleExportComponents(enabled) => {
+            settings.export_components = enabled;
+            crate::Action::none()
+        }
+        Message::SelectExportImagePath(format) => {
+            settings.export_image_path = format;
+            crate::Action::none()
+        }
+        Message::SelectExportStore(store) => {
+            settings.export_store = store;
+            crate::Action::none()
+        }
+        Message::ToggleBlockDuplicatePriceLevelNames(enabled) => {
+            settings.block_duplicate_price_level_names = enabled;
+            crate::Action::none()
+        }
+        Message::FixPriceLevel(id) => {
+            crate::Action::operation(Operation::EditPriceLevel(id))
+        }
+        Message::ToggleRequireDescriptions(enabled) => {
+            settings.require_descriptions = enabled;
+            crate::Action::none()
+        }
+        Message::SelectOnlineMenuPriceLevel(level) => {
+            settings.online_menu_price_level = level;
+            crate::Action::none()
+        }
+        Message::SelectNewItemTaxGroup(id) => {
+            settings.new_item_tax_group = id;
+            crate::Action::none()
+        }
+        Message::SelectNewItemSecurityLevel(id) => {
+            settings.new_item_security_level = id;
+            crate::Action::none()
+        }
+        Message::SelectNewItemReportCategory(id) => {
+            settings.new_item_report_category = id;
+            crate::Action::none()
+        }
+        Message::ExportOnlineMenu => {
+            crate::Action::operation(Operation::RequestOnlineMenu(None))
+        }
+        Message::ToggleAdvanced(enabled) => {
+            settings.show_advanced = enabled;
+            crate::Action::none()
+        }
+        Message::ToggleSearchTiming(enabled) => {
+            settings.show_search_timing = enabled;
+            crate::Action::none()
+        }
+        Message::UpdateIdRangeStart(kind, value) => {
+            let (_, end) = settings.id_range_input(kind);
+            settings.id_range_inputs.insert(kind, (value, end));
+            crate::Action::none()
+        }
+        Message::UpdateIdRangeEnd(kind, value) => {
+            let (start, _) = settings.id_range_input(kind);
+            settings.id_range_inputs.insert(kind, (start, value));
+            crate::Action::none()
+        }
+        Message::ApplyIdRanges => {
+            let mut ranges = settings.id_ranges.clone();
+
+            for (kind, (start, end)) in &settings.id_range_inputs {
+                let (Ok(start), Ok(end)) = (start.trim().parse::<EntityId>(), end.trim().parse::<EntityId>()) else {
+                    settings.id_range_error = Some(format!("{} range must be whole numbers", kind));
+                    return crate::Action::none();
+                };
+
+                if start < 0 {
+                    settings.id_range_error = Some(format!("{} range cannot be negative", kind));
+                    return crate::Action::none();
+                }
+                if start > end {
+                    settings.id_range_error = Some(format!("{} range start must not exceed its end", kind));
+                    return crate::Action::none();
+                }
+
+                ranges.insert(*kind, start..=end);
+            }
+
+            settings.id_ranges = ranges;
+            settings.id_range_inputs.clear();
+            settings.id_range_error = None;
+            crate::Action::none()
+        }
+        Message::ResetIdRanges => {
+            settings.id_ranges = EntityKind::default_id_ranges();
+            settings.id_range_inputs.clear();
+            settings.id_range_error = None;
+            crate::Action::none()
+        }
+        Message::RenumberOutOfRange(kind) => {
+            crate::Action::operation(Operation::RenumberOutOfRange(kind))
+        }
+        Message::ImportItems => {
+            crate::Action::operation(Operation::ImportItems)
+        }
+        Message::SaveMenuAs => {
+            crate::Action::operation(Operation::SaveMenuAs)
+        }
+        Message::OpenMenuFile => {
+            crate::Action::operation(Operation::OpenMenuFile)
+        }
+        Message::ImportRecord => {
+            crate::Action::operation(Operation::ImportRecord)
+        }
+        Message::VerifyRoundTrip => {
+            settings.round_trip_report = None;
+            crate::Action::operation(Operation::VerifyRoundTrip)
+        }
+        Message::RoundTripVerified(result) => {
+            settings.round_trip_report = Some(result);
+            crate::Action::none()
+        }
+        Message::UpdateRuleName(name) => {
+            settings.rule_draft.name = name;
+            crate::Action::none()
+        }
+        Message::SelectRuleKind(kind) => {
+            settings.rule_draft.kind = kind;
+            crate::Action::none()
+        }
+        Message::SelectRuleTextField(field) => {
+            settings.rule_draft.text_field = field;
+            crate::Action::none()
+        }
+        Message::UpdateRuleText(value) => {
+            settings.rule_draft.text = value;
+            crate::Action::none()
+        }
+        Message::SelectRuleReference(field) => {
+            settings.rule_draft.reference = field;
+            crate::Action::none()
+        }
+        Message::SelectRulePrinter(id) => {
+            settings.rule_draft.printer = Some(id);
+            crate::Action::none()
+        }
+        Message::UpdateRuleMinPrice(value) => {
+            settings.rule_draft.min_price = value;
+            crate::Action::none()
+        }
+        Message::UpdateRuleMaxPrice(value) => {
+            settings.rule_draft.max_price = value;
+            crate::Action::none()
+        }
+        Message::SelectRuleReportCategory(id) => {
+            settings.rule_draft.report_category = id;
+            crate::Action::none()
+        }
+        Message::SelectRuleSeverity(severity) => {
+            settings.rule_draft.severity = severity;
+            crate::Action::none()
+        }
+        Message::AddRule => {
+            match settings.rule_draft.build() {
+                Ok(rule) => {
+                    settings.lint_rules.push(rule);
+                    settings.rule_draft = lint::RuleDraft::default();
+                    settings.rule_error = None;
+                }
+                Err(e) => {
+                    settings.rule_error = Some(e);
+                }
+            }
+            crate::Action::none()
+        }
+        Message::RemoveRule(index) => {
+            if index < settings.lint_rules.len() {
+                settings.lint_rules.remove(index);
+            }
+            crate::Action::none()
+        }
+        Message::ScanImages => {
+            settings.missing_images = None;
+            crate::Action::operation(Operation::ScanImages)
+        }
+        Message::ImagesScanned(missing) => {
+            settings.missing_images = Some(missing);
+            crate::Action::none()
+        }
+        Message::ScanStorage => {
+            let path = PathBuf::from(&settings.file_path);
+            let backups_path = path.clone();
+            let task = Task::batch([
+                Task::perform(
+                    async move { persistence::FileManager::storage_report(&path) },
+                    Message::StorageScanned
+                ),
+                Task::perform(
+                    async move { persistence::FileManager::list_backups(&backups_path) },
+                    Message::BackupsScanned
+                ),
+            ]);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::StorageScanned(result) => {
+            match result {
+                Ok(report) => settings.storage_report = Some(report),
+                Err(e) => settings.storage_message = e,
+            }
+            crate::Action::none()
+        }
+        Message::BackupsScanned(result) => {
+            match result {
+                Ok(backups) => settings.backups = Some(backups),
+                Err(e) => settings.storage_message = e,
+            }
+            crate::Action::none()
+        }
+        Message::RequestRestoreBackup(backup) => {
+            settings.backup_pending = Some(backup);
+            crate::Action::none()
+        }
+        Message::CancelRestoreBackup => {
+            settings.backup_pending = None;
+            crate::Action::none()
+        }
+        Message::ConfirmRestoreBackup => {
+            match settings.backup_pending.take() {
+                Some(backup) => crate::Action::operation(Operation::RestoreBackup(backup)),
+                None => crate::Action::none(),
+            }
+        }
+        Message::UpdateBackupRetention(value) => {
+            if let Ok(keep) = value.trim().parse::<usize>() {
+                settings.backup_retention = keep;
+            }
+            crate::Action::none()
+        }
+        Message::RequestStorageAction(action) => {
+            settings.storage_pending = Some(action);
+            crate::Action::none()
+        }
+        Message::CancelStorageAction => {
+            settings.storage_pending = None;
+            crate::Action::none()
+        }
+        Message::ConfirmStorageAction => {
+            let Some(action) = settings.storage_pending.take() else {
+                return crate::Action::none();
+            };
+
+            let path = PathBuf::from(&settings.file_path);
+            let keep = settings.backup_retention;
+            let task = Task::perform(
+                async move {
+                    match action {
+                        StorageAction::PruneBackups => persistence::FileManager::prune_backups(&path, keep)
+                            .map(|count| format!("Removed {} old backup(s)", count)),
+                        StorageAction::DeleteSidecars => persistence::FileManager::delete_sidecars(&path)
+                            .map(|count| format!("Removed {} leftover file(s)", count)),
+                    }
+                },
+                Message::StorageActionDone
+            );
+
+            crate::Action::none().with_task(task)
+        }
+        Message::StorageActionDone(result) => {
+            settings.storage_message = match result {
+                Ok(msg) => msg,
+                Err(e) => e,
+            };
+
+            // Refresh the sizes after every action
+            crate::Action::none().with_task(Task::done(Message::ScanStorage))
+        }
+        Message::ScanVersions => {
+            let path = PathBuf::from(&settings.file_path);
+            let task = Task::perform(
+                async move { persistence::FileManager::list_versions(&path) },
+                Message::VersionsScanned
+            );
+
+            crate::Action::none().with_task(task)
+        }
+        Message::VersionsScanned(result) => {
+            match result {
+                Ok(versions) => settings.versions = Some(versions),
+                Err(e) => settings.version_message = e,
+            }
+            crate::Action::none()
+        }
+        Message::UpdateVersionName(name) => {
+            settings.version_name = name;
+            crate::Action::none()
+        }
+        Message::CreateVersion => {
+            let name = settings.version_name.trim().to_string();
+            if name.is_empty() {
+                settings.version_message = "Give the version a name, e.g. Fall 2024".to_string();
+                return crate::Action::none();
+            }
+
+            settings.version_name.clear();
+            crate::Action::operation(Operation::CreateVersion(name))
+        }
+        Message::UpdateVersionRetention(value) => {
+            if let Ok(keep) = value.trim().parse::<usize>() {
+                settings.version_retention = keep.max(1);
+            }
+            crate::Action::none()
+        }
+        Message::ShowVersionSummary(version) => {
+            let task = Task::perform(
+                async move {
+                    let (state, warnings) = persistence::load_from_file(&version.path.to_string_lossy())?;
+                    let mut lines: Vec<String> = crate::state_diff::counts(&state)
+                        .into_iter()
+                        .map(|(label, count)| format!("{}: {}", label, count))
+                        .collect();
+                    lines.extend(warnings.into_iter().map(|warning| format!("Warning: {}", warning)));
+
+                    Ok::<_, String>((format!("{} ({})", version.name, version.created), lines))
+                },
+                Message::VersionDetail
+            );
+
+            crate::Action::none().with_task(task)
+        }
+        Message::DiffVersion(version) => {
+            crate::Action::operation(Operation::DiffVersion(version))
+        }
+        Message::VersionDetail(result) => {
+            match result {
+                Ok(detail) => settings.version_detail = Some(detail),
+                Err(e) => settings.version_message = e,
+            }
+            crate::Action::none()
+        }
+        Message::CloseVersionDetail => {
+            settings.version_detail = None;
+            crate::Action::none()
+        }
+        Message::RequestVersionAction(action, version) => {
+            settings.version_pending = Some((action, version));
+            crate::Action::none()
+        }
+        Message::CancelVersionAction => {
+            settings.version_pending = None;
+            crate::Action::none()
+        }
+        Message::ConfirmVersionAction => {
+            let Some((action, version)) = settings.version_pending.take() else {
+                return crate::Action::none();
+            };
+
+            match action {
+                VersionAction::Restore => crate::Action::operation(Operation::RestoreVersion(version)),
+                VersionAction::Delete => {
+                    let task = Task::perform(
+                        async move {
+                            persistence::FileManager::delete_version(&version.path)
+                                .map(|_| format!("Deleted version {}", version.name))
+                        },
+                        Message::VersionActionDone
+                    );
+
+                    crate::Action::none().with_task(task)
+                }
+            }
+        }
+        Message::VersionActionDone(result) => {
+            settings.version_message = match result {
+                Ok(msg) => msg,
+                Err(e) => e,
+            };
+
+            crate::Action::none().with_task(Task::done(Message::ScanVersions))
+        }
+    }
+}
+
+pub fn view<'a>(
+    settings: &'a AppSettings,
+    error_message: Option<&'a str>,
+    lint_issues: Vec<lint::LintIssue>,
+    report_categories: Vec<ReportCategory>,
+    printer_logicals: Vec<PrinterLogical>,
+    export_preview: Vec<String>,
+    export_row_count: usize,
+    price_levels: Vec<PriceLevel>,
+    stores: Vec<Store>,
+    id_range_warnings: Vec<(EntityKind, String)>,
+    tax_groups: Vec<TaxGroup>,
+    security_levels: Vec<SecurityLevel>,
+) -> Element<'a, Message> {
+
+    let title_row = row![
+        text("Settings").style(Modern::primary_text()).size(18)
+    ].padding(15);
+
+    let setting_column = column![
+        text("Data File Path:"),
+        text_input("Path to RON file", &settings.file_path)
+            .on_input(Message::UpdateFilePath)
+            .style(Modern::inline_text_input())
+            .padding(5),
+
+        row![
+            button("Save Menu As...")
+                .on_press(Message::SaveMenuAs)
+                .style(Modern::system_button()),
+            button("Open Menu File...")
+                .on_press(Message::OpenMenuFile)
+                .style(Modern::system_button()),
+            text("Save As writes a copy and keeps working on this file").style(Modern::secondary_text()).size(13),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+
+        row![
+            text("Save format:"),
+            pick_list(SaveFormat::ALL, Some(settings.save_format), Message::SelectSaveFormat)
+                .style(Modern::pick_list()),
+            text("Used from the next save, files in either format load").style(Modern::secondary_text()).size(13),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+        
+        row![
+            checkbox("Auto-save on changes", settings.auto_save)
+            .on_toggle(Message::ToggleAutoSave)
+            .style(Modern::checkbox()),
+        
+            checkbox("Create backups before saving", settings.create_backups)
+                .on_toggle(Message::ToggleBackups)
+                .style(Modern::checkbox()),
+        ].spacing(15),
+
+        checkbox("Hold delete buttons to confirm (unreferenced records skip the dialog)", settings.hold_to_delete)
+            .on_toggle(Message::ToggleHoldToDelete)
+            .style(Modern::checkbox()),
+
+        checkbox("Repair references to missing records when loading", settings.repair_on_load)
+            .on_toggle(Message::ToggleRepairOnLoad)
+            .style(Modern::checkbox()),
+
+        accent_view(settings),
+
+        row![
+            text("Confirm bulk changes touching more than"),
+            text_input("50", &settings.bulk_confirm_threshold.to_string())
+                .on_input(Message::UpdateBulkThreshold)
+                .style(Modern::inline_text_input())
+                .width(60),
+            text("records"),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+
+        row![
+            text("Autosave unsaved changes every"),
+            text_input("0", &settings.autosave_secs.to_string())
+                .on_input(Message::UpdateAutosaveInterval)
+                .style(Modern::inline_text_input())
+                .width(60),
+            text("seconds (0 turns it off)"),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+
+        row![
+            text("Warn when report category ids are"),
+            text_input("90", &settings.report_category_warn_percent.to_string())
+                .on_input(Message::UpdateReportCategoryWarnPercent)
+                .style(Modern::inline_text_input())
+                .width(60),
+            text("% used"),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+
+        row![
+            text("Round prices and tax rates to"),
+            text_input("2", &settings.price_scale_input.clone().unwrap_or_else(|| settings.price_scale.to_string()))
+                .on_input(Message::UpdatePriceScale)
+                .style(Modern::validated_text_input(settings.price_scale_error.is_some()))
+                .width(60),
+            text("decimal places"),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+
+        if let Some(error) = &settings.price_scale_error {
+            text(error).size(12).style(Modern::error_text())
+        } else {
+            text("")
+        },
+
+        new_item_defaults_view(settings, tax_groups, security_levels, report_categories.clone()),
+        
+        if let Some(error) = error_message {
+            text(error).style(Modern::error_text())
+        } else {
+            text("")
+        },
+
+        row![
+            button("Save Settings")
+                .on_press(Message::ValidateAndSave)
+                .style(accent::primary_button()),
+        ]
+        .spacing(10),
+    ].spacing(10);
+
+    let setting_container = container(
+        column![
+            title_row,
+            setting_column,
+        ]
+        .spacing(10)
+        .padding(15)
+    )
+    .width(805)
+    .style(Modern::card_container());
+
+    let import_export = container(
+        // Add an export section
+        column![
+            text("Data Export").size(18),
+            row![
+                button("Export Menu Items to CSV")
+                    .on_press(Message::ChooseExportColumns)
+                    .style(Modern::system_button()),
+                re_export_button(settings, ExportKind::Items),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            export_columns_view(settings),
+            checkbox("Keep a timestamped copy of the previous file when re-exporting", settings.keep_previous_export)
+                .on_toggle(Message::ToggleKeepPreviousExport)
+                .style(Modern::checkbox()),
+            checkbox("Append tax rate column to item export", settings.export_tax_rate)
+                .on_toggle(Message::ToggleExportTaxRate)
+                .style(Modern::checkbox()),
+            checkbox("Append course column to item export", settings.export_course)
+                .on_toggle(Message::ToggleExportCourse)
+                .style(Modern::checkbox()),
+            checkbox("Append combo components column to item export", settings.export_components)
+                .on_toggle(Message::ToggleExportComponents)
+                .style(Modern::checkbox()),
+            checkbox("Append created and modified columns to item export", settings.export_timestamps)
+                .on_toggle(Message::ToggleExportTimestamps)
+                .style(Modern::checkbox()),
+            pick_list(ImagePathFormat::ALL, Some(settings.export_image_path), Message::SelectExportImagePath)
+                .style(Modern::pick_list()),
+            row![
+                pick_list(
+                    stores.clone(),
+                    settings.export_store
+                        .and_then(|id| stores.iter().find(|s| s.id == id).cloned()),
+                    |store: Store| Message::SelectExportStore(Some(store.id))
+                )
+                .placeholder("All stores")
+                .style(Modern::pick_list()),
+                button("All Stores")
+                    .on_press(Message::SelectExportStore(None))
+                    .style(Modern::gray_button()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            row![
+                button("Export Online Menu (JSON)")
+                    .on_press(Message::ExportOnlineMenu)
+                    .style(Modern::system_button()),
+                re_export_button(settings, ExportKind::OnlineMenu),
+                pick_list(
+                    price_levels.clone(),
+                    settings.online_menu_price_level
+                        .and_then(|id| price_levels.iter().find(|l| l.id == id).cloned()),
+                    |level: PriceLevel| Message::SelectOnlineMenuPriceLevel(Some(level.id))
+                )
+                .placeholder("Default price")
+                .style(Modern::pick_list()),
+                button("Default Price")
+                    .on_press(Message::SelectOnlineMenuPriceLevel(None))
+                    .style(Modern::gray_button()),
+                checkbox("Require descriptions on active items", settings.require_descriptions)
+                    .on_toggle(Message::ToggleRequireDescriptions)
+                    .style(Modern::checkbox()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            row![
+                button("Import Menu Items from CSV")
+                    .on_press(Message::ImportItems)
+                    .style(Modern::system_button()),
+                button("Import Single Record (JSON)")
+                    .on_press(Message::ImportRecord)
+                    .style(Modern::system_button()),
+            ]
+            .spacing(10),
+            text(&settings.export_message).size(12).style(
+                Modern::validated_text(!settings.export_success.clone())
+            ),
+            text(format!(
+                "Preview (first {} of {} rows, header shown for reference only)",
+                export_preview.len(),
+                export_row_count
+            )).size(12).style(Modern::secondary_text()),
+            container(
+                scrollable(
+                    column![
+                        text(crate::items::export_items::export_header(&settings.export_options()))
+                            .size(11)
+                            .font(iced::Font::MONOSPACE)
+                            .style(Modern::primary_text()),
+                        column(
+                            export_preview
+                                .into_iter()
+                                .map(|line| text(line).size(11).font(iced::Font::MONOSPACE).into())
+                                .collect::<Vec<_>>()
+                        )
+                        .spacing(2),
+                    ]
+                    .spacing(4)
+                )
+                .direction(scrollable::Direction::Both {
+                    vertical: scrollable::Scrollbar::default(),
+                    horizontal: scrollable::Scrollbar::default(),
+                })
+                .height(200)
+            )
+            .style(Modern::sheet_container())
+            .padding(5),
+        ]
+
+        .spacing(10)
+        .padding(10), 
+    )
+    .style(Modern::card_container())
+    .width(805)
+    .padding(15);
+
+
+    // Levels sharing a name, as (name, ids) for the data checks
+    let duplicate_price_levels = entity_component::duplicate_name_groups(&price_levels)
+        .into_iter()
+        .map(|group| (group[0].name.clone(), group.iter().map(|level| level.id).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+
+    let rules = rules_view(settings, lint_issues, report_categories, printer_logicals, duplicate_price_levels);
+
+    let storage = storage_view(settings);
+
+    let versions = versions_view(settings);
+
+    let advanced = advanced_view(settings, id_range_warnings);
+
+    scrollable(
+        column![
+            setting_container,
+            import_export,
+            storage,
+            versions,
+            rules,
+            advanced,
+        ]
+        .spacing(10)
+    )
+    .height(Length::Fill)
+    .into()
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn storage_view<'a>(settings: &'a AppSettings) -> Element<'a, Message> {
+    let sizes: Element<'a, Message> = match &settings.storage_report {
+        Some(report) => column![
+            text(format!("Menu file: {}", format_size(report.main_file))),
+            text(format!("Backups: {} file(s), {}", report.backups.len(), format_size(report.backups_size()))),
+            text(format!("Leftover files: {} file(s), {}", report.sidecars.len(), format_size(report.sidecars_size()))),
+            text(format!("Total: {}", format_size(report.total_size()))).style(Modern::primary_text()),
+        ]
+        .spacing(5)
+        .into(),
+        None => text("Scanning...").style(Modern::secondary_text()).into(),
+    };
+
+    let actions: Element<'a, Message> = match settings.storage_pending {
+        Some(action) => row![
+            text(format!("Are you sure you want to {}?", action)),
+            button("Confirm")
+                .on_press(Message::ConfirmStorageAction)
+                .style(Modern::danger_button()),
+            button("Cancel")
+                .on_press(Message::CancelStorageAction)
+                .style(Modern::system_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into(),
+        None => row![
+            text("Backups to keep:"),
+            text_input("10", &settings.backup_retention.to_string())
+                .on_input(Message::UpdateBackupRetention)
+                .style(Modern::inline_text_input())
+                .width(60),
+            button("Prune Backups")
+                .on_press(Message::RequestStorageAction(StorageAction::PruneBackups))
+                .style(Modern::warning_button()),
+            button("Delete Leftover Files")
+                .on_press(Message::RequestStorageAction(StorageAction::DeleteSidecars))
+                .style(Modern::warning_button()),
+            button("Refresh")
+                .on_press(Message::ScanStorage)
+                .style(Modern::system_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into(),
+    };
+
+    let backups: Element<'a, Message> = match &settings.backups {
+        Some(backups) if backups.is_empty() => text("No backups yet").style(Modern::secondary_text()).into(),
+        Some(backups) => container(
+            scrollable(
+                column(
+                    backups.iter().map(|(backup, modified)| {
+                        row![
+                            text(backup.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()).width(330),
+                            text(chrono::DateTime::<chrono::Local>::from(*modified).format("%Y-%m-%d %H:%M").to_string())
+                                .width(140)
+                                .style(Modern::secondary_text()),
+                            button("Restore")
+                                .on_press(Message::RequestRestoreBackup(backup.clone()))
+                                .style(Modern::warning_button()),
+                        ]
+                        .spacing(8)
+                        .align_y(iced::Alignment::Center)
+                        .into()
+                    })
+                )
+                .spacing(5)
+            )
+        )
+        .max_height(180)
+        .into(),
+        None => column![].into(),
+    };
+
+    let restore_pending: Element<'a, Message> = match &settings.backup_pending {
+        Some(backup) => row![
+            text(format!(
+                "Restore {}? The current data is backed up first and settings are kept.",
+                backup.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+            )),
+            button("Confirm")
+                .on_press(Message::ConfirmRestoreBackup)
+                .style(Modern::danger_button()),
+            button("Cancel")
+                .on_press(Message::CancelRestoreBackup)
+                .style(Modern::system_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into(),
+        None => column![].into(),
+    };
+
+    container(
+        column![
+            text("Storage").size(18),
+            sizes,
+            actions,
+            backups,
+            restore_pending,
+            text(&settings.storage_message).size(12).style(Modern::secondary_text()),
+        ]
+        .spacing(10)
+        .padding(10)
+    )
+    .style(Modern::card_container())
+    .width(805)
+    .padding(15)
+    .into()
+}
+
+fn versions_view<'a>(settings: &'a AppSettings) -> Element<'a, Message> {
+    let create = row![
+        text_input("Version name, e.g. Fall 2024", &settings.version_name)
+            .on_input(Message::UpdateVersionName)
+            .on_submit(Message::CreateVersion)
+            .style(Modern::inline_text_input())
+            .width(250),
+        button("Create Version...")
+            .on_press(Message::CreateVersion)
+            .style(accent::primary_button()),
+        text("Versions to keep:"),
+        text_input("20", &settings.version_retention.to_string())
+            .on_input(Message::UpdateVersionRetention)
+            .style(Modern::inline_text_input())
+            .width(60),
+        button("Refresh")
+            .on_press(Message::ScanVersions)
+            .style(Modern::system_button()),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+
+    let list: Element<'a, Message> = match &settings.versions {
+        Some(versions) if versions.is_empty() => text("No versions yet").style(Modern::secondary_text()).into(),
+        Some(versions) => column(
+            versions.iter().map(|version| {
+                row![
+                    text(&version.name).width(220),
+                    text(&version.created).width(140).style(Modern::secondary_text()),
+                    text(format_size(version.size)).width(70).style(Modern::secondary_text()),
+                    button("Summary")
+                        .on_press(Message::ShowVersionSummary(version.clone()))
+                        .style(Modern::system_button()),
+                    button("Diff")
+                        .on_press(Message::DiffVersion(version.clone()))
+                        .style(Modern::system_button()),
+                    button("Restore")
+                        .on_press(Message::RequestVersionAction(VersionAction::Restore, version.clone()))
+                        .style(Modern::warning_button()),
+                    button("Delete")
+                        .on_press(Message::RequestVersionAction(VersionAction::Delete, version.clone()))
+                        .style(Modern::danger_button()),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
+        )
+        .spacing(5)
+        .into(),
+        None => text("Scanning...").style(Modern::secondary_text()).into(),
+    };
+
+    let pending: Element<'a, Message> = match &settings.version_pending {
+        Some((action, version)) => row![
+            text(match action {
+                VersionAction::Restore => format!(
+                    "Restore {}? The current data is backed up first and settings are kept.",
+                    version.name
+                ),
+                VersionAction::Delete => format!("Delete version {}?", version.name),
+            }),
+            button("Confirm")
+                .on_press(Message::ConfirmVersionAction)
+                .style(Modern::danger_button()),
+            button("Cancel")
+                .on_press(Message::CancelVersionAction)
+                .style(Modern::system_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into(),
+        None => column![].into(),
+    };
+
+    let detail: Element<'a, Message> = match &settings.version_detail {
+        Some((title, lines)) => column![
+            row![
+                text(title).style(Modern::primary_text()),
+                iced::widget::horizontal_space(),
+                button("Close")
+                    .on_press(Message::CloseVersionDetail)
+                    .style(Modern::system_button()),
+            ]
+            .align_y(iced::Alignment::Center),
+            container(
+                scrollable(
+                    column(lines.iter().map(|line| text(line).size(12).into()))
+                        .spacing(2)
+                )
+                .height(200)
+            )
+            .style(Modern::sheet_container())
+            .padding(5),
+        ]
+        .spacing(5)
+        .into(),
+        None => column![].into(),
+    };
+
+    container(
+        column![
+            text("Versions").size(18),
+            text("Named snapshots of the whole menu, to compare against or roll back to later")
+                .size(12)
+                .style(Modern::secondary_text()),
+            create,
+            list,
+            pending,
+            detail,
+            text(&settings.version_message).size(12).style(Modern::secondary_text()),
+        ]
+        .spacing(10)
+        .padding(10)
+    )
+    .style(Modern::card_container())
+    .width(805)
+    .padding(15)
+    .into()
+}
 
-                    return crate::Action::operation(Operation::RequestItemsList(path)).with_task(update_message)
-                }
-                Err(e) => {
-                    println!("Error with the path: {:?}", e);
-                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
-                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Error with the path: {:?}", e)));
+fn advanced_view<'a>(settings: &'a AppSettings, id_range_warnings: Vec<(EntityKind, String)>) -> Element<'a, Message> {
+    let toggle = checkbox("Show developer tools", settings.show_advanced)
+        .on_toggle(Message::ToggleAdvanced)
+        .style(Modern::checkbox());
 
-                    let combined_task = update_success_task.chain(update_message_task);
+    if !settings.show_advanced {
+        return container(toggle)
+            .style(Modern::card_container())
+            .width(805)
+            .padding(15)
+            .into();
+    }
 
-                    return crate::Action::none().with_task(combined_task)
-                }
-            }
+    let report: Element<'a, Message> = match &settings.round_trip_report {
+        Some(Ok(differences)) if differences.is_empty() => {
+            text("Round trip clean, every record survived export and import")
+                .style(Modern::primary_text())
+                .into()
         }
-        Message::ProcessItems( (items, path) ) => {
-            println!("Processing Items!");
-            println!("Item Count: {}", &items.len());
-            println!("Path: {:?}", &path);
-
-            let task = Task::perform(
-                write_to_item_export(items, Some(path)),
-                Message::ExportMessage
-            );
-            println!("Task Created");
-            println!("export message: {:?}", settings.export_message);
-            println!("export success: {:?}", settings.export_success);
+        Some(Ok(differences)) => column![
+            text(format!("{} record(s) did not survive the round trip", differences.len()))
+                .style(Modern::error_text()),
+            container(
+                scrollable(
+                    column(
+                        differences
+                            .iter()
+                            .map(|line| text(line).size(12).into())
+                            .collect::<Vec<_>>()
+                    )
+                    .spacing(3)
+                )
+                .height(200)
+            )
+            .style(Modern::sheet_container())
+            .padding(5),
+        ]
+        .spacing(10)
+        .into(),
+        Some(Err(e)) => text(e).style(Modern::error_text()).into(),
+        None => text("").into(),
+    };
 
-            return crate::Action::none().with_task(task)
-        }
-        Message::ExportMessage(result) => {
-            println!("Export Message triggered: {:?}", &result);
-            match result {
-                Ok(saved_path) => {
-                    let update_success_task = Task::done(Message::UpdateExportSuccess(true));
-                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Items successfully exported to {}", saved_path.to_string_lossy().to_string())));
+    container(
+        column![
+            text("Developer Tools").size(18),
+            toggle,
+            row![
+                button("Verify Round-Trip")
+                    .on_press(Message::VerifyRoundTrip)
+                    .style(Modern::system_button()),
+                text("Sends items through the CSV export and the whole menu through each save format, then compares every record")
+                    .size(12)
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            report,
+            checkbox("Show item search timing", settings.show_search_timing)
+                .on_toggle(Message::ToggleSearchTiming)
+                .style(Modern::checkbox()),
+            id_ranges_view(settings, id_range_warnings),
+        ]
+        .spacing(10)
+        .padding(10)
+    )
+    .style(Modern::card_container())
+    .width(805)
+    .padding(15)
+    .into()
+}
 
-                    let combined_task = update_success_task.chain(update_message_task);
+fn id_ranges_view<'a>(settings: &'a AppSettings, id_range_warnings: Vec<(EntityKind, String)>) -> Element<'a, Message> {
+    // Kinds that can be moved back into range, rewriting the item references
+    let renumberable: Vec<EntityKind> = [EntityKind::ReportCategory, EntityKind::RevenueCategory]
+        .into_iter()
+        .filter(|kind| id_range_warnings.iter().any(|(warning_kind, _)| warning_kind == kind))
+        .collect();
 
-                    return crate::Action::none().with_task(combined_task)
-                }
-                Err(e) => {
-                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
-                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Items successfully exported to {:?}", e)));
+    let table = column(
+        EntityKind::ALL
+            .iter()
+            .map(|kind| {
+                let kind = *kind;
+                let (start, end) = settings.id_range_input(kind);
 
-                    let combined_task = update_success_task.chain(update_message_task);
+                row![
+                    text(kind.to_string()).width(150),
+                    text_input("Start", &start)
+                        .on_input(move |value| Message::UpdateIdRangeStart(kind, value))
+                        .style(Modern::inline_text_input())
+                        .width(80),
+                    text("to"),
+                    text_input("End", &end)
+                        .on_input(move |value| Message::UpdateIdRangeEnd(kind, value))
+                        .style(Modern::inline_text_input())
+                        .width(80),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
+            .collect::<Vec<_>>()
+    )
+    .spacing(5);
 
-                    return crate::Action::none().with_task(combined_task)
-                }
-            }
-        }
-        Message::UpdateExportSuccess(b) => crate::Action::operation(Operation::UpdateExportSuccess(b)),
-        Message::UpdateExportMessage(msg) => crate::Action::operation(Operation::UpdateExportMessage(msg)),
-    }
+    column![
+        text("ID Ranges").size(16),
+        table,
+        row![
+            button("Apply Ranges")
+                .on_press(Message::ApplyIdRanges)
+                .style(accent::primary_button()),
+            button("Reset to Defaults")
+                .on_press(Message::ResetIdRanges)
+                .style(Modern::gray_button()),
+        ]
+        .spacing(10),
+        if let Some(error) = &settings.id_range_error {
+            text(error).style(Modern::error_text())
+        } else {
+            text("")
+        },
+        column(
+            id_range_warnings
+                .into_iter()
+                .map(|(_, warning)| text(warning).size(12).style(Modern::error_text()).into())
+                .collect::<Vec<_>>()
+        )
+        .spacing(3),
+        row(
+            renumberable
+                .into_iter()
+                .map(|kind| {
+                    button(text(format!("Renumber out-of-range {} IDs", kind)))
+                        .on_press(Message::RenumberOutOfRange(kind))
+                        .style(Modern::warning_button())
+                        .into()
+                })
+                .collect::<Vec<_>>()
+        )
+        .spacing(10),
+    ]
+    .spacing(10)
+    .into()
 }
 
-pub fn view<'a>(
+fn rules_view<'a>(
     settings: &'a AppSettings,
-    error_message: Option<&'a str>,
+    lint_issues: Vec<lint::LintIssue>,
+    report_categories: Vec<ReportCategory>,
+    printer_logicals: Vec<PrinterLogical>,
+    duplicate_price_levels: Vec<(String, Vec<EntityId>)>,
 ) -> Element<'a, Message> {
+    let draft = &settings.rule_draft;
 
-    let title_row = row![
-        text("Settings").style(Modern::primary_text()).size(18)
-    ].padding(15);
+    let rule_list = column(
+        settings.lint_rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                let scope = rule.report_category
+                    .and_then(|id| report_categories.iter().find(|c| c.id == id))
+                    .map_or("All items".to_string(), |c| c.name.clone());
 
-    let setting_column = column![
-        text("Data File Path:"),
-        text_input("Path to RON file", &settings.file_path)
-            .on_input(Message::UpdateFilePath)
-            .style(Modern::inline_text_input())
-            .padding(5),
-        
+                row![
+                    text(format!("[{}] {}", rule.severity, rule.name)).width(250),
+                    text(rule.condition.to_string()).width(250),
+                    text(scope).width(150),
+                    button("Remove")
+                        .on_press(Message::RemoveRule(index))
+                        .style(Modern::danger_button()),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
+            .collect::<Vec<_>>()
+    )
+    .spacing(5);
+
+    // Parameter inputs depend on the selected condition
+    let parameters: Element<'a, Message> = match draft.kind {
+        lint::ConditionKind::TextContains | lint::ConditionKind::TextMatches => row![
+            pick_list(lint::TextField::ALL, Some(draft.text_field), Message::SelectRuleTextField)
+                .style(Modern::pick_list()),
+            text_input(if draft.kind == lint::ConditionKind::TextMatches { "Pattern" } else { "Text" }, &draft.text)
+                .on_input(Message::UpdateRuleText)
+                .style(Modern::inline_text_input())
+                .width(150),
+        ].spacing(10).into(),
+        lint::ConditionKind::MissingReference => row![
+            pick_list(lint::ReferenceField::ALL, Some(draft.reference), Message::SelectRuleReference)
+                .style(Modern::pick_list()),
+        ].into(),
+        lint::ConditionKind::MissingPrinter => {
+            let selected = draft.printer
+                .and_then(|id| printer_logicals.iter().find(|p| p.id == id).cloned());
+
+            row![
+                pick_list(printer_logicals, selected, |printer: PrinterLogical| Message::SelectRulePrinter(printer.id))
+                    .placeholder("Printer Logical")
+                    .style(Modern::pick_list()),
+            ].into()
+        }
+        lint::ConditionKind::PriceOutside => row![
+            text_input("Min", &draft.min_price)
+                .on_input(Message::UpdateRuleMinPrice)
+                .style(Modern::inline_text_input())
+                .width(80),
+            text_input("Max", &draft.max_price)
+                .on_input(Message::UpdateRuleMaxPrice)
+                .style(Modern::inline_text_input())
+                .width(80),
+        ].spacing(10).into(),
+        lint::ConditionKind::PriceLevelMismatch => row![].into(),
+    };
+
+    let selected_category = draft.report_category
+        .and_then(|id| report_categories.iter().find(|c| c.id == id).cloned());
+
+    let new_rule = column![
         row![
-            checkbox("Auto-save on changes", settings.auto_save)
-            .on_toggle(Message::ToggleAutoSave)
-            .style(Modern::checkbox()),
-        
-            checkbox("Create backups before saving", settings.create_backups)
-                .on_toggle(Message::ToggleBackups)
-                .style(Modern::checkbox()),
-        ].spacing(15),
-        
-        if let Some(error) = error_message {
+            text_input("Rule name", &draft.name)
+                .on_input(Message::UpdateRuleName)
+                .style(Modern::inline_text_input())
+                .width(200),
+            pick_list(lint::Severity::ALL, Some(draft.severity), Message::SelectRuleSeverity)
+                .style(Modern::pick_list()),
+            pick_list(report_categories, selected_category, |category: ReportCategory| Message::SelectRuleReportCategory(Some(category.id)))
+                .placeholder("All items")
+                .style(Modern::pick_list()),
+            button("Any")
+                .on_press(Message::SelectRuleReportCategory(None))
+                .style(Modern::gray_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+        row![
+            pick_list(lint::ConditionKind::ALL, Some(draft.kind), Message::SelectRuleKind)
+                .style(Modern::pick_list()),
+            parameters,
+            button("Add Rule")
+                .on_press(Message::AddRule)
+                .style(accent::primary_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+        if let Some(error) = &settings.rule_error {
             text(error).style(Modern::error_text())
         } else {
             text("")
         },
+    ]
+    .spacing(10);
 
-        row![
-            button("Save Settings")
-                .on_press(Message::ValidateAndSave)
-                .style(Modern::primary_button()),
-        ]
-        .spacing(10),
-    ].spacing(10);
-
-    let setting_container = container(
-        column![
-            title_row,
-            setting_column,
-        ]
-        .spacing(10)
-        .padding(15)
+    let results = column(
+        lint_issues
+            .iter()
+            .map(|issue| {
+                text(issue.to_string())
+                    .size(12)
+                    .style(Modern::validated_text(issue.severity == lint::Severity::Error))
+                    .into()
+            })
+            .collect::<Vec<_>>()
     )
-    .width(805)
-    .style(Modern::card_container());
+    .spacing(3);
 
-    let import_export = container(
-        // Add an export section
+    container(
         column![
-            text("Data Export").size(18),
+            text("Configuration Rules").size(18),
+            rule_list,
+            new_rule,
+            text(format!("{} rule violation(s)", lint_issues.len())).style(Modern::secondary_text()),
+            results,
             row![
-                button("Export Menu Items to CSV")
-                    .on_press(Message::OpenFile)
+                button("Check Image Files")
+                    .on_press(Message::ScanImages)
                     .style(Modern::system_button()),
+                text(match &settings.missing_images {
+                    Some(missing) => format!("{} missing image file(s)", missing.len()),
+                    None => String::new(),
+                }).style(Modern::secondary_text()),
             ]
-            .spacing(10),
-            text(&settings.export_message).size(12).style(
-                Modern::validated_text(!settings.export_success.clone())
-            ),
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            column(
+                settings.missing_images
+                    .iter()
+                    .flatten()
+                    .map(|line| text(line).size(12).style(Modern::error_text()).into())
+                    .collect::<Vec<_>>()
+            )
+            .spacing(3),
+            text(format!("{} duplicate price level name(s)", duplicate_price_levels.len()))
+                .style(Modern::secondary_text()),
+            column(
+                duplicate_price_levels
+                    .into_iter()
+                    .map(|(name, ids)| {
+                        row(
+                            std::iter::once(
+                                text(format!("'{}' is used by {} levels", name, ids.len()))
+                                    .size(12)
+                                    .style(Modern::error_text())
+                                    .width(250)
+                                    .into()
+                            )
+                            .chain(ids.into_iter().map(|id| {
+                                button(text(format!("Fix {}", id)).size(12))
+                                    .on_press(Message::FixPriceLevel(id))
+                                    .style(Modern::gray_button())
+                                    .into()
+                            }))
+                            .collect::<Vec<_>>()
+                        )
+                        .spacing(5)
+                        .align_y(iced::Alignment::Center)
+                        .into()
+                    })
+                    .collect::<Vec<_>>()
+            )
+            .spacing(3),
+            checkbox("Block saving price levels with a duplicate name", settings.block_duplicate_price_level_names)
+                .on_toggle(Message::ToggleBlockDuplicatePriceLevelNames)
+                .style(Modern::checkbox()),
         ]
-
         .spacing(10)
-        .padding(10), 
+        .padding(10)
     )
     .style(Modern::card_container())
     .width(805)
-    .padding(15);
-
-
-    column![
-        setting_container,
-        import_export,
-    ]
-    .spacing(10)
+    .padding(15)
     .into()
 }
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ThemeChoice {
     Light,
+    #[default]
     Dark,
+    // Follows the OS light/dark preference, rechecked while the app runs
+    System,
 }
 
 impl ThemeChoice {
     pub const ALL: &'static [Self] = &[
         Self::Light,
         Self::Dark,
+        Self::System,
+    ];
+}
+
+impl persistence::KnownVariants for ThemeChoice {
+    fn variants() -> &'static [Self] {
+        Self::ALL
+    }
+}
+
+// Serialization used for the data file. Both are text, so a saved menu
+// can be diffed in git.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SaveFormat {
+    #[default]
+    Ron,
+    Json,
+}
+
+impl SaveFormat {
+    pub const ALL: &'static [Self] = &[
+        Self::Ron,
+        Self::Json,
     ];
+
+    // Extension for files written in this format, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Ron => "ron",
+            Self::Json => "json",
+        }
+    }
+
+    // Format named by a file's extension, None when it isn't one we write
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?;
+        Self::ALL.iter().copied().find(|format| ext.eq_ignore_ascii_case(format.extension()))
+    }
+}
+
+impl persistence::KnownVariants for SaveFormat {
+    fn variants() -> &'static [Self] {
+        Self::ALL
+    }
+}
+
+impl fmt::Display for SaveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ron => write!(f, "RON"),
+            Self::Json => write!(f, "JSON"),
+        }
+    }
 }
 
 impl fmt::Display for ThemeChoice {
@@ -272,6 +2067,7 @@ impl fmt::Display for ThemeChoice {
         match self {
             Self::Light => write!(f, "Light"),
             Self::Dark => write!(f, "Dark"),
+            Self::System => write!(f, "Match system"),
         }
     }
 }
@@ -312,6 +2108,7 @@ pub fn settings() -> Settings {
 pub enum Error {
     DialogClosed,
     IoError(std::io::ErrorKind),
+    InvalidData(String),
 }
 
 pub async fn open_or_create_file() -> Result<(PathBuf, Option<Arc<String>>), Error> {
@@ -353,9 +2150,218 @@ pub async fn load_file(
     Ok((path, contents))
 }
 
+// References every new item starts with, to save picking the same ones on each
+fn new_item_defaults_view<'a>(
+    settings: &'a AppSettings,
+    tax_groups: Vec<TaxGroup>,
+    security_levels: Vec<SecurityLevel>,
+    report_categories: Vec<ReportCategory>,
+) -> Element<'a, Message> {
+    column![
+        text("New item defaults").size(16),
+        default_picker("Tax group", tax_groups, settings.new_item_tax_group, Message::SelectNewItemTaxGroup),
+        default_picker("Security level", security_levels, settings.new_item_security_level, Message::SelectNewItemSecurityLevel),
+        default_picker("Report category", report_categories, settings.new_item_report_category, Message::SelectNewItemReportCategory),
+    ]
+    .spacing(5)
+    .into()
+}
+
+fn default_picker<'a, T: Entity + PartialEq + 'a>(
+    label: &'a str,
+    options: Vec<T>,
+    selected: Option<EntityId>,
+    on_select: fn(Option<EntityId>) -> Message,
+) -> Element<'a, Message> {
+    let current = selected.and_then(|id| options.iter().find(|option| option.id() == id).cloned());
+
+    row![
+        text(label).width(130),
+        pick_list(options, current, move |option: T| on_select(Some(option.id())))
+            .placeholder("None")
+            .width(200)
+            .style(Modern::pick_list()),
+        button("None")
+            .on_press(on_select(None))
+            .style(Modern::gray_button()),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+// Palette swatches, a hex input and a preview of the accented buttons
+fn accent_view<'a>(settings: &'a AppSettings) -> Element<'a, Message> {
+    let input = settings.accent_input.clone()
+        .unwrap_or_else(|| settings.accent_color.map(accent::to_hex).unwrap_or_default());
+
+    let swatches = row(
+        accent::PALETTE
+            .iter()
+            .map(|(name, rgb)| {
+                tooltip(
+                    button(text("").width(16).height(16))
+                        .on_press(Message::SelectAccent(Some(*rgb)))
+                        .style(accent::swatch_button(*rgb)),
+                    container(text(*name)).padding(5).style(Modern::card_container()),
+                    tooltip::Position::Top,
+                )
+                .into()
+            })
+            .collect::<Vec<_>>()
+    )
+    .spacing(5);
+
+    column![
+        row![
+            text("Theme"),
+            pick_list(ThemeChoice::ALL, Some(settings.app_theme), Message::ThemeChanged)
+                .style(Modern::pick_list()),
+            text("Match system follows the OS setting as it changes").style(Modern::secondary_text()).size(13),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+        row![
+            text("Accent color"),
+            swatches,
+            text_input("Theme default", &input)
+                .on_input(Message::UpdateAccentInput)
+                .style(Modern::validated_text_input(settings.accent_error.is_some()))
+                .width(90),
+            button("Default")
+                .on_press(Message::SelectAccent(None))
+                .style(Modern::gray_button()),
+            // Pressable so it shows the active color rather than the disabled one
+            button("Preview")
+                .on_press(Message::SelectAccent(settings.accent_color))
+                .style(accent::primary_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+        if let Some(error) = &settings.accent_error {
+            text(error).size(12).style(Modern::error_text())
+        } else {
+            text("")
+        },
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Ticked template columns are written in list order, the optional columns
+// below still follow their own checkboxes
+fn export_columns_view<'a>(settings: &'a AppSettings) -> Element<'a, Message> {
+    let Some(draft) = &settings.column_draft else {
+        return column![].into();
+    };
+
+    let selected_count = draft.iter().filter(|(_, enabled)| *enabled).count();
+
+    let rows = draft
+        .iter()
+        .enumerate()
+        .map(|(position, &(index, enabled))| {
+            row![
+                entity_component::move_buttons(
+                    Message::MoveExportColumnUp(position),
+                    Message::MoveExportColumnDown(position),
+                ),
+                checkbox(export_items::EXPORT_HEADERS[index], enabled)
+                    .on_toggle(move |enabled| Message::ToggleExportColumn(index, enabled))
+                    .style(Modern::checkbox()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+        })
+        .collect::<Vec<_>>();
+
+    container(
+        column![
+            text("Columns to export").size(16),
+            row![
+                button("All")
+                    .on_press(Message::SelectAllExportColumns(true))
+                    .style(Modern::gray_button()),
+                button("None")
+                    .on_press(Message::SelectAllExportColumns(false))
+                    .style(Modern::gray_button()),
+                text(format!("{} of {} columns", selected_count, draft.len()))
+                    .style(Modern::secondary_text())
+                    .size(13),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            container(scrollable(column(rows).spacing(4)).height(250))
+                .style(Modern::sheet_container())
+                .padding(5),
+            row![
+                button("Export")
+                    .on_press_maybe((selected_count > 0).then_some(Message::ConfirmExportColumns))
+                    .style(accent::primary_button()),
+                button("Cancel")
+                    .on_press(Message::CancelExportColumns)
+                    .style(Modern::system_button()),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10)
+    )
+    .style(Modern::card_container())
+    .padding(10)
+    .into()
+}
+
+// "Re-export to <file>" with the full path on hover, plus a button to forget it
+fn re_export_button<'a>(settings: &'a AppSettings, kind: ExportKind) -> Element<'a, Message> {
+    let Some(path) = settings.last_export_paths.get(&kind) else {
+        return row![].into();
+    };
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned());
+
+    row![
+        tooltip(
+            button(text(format!("Re-export to {}", file_name)))
+                .on_press(Message::ReExport(kind))
+                .style(accent::primary_button()),
+            container(text(path.to_string_lossy())).padding(10).style(Modern::card_container()),
+            tooltip::Position::Top,
+        ),
+        button("Forget")
+            .on_press(Message::ClearExportPath(kind))
+            .style(Modern::gray_button()),
+    ]
+    .spacing(5)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+// Copies an existing export aside as "{stem}_{timestamp}.{ext}" before it is overwritten
+fn keep_previous_export(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let copy_name = match path.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, chrono::Local::now().format("%Y%m%d_%H%M%S"), ext.to_string_lossy()),
+        None => format!("{}_{}", stem, chrono::Local::now().format("%Y%m%d_%H%M%S")),
+    };
+
+    std::fs::copy(path, path.with_file_name(copy_name))
+        .map(|_| ())
+        .map_err(|e| format!("Failed to keep previous export: {}", e))
+}
+
 pub async fn write_to_item_export(
     items: BTreeMap<i32, crate::items::Item>, 
-    path: Option<PathBuf>
+    path: Option<PathBuf>,
+    tax_rates: BTreeMap<i32, Decimal>,
+    options: ExportOptions,
 ) -> Result<PathBuf, Error> {
     println!("write-to-items-export function triggered");
     // If path is None, prompt for a save location
@@ -365,6 +2371,10 @@ pub async fn write_to_item_export(
         rfd::AsyncFileDialog::new()
             .add_filter("CSV Files", &["csv"])
             .add_filter("Text Files", &["txt"])
+            .add_filter(
+                "InfoGenesis Fixed Width",
+                &[ExportFormat::FIXED_WIDTH_EXTENSION],
+            )
             .set_title("Save Items Export")
             .save_file()
             .await
@@ -374,21 +2384,17 @@ pub async fn write_to_item_export(
             .ok_or(Error::DialogClosed)?
     };
 
-    // Convert items to export strings
-    let mut content = String::new();
-
-    let items_vec: Vec<&crate::items::Item> = items.values().collect();
-    
-    for (i, item) in items_vec.iter().enumerate() {
-        // Convert each item to its export string representation
-        let export_string = crate::items::export_items::item_to_export_string(item);
-        content.push_str(&export_string);
-        
-        // Add newline if not the last item
-        if i < items.len() - 1 {
-            content.push('\n');
+    // Convert items to export strings, a .prn file gets the fixed-width layout
+    let lines = match ExportFormat::from_path(&path) {
+        ExportFormat::Csv => {
+            crate::items::export_items::export_lines(items.values(), &tax_rates, &options)
         }
-    }
+        ExportFormat::FixedWidth => {
+            crate::items::export_items::fixed_width_lines(items.values())
+                .map_err(Error::InvalidData)?
+        }
+    };
+    let content = lines.join("\n");
 
     // Write the content to the file
     tokio::fs::write(&path, content)
@@ -396,4 +2402,33 @@ pub async fn write_to_item_export(
         .map_err(|error| Error::IoError(error.kind()))?;
 
     Ok(path)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_scale_accepts_values_in_range() {
+        assert_eq!(parse_price_scale("0"), Ok(0));
+        assert_eq!(parse_price_scale(" 2 "), Ok(2));
+        assert_eq!(parse_price_scale(&MAX_PRICE_SCALE.to_string()), Ok(MAX_PRICE_SCALE));
+    }
+
+    #[test]
+    fn price_scale_rejects_values_out_of_range() {
+        assert!(parse_price_scale(&(MAX_PRICE_SCALE + 1).to_string()).is_err());
+        assert!(parse_price_scale("-1").is_err());
+        assert!(parse_price_scale("").is_err());
+        assert!(parse_price_scale("two").is_err());
+    }
+
+    #[test]
+    fn save_format_follows_the_chosen_extension() {
+        assert_eq!(SaveFormat::from_path(Path::new("menu.json")), Some(SaveFormat::Json));
+        assert_eq!(SaveFormat::from_path(Path::new("menu.JSON")), Some(SaveFormat::Json));
+        assert_eq!(SaveFormat::from_path(Path::new("menu.ron")), Some(SaveFormat::Ron));
+        assert_eq!(SaveFormat::from_path(Path::new("menu.txt")), None);
+        assert_eq!(SaveFormat::from_path(Path::new("menu")), None);
+    }
+}
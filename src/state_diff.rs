@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use crate::data_types::EntityId;
+use crate::entity_component::Entity;
+use crate::persistence::AppState;
+
+// Record level differences between two whole states, for comparing a saved
+// version with the current data. Settings are not compared, only the menu.
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CollectionDiff {
+    pub label: &'static str,
+    // "id name" of each record
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl CollectionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {} added, {} removed, {} changed",
+            self.label,
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+}
+
+fn describe<T: Entity>(entity: &T) -> String {
+    format!("{} {}", entity.id(), entity.name())
+}
+
+pub fn diff_collection<T: Entity + PartialEq>(label: &'static str, from: &[T], to: &[T]) -> CollectionDiff {
+    let from: BTreeMap<EntityId, &T> = from.iter().map(|entity| (entity.id(), entity)).collect();
+    let to: BTreeMap<EntityId, &T> = to.iter().map(|entity| (entity.id(), entity)).collect();
+
+    let mut diff = CollectionDiff { label, ..CollectionDiff::default() };
+
+    for (id, before) in &from {
+        match to.get(id) {
+            Some(after) if after != before => diff.changed.push(describe(*after)),
+            Some(_) => {}
+            None => diff.removed.push(describe(*before)),
+        }
+    }
+    diff.added = to
+        .iter()
+        .filter(|(id, _)| !from.contains_key(id))
+        .map(|(_, after)| describe(*after))
+        .collect();
+
+    diff
+}
+
+// What changed going from `from` to `to`, one entry per collection with differences
+pub fn diff_states(from: &AppState, to: &AppState) -> Vec<CollectionDiff> {
+    vec![
+        diff_collection("Items", &from.items, &to.items),
+        diff_collection("Item Groups", &from.item_groups, &to.item_groups),
+        diff_collection("Price Levels", &from.price_levels, &to.price_levels),
+        diff_collection("Product Classes", &from.product_classes, &to.product_classes),
+        diff_collection("Tax Groups", &from.tax_groups, &to.tax_groups),
+        diff_collection("Security Levels", &from.security_levels, &to.security_levels),
+        diff_collection("Revenue Categories", &from.revenue_categories, &to.revenue_categories),
+        diff_collection("Report Categories", &from.report_categories, &to.report_categories),
+        diff_collection("Choice Groups", &from.choice_groups, &to.choice_groups),
+        diff_collection("Printer Logicals", &from.printer_logicals, &to.printer_logicals),
+        diff_collection("Stores", &from.stores, &to.stores),
+    ]
+    .into_iter()
+    .filter(|diff| !diff.is_empty())
+    .collect()
+}
+
+// Record counts per collection
+pub fn counts(state: &AppState) -> Vec<(&'static str, usize)> {
+    vec![
+        ("Items", state.items.len()),
+        ("Item Groups", state.item_groups.len()),
+        ("Price Levels", state.price_levels.len()),
+        ("Product Classes", state.product_classes.len()),
+        ("Tax Groups", state.tax_groups.len()),
+        ("Security Levels", state.security_levels.len()),
+        ("Revenue Categories", state.revenue_categories.len()),
+        ("Report Categories", state.report_categories.len()),
+        ("Choice Groups", state.choice_groups.len()),
+        ("Printer Logicals", state.printer_logicals.len()),
+        ("Stores", state.stores.len()),
+    ]
+}
+
+// Lines for the versions panel: each collection's summary followed by its records
+pub fn report_lines(diffs: &[CollectionDiff]) -> Vec<String> {
+    if diffs.is_empty() {
+        return vec!["No differences".to_string()];
+    }
+
+    let mut lines = Vec::new();
+    for diff in diffs {
+        lines.push(diff.summary());
+        lines.extend(diff.added.iter().map(|record| format!("  + {}", record)));
+        lines.extend(diff.removed.iter().map(|record| format!("  - {}", record)));
+        lines.extend(diff.changed.iter().map(|record| format!("  ~ {}", record)));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::Item;
+    use crate::tax_groups::TaxGroup;
+
+    fn item(id: EntityId, name: &str) -> Item {
+        Item { id, name: name.to_string(), ..Item::default() }
+    }
+
+    #[test]
+    fn only_collections_with_differences_are_listed() {
+        let from = AppState {
+            items: vec![item(1, "Burger"), item(2, "Fries")],
+            tax_groups: vec![TaxGroup { id: 1, name: "Food".to_string(), ..TaxGroup::default() }],
+            ..AppState::default()
+        };
+        let to = AppState {
+            items: vec![item(1, "Cheeseburger"), item(3, "Shake")],
+            ..from.clone()
+        };
+
+        let diffs = diff_states(&from, &to);
+
+        assert_eq!(
+            diffs,
+            vec![CollectionDiff {
+                label: "Items",
+                added: vec!["3 Shake".to_string()],
+                removed: vec!["2 Fries".to_string()],
+                changed: vec!["1 Cheeseburger".to_string()],
+            }]
+        );
+        assert_eq!(diffs[0].summary(), "Items: 1 added, 1 removed, 1 changed");
+        assert_eq!(
+            report_lines(&diffs),
+            vec![
+                "Items: 1 added, 1 removed, 1 changed".to_string(),
+                "  + 3 Shake".to_string(),
+                "  - 2 Fries".to_string(),
+                "  ~ 1 Cheeseburger".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_states_report_no_differences() {
+        let state = AppState { items: vec![item(1, "Burger")], ..AppState::default() };
+
+        assert!(diff_states(&state, &state.clone()).is_empty());
+        assert_eq!(report_lines(&[]), vec!["No differences".to_string()]);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
+use crate::Action;
+use crate::entity_component::{self, Entity, EditState, ListSort};
+use serde::{Serialize, Deserialize};
+use iced::Element;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
+    CopyStore(EntityId),
+    EditStore(EntityId),
+    SaveAll(EntityId, EditState),
+    UpdateName(EntityId, String),
+    CreateNew,
+    CancelEdit(EntityId),
+}
+
+#[derive(Debug, Clone)]
+pub enum Operation {
+    RequestDelete(EntityId),
+    ExportRecord(EntityId),
+    CopyJson(EntityId),
+    CopyStore(EntityId),
+    EditStore(EntityId),
+    SaveAll(EntityId, EditState),
+    UpdateName(EntityId, String),
+    CreateNew,
+    CancelEdit(EntityId),
+}
+
+// A physical outlet, store type price levels belong to one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Store {
+    pub id: EntityId,
+    pub name: String,
+    #[serde(default)]
+    pub timestamps: Timestamps,
+}
+
+impl std::fmt::Display for Store {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Self {
+            id: -1,
+            name: String::new(),
+            timestamps: Timestamps::default(),
+        }
+    }
+}
+
+impl Entity for Store {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn with_id(&self, id: EntityId) -> Self {
+        let mut clone = self.clone();
+        clone.id = id;
+        clone
+    }
+    
+    fn with_name(&self, name: String) -> Self {
+        let mut clone = self.clone();
+        clone.name = name;
+        clone
+    }
+    
+    fn default_new() -> Self {
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
+    }
+}
+
+impl Store {
+    pub fn new_draft() -> Self {
+        Self::default()
+    }
+
+    pub fn validate(&self, other_stores: &[&Store], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::Store, self.id, id_range)?;
+
+        for other in other_stores {
+            if other.id == self.id {
+                return Err(ValidationError::DuplicateId(
+                    format!("Store with ID {} already exists", self.id)
+                ));
+            }
+        }
+
+        if self.name.trim().is_empty() {
+            return Err(ValidationError::EmptyName(
+                "Store name cannot be empty".to_string()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+pub fn update(
+    message: Message,
+) -> Action<Operation, Message> {
+    match message {
+        Message::ExportRecord(id) => {
+            Action::operation(Operation::ExportRecord(id))
+        },
+        Message::CopyJson(id) => {
+            Action::operation(Operation::CopyJson(id))
+        },
+        Message::RequestDelete(id) => {
+            Action::operation(Operation::RequestDelete(id))
+        },
+        Message::CopyStore(id) => {
+            Action::operation(Operation::CopyStore(id))
+        },
+        Message::EditStore(id) => {
+            Action::operation(Operation::EditStore(id))
+        },
+        Message::CreateNew => {
+            Action::operation(Operation::CreateNew)
+        },
+        Message::SaveAll(id, edit_state) => {
+            Action::operation(Operation::SaveAll(id, edit_state))
+        }
+        Message::UpdateName(id, new_name) => {
+            Action::operation(Operation::UpdateName(id, new_name))
+        }
+        Message::CancelEdit(id) => {
+            Action::operation(Operation::CancelEdit(id))
+        }
+    }
+}
+
+pub fn view<'a>(
+    all_stores: &'a BTreeMap<EntityId, Store>,
+    edit_states: &'a Vec<EditState>,
+    search: &str,
+    sort: ListSort,
+) -> Element<'a, Message> {
+    entity_component::entity_view(
+        "Stores",
+        Message::CreateNew,
+        all_stores,
+        edit_states,
+        search,
+        sort,
+        |store, edit_states| render_store_row(store, edit_states),
+    )
+}
+
+fn render_store_row<'a>(
+    store: &'a Store,
+    edit_states: &'a Vec<EditState>
+) -> Element<'a, Message> {
+    entity_component::entity_quick_edit_view(
+        store,
+        edit_states,
+        Message::EditStore,
+        Message::SaveAll,
+        Message::CopyStore,
+        Message::ExportRecord,
+        Message::CopyJson,
+        Message::RequestDelete,
+        Message::CancelEdit,
+        Message::UpdateName,
+        None,
+        "Store Name"
+    )
+}
+
+fn get_next_id(stores: &BTreeMap<EntityId, Store>) -> EntityId {
+    stores
+        .keys()
+        .max()
+        .map_or(1, |max_id| max_id + 1)
+}
\ No newline at end of file
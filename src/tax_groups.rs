@@ -1,6 +1,8 @@
-use crate::data_types::{ EntityId, ValidationError };
+use crate::accent;
+use crate::data_types::{self, EntityId, ValidationError, EntityKind, Timestamps};
+use std::ops::RangeInclusive;
 use crate::Action;
-use crate::entity_component::{self, Entity, EditState as BaseEditState};
+use crate::entity_component::{self, Entity, EditState as BaseEditState, ListSort};
 use crate::icon;
 use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
@@ -46,8 +48,8 @@ impl TaxGroupEditState {
     pub fn new(tax_group: &TaxGroup) -> Self {
         Self {
             base: BaseEditState::new(tax_group),
-            rate: tax_group.rate_percentage().to_string(),
-            original_rate: tax_group.rate_percentage().to_string(),
+            rate: tax_group.rate_percentage().normalize().to_string(),
+            original_rate: tax_group.rate_percentage().normalize().to_string(),
             rate_validation_error: None,
         }
     }
@@ -58,39 +60,36 @@ impl TaxGroupEditState {
         self.rate_validation_error = None;
     }
  
-    pub fn validate(&self) -> Result<(), ValidationError> {
+    pub fn validate(&self, id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
         // First validate the base fields
         self.base.validate(1..=99999)?;
  
         if let Ok(id) = self.base.id.parse::<EntityId>() {
-            if !(1..=99).contains(&id) {
-                return Err(ValidationError::InvalidId(
-                    "Tax group ID must be between 1 and 99".to_string()
-                ));
-            }
+            data_types::check_id_range(EntityKind::TaxGroup, id, id_range)?;
         } else {
             return Err(ValidationError::InvalidId(
                 "Invalid ID format".to_string()
             ));
         }
 
-        match self.rate.parse::<Decimal>() {
-            Ok(rate) => {
-                if !(Decimal::ZERO..=Decimal::from(100)).contains(&rate) {
-                    return Err(ValidationError::InvalidValue(
-                        "Tax rate must be between 0 and 100%".to_string()
-                    ));
-                }
-            }
-            Err(_) => {
-                return Err(ValidationError::InvalidValue(
-                    "Invalid tax rate format".to_string()
-                ));
-            }
-        }
+        Self::parse_rate(&self.rate)?;
 
         Ok(())
     }
+
+    // The rate is typed as a percentage (8.25) and stored as a decimal (0.0825)
+    pub fn parse_rate(rate: &str) -> Result<Decimal, ValidationError> {
+        let percentage = rate.trim().trim_end_matches('%').trim().parse::<Decimal>()
+            .map_err(|_| ValidationError::InvalidRate("Enter a percentage, e.g. 8.25".to_string()))?;
+
+        if !(Decimal::ZERO..=Decimal::from(100)).contains(&percentage) {
+            return Err(ValidationError::InvalidRate(
+                "Tax rate must be between 0 and 100%".to_string()
+            ));
+        }
+
+        Ok(percentage / Decimal::from(100))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -98,6 +97,8 @@ pub struct TaxGroup {
     pub id: EntityId,
     pub name: String,
     pub rate: Decimal, // Stored as decimal (e.g., 0.08 for 8%)
+    #[serde(default)]
+    pub timestamps: Timestamps,
 }
 
 impl std::fmt::Display for TaxGroup {
@@ -112,6 +113,7 @@ impl Default for TaxGroup {
             id: -1,
             name: String::new(),
             rate: Decimal::ZERO,
+            timestamps: Timestamps::default(),
         }
     }
 }
@@ -138,7 +140,14 @@ impl Entity for TaxGroup {
     }
     
     fn default_new() -> Self {
-        Self::default()
+        Self {
+            timestamps: Timestamps::created_now(),
+            ..Self::default()
+        }
+    }
+
+    fn timestamps(&self) -> Timestamps {
+        self.timestamps
     }
 }
 
@@ -147,12 +156,8 @@ impl TaxGroup {
         Self::default()
     }
 
-    fn validate(&self, other_groups: &[&TaxGroup]) -> Result<(), ValidationError> {
-        if !(1..=99).contains(&self.id) {
-            return Err(ValidationError::InvalidId(
-                "Tax group ID must be between 1 and 99".to_string()
-            ));
-        }
+    pub fn validate(&self, other_groups: &[&TaxGroup], id_range: &RangeInclusive<EntityId>) -> Result<(), ValidationError> {
+        data_types::check_id_range(EntityKind::TaxGroup, self.id, id_range)?;
 
         for other in other_groups {
             if other.id == self.id {
@@ -181,6 +186,11 @@ impl TaxGroup {
     pub fn rate_percentage(&self) -> Decimal {
         self.rate * Decimal::from(100)
     }
+
+    // Display label for the effective rate, e.g. "Food (8.25%)"
+    pub fn rate_label(&self) -> String {
+        format!("{} ({}%)", self.name, self.rate_percentage().normalize())
+    }
 }
 
 pub fn update(
@@ -217,6 +227,9 @@ pub fn update(
 pub fn view<'a>(
     all_groups: &'a BTreeMap<EntityId, TaxGroup>,
     edit_states: &'a Vec<TaxGroupEditState>,
+    search: &str,
+    sort: ListSort,
+    id_range: RangeInclusive<EntityId>,
 ) -> Element<'a, Message> {
     let title_row = entity_component::render_title_row(
         "Tax groups", 
@@ -233,14 +246,20 @@ pub fn view<'a>(
     ]
     .padding(15);
 
+    let mut groups: Vec<&TaxGroup> = all_groups
+        .values()
+        .filter(|group| entity_component::matches_search(*group, search, edit_states.iter().map(|state| &state.base)))
+        .collect();
+    sort.apply(&mut groups);
+
     // List of Tax groups
     let groups_list = scrollable(
         column(
-            all_groups
-                .values()
+            groups
+                .into_iter()
                 .map(|group| 
                     row![
-                        render_tax_group_row(group, edit_states)
+                        render_tax_group_row(group, edit_states, &id_range)
                     ]
                     .padding(5)
                     .into()
@@ -262,11 +281,12 @@ pub fn view<'a>(
 
 fn render_tax_group_row<'a>(
     tax_group: &'a TaxGroup,
-    edit_states: &'a Vec<TaxGroupEditState>
+    edit_states: &'a Vec<TaxGroupEditState>,
+    id_range: &RangeInclusive<EntityId>,
 ) -> Element<'a, Message> {
     // Find edit state for this tax_group if it exists
     let edit_state = edit_states.iter()
-        .find(|state| state.base.id.parse::<i32>().unwrap_or(-999) == tax_group.id);
+        .find(|state| data_types::parse_entity_id(&state.base.id) == Some(tax_group.id));
 
     let editing = edit_state.is_some();
 
@@ -275,9 +295,10 @@ fn render_tax_group_row<'a>(
         .map(|state| state.base.name.clone())
         .unwrap_or_else(|| tax_group.name.clone());
 
+    // A percentage either way, the same as the edit field
     let tax_rate = edit_state
         .map(|state| state.rate.clone())
-        .unwrap_or_else(|| tax_group.rate.to_string());
+        .unwrap_or_else(|| tax_group.rate_percentage().normalize().to_string());
 
     // Check for validation errors
     let id_validation_error = edit_state
@@ -286,12 +307,20 @@ fn render_tax_group_row<'a>(
     let name_validation_error = edit_state
         .and_then(|state| state.base.name_validation_error.as_ref());
 
+    let name_warning = edit_state
+        .and_then(|state| state.base.name_warning.as_ref());
+
     let rate_validation_error = edit_state
         .and_then(|state| state.rate_validation_error.as_ref());
 
+    let id_hint = edit_state
+        .and_then(|state| state.base.id_range.as_ref())
+        .map(|range| data_types::id_hint(tax_group.id, range));
+    let id_placeholder = format!("ID ({}-{})", id_range.start(), id_range.end());
+
     // ID input with validation
     let id_input: Element<'_, Message> = {
-        let input = text_input("ID (1-999)", &tax_group.id.to_string())
+        let input = text_input(&id_placeholder, &tax_group.id.to_string())
             .style(Modern::validated_text_input(id_validation_error.is_some()))
             .width(Length::Fixed(75.0));
 
@@ -301,6 +330,12 @@ fn render_tax_group_row<'a>(
                 container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
                 tooltip::Position::Top,
             ).into()
+        } else if let Some(hint) = id_hint {
+            column![
+                input,
+                text(hint).size(11).style(Modern::secondary_text()),
+            ]
+            .into()
         } else {
             input.into()
         }
@@ -309,6 +344,7 @@ fn render_tax_group_row<'a>(
     // Name input with validation
     let name_input: Element<'_, Message> = {
         let input = text_input("Tax group Name", &display_name)
+            .id(entity_component::name_input_id(tax_group.id))
             .on_input_maybe(
                 if editing {
                     Some(|name| Message::UpdateName(tax_group.id, name))
@@ -325,6 +361,12 @@ fn render_tax_group_row<'a>(
                 container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
                 tooltip::Position::Top,
             ).into()
+        } else if let Some(warning) = name_warning {
+            column![
+                input,
+                text(warning).size(11).style(entity_component::warning_text),
+            ]
+            .into()
         } else {
             input.into()
         }
@@ -348,6 +390,13 @@ fn render_tax_group_row<'a>(
                 container(error.as_str()).padding(10).style(Modern::danger_tooltip_container()),
                 tooltip::Position::Top,
             ).into()
+        } else if let Some(rate) = edit_state.and_then(|state| TaxGroupEditState::parse_rate(&state.rate).ok()) {
+            // Show what will be stored while the rate is being typed
+            column![
+                input,
+                text(format!("Stored as {}", rate.normalize())).size(11).style(Modern::secondary_text()),
+            ]
+            .into()
         } else {
             input.into()
         }
@@ -363,21 +412,20 @@ fn render_tax_group_row<'a>(
                     Message::EditTaxGroup(tax_group.id) 
                 }
             )
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
         iced::widget::horizontal_space().width(2),
         button(icon::copy().size(14))
             .on_press(Message::CopyTaxGroup(tax_group.id))
-            .style(Modern::primary_button()),
+            .style(accent::primary_button()),
         iced::widget::horizontal_space().width(2),
-        button(if editing { icon::cancel().size(14) } else { icon::trash().size(14) })
-            .on_press(
-                if editing { 
-                    Message::CancelEdit(tax_group.id) 
-                } else { 
-                    Message::RequestDelete(tax_group.id) 
-                }
-            )
-            .style(Modern::danger_button()),
+        if editing {
+            button(icon::cancel().size(14))
+                .on_press(Message::CancelEdit(tax_group.id))
+                .style(Modern::danger_button())
+                .into()
+        } else {
+            entity_component::delete_button(Message::RequestDelete(tax_group.id))
+        },
     ].width(150);
 
 
@@ -392,4 +440,38 @@ fn render_tax_group_row<'a>(
     .align_y(iced::Alignment::Center)
     .width(Length::Fixed(595.0))
     .into()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_label_shows_the_percentage() {
+        let group = TaxGroup { name: "Food".to_string(), rate: Decimal::new(825, 4), ..TaxGroup::default() };
+
+        assert_eq!(group.rate_label(), "Food (8.25%)");
+    }
+
+    #[test]
+    fn rates_are_typed_as_percentages() {
+        assert_eq!(TaxGroupEditState::parse_rate("8.25").unwrap(), Decimal::new(825, 4));
+        assert_eq!(TaxGroupEditState::parse_rate(" 10 % ").unwrap(), Decimal::new(1, 1));
+        assert_eq!(TaxGroupEditState::parse_rate("0").unwrap(), Decimal::ZERO);
+        assert_eq!(TaxGroupEditState::parse_rate("100").unwrap(), Decimal::ONE);
+    }
+
+    #[test]
+    fn rates_outside_zero_to_a_hundred_or_not_numbers_are_rejected() {
+        for input in ["-1", "100.01", "", "abc", "8.25.1"] {
+            assert!(matches!(TaxGroupEditState::parse_rate(input), Err(ValidationError::InvalidRate(_))), "{}", input);
+        }
+    }
+
+    #[test]
+    fn edit_field_starts_from_the_shown_percentage() {
+        let group = TaxGroup { rate: Decimal::new(825, 4), ..TaxGroup::default() };
+
+        assert_eq!(TaxGroupEditState::new(&group).rate, "8.25");
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+use crate::MenuBuilder;
+use crate::data_types::EntityId;
+use crate::{
+    items::Item,
+    item_groups::ItemGroup,
+    price_levels::PriceLevel,
+    product_classes::ProductClass,
+    tax_groups::TaxGroup,
+    security_levels::SecurityLevel,
+    revenue_categories::RevenueCategory,
+    report_categories::ReportCategory,
+    choice_groups::ChoiceGroup,
+    printer_logicals::PrinterLogical,
+    stores::Store,
+};
+
+// Undo and redo for saves, copies and deletes. A step keeps only the records
+// the action changed, as they were before it (None for records it added), so
+// a step stays small on a large menu. Undoing a delete puts back the deleted
+// record and every item reference the delete stripped. Settings are not covered.
+
+pub const HISTORY_LIMIT: usize = 50;
+
+// Before values by id, None when the record did not exist
+type Changes<T> = BTreeMap<EntityId, Option<T>>;
+
+// Records in `before` that differ from `after`, and records `after` added
+fn changed<T: Clone + PartialEq>(before: BTreeMap<EntityId, T>, after: &BTreeMap<EntityId, T>) -> Changes<T> {
+    let added: Vec<EntityId> = after.keys().filter(|id| !before.contains_key(id)).copied().collect();
+
+    before
+        .into_iter()
+        .filter(|(id, entity)| after.get(id) != Some(entity))
+        .map(|(id, entity)| (id, Some(entity)))
+        .chain(added.into_iter().map(|id| (id, None)))
+        .collect()
+}
+
+// Writes the recorded values back, returning what they replaced
+fn swap<T>(changes: Changes<T>, collection: &mut BTreeMap<EntityId, T>) -> Changes<T> {
+    changes
+        .into_iter()
+        .map(|(id, entity)| {
+            let replaced = match entity {
+                Some(entity) => collection.insert(id, entity),
+                None => collection.remove(&id),
+            };
+            (id, replaced)
+        })
+        .collect()
+}
+
+macro_rules! undo_collections {
+    ($($field:ident: $entity:ty),* $(,)?) => {
+        // Collections an action can change. Only these are copied before it
+        // runs, the others can't end up in its step.
+        #[derive(Debug, Clone, Copy)]
+        pub struct Scope {
+            $(pub $field: bool,)*
+        }
+
+        impl Scope {
+            pub const ALL: Self = Self { $($field: true,)* };
+            pub const NONE: Self = Self { $($field: false,)* };
+        }
+
+        // The collections in scope as they were before an action
+        pub struct Before {
+            $($field: Option<BTreeMap<EntityId, $entity>>,)*
+        }
+
+        impl Before {
+            pub fn capture(app: &MenuBuilder, scope: Scope) -> Self {
+                Self { $($field: scope.$field.then(|| app.$field.clone()),)* }
+            }
+        }
+
+        #[derive(Debug)]
+        pub struct Step {
+            pub label: String,
+            $($field: Changes<$entity>,)*
+        }
+
+        impl Step {
+            fn between(label: String, before: Before, app: &MenuBuilder) -> Self {
+                Self {
+                    label,
+                    $($field: before.$field
+                        .map(|before| changed(before, &app.$field))
+                        .unwrap_or_default(),)*
+                }
+            }
+
+            fn is_empty(&self) -> bool {
+                true $(&& self.$field.is_empty())*
+            }
+
+            // Puts the recorded records back, returning the step that reverses this one
+            fn apply(self, app: &mut MenuBuilder) -> Self {
+                Self { label: self.label, $($field: swap(self.$field, &mut app.$field),)* }
+            }
+        }
+    };
+}
+
+undo_collections!(
+    items: Item,
+    item_groups: ItemGroup,
+    price_levels: PriceLevel,
+    product_classes: ProductClass,
+    tax_groups: TaxGroup,
+    security_levels: SecurityLevel,
+    revenue_categories: RevenueCategory,
+    report_categories: ReportCategory,
+    choice_groups: ChoiceGroup,
+    printer_logicals: PrinterLogical,
+    stores: Store,
+);
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    // Set while an action is being recorded, so nested actions fold into it
+    pub recording: bool,
+}
+
+// Adds the changes made since `before` as an undo step, returning whether
+// anything changed. A new step drops anything that could have been redone.
+pub fn record(app: &mut MenuBuilder, label: String, before: Before) -> bool {
+    let step = Step::between(label, before, app);
+    if step.is_empty() {
+        return false;
+    }
+
+    let history = &mut app.undo_history;
+    history.redo.clear();
+    history.undo.push(step);
+    if history.undo.len() > HISTORY_LIMIT {
+        history.undo.remove(0);
+    }
+    true
+}
+
+// Reverts the latest step, returning its label
+pub fn undo(app: &mut MenuBuilder) -> Option<String> {
+    let step = app.undo_history.undo.pop()?;
+    let reverse = step.apply(app);
+    let label = reverse.label.clone();
+    app.undo_history.redo.push(reverse);
+    Some(label)
+}
+
+// Reapplies the latest undone step, returning its label
+pub fn redo(app: &mut MenuBuilder) -> Option<String> {
+    let step = app.undo_history.redo.pop()?;
+    let reverse = step.apply(app);
+    let label = reverse.label.clone();
+    app.undo_history.undo.push(reverse);
+    Some(label)
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use crate::data_types::{DeletionInfo, EntityId};
+use crate::items::Item;
+use crate::persistence::AppState;
+use crate::price_levels::PriceLevel;
+
+// One slot undo for the last confirmed delete. The snapshot is taken before the
+// delete touches anything, and only the records the delete changed are put
+// back, so edits made elsewhere in the meantime are kept.
+
+// How long the "Undo" toast stays up after a delete
+pub const UNDO_WINDOW: Duration = Duration::from_secs(10);
+
+pub struct PendingRestore {
+    pub deletion: DeletionInfo,
+    pub deleted_at: Instant,
+    pub before: AppState,
+    // Records the delete changed, as the delete left them (None when removed)
+    items_after: BTreeMap<EntityId, Option<Item>>,
+    price_levels_after: BTreeMap<EntityId, Option<PriceLevel>>,
+}
+
+impl PendingRestore {
+    pub fn new(
+        deletion: DeletionInfo,
+        before: AppState,
+        items: &BTreeMap<EntityId, Item>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+    ) -> Self {
+        Self {
+            deletion,
+            deleted_at: Instant::now(),
+            items_after: changed(&before.items, |item| item.id, items),
+            price_levels_after: changed(&before.price_levels, |level| level.id, price_levels),
+            before,
+        }
+    }
+
+    // What the delete touched besides the record itself, e.g.
+    // "Removed price level 'Happy Hour' (ID 4) from 12 item(s)"
+    pub fn summary(&self) -> String {
+        let is_deleted = |kind: &str, id: &EntityId| {
+            self.deletion.entity_type == kind && *id == self.deletion.entity_id
+        };
+        let items = self.items_after.keys().filter(|id| !is_deleted("Item", id)).count();
+        let price_levels = self.price_levels_after.keys().filter(|id| !is_deleted("PriceLevel", id)).count();
+
+        let mut touched = Vec::new();
+        if items > 0 {
+            touched.push(format!("{} item(s)", items));
+        }
+        if price_levels > 0 {
+            touched.push(format!("{} price level(s)", price_levels));
+        }
+
+        if touched.is_empty() {
+            format!("Deleted {} '{}', nothing referenced it", self.deletion.type_label(), self.deletion.entity_name)
+        } else {
+            format!(
+                "Removed {} '{}' (ID {}) from {}",
+                self.deletion.type_label(),
+                self.deletion.entity_name,
+                self.deletion.entity_id,
+                touched.join(" and ")
+            )
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.deleted_at.elapsed() >= UNDO_WINDOW
+    }
+
+    // False once any record touched by the delete has been edited and saved
+    // again, at which point restoring the snapshot would lose that work
+    pub fn still_applies(
+        &self,
+        items: &BTreeMap<EntityId, Item>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+    ) -> bool {
+        self.items_after.iter().all(|(id, after)| items.get(id) == after.as_ref())
+            && self.price_levels_after.iter().all(|(id, after)| price_levels.get(id) == after.as_ref())
+    }
+
+    // Puts the changed items and price levels back to their pre-delete versions,
+    // returning how many records were restored
+    pub fn restore_references(
+        &self,
+        items: &mut BTreeMap<EntityId, Item>,
+        price_levels: &mut BTreeMap<EntityId, PriceLevel>,
+    ) -> usize {
+        let restored_items = restore_changed(&self.before.items, |item| item.id, &self.items_after, items);
+        let restored_levels = restore_changed(&self.before.price_levels, |level| level.id, &self.price_levels_after, price_levels);
+        restored_items + restored_levels
+    }
+}
+
+// Puts the entity with this id back from the snapshot, unless the id has been reused
+pub fn restore_entity<T: Clone>(
+    collection: &mut BTreeMap<EntityId, T>,
+    snapshot: &[T],
+    id: EntityId,
+    entity_id: impl Fn(&T) -> EntityId,
+) -> Result<(), String> {
+    if collection.contains_key(&id) {
+        return Err(format!("ID {} has been used again since the delete", id));
+    }
+
+    let entity = snapshot
+        .iter()
+        .find(|entity| entity_id(entity) == id)
+        .ok_or_else(|| format!("ID {} was not in the snapshot", id))?;
+
+    collection.insert(id, entity.clone());
+    Ok(())
+}
+
+fn changed<T: Clone + PartialEq>(
+    before: &[T],
+    entity_id: impl Fn(&T) -> EntityId,
+    now: &BTreeMap<EntityId, T>,
+) -> BTreeMap<EntityId, Option<T>> {
+    before
+        .iter()
+        .filter(|entity| now.get(&entity_id(entity)) != Some(*entity))
+        .map(|entity| (entity_id(entity), now.get(&entity_id(entity)).cloned()))
+        .collect()
+}
+
+fn restore_changed<T: Clone>(
+    before: &[T],
+    entity_id: impl Fn(&T) -> EntityId,
+    after: &BTreeMap<EntityId, Option<T>>,
+    collection: &mut BTreeMap<EntityId, T>,
+) -> usize {
+    before
+        .iter()
+        .filter(|entity| after.contains_key(&entity_id(entity)))
+        .map(|entity| collection.insert(entity_id(entity), entity.clone()))
+        .count()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::ItemPrice;
+    use rust_decimal::Decimal;
+
+    fn happy_hour() -> PriceLevel {
+        PriceLevel { id: 4, name: "Happy Hour".to_string(), ..PriceLevel::default() }
+    }
+
+    fn priced_item(id: EntityId) -> Item {
+        Item {
+            id,
+            price_levels: Some(vec![4]),
+            item_prices: Some(vec![ItemPrice { price_level_id: 4, price: Decimal::ONE }]),
+            ..Item::default()
+        }
+    }
+
+    // Deletes the Happy Hour level, which item 1 is priced at and item 2 isn't
+    fn delete_happy_hour() -> (PendingRestore, BTreeMap<EntityId, Item>, BTreeMap<EntityId, PriceLevel>) {
+        let before = AppState {
+            items: vec![priced_item(1), Item { id: 2, ..Item::default() }],
+            price_levels: vec![happy_hour()],
+            ..AppState::default()
+        };
+        let mut items: BTreeMap<EntityId, Item> = before.items.iter().map(|item| (item.id, item.clone())).collect();
+        let price_levels = BTreeMap::new();
+        items.get_mut(&1).unwrap().remove_price_level(4);
+
+        let deletion = DeletionInfo {
+            entity_type: "PriceLevel".to_string(),
+            entity_id: 4,
+            entity_name: "Happy Hour".to_string(),
+            affected_items: Vec::new(),
+        };
+        let pending = PendingRestore::new(deletion, before, &items, &price_levels);
+
+        (pending, items, price_levels)
+    }
+
+    #[test]
+    fn summary_counts_the_records_the_delete_touched() {
+        let (pending, _, _) = delete_happy_hour();
+
+        assert_eq!(pending.summary(), "Removed price level 'Happy Hour' (ID 4) from 1 item(s)");
+    }
+
+    #[test]
+    fn restore_puts_back_only_what_the_delete_changed() {
+        let (pending, mut items, mut price_levels) = delete_happy_hour();
+        items.get_mut(&2).unwrap().name = "Edited since".to_string();
+
+        assert!(pending.still_applies(&items, &price_levels));
+        assert_eq!(pending.restore_references(&mut items, &mut price_levels), 2);
+
+        assert_eq!(items[&1], priced_item(1));
+        assert_eq!(items[&2].name, "Edited since");
+        assert_eq!(price_levels.get(&4), Some(&happy_hour()));
+    }
+
+    #[test]
+    fn editing_a_touched_record_stops_the_undo() {
+        let (pending, mut items, price_levels) = delete_happy_hour();
+        items.get_mut(&1).unwrap().name = "Edited since".to_string();
+
+        assert!(!pending.still_applies(&items, &price_levels));
+    }
+
+    #[test]
+    fn restore_entity_refuses_a_reused_id() {
+        let snapshot = vec![happy_hour()];
+        let mut levels = BTreeMap::new();
+
+        assert_eq!(restore_entity(&mut levels, &snapshot, 4, |level| level.id), Ok(()));
+        assert!(restore_entity(&mut levels, &snapshot, 4, |level| level.id).is_err());
+        assert!(restore_entity(&mut BTreeMap::new(), &snapshot, 9, |level| level.id).is_err());
+    }
+}
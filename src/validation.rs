@@ -0,0 +1,346 @@
+use crate::data_types::{EntityId, EntityKind, ValidationError};
+use crate::entity_component::{self, Entity};
+use crate::lint::{ReferenceField, Severity};
+use crate::persistence::AppState;
+use iced::widget::{button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+use iced_modern_theme::Modern;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+// Whole-menu integrity checks for the Validation screen. Unlike the lint rules
+// in settings these aren't configurable: every record is run through its own
+// validate and every reference is checked against the other collections.
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Open(Target),
+    ExportReport,
+    ReportExported(Result<PathBuf, String>),
+}
+
+// The record an issue is about, opening it selects the item or starts editing the row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Item(EntityId),
+    Entity(EntityKind, EntityId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssueGroup {
+    MissingReference,
+    InvalidRecord,
+    OutsideItemGroup,
+    DuplicateName,
+    UnusedChoiceGroup,
+}
+
+impl fmt::Display for IssueGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingReference => write!(f, "Missing references"),
+            Self::InvalidRecord => write!(f, "Invalid records"),
+            Self::OutsideItemGroup => write!(f, "Items outside their item group's range"),
+            Self::DuplicateName => write!(f, "Duplicate names"),
+            Self::UnusedChoiceGroup => write!(f, "Choice groups not used by any item"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub group: IssueGroup,
+    pub severity: Severity,
+    pub target: Target,
+    // "Item 'Pancakes' (7000001)", "Tax Group 'Food' (3)"
+    pub record: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn item(group: IssueGroup, severity: Severity, id: EntityId, name: &str, message: String) -> Self {
+        Self {
+            group,
+            severity,
+            target: Target::Item(id),
+            record: format!("Item '{}' ({})", name, id),
+            message,
+        }
+    }
+
+    fn entity<T: Entity>(group: IssueGroup, severity: Severity, kind: EntityKind, entity: &T, message: String) -> Self {
+        Self {
+            group,
+            severity,
+            target: Target::Entity(kind, entity.id()),
+            record: format!("{} '{}' ({})", kind, entity.name(), entity.id()),
+            message,
+        }
+    }
+}
+
+pub fn validate_all(state: &AppState) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let range = |kind| state.settings.id_range(kind);
+
+    check_collection(&mut issues, EntityKind::ItemGroup, &state.item_groups, &range(EntityKind::ItemGroup), crate::item_groups::ItemGroup::validate);
+    check_collection(&mut issues, EntityKind::PriceLevel, &state.price_levels, &range(EntityKind::PriceLevel), crate::price_levels::PriceLevel::validate);
+    check_collection(&mut issues, EntityKind::ProductClass, &state.product_classes, &range(EntityKind::ProductClass), crate::product_classes::ProductClass::validate);
+    check_collection(&mut issues, EntityKind::TaxGroup, &state.tax_groups, &range(EntityKind::TaxGroup), crate::tax_groups::TaxGroup::validate);
+    check_collection(&mut issues, EntityKind::SecurityLevel, &state.security_levels, &range(EntityKind::SecurityLevel), crate::security_levels::SecurityLevel::validate);
+    check_collection(&mut issues, EntityKind::RevenueCategory, &state.revenue_categories, &range(EntityKind::RevenueCategory), crate::revenue_categories::RevenueCategory::validate);
+    check_collection(&mut issues, EntityKind::ReportCategory, &state.report_categories, &range(EntityKind::ReportCategory), crate::report_categories::ReportCategory::validate);
+    check_collection(&mut issues, EntityKind::ChoiceGroup, &state.choice_groups, &range(EntityKind::ChoiceGroup), crate::choice_groups::ChoiceGroup::validate);
+    check_collection(&mut issues, EntityKind::PrinterLogical, &state.printer_logicals, &range(EntityKind::PrinterLogical), crate::printer_logicals::PrinterLogical::validate);
+    check_collection(&mut issues, EntityKind::Store, &state.stores, &range(EntityKind::Store), crate::stores::Store::validate);
+
+    let item_ids: BTreeSet<EntityId> = state.items.iter().map(|item| item.id).collect();
+    let choice_group_ids: BTreeSet<EntityId> = state.choice_groups.iter().map(|group| group.id).collect();
+    let printer_ids: BTreeSet<EntityId> = state.printer_logicals.iter().map(|printer| printer.id).collect();
+    let price_level_ids: BTreeSet<EntityId> = state.price_levels.iter().map(|level| level.id).collect();
+
+    for item in &state.items {
+        let mut missing = Vec::new();
+
+        for field in ReferenceField::ALL {
+            if let Some(id) = field.value(item) {
+                if !field.exists(state, id) {
+                    missing.push(format!("{} {}", field, id));
+                }
+            }
+        }
+        for &(id, _) in item.choice_groups.iter().flatten() {
+            if !choice_group_ids.contains(&id) {
+                missing.push(format!("Choice Group {}", id));
+            }
+        }
+        for &(id, _) in item.printer_logicals.iter().flatten() {
+            if !printer_ids.contains(&id) {
+                missing.push(format!("Printer Logical {}", id));
+            }
+        }
+        for price in item.item_prices.iter().flatten() {
+            if !price_level_ids.contains(&price.price_level_id) {
+                missing.push(format!("Price Level {}", price.price_level_id));
+            }
+        }
+        for &id in item.components.iter().flatten() {
+            if !item_ids.contains(&id) {
+                missing.push(format!("Component item {}", id));
+            }
+        }
+
+        if !missing.is_empty() {
+            issues.push(ValidationIssue::item(
+                IssueGroup::MissingReference,
+                Severity::Error,
+                item.id,
+                &item.name,
+                format!("Points at records that no longer exist: {}", missing.join(", ")),
+            ));
+        }
+
+        // Same inclusive bounds the item editor checks on save
+        let group = item.item_group
+            .and_then(|id| state.item_groups.iter().find(|group| group.id == id));
+        if let Some(group) = group {
+            if item.id < group.id_range.start || item.id > group.id_range.end {
+                issues.push(ValidationIssue::item(
+                    IssueGroup::OutsideItemGroup,
+                    Severity::Error,
+                    item.id,
+                    &item.name,
+                    format!("ID is outside '{}' ({}-{})", group.name, group.id_range.start, group.id_range.end),
+                ));
+            }
+        }
+
+        if item.name.trim().is_empty() {
+            issues.push(ValidationIssue::item(
+                IssueGroup::InvalidRecord,
+                Severity::Error,
+                item.id,
+                &item.name,
+                "Item name cannot be empty".to_string(),
+            ));
+        }
+    }
+
+    for group in entity_component::duplicate_name_groups(&state.items) {
+        for item in &group {
+            issues.push(ValidationIssue::item(
+                IssueGroup::DuplicateName,
+                Severity::Warning,
+                item.id,
+                &item.name,
+                format!("Shares its name with {} other item(s)", group.len() - 1),
+            ));
+        }
+    }
+
+    let used_choice_groups: BTreeSet<EntityId> = state.items
+        .iter()
+        .flat_map(|item| item.choice_groups.iter().flatten().map(|(id, _)| *id))
+        .collect();
+    for group in state.choice_groups.iter().filter(|group| !used_choice_groups.contains(&group.id)) {
+        issues.push(ValidationIssue::entity(
+            IssueGroup::UnusedChoiceGroup,
+            Severity::Warning,
+            EntityKind::ChoiceGroup,
+            group,
+            "No item uses this choice group".to_string(),
+        ));
+    }
+
+    issues
+}
+
+// Runs each record through its type's own validate against the rest of the
+// collection, then flags names shared within it
+fn check_collection<T: Entity>(
+    issues: &mut Vec<ValidationIssue>,
+    kind: EntityKind,
+    entities: &[T],
+    id_range: &RangeInclusive<EntityId>,
+    validate: impl Fn(&T, &[&T], &RangeInclusive<EntityId>) -> Result<(), ValidationError>,
+) {
+    for entity in entities {
+        let others: Vec<&T> = entities.iter().filter(|other| other.id() != entity.id()).collect();
+        if let Err(e) = validate(entity, &others, id_range) {
+            issues.push(ValidationIssue::entity(IssueGroup::InvalidRecord, Severity::Error, kind, entity, e.to_string()));
+        }
+    }
+
+    for group in entity_component::duplicate_name_groups(entities) {
+        for entity in &group {
+            issues.push(ValidationIssue::entity(
+                IssueGroup::DuplicateName,
+                Severity::Warning,
+                kind,
+                *entity,
+                format!("Shares its name with {} other {}(s)", group.len() - 1, kind),
+            ));
+        }
+    }
+}
+
+impl Target {
+    // Type and id columns of the exported report
+    fn kind_and_id(&self) -> (String, EntityId) {
+        match self {
+            Self::Item(id) => ("Item".to_string(), *id),
+            Self::Entity(kind, id) => (kind.to_string(), *id),
+        }
+    }
+}
+
+// Writes every issue to a CSV file the user picks, one line per issue
+pub async fn export_report(issues: Vec<ValidationIssue>) -> Result<PathBuf, String> {
+    let path = rfd::AsyncFileDialog::new()
+        .add_filter("CSV Files", &["csv"])
+        .set_title("Save Validation Report")
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or("Export canceled".to_string())?;
+
+    let quote = |value: &str| format!("\"{}\"", value.replace('"', "\"\""));
+
+    let mut content = String::from("\"Severity\",\"Group\",\"Entity Type\",\"ID\",\"Record\",\"Issue\"\n");
+    for issue in issues {
+        let (kind, id) = issue.target.kind_and_id();
+        content.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            quote(&issue.severity.to_string()),
+            quote(&issue.group.to_string()),
+            quote(&kind),
+            id,
+            quote(&issue.record),
+            quote(&issue.message),
+        ));
+    }
+
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| format!("Failed to write validation report: {}", e))?;
+
+    Ok(path)
+}
+
+pub fn view<'a>(issues: Vec<ValidationIssue>) -> Element<'a, Message> {
+    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+    let summary = if issues.is_empty() {
+        text("No problems found").style(Modern::secondary_text())
+    } else {
+        text(format!("{} error(s), {} warning(s)", errors, issues.len() - errors)).style(Modern::error_text())
+    };
+
+    let mut grouped: BTreeMap<IssueGroup, Vec<ValidationIssue>> = BTreeMap::new();
+    for issue in issues {
+        grouped.entry(issue.group).or_default().push(issue);
+    }
+
+    let groups = grouped
+        .into_iter()
+        .map(|(group, issues)| {
+            container(
+                column![
+                    text(format!("{} ({})", group, issues.len())).size(16).style(Modern::primary_text()),
+                    column(
+                        issues
+                            .into_iter()
+                            .map(|issue| {
+                                let severity = text(issue.severity.to_string())
+                                    .size(12)
+                                    .width(Length::Fixed(60.0));
+                                let severity = if issue.severity == Severity::Error {
+                                    severity.style(Modern::error_text())
+                                } else {
+                                    severity.style(entity_component::warning_text)
+                                };
+
+                                row![
+                                    severity,
+                                    button(text(issue.record).size(13))
+                                        .on_press(Message::Open(issue.target))
+                                        .style(Modern::gray_button()),
+                                    text(issue.message).size(13).style(Modern::secondary_text()),
+                                ]
+                                .spacing(10)
+                                .align_y(Alignment::Center)
+                                .into()
+                            })
+                            .collect::<Vec<_>>()
+                    )
+                    .spacing(5),
+                ]
+                .spacing(10)
+            )
+            .padding(15)
+            .width(Length::Fill)
+            .style(Modern::card_container())
+            .into()
+        })
+        .collect::<Vec<Element<'a, Message>>>();
+
+    column![
+        row![
+            text("Validation").size(18).style(Modern::primary_text()),
+            summary,
+            iced::widget::horizontal_space(),
+            button("Export Report")
+                .on_press(Message::ExportReport)
+                .style(Modern::system_button()),
+        ]
+        .spacing(15)
+        .align_y(Alignment::Center),
+        text("Click a record to open it").size(12).style(Modern::secondary_text()),
+        scrollable(column(groups).spacing(15)).height(Length::Fill),
+    ]
+    .spacing(10)
+    .into()
+}